@@ -14,14 +14,37 @@ pub struct GltfExportOptions {
     pub export_normals: bool,
     /// Include UVs in export
     pub export_uvs: bool,
+    /// UV channel written as TEXCOORD_0
+    pub uv_channel: usize,
+    /// Write every UV channel as TEXCOORD_0..N instead of just `uv_channel`
+    pub export_all_uv_channels: bool,
     /// Include vertex colors
     pub export_colors: bool,
     /// Include tangents
     pub export_tangents: bool,
     /// Include skin weights/indices
     pub export_skin: bool,
+    /// Include emissive color, texture and strength in exported materials
+    pub export_emissive: bool,
+    /// Clamp bone indices past the skeleton instead of failing the export
+    pub clamp_bone_indices: bool,
     /// Pretty-print JSON
     pub pretty_json: bool,
+    /// Quantize vertex data to this many decimal places (`None` keeps full precision)
+    pub float_decimals: Option<u8>,
+    /// Merge chains of empty single-child nodes, composing their transforms
+    pub collapse_empty_nodes: bool,
+    /// Largest binary buffer in bytes before data spills into another one
+    ///
+    /// Buffers past the first are written as `<name>_N.bin` next to the
+    /// output, for GLB too since it can only embed one. An accessor bigger
+    /// than the limit still gets a buffer to itself.
+    pub max_buffer_size: usize,
+    /// Factor applied to vertex positions and node translations
+    ///
+    /// Source geometry is in meters; use 100.0 for centimeter-based tools.
+    /// Rotations and node scales are left alone.
+    pub scale: f32,
 }
 
 impl Default for GltfExportOptions {
@@ -30,14 +53,36 @@ impl Default for GltfExportOptions {
             use_glb: false,
             export_normals: true,
             export_uvs: true,
+            uv_channel: 0,
+            export_all_uv_channels: false,
             export_colors: false,
             export_tangents: false,
             export_skin: true,
+            export_emissive: true,
+            clamp_bone_indices: true,
             pretty_json: true,
+            float_decimals: None,
+            collapse_empty_nodes: false,
+            // Many tools read buffer lengths as signed 32-bit
+            max_buffer_size: i32::MAX as usize,
+            scale: 1.0,
         }
     }
 }
 
+impl GltfExportOptions {
+    /// Set `scale` from the target's units per meter, e.g. as recorded in a
+    /// model's SourceInfo export settings
+    ///
+    /// Missing, zero, negative or non-finite values keep the current scale.
+    pub fn with_units_per_meter(mut self, units_per_meter: Option<f32>) -> Self {
+        if let Some(units) = units_per_meter.filter(|u| u.is_finite() && *u > 0.0) {
+            self.scale = units;
+        }
+        self
+    }
+}
+
 /// glTF export errors
 #[derive(Debug, thiserror::Error)]
 pub enum GltfExportError {
@@ -49,6 +94,16 @@ pub enum GltfExportError {
     
     #[error("Invalid mesh data: {0}")]
     InvalidMeshData(String),
+
+    #[error(transparent)]
+    InvalidMesh(#[from] starbreaker_parsers::cgf::MeshError),
+
+    #[error(
+        "Invalid skin: {} problem(s), first: {}",
+        .0.len(),
+        .0.first().map_or_else(|| "none".to_string(), ToString::to_string)
+    )]
+    InvalidSkin(Vec<starbreaker_parsers::cgf::SkinError>),
 }
 
 pub type GltfResult<T> = Result<T, GltfExportError>;
@@ -56,9 +111,17 @@ pub type GltfResult<T> = Result<T, GltfExportError>;
 /// glTF exporter
 pub struct GltfExporter {
     options: GltfExportOptions,
+    /// Buffer currently being filled
     binary_data: Vec<u8>,
+    /// Buffers that reached `max_buffer_size`, in order before `binary_data`
+    full_buffers: Vec<Vec<u8>>,
     accessors: Vec<Accessor>,
     buffer_views: Vec<BufferView>,
+    materials: Vec<Material>,
+    images: Vec<Image>,
+    textures: Vec<Texture>,
+    extensions_used: Vec<String>,
+    warnings: Vec<String>,
 }
 
 impl GltfExporter {
@@ -67,9 +130,38 @@ impl GltfExporter {
         Self {
             options,
             binary_data: Vec::new(),
+            full_buffers: Vec::new(),
             accessors: Vec::new(),
             buffer_views: Vec::new(),
+            materials: Vec::new(),
+            images: Vec::new(),
+            textures: Vec::new(),
+            extensions_used: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Non-fatal issues from the last export (e.g. an out-of-range UV channel)
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Export a full CGF model (all meshes and nodes) to a glTF file
+    pub fn export_model(
+        &mut self,
+        model: &starbreaker_parsers::cgf::CgfModel,
+        output_path: impl AsRef<Path>,
+    ) -> GltfResult<()> {
+        let output_path = output_path.as_ref();
+        let mut gltf = self.build_gltf_from_model(model)?;
+
+        if self.options.use_glb {
+            self.write_glb(&mut gltf, output_path)?;
+        } else {
+            self.write_separate_files(&mut gltf, output_path)?;
         }
+
+        Ok(())
     }
 
     /// Export CGF mesh to glTF file
@@ -81,12 +173,12 @@ impl GltfExporter {
         let output_path = output_path.as_ref();
         
         // Build glTF structure
-        let gltf = self.build_gltf_from_mesh(mesh)?;
+        let mut gltf = self.build_gltf_from_mesh(mesh)?;
         
         if self.options.use_glb {
-            self.write_glb(&gltf, output_path)?;
+            self.write_glb(&mut gltf, output_path)?;
         } else {
-            self.write_separate_files(&gltf, output_path)?;
+            self.write_separate_files(&mut gltf, output_path)?;
         }
         
         Ok(())
@@ -94,10 +186,251 @@ impl GltfExporter {
 
     /// Build glTF structure from CGF mesh
     fn build_gltf_from_mesh(&mut self, mesh: &starbreaker_parsers::cgf::Mesh) -> GltfResult<Gltf> {
-        // Reset state
+        self.reset();
+
+        let gltf_mesh = self.build_mesh(mesh, Some(0))?;
+
+        let node = Node {
+            name: Some("MeshNode".to_string()),
+            mesh: Some(0),
+            skin: None,
+            translation: None,
+            rotation: None,
+            scale: None,
+            children: vec![],
+            extras: None,
+        };
+
+        Ok(self.assemble(vec![node], vec![0], vec![gltf_mesh]))
+    }
+
+    /// Build glTF structure from a full CGF model, keeping its node hierarchy
+    ///
+    /// Nodes without a mesh (CryEngine helpers/dummies such as locators and
+    /// hardpoints) are exported as empty nodes so their names and transforms
+    /// survive. Hardpoint-style helpers are tagged with
+    /// `extras: {"hardpoint": true}`.
+    fn build_gltf_from_model(&mut self, model: &starbreaker_parsers::cgf::CgfModel) -> GltfResult<Gltf> {
+        self.reset();
+
+        let source_meshes = self.checked_skin_meshes(model)?;
+
+        // Primitives pick their material by the first face's material id;
+        // ids with no matching material leave the primitive without one
+        let material_of_id: HashMap<u32, usize> = model.materials.iter().enumerate()
+            .map(|(slot, material)| {
+                let built = self.build_material(material);
+                self.materials.push(built);
+                (material.index, slot)
+            })
+            .collect();
+
+        let meshes = source_meshes.iter()
+            .map(|mesh| {
+                let material = mesh.faces.first()
+                    .and_then(|face| material_of_id.get(&face.material_id))
+                    .copied();
+                self.build_mesh(mesh, material)
+            })
+            .collect::<GltfResult<Vec<_>>>()?;
+
+        // Models without node chunks get one node per mesh
+        if model.nodes.is_empty() {
+            let nodes: Vec<Node> = model.meshes.iter().enumerate()
+                .map(|(idx, mesh)| Node {
+                    name: Some(mesh.name.clone()),
+                    mesh: Some(idx),
+                    skin: None,
+                    translation: None,
+                    rotation: None,
+                    scale: None,
+                    children: vec![],
+                    extras: None,
+                })
+                .collect();
+            let roots = (0..nodes.len()).collect();
+            return Ok(self.assemble(nodes, roots, meshes));
+        }
+
+        let index_of_id: HashMap<u32, usize> = model.nodes.iter().enumerate()
+            .map(|(idx, node)| (node.id, idx))
+            .collect();
+
+        let mut nodes: Vec<Node> = model.nodes.iter()
+            .map(|node| {
+                let mesh = node.mesh_index.filter(|&idx| {
+                    let valid = idx < meshes.len();
+                    if !valid {
+                        self.warnings.push(format!(
+                            "Node '{}' references missing mesh {}, exporting as helper",
+                            node.name, idx
+                        ));
+                    }
+                    valid
+                });
+
+                Node {
+                    name: Some(node.name.clone()),
+                    mesh,
+                    skin: None,
+                    translation: Some(node.position.map(|v| v * self.options.scale)),
+                    rotation: Some(node.rotation),
+                    scale: Some(node.scale),
+                    children: vec![],
+                    extras: node.is_hardpoint()
+                        .then(|| serde_json::json!({ "hardpoint": true })),
+                }
+            })
+            .collect();
+
+        let mut roots = Vec::new();
+        for (idx, node) in model.nodes.iter().enumerate() {
+            match index_of_id.get(&node.parent_id) {
+                Some(&parent) if !node.is_root() && parent != idx => nodes[parent].children.push(idx),
+                _ => roots.push(idx),
+            }
+        }
+
+        if self.options.collapse_empty_nodes {
+            let (collapsed, collapsed_roots) = collapse_empty_chains(nodes, roots);
+            nodes = collapsed;
+            roots = collapsed_roots;
+        }
+
+        Ok(self.assemble(nodes, roots, meshes))
+    }
+
+    /// Validate skinning before export, clamping or failing per the options
+    ///
+    /// Out-of-range bone indices crash most viewers. With
+    /// `clamp_bone_indices` they are clamped to the last bone (on a copy of
+    /// the meshes) and reported as warnings; otherwise the export fails.
+    /// Bad weight sums are only reported.
+    fn checked_skin_meshes<'a>(
+        &mut self,
+        model: &'a starbreaker_parsers::cgf::CgfModel,
+    ) -> GltfResult<std::borrow::Cow<'a, [starbreaker_parsers::cgf::Mesh]>> {
+        use starbreaker_parsers::cgf::SkinError;
+
+        let Some(skeleton) = model.skeleton.as_ref().filter(|_| self.options.export_skin) else {
+            return Ok(std::borrow::Cow::Borrowed(&model.meshes));
+        };
+
+        let errors = model.validate_skin();
+        let has_bad_index = errors.iter().any(|e| matches!(e, SkinError::BoneIndexOutOfRange { .. }));
+        if has_bad_index && !self.options.clamp_bone_indices {
+            return Err(GltfExportError::InvalidSkin(errors));
+        }
+        self.warnings.extend(errors.iter().map(|e| e.to_string()));
+
+        if !has_bad_index {
+            return Ok(std::borrow::Cow::Borrowed(&model.meshes));
+        }
+
+        let mut meshes = model.meshes.clone();
+        for mesh in &mut meshes {
+            mesh.clamp_bone_indices(skeleton.bone_count());
+        }
+        Ok(std::borrow::Cow::Owned(meshes))
+    }
+
+    /// Clear buffers and warnings left over from a previous export
+    fn reset(&mut self) {
         self.binary_data.clear();
+        self.full_buffers.clear();
         self.accessors.clear();
         self.buffer_views.clear();
+        self.materials.clear();
+        self.images.clear();
+        self.textures.clear();
+        self.extensions_used.clear();
+        self.warnings.clear();
+    }
+
+    /// Convert a CGF material, filling in its emissive color, texture and strength
+    ///
+    /// The emissive parts are only filled in with `export_emissive`. The emissive color comes from the `Emissive` shader param and the
+    /// strength from `GlowAmount` (or `EmissiveIntensity`). Strengths up to
+    /// 1.0 scale `emissiveFactor` directly; stronger ones can't be expressed
+    /// by it alone, so they are written through
+    /// `KHR_materials_emissive_strength`. Emissive textures are
+    /// referenced by their archive path with a `.png` extension, matching
+    /// where the texture exporter writes converted DDS files.
+    fn build_material(&mut self, material: &starbreaker_parsers::cgf::MaterialRef) -> Material {
+        use starbreaker_parsers::cgf::ShaderParam;
+
+        let mut built = Material {
+            name: Some(material.name.clone()),
+            pbr_metallic_roughness: Some(PbrMetallicRoughness {
+                base_color_factor: Some([1.0, 1.0, 1.0, 1.0]),
+                metallic_factor: Some(0.0),
+                roughness_factor: Some(0.5),
+            }),
+            emissive_factor: None,
+            emissive_texture: None,
+            extensions: None,
+        };
+        if !self.options.export_emissive {
+            return built;
+        }
+
+        let emissive_color = match material.params.get("Emissive") {
+            Some(ShaderParam::Float3(rgb)) => Some(*rgb),
+            Some(ShaderParam::Float4([r, g, b, _])) => Some([*r, *g, *b]),
+            _ => None,
+        };
+        let strength = ["GlowAmount", "EmissiveIntensity"].iter()
+            .find_map(|key| match material.params.get(*key) {
+                Some(ShaderParam::Float(value)) => Some(*value),
+                _ => None,
+            })
+            .filter(|value| value.is_finite() && *value >= 0.0);
+
+        let emissive_texture = material.textures.emissive.as_ref().map(|path| {
+            let uri = Path::new(&path.replace('\\', "/")).with_extension("png");
+            self.images.push(Image {
+                name: Some(path.clone()),
+                uri: Some(uri.to_string_lossy().into_owned()),
+            });
+            self.textures.push(Texture {
+                name: Some(path.clone()),
+                source: self.images.len() - 1,
+            });
+            TextureInfo { index: self.textures.len() - 1 }
+        });
+
+        // A texture without a color param glows at its own color
+        let emissive_factor = emissive_color
+            .or_else(|| emissive_texture.as_ref().map(|_| [1.0, 1.0, 1.0]))
+            .map(|factor| factor.map(|c| c * strength.unwrap_or(1.0).min(1.0)));
+
+        let extensions = strength
+            .filter(|value| *value > 1.0 && emissive_factor.is_some())
+            .map(|value| {
+                if !self.extensions_used.iter().any(|ext| ext == KHR_MATERIALS_EMISSIVE_STRENGTH) {
+                    self.extensions_used.push(KHR_MATERIALS_EMISSIVE_STRENGTH.to_string());
+                }
+                MaterialExtensions {
+                    emissive_strength: Some(EmissiveStrength { emissive_strength: value }),
+                }
+            });
+
+        built.emissive_factor = emissive_factor;
+        built.emissive_texture = emissive_texture;
+        built.extensions = extensions;
+        built
+    }
+
+    /// Write one CGF mesh's attributes and indices, returning the glTF mesh
+    fn build_mesh(&mut self, mesh: &starbreaker_parsers::cgf::Mesh, material: Option<usize>) -> GltfResult<Mesh> {
+        // Reject meshes that would index past the vertex buffer; other
+        // problems are exportable and only reported
+        if let Err(err) = mesh.validate() {
+            if err.is_fatal() {
+                return Err(err.into());
+            }
+            self.warnings.push(err.to_string());
+        }
 
         // Build primitive with attributes
         let mut attributes = HashMap::new();
@@ -113,9 +446,33 @@ impl GltfExporter {
         }
 
         // UVs
-        if self.options.export_uvs && !mesh.vertices.is_empty() && !mesh.vertices[0].uv.is_empty() {
-            let uv_accessor = self.add_uvs(&mesh.vertices)?;
-            attributes.insert("TEXCOORD_0".to_string(), uv_accessor);
+        let uv_channels = mesh.uv_channel_count();
+        if self.options.export_uvs && uv_channels > 0 {
+            if self.options.export_all_uv_channels {
+                for channel in 0..uv_channels {
+                    let uv_accessor = self.add_uvs(&mesh.vertices, channel)?;
+                    attributes.insert(format!("TEXCOORD_{}", channel), uv_accessor);
+                }
+            } else {
+                let channel = if self.options.uv_channel < uv_channels {
+                    self.options.uv_channel
+                } else {
+                    self.warnings.push(format!(
+                        "UV channel {} out of range for mesh '{}' ({} channels), using channel 0",
+                        self.options.uv_channel, mesh.name, uv_channels
+                    ));
+                    0
+                };
+
+                let uv_accessor = self.add_uvs(&mesh.vertices, channel)?;
+                attributes.insert("TEXCOORD_0".to_string(), uv_accessor);
+            }
+        }
+
+        // Vertex colors
+        if self.options.export_colors && mesh.has_colors() {
+            let color_accessor = self.add_colors(&mesh.vertices)?;
+            attributes.insert("COLOR_0".to_string(), color_accessor);
         }
 
         // Indices
@@ -125,78 +482,81 @@ impl GltfExporter {
         let primitive = Primitive {
             attributes,
             indices: Some(indices_accessor),
-            material: Some(0), // Default material
+            material,
             mode: Some(MODE_TRIANGLES),
         };
 
-        // Build mesh
-        let gltf_mesh = Mesh {
+        Ok(Mesh {
             name: Some(mesh.name.clone()),
             primitives: vec![primitive],
-        };
+        })
+    }
 
-        // Build default material
-        let material = Material {
-            name: Some("DefaultMaterial".to_string()),
-            pbr_metallic_roughness: Some(PbrMetallicRoughness {
-                base_color_factor: Some([1.0, 1.0, 1.0, 1.0]),
-                metallic_factor: Some(0.0),
-                roughness_factor: Some(0.5),
-            }),
+    /// Wrap nodes and meshes into a document with the collected materials and buffer
+    ///
+    /// Falls back to a single default material when none were exported.
+    fn assemble(&self, nodes: Vec<Node>, roots: Vec<usize>, meshes: Vec<Mesh>) -> Gltf {
+        let materials = if self.materials.is_empty() {
+            vec![Material {
+                name: Some("DefaultMaterial".to_string()),
+                pbr_metallic_roughness: Some(PbrMetallicRoughness {
+                    base_color_factor: Some([1.0, 1.0, 1.0, 1.0]),
+                    metallic_factor: Some(0.0),
+                    roughness_factor: Some(0.5),
+                }),
+                emissive_factor: None,
+                emissive_texture: None,
+                extensions: None,
+            }]
+        } else {
+            self.materials.clone()
         };
 
-        // Build buffer
-        let buffer = Buffer {
-            uri: Some("data.bin".to_string()),
-            byte_length: self.binary_data.len(),
-        };
+        // URIs depend on the output file name and are set when writing
+        let buffers = self.buffers()
+            .map(|data| Buffer { uri: None, byte_length: data.len() })
+            .collect();
 
         // Build scene
         let scene = Scene {
             name: Some("Scene".to_string()),
-            nodes: vec![0],
-        };
-
-        // Build node
-        let node = Node {
-            name: Some("MeshNode".to_string()),
-            mesh: Some(0),
-            skin: None,
-            translation: None,
-            rotation: None,
-            scale: None,
-            children: vec![],
+            nodes: roots,
         };
 
         // Build final glTF
-        Ok(Gltf {
+        Gltf {
             asset: Asset {
                 version: "2.0".to_string(),
                 generator: Some("StarBreaker glTF Exporter".to_string()),
             },
             scene: Some(0),
             scenes: vec![scene],
-            nodes: vec![node],
-            meshes: vec![gltf_mesh],
-            materials: vec![material],
+            nodes,
+            meshes,
+            materials,
             accessors: self.accessors.clone(),
             buffer_views: self.buffer_views.clone(),
-            buffers: vec![buffer],
+            buffers,
             skins: vec![],
-        })
+            images: self.images.clone(),
+            textures: self.textures.clone(),
+            extensions_used: self.extensions_used.clone(),
+        }
     }
 
     /// Add position data
     fn add_positions(&mut self, vertices: &[starbreaker_parsers::cgf::Vertex]) -> GltfResult<usize> {
         let offset = self.binary_data.len();
-        let mut min = [f32::MAX, f32::MAX, f32::MAX];
-        let mut max = [f32::MIN, f32::MIN, f32::MIN];
+        // Scaling and rounding are monotonic, so transforming the bounds
+        // matches the written data
+        let scale = self.options.scale;
+        let (min, max) = position_bounds(vertices);
+        let min = min.map(|v| self.quantize(v * scale));
+        let max = max.map(|v| self.quantize(v * scale));
 
         for vertex in vertices {
             for i in 0..3 {
-                self.binary_data.extend_from_slice(&vertex.position[i].to_le_bytes());
-                min[i] = min[i].min(vertex.position[i]);
-                max[i] = max[i].max(vertex.position[i]);
+                self.push_f32(vertex.position[i] * scale);
             }
         }
 
@@ -209,26 +569,54 @@ impl GltfExporter {
 
         for vertex in vertices {
             for i in 0..3 {
-                self.binary_data.extend_from_slice(&vertex.normal[i].to_le_bytes());
+                self.push_f32(vertex.normal[i]);
             }
         }
 
         self.add_accessor(offset, vertices.len(), "VEC3", COMPONENT_TYPE_FLOAT, None, None, Some(TARGET_ARRAY_BUFFER))
     }
 
-    /// Add UV data
-    fn add_uvs(&mut self, vertices: &[starbreaker_parsers::cgf::Vertex]) -> GltfResult<usize> {
+    /// Add UV data for one channel
+    fn add_uvs(&mut self, vertices: &[starbreaker_parsers::cgf::Vertex], channel: usize) -> GltfResult<usize> {
         let offset = self.binary_data.len();
 
         for vertex in vertices {
-            let uv = vertex.uv.first().unwrap_or(&[0.0, 0.0]);
-            self.binary_data.extend_from_slice(&uv[0].to_le_bytes());
-            self.binary_data.extend_from_slice(&uv[1].to_le_bytes());
+            let uv = vertex.uv.get(channel).copied().unwrap_or([0.0, 0.0]);
+            self.push_f32(uv[0]);
+            self.push_f32(uv[1]);
         }
 
         self.add_accessor(offset, vertices.len(), "VEC2", COMPONENT_TYPE_FLOAT, None, None, Some(TARGET_ARRAY_BUFFER))
     }
 
+    /// Add RGBA vertex colors, defaulting uncolored vertices to white
+    fn add_colors(&mut self, vertices: &[starbreaker_parsers::cgf::Vertex]) -> GltfResult<usize> {
+        let offset = self.binary_data.len();
+
+        for vertex in vertices {
+            let rgba = vertex.color_rgba_f32().unwrap_or([1.0; 4]);
+            for channel in rgba {
+                self.binary_data.extend_from_slice(&channel.to_le_bytes());
+            }
+        }
+
+        self.add_accessor(offset, vertices.len(), "VEC4", COMPONENT_TYPE_FLOAT, None, None, Some(TARGET_ARRAY_BUFFER))
+    }
+
+    /// Apply `float_decimals` quantization to a value
+    fn quantize(&self, value: f32) -> f32 {
+        match self.options.float_decimals {
+            Some(decimals) => crate::json::round_float(value as f64, decimals) as f32,
+            None => value,
+        }
+    }
+
+    /// Append a (possibly quantized) float to the binary buffer
+    fn push_f32(&mut self, value: f32) {
+        let value = self.quantize(value);
+        self.binary_data.extend_from_slice(&value.to_le_bytes());
+    }
+
     /// Add index data
     fn add_indices(&mut self, faces: &[starbreaker_parsers::cgf::Face]) -> GltfResult<usize> {
         let offset = self.binary_data.len();
@@ -243,13 +631,24 @@ impl GltfExporter {
         self.add_accessor(offset, count, "SCALAR", COMPONENT_TYPE_UNSIGNED_SHORT, None, None, Some(TARGET_ELEMENT_ARRAY_BUFFER))
     }
 
-    /// Add accessor and buffer view
-    fn add_accessor(&mut self, offset: usize, count: usize, accessor_type: &str, component_type: u32, min: Option<Vec<f32>>, max: Option<Vec<f32>>, target: Option<u32>) -> GltfResult<usize> {
+    /// Add accessor and buffer view for the data written since `offset`
+    ///
+    /// If that data took the buffer past `max_buffer_size` it is moved to a
+    /// fresh buffer, so views never straddle two buffers.
+    fn add_accessor(&mut self, mut offset: usize, count: usize, accessor_type: &str, component_type: u32, min: Option<Vec<f32>>, max: Option<Vec<f32>>, target: Option<u32>) -> GltfResult<usize> {
+        if self.binary_data.len() > self.options.max_buffer_size && offset > 0 {
+            let data = self.binary_data.split_off(offset);
+            let full = std::mem::replace(&mut self.binary_data, data);
+            self.full_buffers.push(full);
+            offset = 0;
+        }
         let byte_length = self.binary_data.len() - offset;
+        // Keep the next view 4-byte aligned; u16 indices can end mid-word
+        self.binary_data.resize(self.binary_data.len().next_multiple_of(4), 0);
         
         let buffer_view_index = self.buffer_views.len();
         self.buffer_views.push(BufferView {
-            buffer: 0,
+            buffer: self.full_buffers.len(),
             byte_offset: Some(offset),
             byte_length,
             byte_stride: None,
@@ -270,8 +669,33 @@ impl GltfExporter {
         Ok(accessor_index)
     }
 
+    /// Every binary buffer in order, the one still being filled last
+    fn buffers(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.full_buffers.iter().chain(std::iter::once(&self.binary_data))
+    }
+
+    /// Write buffers from `first` on as `.bin` files next to `output_path`,
+    /// pointing their URIs at them
+    fn write_bin_files(&self, gltf: &mut Gltf, output_path: &Path, first: usize) -> GltfResult<()> {
+        let stem = output_path.file_stem().and_then(|s| s.to_str()).unwrap_or("data");
+
+        for (index, data) in self.buffers().enumerate().skip(first) {
+            let name = match index {
+                0 => format!("{}.bin", stem),
+                _ => format!("{}_{}.bin", stem, index),
+            };
+            std::fs::write(output_path.with_file_name(&name), data)?;
+            gltf.buffers[index].uri = Some(name);
+        }
+
+        Ok(())
+    }
+
     /// Write separate JSON + BIN files
-    fn write_separate_files(&self, gltf: &Gltf, output_path: &Path) -> GltfResult<()> {
+    fn write_separate_files(&self, gltf: &mut Gltf, output_path: &Path) -> GltfResult<()> {
+        // Write BIN first so the JSON carries the buffer URIs
+        self.write_bin_files(gltf, output_path, 0)?;
+
         // Write JSON
         let json_path = output_path.with_extension("gltf");
         let json = if self.options.pretty_json {
@@ -281,15 +705,16 @@ impl GltfExporter {
         };
         std::fs::write(&json_path, json)?;
 
-        // Write BIN
-        let bin_path = output_path.with_extension("bin");
-        std::fs::write(&bin_path, &self.binary_data)?;
-
         Ok(())
     }
 
     /// Write GLB (binary glTF)
-    fn write_glb(&self, gltf: &Gltf, output_path: &Path) -> GltfResult<()> {
+    ///
+    /// Only the first buffer is embedded; the rest go to `.bin` files.
+    fn write_glb(&self, gltf: &mut Gltf, output_path: &Path) -> GltfResult<()> {
+        self.write_bin_files(gltf, output_path, 1)?;
+        let bin_data = self.full_buffers.first().unwrap_or(&self.binary_data);
+
         let glb_path = output_path.with_extension("glb");
         let mut file = std::fs::File::create(&glb_path)?;
 
@@ -298,10 +723,10 @@ impl GltfExporter {
         file.write_all(&2u32.to_le_bytes())?; // Version
 
         // Calculate lengths
-        let json = serde_json::to_string(gltf)?;
+        let json = serde_json::to_string(&*gltf)?;
         let json_len = json.len();
         let json_padding = (4 - (json_len % 4)) % 4;
-        let bin_len = self.binary_data.len();
+        let bin_len = bin_data.len();
         let bin_padding = (4 - (bin_len % 4)) % 4;
         
         let total_len = 12 + 8 + json_len + json_padding + 8 + bin_len + bin_padding;
@@ -318,7 +743,7 @@ impl GltfExporter {
         // BIN chunk
         file.write_all(&((bin_len + bin_padding) as u32).to_le_bytes())?;
         file.write_all(&0x004E4942u32.to_le_bytes())?; // "BIN\0"
-        file.write_all(&self.binary_data)?;
+        file.write_all(bin_data)?;
         for _ in 0..bin_padding {
             file.write_all(&[0x00])?; // Zero padding
         }
@@ -326,3 +751,549 @@ impl GltfExporter {
         Ok(())
     }
 }
+
+/// Per-component min/max of vertex positions for the accessor bounds
+///
+/// Non-finite components are skipped so a single bad vertex can't poison the
+/// bounds; a component with no finite values falls back to 0.0.
+fn position_bounds(vertices: &[starbreaker_parsers::cgf::Vertex]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::MAX, f32::MAX, f32::MAX];
+    let mut max = [f32::MIN, f32::MIN, f32::MIN];
+
+    for vertex in vertices {
+        for i in 0..3 {
+            let v = vertex.position[i];
+            if v.is_finite() {
+                min[i] = min[i].min(v);
+                max[i] = max[i].max(v);
+            }
+        }
+    }
+
+    for i in 0..3 {
+        if min[i] > max[i] {
+            min[i] = 0.0;
+            max[i] = 0.0;
+        }
+    }
+
+    (min, max)
+}
+
+/// Merge empty nodes into their empty parent when the parent has no other child
+///
+/// A node absorbs its only child while both have no mesh or skin and neither
+/// is tagged as a hardpoint; the parent keeps its name and takes over the
+/// child's children. Merges that would need a shear (a non-uniform parent
+/// scale over a rotated child) can't be expressed in TRS and are skipped.
+/// Returns the compacted nodes and remapped roots.
+fn collapse_empty_chains(mut nodes: Vec<Node>, roots: Vec<usize>) -> (Vec<Node>, Vec<usize>) {
+    fn is_empty(node: &Node) -> bool {
+        node.mesh.is_none()
+            && node.skin.is_none()
+            && node.extras.as_ref().and_then(|e| e.get("hardpoint")).is_none()
+    }
+
+    let mut removed = vec![false; nodes.len()];
+    let mut stack = roots.clone();
+
+    while let Some(idx) = stack.pop() {
+        while let &[child] = nodes[idx].children.as_slice() {
+            if removed[child] || !is_empty(&nodes[idx]) || !is_empty(&nodes[child]) {
+                break;
+            }
+            let Some((translation, rotation, scale)) = compose_trs(&nodes[idx], &nodes[child]) else {
+                break;
+            };
+
+            let grandchildren = std::mem::take(&mut nodes[child].children);
+            let node = &mut nodes[idx];
+            node.translation = Some(translation);
+            node.rotation = Some(rotation);
+            node.scale = Some(scale);
+            node.children = grandchildren;
+            removed[child] = true;
+        }
+        stack.extend(nodes[idx].children.iter().copied().filter(|&c| !removed[c]));
+    }
+
+    let mut new_index = vec![usize::MAX; nodes.len()];
+    let mut kept = Vec::with_capacity(nodes.len());
+    for (idx, node) in nodes.into_iter().enumerate() {
+        if !removed[idx] {
+            new_index[idx] = kept.len();
+            kept.push(node);
+        }
+    }
+    for node in &mut kept {
+        for child in &mut node.children {
+            *child = new_index[*child];
+        }
+    }
+
+    let roots = roots.into_iter().map(|idx| new_index[idx]).collect();
+    (kept, roots)
+}
+
+/// TRS of `parent * child`, or `None` if the product has a shear
+fn compose_trs(parent: &Node, child: &Node) -> Option<([f32; 3], [f32; 4], [f32; 3])> {
+    const IDENTITY: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+    let (pt, pr, ps) = (
+        parent.translation.unwrap_or([0.0; 3]),
+        parent.rotation.unwrap_or(IDENTITY),
+        parent.scale.unwrap_or([1.0; 3]),
+    );
+    let (ct, cr, cs) = (
+        child.translation.unwrap_or([0.0; 3]),
+        child.rotation.unwrap_or(IDENTITY),
+        child.scale.unwrap_or([1.0; 3]),
+    );
+
+    let uniform = ps[0] == ps[1] && ps[1] == ps[2];
+    if !uniform && cr != IDENTITY {
+        return None;
+    }
+
+    let offset = quat_rotate(pr, [ps[0] * ct[0], ps[1] * ct[1], ps[2] * ct[2]]);
+    Some((
+        [pt[0] + offset[0], pt[1] + offset[1], pt[2] + offset[2]],
+        quat_mul(pr, cr),
+        [ps[0] * cs[0], ps[1] * cs[1], ps[2] * cs[2]],
+    ))
+}
+
+/// Hamilton product of two `[x, y, z, w]` quaternions
+fn quat_mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [
+        a[3] * b[0] + a[0] * b[3] + a[1] * b[2] - a[2] * b[1],
+        a[3] * b[1] - a[0] * b[2] + a[1] * b[3] + a[2] * b[0],
+        a[3] * b[2] + a[0] * b[1] - a[1] * b[0] + a[2] * b[3],
+        a[3] * b[3] - a[0] * b[0] - a[1] * b[1] - a[2] * b[2],
+    ]
+}
+
+/// Rotate `v` by the unit quaternion `q`
+fn quat_rotate(q: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    // v + 2w(u x v) + 2u x (u x v), with u the vector part
+    let u = [q[0], q[1], q[2]];
+    let cross = |a: [f32; 3], b: [f32; 3]| [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+    let uv = cross(u, v);
+    let uuv = cross(u, uv);
+    [
+        v[0] + 2.0 * (q[3] * uv[0] + uuv[0]),
+        v[1] + 2.0 * (q[3] * uv[1] + uuv[1]),
+        v[2] + 2.0 * (q[3] * uv[2] + uuv[2]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starbreaker_parsers::cgf::Vertex;
+    use tempfile::TempDir;
+
+    fn make_two_channel_mesh() -> starbreaker_parsers::cgf::Mesh {
+        let mut mesh = starbreaker_parsers::cgf::Mesh::new("lightmapped");
+        mesh.vertices = vec![
+            Vertex::new([0.0, 0.0, 0.0]),
+            Vertex::new([1.0, 0.0, 0.0]),
+            Vertex::new([0.0, 1.0, 0.0]),
+        ];
+        for (i, vertex) in mesh.vertices.iter_mut().enumerate() {
+            vertex.uv.push([i as f32 * 0.5, 1.0]);
+        }
+        mesh.faces = vec![starbreaker_parsers::cgf::Face::new(0, 1, 2)];
+        mesh
+    }
+
+    #[test]
+    fn test_export_all_uv_channels() {
+        let mut exporter = GltfExporter::new(GltfExportOptions {
+            export_all_uv_channels: true,
+            ..Default::default()
+        });
+
+        let gltf = exporter.build_gltf_from_mesh(&make_two_channel_mesh()).unwrap();
+        let attributes = &gltf.meshes[0].primitives[0].attributes;
+
+        let tex0 = attributes["TEXCOORD_0"];
+        let tex1 = attributes["TEXCOORD_1"];
+        assert_ne!(tex0, tex1);
+        assert_eq!(gltf.accessors[tex1].accessor_type, "VEC2");
+        assert_eq!(gltf.accessors[tex1].count, 3);
+        assert!(exporter.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_float_decimals_quantizes_positions() {
+        let mut mesh = make_two_channel_mesh();
+        mesh.vertices[1].position[0] = 0.1 + 0.2;
+
+        let mut exporter = GltfExporter::new(GltfExportOptions {
+            float_decimals: Some(3),
+            ..Default::default()
+        });
+        let gltf = exporter.build_gltf_from_mesh(&mesh).unwrap();
+
+        let x = f32::from_le_bytes(exporter.binary_data[12..16].try_into().unwrap());
+        assert_eq!(x, 0.3);
+        assert_eq!(gltf.accessors[0].max.as_ref().unwrap()[0], 0.3);
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_scale_shrinks_positions_and_translations() {
+        let mut mesh = make_two_channel_mesh();
+        mesh.vertices[1].position = [250.0, -40.0, 8.0];
+
+        let mut exporter = GltfExporter::new(GltfExportOptions {
+            scale: 0.01,
+            ..Default::default()
+        });
+        let gltf = exporter.build_gltf_from_mesh(&mesh).unwrap();
+
+        let read = |at: usize| f32::from_le_bytes(exporter.binary_data[at..at + 4].try_into().unwrap());
+        assert_close(&[read(12), read(16), read(20)], &[2.5, -0.4, 0.08]);
+        assert_close(gltf.accessors[0].max.as_ref().unwrap(), &[2.5, 0.01, 0.08]);
+        assert_close(gltf.accessors[0].min.as_ref().unwrap(), &[0.0, -0.4, 0.0]);
+
+        let mut model = starbreaker_parsers::cgf::CgfModel::new(starbreaker_parsers::cgf::CgfVersion::Ivo(1));
+        model.meshes.push(mesh);
+        model.nodes = vec![make_node(1, 0, "hull", Some(0))];
+        let gltf = exporter.build_gltf_from_model(&model).unwrap();
+        assert_close(&gltf.nodes[0].translation.unwrap(), &[0.0, 0.02, 0.0]);
+        assert_eq!(gltf.nodes[0].scale, Some([1.0, 1.0, 1.0]));
+
+        let options = GltfExportOptions::default().with_units_per_meter(Some(100.0));
+        assert_eq!(options.scale, 100.0);
+        assert_eq!(options.with_units_per_meter(Some(f32::NAN)).scale, 100.0);
+    }
+
+    #[test]
+    fn test_max_buffer_size_splits_buffers() {
+        // Positions and normals (36 bytes each) fill the first buffer; UVs
+        // and indices spill into a second one
+        let mut exporter = GltfExporter::new(GltfExportOptions {
+            max_buffer_size: 72,
+            uv_channel: 1,
+            ..Default::default()
+        });
+        let mut gltf = exporter.build_gltf_from_mesh(&make_two_channel_mesh()).unwrap();
+
+        let byte_lengths: Vec<usize> = gltf.buffers.iter().map(|b| b.byte_length).collect();
+        assert_eq!(byte_lengths, vec![72, 32]);
+
+        let attributes = &gltf.meshes[0].primitives[0].attributes;
+        let view_of = |accessor: usize| &gltf.buffer_views[gltf.accessors[accessor].buffer_view.unwrap()];
+        assert_eq!(view_of(attributes["POSITION"]).buffer, 0);
+        assert_eq!(view_of(attributes["NORMAL"]).byte_offset, Some(36));
+        let uv_accessor = &gltf.accessors[attributes["TEXCOORD_0"]];
+        let uv_view = view_of(attributes["TEXCOORD_0"]);
+        assert_eq!((uv_view.buffer, uv_view.byte_offset), (1, Some(0)));
+        let index_view = view_of(gltf.meshes[0].primitives[0].indices.unwrap());
+        assert_eq!((index_view.buffer, index_view.byte_offset, index_view.byte_length), (1, Some(24), 6));
+
+        // Second vertex's U from UV channel 1, read through the accessor and
+        // its view; the second buffer is still the exporter's open one
+        let at = uv_view.byte_offset.unwrap_or(0) + uv_accessor.byte_offset.unwrap_or(0) + 8;
+        let u = f32::from_le_bytes(exporter.binary_data[at..at + 4].try_into().unwrap());
+        assert_eq!(u, 0.5);
+
+        let dir = TempDir::new().unwrap();
+        exporter.write_separate_files(&mut gltf, &dir.path().join("ship.gltf")).unwrap();
+        assert_eq!(gltf.buffers[0].uri.as_deref(), Some("ship.bin"));
+        assert_eq!(gltf.buffers[1].uri.as_deref(), Some("ship_1.bin"));
+        assert_eq!(std::fs::read(dir.path().join("ship_1.bin")).unwrap().len(), 32);
+    }
+
+    #[test]
+    fn test_vertex_colors_exported_as_color_0() {
+        let mut mesh = make_two_channel_mesh();
+        mesh.vertices[0].color = Some([255, 0, 0, 255]);
+
+        // Colors are opt-in
+        let gltf = GltfExporter::new(GltfExportOptions::default()).build_gltf_from_mesh(&mesh).unwrap();
+        assert!(!gltf.meshes[0].primitives[0].attributes.contains_key("COLOR_0"));
+
+        let mut exporter = GltfExporter::new(GltfExportOptions {
+            export_colors: true,
+            ..Default::default()
+        });
+        let gltf = exporter.build_gltf_from_mesh(&mesh).unwrap();
+
+        let colors = gltf.meshes[0].primitives[0].attributes["COLOR_0"];
+        assert_eq!(gltf.accessors[colors].accessor_type, "VEC4");
+        assert_eq!(gltf.accessors[colors].count, 3);
+
+        let without = exporter.build_gltf_from_mesh(&make_two_channel_mesh()).unwrap();
+        assert!(!without.meshes[0].primitives[0].attributes.contains_key("COLOR_0"));
+    }
+
+    fn make_node(id: u32, parent_id: u32, name: &str, mesh_index: Option<usize>) -> starbreaker_parsers::cgf::Node {
+        starbreaker_parsers::cgf::Node {
+            name: name.to_string(),
+            id,
+            parent_id,
+            transform: [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]],
+            position: [0.0, 2.0, 0.0],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0, 1.0, 1.0],
+            mesh_index,
+            material_index: None,
+            properties: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_helper_nodes_survive_model_export() {
+        let mut model = starbreaker_parsers::cgf::CgfModel::new(starbreaker_parsers::cgf::CgfVersion::Ivo(1));
+        model.meshes.push(make_two_channel_mesh());
+        model.nodes = vec![
+            make_node(1, 0, "hull", Some(0)),
+            make_node(2, 1, "hardpoint_weapon_left", None),
+            make_node(3, 1, "$locator_cockpit", None),
+        ];
+        assert_eq!(model.helpers().len(), 2);
+
+        let mut exporter = GltfExporter::new(GltfExportOptions::default());
+        let gltf = exporter.build_gltf_from_model(&model).unwrap();
+
+        assert_eq!(gltf.nodes.len(), 3);
+        assert_eq!(gltf.scenes[0].nodes, vec![0]);
+        assert_eq!(gltf.nodes[0].children, vec![1, 2]);
+
+        let hardpoint = &gltf.nodes[1];
+        assert_eq!(hardpoint.name.as_deref(), Some("hardpoint_weapon_left"));
+        assert!(hardpoint.mesh.is_none());
+        assert_eq!(hardpoint.translation, Some([0.0, 2.0, 0.0]));
+        assert_eq!(hardpoint.extras, Some(serde_json::json!({ "hardpoint": true })));
+
+        let locator = &gltf.nodes[2];
+        assert_eq!(locator.name.as_deref(), Some("$locator_cockpit"));
+        assert!(locator.extras.is_none());
+    }
+
+    /// Column-major 4x4 matrix of a node's TRS
+    fn trs_matrix(node: &Node) -> [[f32; 4]; 4] {
+        let [x, y, z, w] = node.rotation.unwrap();
+        let s = node.scale.unwrap();
+        let t = node.translation.unwrap();
+        let r = [
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y + z * w), 2.0 * (x * z - y * w)],
+            [2.0 * (x * y - z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z + x * w)],
+            [2.0 * (x * z + y * w), 2.0 * (y * z - x * w), 1.0 - 2.0 * (x * x + y * y)],
+        ];
+        let mut m = [[0.0; 4]; 4];
+        for col in 0..3 {
+            for row in 0..3 {
+                m[col][row] = r[col][row] * s[col];
+            }
+        }
+        m[3] = [t[0], t[1], t[2], 1.0];
+        m
+    }
+
+    fn mat_mul(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
+        let mut m = [[0.0; 4]; 4];
+        for col in 0..4 {
+            for row in 0..4 {
+                m[col][row] = (0..4).map(|k| a[k][row] * b[col][k]).sum();
+            }
+        }
+        m
+    }
+
+    #[test]
+    fn test_collapse_empty_node_chain() {
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let mut root = make_node(1, 0, "root", None);
+        root.rotation = [0.0, 0.0, half, half];
+        root.scale = [2.0, 2.0, 2.0];
+        let mut middle = make_node(2, 1, "middle", None);
+        middle.position = [1.0, 0.0, 3.0];
+        middle.rotation = [half, 0.0, 0.0, half];
+        let mut leaf = make_node(3, 2, "leaf", None);
+        leaf.scale = [1.0, 0.5, 4.0];
+
+        let mut model = starbreaker_parsers::cgf::CgfModel::new(starbreaker_parsers::cgf::CgfVersion::Ivo(1));
+        model.nodes = vec![root, middle, leaf];
+
+        let mut exporter = GltfExporter::new(GltfExportOptions::default());
+        let full = exporter.build_gltf_from_model(&model).unwrap();
+        assert_eq!(full.nodes.len(), 3);
+        let expected = mat_mul(mat_mul(trs_matrix(&full.nodes[0]), trs_matrix(&full.nodes[1])), trs_matrix(&full.nodes[2]));
+
+        let mut exporter = GltfExporter::new(GltfExportOptions {
+            collapse_empty_nodes: true,
+            ..Default::default()
+        });
+        let collapsed = exporter.build_gltf_from_model(&model).unwrap();
+        assert_eq!(collapsed.nodes.len(), 1);
+        assert_eq!(collapsed.scenes[0].nodes, vec![0]);
+        assert_eq!(collapsed.nodes[0].name.as_deref(), Some("root"));
+        assert!(collapsed.nodes[0].children.is_empty());
+
+        let actual = trs_matrix(&collapsed.nodes[0]);
+        for col in 0..4 {
+            for row in 0..4 {
+                assert!((actual[col][row] - expected[col][row]).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+            }
+        }
+
+        // Hardpoints and mesh nodes stay put
+        model.meshes.push(make_two_channel_mesh());
+        model.nodes[1].name = "hardpoint_gun".to_string();
+        model.nodes[2].mesh_index = Some(0);
+        let kept = exporter.build_gltf_from_model(&model).unwrap();
+        assert_eq!(kept.nodes.len(), 3);
+        assert_eq!(kept.nodes[1].children, vec![2]);
+    }
+
+    #[test]
+    fn test_out_of_range_index_is_rejected() {
+        let mut mesh = make_two_channel_mesh();
+        mesh.faces.push(starbreaker_parsers::cgf::Face::new(0, 1, 9));
+
+        let mut exporter = GltfExporter::new(GltfExportOptions::default());
+        assert!(matches!(exporter.build_gltf_from_mesh(&mesh), Err(GltfExportError::InvalidMesh(_))));
+    }
+
+    #[test]
+    fn test_bad_bone_index_clamped_or_rejected() {
+        use starbreaker_parsers::cgf::{Bone, CgfModel, CgfVersion, Skeleton};
+
+        let mut skeleton = Skeleton::new();
+        skeleton.add_bone(Bone::new("root"));
+
+        let mut mesh = make_two_channel_mesh();
+        for vertex in &mut mesh.vertices {
+            vertex.bone_indices = Some([0, 0, 0, 0]);
+            vertex.bone_weights = Some([1.0, 0.0, 0.0, 0.0]);
+        }
+        mesh.vertices[1].bone_indices = Some([3, 0, 0, 0]);
+
+        let mut model = CgfModel::new(CgfVersion::Ivo(1));
+        model.meshes.push(mesh);
+        model.skeleton = Some(skeleton);
+
+        let mut exporter = GltfExporter::new(GltfExportOptions::default());
+        assert!(exporter.build_gltf_from_model(&model).is_ok());
+        assert_eq!(exporter.warnings().len(), 1);
+        assert!(exporter.warnings()[0].contains("bone index 3"));
+
+        let mut strict = GltfExporter::new(GltfExportOptions {
+            clamp_bone_indices: false,
+            ..Default::default()
+        });
+        assert!(matches!(
+            strict.build_gltf_from_model(&model),
+            Err(GltfExportError::InvalidSkin(errors)) if errors.len() == 1
+        ));
+
+        // An empty problem list still formats
+        assert_eq!(GltfExportError::InvalidSkin(Vec::new()).to_string(), "Invalid skin: 0 problem(s), first: none");
+    }
+
+    #[test]
+    fn test_emissive_material_export() {
+        use starbreaker_parsers::cgf::{CgfModel, CgfVersion, MaterialRef, MaterialTextures, ShaderParam};
+
+        let mut params = HashMap::new();
+        params.insert("Emissive".to_string(), ShaderParam::Float3([1.0, 0.5, 0.0]));
+        params.insert("GlowAmount".to_string(), ShaderParam::Float(4.0));
+
+        let mut model = CgfModel::new(CgfVersion::Ivo(1));
+        model.meshes.push(make_two_channel_mesh());
+        model.materials.push(MaterialRef {
+            name: "screen_glow".to_string(),
+            index: 0,
+            shader: "Illum".to_string(),
+            textures: MaterialTextures {
+                emissive: Some("Textures\\screens\\glow_emit.dds".to_string()),
+                ..Default::default()
+            },
+            params,
+            sub_materials: vec![],
+        });
+
+        let mut exporter = GltfExporter::new(GltfExportOptions::default());
+        let gltf = exporter.build_gltf_from_model(&model).unwrap();
+        let json = serde_json::to_value(&gltf).unwrap();
+
+        let material = &json["materials"][0];
+        assert_eq!(material["emissiveFactor"], serde_json::json!([1.0, 0.5, 0.0]));
+        assert_eq!(material["emissiveTexture"]["index"], 0);
+        assert_eq!(material["extensions"]["KHR_materials_emissive_strength"]["emissiveStrength"], 4.0);
+        assert_eq!(json["extensionsUsed"], serde_json::json!(["KHR_materials_emissive_strength"]));
+        assert_eq!(json["images"][0]["uri"], "Textures/screens/glow_emit.png");
+        assert_eq!(json["meshes"][0]["primitives"][0]["material"], 0);
+
+        // Dim glows scale the factor instead of needing the extension
+        model.materials[0].params.insert("GlowAmount".to_string(), ShaderParam::Float(0.5));
+        let mut dim = GltfExporter::new(GltfExportOptions::default());
+        let json = serde_json::to_value(dim.build_gltf_from_model(&model).unwrap()).unwrap();
+        assert_eq!(json["materials"][0]["emissiveFactor"], serde_json::json!([0.5, 0.25, 0.0]));
+        assert!(json["materials"][0].get("extensions").is_none());
+        assert!(json.get("extensionsUsed").is_none());
+
+        // Disabled: the material is kept, without its emissive parts
+        let mut plain = GltfExporter::new(GltfExportOptions {
+            export_emissive: false,
+            ..Default::default()
+        });
+        let json = serde_json::to_value(plain.build_gltf_from_model(&model).unwrap()).unwrap();
+        assert_eq!(json["materials"][0]["name"], "screen_glow");
+        assert!(json["materials"][0].get("emissiveFactor").is_none());
+        assert!(json["materials"][0].get("emissiveTexture").is_none());
+        assert!(json.get("images").is_none());
+        assert!(json.get("extensionsUsed").is_none());
+        assert_eq!(json["meshes"][0]["primitives"][0]["material"], 0);
+
+        // A material id with no matching material leaves the primitive without one
+        model.materials[0].index = 7;
+        let json = serde_json::to_value(exporter.build_gltf_from_model(&model).unwrap()).unwrap();
+        assert!(json["meshes"][0]["primitives"][0].get("material").is_none());
+    }
+
+    #[test]
+    fn test_uv_channel_out_of_range_falls_back() {
+        let mut exporter = GltfExporter::new(GltfExportOptions {
+            uv_channel: 5,
+            ..Default::default()
+        });
+
+        let gltf = exporter.build_gltf_from_mesh(&make_two_channel_mesh()).unwrap();
+        let attributes = &gltf.meshes[0].primitives[0].attributes;
+
+        assert!(attributes.contains_key("TEXCOORD_0"));
+        assert!(!attributes.contains_key("TEXCOORD_1"));
+        assert_eq!(exporter.warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_position_bounds_skip_non_finite() {
+        let vertices = vec![
+            Vertex::new([-1.0, 0.0, 2.0]),
+            Vertex::new([f32::NAN, 5.0, f32::INFINITY]),
+            Vertex::new([3.0, -4.0, 1.0]),
+        ];
+
+        let (min, max) = position_bounds(&vertices);
+        assert_eq!(min, [-1.0, -4.0, 1.0]);
+        assert_eq!(max, [3.0, 5.0, 2.0]);
+
+        let (min, max) = position_bounds(&[Vertex::new([f32::NAN, 0.0, 0.0])]);
+        assert_eq!(min[0], 0.0);
+        assert_eq!(max[0], 0.0);
+    }
+}
@@ -3,8 +3,10 @@
 //! Exports CGF models to glTF 2.0 format (JSON + BIN)
 
 mod exporter;
+mod skeleton;
 
 pub use exporter::{GltfExporter, GltfExportOptions, GltfExportError};
+pub use skeleton::SkeletonGltfExt;
 
 use serde::{Deserialize, Serialize};
 
@@ -30,6 +32,12 @@ pub struct Gltf {
     pub buffers: Vec<Buffer>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub skins: Vec<Skin>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub images: Vec<Image>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub textures: Vec<Texture>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default, rename = "extensionsUsed")]
+    pub extensions_used: Vec<String>,
 }
 
 /// glTF asset metadata
@@ -65,6 +73,8 @@ pub struct Node {
     pub scale: Option<[f32; 3]>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub children: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extras: Option<serde_json::Value>,
 }
 
 /// glTF mesh
@@ -94,8 +104,54 @@ pub struct Material {
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "pbrMetallicRoughness")]
     pub pbr_metallic_roughness: Option<PbrMetallicRoughness>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "emissiveFactor")]
+    pub emissive_factor: Option<[f32; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "emissiveTexture")]
+    pub emissive_texture: Option<TextureInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<MaterialExtensions>,
 }
 
+/// Extensions attached to a material
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaterialExtensions {
+    #[serde(skip_serializing_if = "Option::is_none", rename = "KHR_materials_emissive_strength")]
+    pub emissive_strength: Option<EmissiveStrength>,
+}
+
+/// `KHR_materials_emissive_strength` payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmissiveStrength {
+    #[serde(rename = "emissiveStrength")]
+    pub emissive_strength: f32,
+}
+
+/// Reference from a material slot to a texture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureInfo {
+    pub index: usize,
+}
+
+/// glTF texture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Texture {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub source: usize,
+}
+
+/// glTF image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Image {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+/// Name of the emissive strength extension
+pub const KHR_MATERIALS_EMISSIVE_STRENGTH: &str = "KHR_materials_emissive_strength";
+
 /// PBR metallic roughness material
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PbrMetallicRoughness {
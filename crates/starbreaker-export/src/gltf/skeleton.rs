@@ -0,0 +1,146 @@
+//! Skeleton to glTF joint node conversion
+
+use super::Node;
+use starbreaker_parsers::cgf::{matrix_to_quaternion, Skeleton};
+
+/// glTF conversion for CGF skeletons
+pub trait SkeletonGltfExt {
+    /// Build one glTF node per bone
+    ///
+    /// Nodes are emitted depth-first from the root bones, so parents always
+    /// precede their children. The returned joint order maps each node index
+    /// to its bone index; inverse bind matrices must be written in this order
+    /// and JOINTS_0 values remapped from bone to joint index. Child indices
+    /// are relative to the first returned node, so offset them when appending
+    /// to a document that already has nodes.
+    fn to_gltf_nodes(&self) -> (Vec<Node>, Vec<usize>) {
+        self.to_gltf_nodes_scaled(1.0)
+    }
+
+    /// Like [`to_gltf_nodes`](Self::to_gltf_nodes), with bone translations
+    /// multiplied by `scale` to match `GltfExportOptions::scale`
+    fn to_gltf_nodes_scaled(&self, scale: f32) -> (Vec<Node>, Vec<usize>);
+}
+
+impl SkeletonGltfExt for Skeleton {
+    fn to_gltf_nodes_scaled(&self, unit_scale: f32) -> (Vec<Node>, Vec<usize>) {
+        let bone_count = self.bones.len();
+        let mut joints = Vec::with_capacity(bone_count);
+        let mut visited = vec![false; bone_count];
+
+        // Bones with a dangling parent index are treated as extra roots
+        let roots = self.root_bones.iter().copied()
+            .chain(0..bone_count)
+            .collect::<Vec<_>>();
+
+        for root in roots {
+            if visited[root] {
+                continue;
+            }
+
+            let mut stack = vec![root];
+            while let Some(bone_idx) = stack.pop() {
+                if std::mem::replace(&mut visited[bone_idx], true) {
+                    continue;
+                }
+                joints.push(bone_idx);
+
+                // Reverse so children come off the stack in ascending order
+                stack.extend(self.children(bone_idx).into_iter().rev());
+            }
+        }
+
+        let mut node_of_bone = vec![0usize; bone_count];
+        for (node_idx, &bone_idx) in joints.iter().enumerate() {
+            node_of_bone[bone_idx] = node_idx;
+        }
+
+        let nodes = joints.iter().map(|&bone_idx| {
+            let bone = &self.bones[bone_idx];
+            let (translation, rotation, scale) = decompose(bone.local_transform);
+
+            Node {
+                name: Some(bone.name.clone()),
+                mesh: None,
+                skin: None,
+                translation: Some(translation.map(|v| v * unit_scale)),
+                rotation: Some(rotation),
+                scale: Some(scale),
+                children: self.children(bone_idx)
+                    .into_iter()
+                    .map(|child| node_of_bone[child])
+                    .collect(),
+                extras: None,
+            }
+        }).collect();
+
+        (nodes, joints)
+    }
+}
+
+/// Split a row-major transform (translation in the last row) into TRS
+fn decompose(m: [[f32; 4]; 4]) -> ([f32; 3], [f32; 4], [f32; 3]) {
+    let translation = [m[3][0], m[3][1], m[3][2]];
+
+    let mut scale = [0.0f32; 3];
+    let mut rotation = m;
+    for (row, scale) in rotation.iter_mut().zip(&mut scale) {
+        *scale = (row[0] * row[0] + row[1] * row[1] + row[2] * row[2]).sqrt();
+        if *scale > 0.0 {
+            for value in &mut row[..3] {
+                *value /= *scale;
+            }
+        }
+    }
+
+    (translation, matrix_to_quaternion(rotation), scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starbreaker_parsers::cgf::Bone;
+
+    fn make_skeleton() -> Skeleton {
+        let mut skeleton = Skeleton::new();
+
+        let mut spine = Bone::new("spine");
+        spine.set_position([0.0, 1.0, 0.0]);
+        skeleton.add_bone(spine);
+
+        let mut left = Bone::new("arm_l");
+        left.parent_index = Some(2);
+        skeleton.add_bone(left);
+
+        let mut chest = Bone::new("chest");
+        chest.parent_index = Some(0);
+        skeleton.add_bone(chest);
+
+        skeleton
+    }
+
+    #[test]
+    fn test_to_gltf_nodes() {
+        let skeleton = make_skeleton();
+        let (nodes, joints) = skeleton.to_gltf_nodes();
+
+        // Depth-first: spine -> chest -> arm_l
+        assert_eq!(joints, vec![0, 2, 1]);
+        assert_eq!(nodes[0].name.as_deref(), Some("spine"));
+        assert_eq!(nodes[0].children, vec![1]);
+        assert_eq!(nodes[1].name.as_deref(), Some("chest"));
+        assert_eq!(nodes[1].children, vec![2]);
+        assert!(nodes[2].children.is_empty());
+
+        assert_eq!(nodes[0].translation, Some([0.0, 1.0, 0.0]));
+        assert_eq!(nodes[0].rotation, Some([0.0, 0.0, 0.0, 1.0]));
+        assert_eq!(nodes[0].scale, Some([1.0, 1.0, 1.0]));
+
+        // Stable across calls
+        assert_eq!(skeleton.to_gltf_nodes().1, joints);
+
+        let (scaled, _) = skeleton.to_gltf_nodes_scaled(100.0);
+        assert_eq!(scaled[0].translation, Some([0.0, 100.0, 0.0]));
+        assert_eq!(scaled[0].scale, Some([1.0, 1.0, 1.0]));
+    }
+}
@@ -0,0 +1,487 @@
+//! JSON import for DataCore records
+//!
+//! Reads the JSON written by [`JsonExporter`](super::JsonExporter) back into a
+//! [`DataCore`], so exported game data can be edited and re-packed.
+
+use base64::Engine;
+use serde_json::{Map, Value};
+use starbreaker_parsers::dcb::{
+    DataCore, DataCoreHeader, DataType, PropertyDef, Record, RecordRef, RecordValue, StringEncoding,
+    StringTable, StructDef,
+};
+use starbreaker_parsers::{ParseError, ParseResult};
+use std::collections::HashMap;
+use std::io::{BufRead, Read};
+
+/// Struct layout records are checked against
+#[derive(Debug, Clone)]
+struct Schema {
+    header: DataCoreHeader,
+    structs: Vec<StructDef>,
+    properties: Vec<PropertyDef>,
+}
+
+/// JSON DataCore importer, the inverse of [`JsonExporter`](super::JsonExporter)
+///
+/// Without a schema, value types are inferred from their JSON shape: integers
+/// become the narrowest of `Int32`/`UInt32`/`Int64`/`UInt64`, other numbers
+/// `Float`, and strings stay strings (so GUIDs and blobs can't be told apart
+/// from text). With a schema, every property is read as its declared
+/// [`DataType`] and unknown structs or properties are rejected.
+#[derive(Debug, Clone, Default)]
+pub struct JsonImporter {
+    schema: Option<Schema>,
+}
+
+impl JsonImporter {
+    /// Create an importer that infers value types
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an importer that checks records against `schema`'s structs
+    ///
+    /// The imported DataCore takes its header, structs and properties from
+    /// `schema`, so it can be written back out with [`DataCore::write`].
+    pub fn with_schema(schema: &DataCore) -> Self {
+        Self {
+            schema: Some(Schema {
+                header: schema.header.clone(),
+                structs: schema.structs.clone(),
+                properties: schema.properties.clone(),
+            }),
+        }
+    }
+
+    /// Read records grouped by struct, as written by
+    /// [`JsonExporter::export_datacore`](super::JsonExporter::export_datacore)
+    ///
+    /// Both the plain and the `include_metadata` layouts are accepted.
+    /// Records come back in id order.
+    pub fn import_datacore<R: Read>(&self, reader: R) -> ParseResult<DataCore> {
+        let root: Value = serde_json::from_reader(reader).map_err(json_error)?;
+
+        let (metadata, groups) = match (root.get("metadata"), root.get("structs")) {
+            (Some(metadata), Some(Value::Object(groups))) => (Some(metadata), groups),
+            _ => {
+                let groups = root.as_object().ok_or_else(|| {
+                    ParseError::InvalidStructure("Expected an object of struct groups".to_string())
+                })?;
+                (None, groups)
+            }
+        };
+
+        let mut records = Vec::new();
+        for (struct_name, group) in groups {
+            let group = group.as_array().ok_or_else(|| {
+                ParseError::InvalidStructure(format!("Struct group '{}' isn't an array", struct_name))
+            })?;
+            for record in group {
+                let record = self.import_record(record, Some(struct_name))
+                    .map_err(|e| e.with_context(format!("Struct {}", struct_name)))?;
+                records.push(record);
+            }
+        }
+        // Grouping by struct loses the original order; the ids keep it
+        records.sort_by_key(|r| r.id);
+
+        let version = metadata
+            .and_then(|m| m.get("version"))
+            .and_then(Value::as_u64)
+            .map(|v| v as u32);
+        Ok(self.build(records, version))
+    }
+
+    /// Read newline-delimited records, as written by
+    /// [`JsonExporter::export_datacore_ndjson`](super::JsonExporter::export_datacore_ndjson)
+    ///
+    /// Records are read one line at a time, so only the result grows with
+    /// the input. Blank lines are skipped.
+    pub fn import_datacore_ndjson<R: BufRead>(&self, reader: R) -> ParseResult<DataCore> {
+        let mut records = Vec::new();
+
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str::<Value>(&line)
+                .map_err(json_error)
+                .and_then(|value| {
+                    let struct_name = value.get("struct").and_then(Value::as_str);
+                    self.import_record(&value, struct_name)
+                })
+                .map_err(|e| e.with_context(format!("Line {}", line_no + 1)))?;
+            records.push(record);
+        }
+
+        Ok(self.build(records, None))
+    }
+
+    /// Rebuild one record from its [`Record::to_json`] form
+    fn import_record(&self, json: &Value, struct_name: Option<&str>) -> ParseResult<Record> {
+        let field = |name: &str| {
+            json.get(name).ok_or_else(|| ParseError::MissingField(format!("record.{}", name)))
+        };
+
+        let id = as_u32(field("id")?)?;
+        let struct_id = as_u32(field("struct_id")?)?;
+        let name = as_str(field("name")?)?.to_string();
+        let guid_text = as_str(field("guid")?)?;
+        let guid = u64::from_str_radix(guid_text, 16)
+            .map_err(|_| ParseError::InvalidStructure(format!("Bad record GUID '{}'", guid_text)))?;
+        let values_json = field("values")?.as_object().ok_or_else(|| {
+            ParseError::InvalidStructure(format!("Values of record {} aren't an object", id))
+        })?;
+
+        // Records the exporter couldn't name a struct for have nothing to check against
+        let known_struct = struct_name.filter(|name| !name.starts_with("Unknown_"));
+        let properties = match (&self.schema, known_struct) {
+            (Some(schema), Some(struct_name)) => {
+                // Struct names aren't unique, so go by id and check the name matches
+                let struct_def = schema.structs.get(struct_id as usize).ok_or_else(|| {
+                    ParseError::InvalidStructure(format!("Record {} has struct_id {}, which isn't in the schema", id, struct_id))
+                })?;
+                if struct_def.name != struct_name {
+                    return Err(ParseError::InvalidStructure(format!(
+                        "Record {} has struct_id {} ('{}') but is listed under '{}'",
+                        id, struct_id, struct_def.name, struct_name
+                    )));
+                }
+                let properties: Vec<&PropertyDef> = struct_def.property_indices()
+                    .filter_map(|i| schema.properties.get(i))
+                    .collect();
+                Some(properties)
+            }
+            _ => None,
+        };
+
+        let mut values = HashMap::with_capacity(values_json.len());
+        for (prop_name, value) in values_json {
+            let value = match &properties {
+                Some(properties) => properties.iter()
+                    .find(|p| &p.name == prop_name)
+                    .ok_or_else(|| ParseError::InvalidStructure("Property isn't part of the struct".to_string()))
+                    .and_then(|prop| typed_value(value, &prop.data_type)),
+                None => inferred_value(value),
+            }
+            .map_err(|e| e.with_context(format!("Record {} ({}).{}", id, name, prop_name)))?;
+            values.insert(prop_name.clone(), value);
+        }
+
+        Ok(Record { id, struct_id, name, guid, values })
+    }
+
+    /// Assemble the DataCore, taking its tables from the schema if there is one
+    fn build(&self, records: Vec<Record>, version: Option<u32>) -> DataCore {
+        let (mut header, structs, properties) = match &self.schema {
+            Some(schema) => (schema.header.clone(), schema.structs.clone(), schema.properties.clone()),
+            None => {
+                let header = DataCoreHeader {
+                    version: version.unwrap_or(0),
+                    struct_count: 0,
+                    property_count: 0,
+                    record_count: 0,
+                    string_offset: 0,
+                    struct_offset: 0,
+                    property_offset: 0,
+                    record_offset: 0,
+                    value_offset: None,
+                    enum_offset: None,
+                    string_encoding: StringEncoding::Utf8,
+                };
+                (header, Vec::new(), Vec::new())
+            }
+        };
+        header.record_count = records.len() as u32;
+
+        DataCore::from_parts(header, StringTable::default(), structs, properties, records)
+    }
+}
+
+/// Read a value as `data_type`, undoing [`RecordValue::to_json`]
+fn typed_value(value: &Value, data_type: &DataType) -> ParseResult<RecordValue> {
+    let mismatch = || {
+        ParseError::InvalidStructure(format!("{} doesn't fit a {} property", value, data_type.type_name()))
+    };
+    let int = || value.as_i64().ok_or_else(mismatch);
+    let uint = || value.as_u64().ok_or_else(mismatch);
+    let float = || value.as_f64().ok_or_else(mismatch);
+    let text = || value.as_str().ok_or_else(mismatch);
+
+    Ok(match data_type {
+        DataType::Boolean => RecordValue::Boolean(value.as_bool().ok_or_else(mismatch)?),
+        // Narrow integers widen to 32 bits like the parser does, but must still fit
+        DataType::Int8 => RecordValue::Int32(i8::try_from(int()?).map_err(|_| mismatch())?.into()),
+        DataType::Int16 => RecordValue::Int32(i16::try_from(int()?).map_err(|_| mismatch())?.into()),
+        DataType::Int32 => RecordValue::Int32(i32::try_from(int()?).map_err(|_| mismatch())?),
+        DataType::Int64 => RecordValue::Int64(int()?),
+        DataType::UInt8 => RecordValue::UInt32(u8::try_from(uint()?).map_err(|_| mismatch())?.into()),
+        DataType::UInt16 => RecordValue::UInt32(u16::try_from(uint()?).map_err(|_| mismatch())?.into()),
+        DataType::UInt32 => RecordValue::UInt32(u32::try_from(uint()?).map_err(|_| mismatch())?),
+        DataType::UInt64 => RecordValue::UInt64(uint()?),
+        DataType::Float => RecordValue::Float(float()? as f32),
+        DataType::Double => RecordValue::Double(float()?),
+        DataType::Enum => RecordValue::Enum(u32::try_from(uint()?).map_err(|_| mismatch())?),
+        DataType::String => RecordValue::String(text()?.to_string()),
+        DataType::Guid => RecordValue::Guid(parse_guid(text()?).ok_or_else(mismatch)?),
+        DataType::Blob => RecordValue::Bytes(
+            base64::engine::general_purpose::STANDARD.decode(text()?).map_err(|_| mismatch())?,
+        ),
+        DataType::Array(element) => {
+            // Empty arrays come as an object naming the element type
+            let items = match value {
+                Value::Object(map) if matches!(object_value(map)?, RecordValue::Array { .. }) => Vec::new(),
+                _ => value.as_array()
+                    .ok_or_else(mismatch)?
+                    .iter()
+                    .map(|item| typed_value(item, element))
+                    .collect::<ParseResult<_>>()?,
+            };
+            RecordValue::array((**element).clone(), items)
+        }
+        DataType::Reference | DataType::Vec3 | DataType::Vec4 | DataType::LocaleString | DataType::Unknown(_) => {
+            let parsed = object_value(value.as_object().ok_or_else(mismatch)?)?;
+            let fits = matches!(
+                (data_type, &parsed),
+                (DataType::Reference, RecordValue::Reference(_))
+                    | (DataType::Vec3, RecordValue::Vec3(_))
+                    | (DataType::Vec4, RecordValue::Vec4(_))
+                    | (DataType::LocaleString, RecordValue::LocaleString { .. })
+                    | (DataType::Unknown(_), RecordValue::Unknown(_))
+            );
+            if !fits {
+                return Err(mismatch());
+            }
+            parsed
+        }
+    })
+}
+
+/// Guess a value's type from its JSON shape
+fn inferred_value(value: &Value) -> ParseResult<RecordValue> {
+    Ok(match value {
+        Value::Bool(v) => RecordValue::Boolean(*v),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(v), _) if i32::try_from(v).is_ok() => RecordValue::Int32(v as i32),
+            (Some(v), _) if u32::try_from(v).is_ok() => RecordValue::UInt32(v as u32),
+            (Some(v), _) => RecordValue::Int64(v),
+            (None, Some(v)) => RecordValue::UInt64(v),
+            (None, None) => RecordValue::Float(n.as_f64().unwrap_or_default() as f32),
+        },
+        Value::String(s) => RecordValue::String(s.clone()),
+        Value::Array(items) => {
+            let items: Vec<RecordValue> = items.iter().map(inferred_value).collect::<ParseResult<_>>()?;
+            let elem_type = items.first()
+                .and_then(RecordValue::data_type)
+                .unwrap_or(DataType::Unknown(0));
+            RecordValue::array(elem_type, items)
+        }
+        Value::Object(map) => object_value(map)?,
+        Value::Null => return Err(ParseError::InvalidStructure("Unexpected null value".to_string())),
+    })
+}
+
+/// Recognise the object forms the exporter writes for compound values;
+/// any other object is an inline struct
+fn object_value(map: &Map<String, Value>) -> ParseResult<RecordValue> {
+    let has_keys = |keys: &[&str]| map.len() == keys.len() && keys.iter().all(|k| map.contains_key(*k));
+    let float = |key: &str| {
+        map[key].as_f64()
+            .map(|v| v as f32)
+            .ok_or_else(|| ParseError::InvalidStructure(format!("Component '{}' isn't a number", key)))
+    };
+
+    if has_keys(&["record_id", "struct_id"]) {
+        Ok(RecordValue::Reference(RecordRef {
+            record_id: as_u32(&map["record_id"])?,
+            struct_id: as_u32(&map["struct_id"])?,
+        }))
+    } else if has_keys(&["x", "y", "z"]) {
+        Ok(RecordValue::Vec3([float("x")?, float("y")?, float("z")?]))
+    } else if has_keys(&["x", "y", "z", "w"]) {
+        Ok(RecordValue::Vec4([float("x")?, float("y")?, float("z")?, float("w")?]))
+    } else if has_keys(&["key", "value"]) {
+        Ok(RecordValue::LocaleString {
+            key: as_str(&map["key"])?.to_string(),
+            value: as_str(&map["value"])?.to_string(),
+        })
+    } else if has_keys(&["element_type", "items"]) && map["items"].as_array().is_some_and(|items| items.is_empty()) {
+        let elem_type = DataType::from_type_name(as_str(&map["element_type"])?).unwrap_or(DataType::Unknown(0));
+        Ok(RecordValue::array(elem_type, Vec::new()))
+    } else if has_keys(&["unknown_type"]) {
+        Ok(RecordValue::Unknown(as_u32(&map["unknown_type"])?))
+    } else {
+        map.iter()
+            .map(|(name, value)| {
+                let value = inferred_value(value).map_err(|e| e.with_context(format!("Field {}", name)))?;
+                Ok((name.clone(), value))
+            })
+            .collect::<ParseResult<_>>()
+            .map(RecordValue::Struct)
+    }
+}
+
+/// Parse the 32-digit hex form [`RecordValue::Guid`] is exported as
+fn parse_guid(text: &str) -> Option<[u8; 16]> {
+    if text.len() != 32 || !text.is_ascii() {
+        return None;
+    }
+    let mut guid = [0u8; 16];
+    for (i, byte) in guid.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(guid)
+}
+
+fn as_u32(value: &Value) -> ParseResult<u32> {
+    value.as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| ParseError::InvalidStructure(format!("{} isn't a u32", value)))
+}
+
+fn as_str(value: &Value) -> ParseResult<&str> {
+    value.as_str()
+        .ok_or_else(|| ParseError::InvalidStructure(format!("{} isn't a string", value)))
+}
+
+fn json_error(err: serde_json::Error) -> ParseError {
+    ParseError::InvalidStructure(format!("Invalid JSON: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{JsonExportOptions, JsonExporter};
+    use starbreaker_parsers::testutil::DataCoreBuilder;
+    use tempfile::TempDir;
+
+    fn make_datacore() -> DataCore {
+        let mut builder = DataCoreBuilder::new();
+        let ship = builder.add_struct("Ship", None, &[
+            ("mass", DataType::Double),
+            ("crew", DataType::UInt8),
+            ("label", DataType::String),
+            ("id", DataType::Guid),
+        ]);
+        let thruster = builder.add_struct("Thruster", None, &[
+            ("thrust", DataType::Float),
+            ("offset", DataType::Vec3),
+            ("ship", DataType::Reference),
+            ("tuning", DataType::Blob),
+        ]);
+
+        builder.add_record(ship, "Ship.Hauler", 0x1122_3344_5566_7788, [
+            ("mass", RecordValue::Double(125000.5)),
+            ("crew", RecordValue::UInt32(4)),
+            ("label", RecordValue::String("Hauler".to_string())),
+            ("id", RecordValue::Guid([0xAB; 16])),
+        ]);
+        builder.add_record(thruster, "Thruster.Main", 42, [
+            ("thrust", RecordValue::Float(9.81)),
+            ("offset", RecordValue::Vec3([1.0, -2.0, 0.5])),
+            ("ship", RecordValue::Reference(RecordRef { record_id: 0, struct_id: 0 })),
+            ("tuning", RecordValue::Bytes(vec![1, 2, 3])),
+        ]);
+
+        builder.build()
+    }
+
+    /// Export to a temp file and read the JSON back
+    fn export(datacore: &DataCore, options: JsonExportOptions) -> Vec<u8> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("Game.json");
+        JsonExporter::with_options(options).export_datacore(datacore, &path).unwrap();
+        std::fs::read(&path).unwrap()
+    }
+
+    #[test]
+    fn test_import_round_trip() {
+        let original = make_datacore();
+        let json = export(&original, JsonExportOptions::default());
+
+        let imported = JsonImporter::with_schema(&original).import_datacore(json.as_slice()).unwrap();
+        assert_eq!(imported.header.version, 5);
+        assert_eq!(imported.records.len(), 2);
+        for (before, after) in original.records.iter().zip(&imported.records) {
+            assert_eq!(before.to_json(), after.to_json());
+        }
+        assert!(matches!(imported.records[0].get("crew"), Some(RecordValue::UInt32(4))));
+        assert!(matches!(imported.records[0].get("id"), Some(RecordValue::Guid([0xAB, ..]))));
+        assert!(matches!(imported.records[1].get("tuning"), Some(RecordValue::Bytes(b)) if b == &[1, 2, 3]));
+        assert_eq!(imported.get_record(42).unwrap().name, "Thruster.Main");
+
+        // Typed values are exactly what the binary writer needs
+        let mut out = std::io::Cursor::new(Vec::new());
+        imported.write(&mut out).unwrap();
+    }
+
+    #[test]
+    fn test_import_infers_types_without_schema() {
+        let original = make_datacore();
+        let json = export(&original, JsonExportOptions { include_metadata: false, ..Default::default() });
+
+        let imported = JsonImporter::new().import_datacore(json.as_slice()).unwrap();
+        assert!(imported.structs.is_empty());
+        let ship = &imported.records[0];
+        assert!(matches!(ship.get("crew"), Some(RecordValue::Int32(4))));
+        assert!(matches!(ship.get("id"), Some(RecordValue::String(_))));
+        let thruster = &imported.records[1];
+        assert_eq!(thruster.get_vec3("offset"), Some([1.0, -2.0, 0.5]));
+        assert!(matches!(thruster.get("thrust"), Some(RecordValue::Float(v)) if *v == 9.81));
+        assert_eq!(thruster.get_reference("ship").map(|r| r.record_id), Some(0));
+    }
+
+    #[test]
+    fn test_import_ndjson() {
+        let original = make_datacore();
+        let mut ndjson = Vec::new();
+        JsonExporter::new().export_datacore_ndjson(&original, &mut ndjson).unwrap();
+        ndjson.extend_from_slice(b"\n");
+
+        let imported = JsonImporter::with_schema(&original).import_datacore_ndjson(ndjson.as_slice()).unwrap();
+        assert_eq!(imported.records.len(), 2);
+        assert_eq!(imported.records[1].to_json(), original.records[1].to_json());
+    }
+
+    #[test]
+    fn test_import_rejects_schema_mismatch() {
+        let original = make_datacore();
+        let importer = JsonImporter::with_schema(&original);
+
+        let unknown_property = br#"{"Ship": [{"id": 0, "struct_id": 0, "name": "Ship.A", "guid": "01",
+            "values": {"speed": 1.0}}]}"#;
+        assert!(importer.import_datacore(unknown_property.as_slice()).is_err());
+
+        let wrong_type = br#"{"Ship": [{"id": 0, "struct_id": 0, "name": "Ship.A", "guid": "01",
+            "values": {"crew": 300}}]}"#;
+        assert!(importer.import_datacore(wrong_type.as_slice()).is_err());
+
+        let unknown_struct = br#"{"Station": []}"#;
+        assert!(importer.import_datacore(unknown_struct.as_slice()).is_ok());
+        let unknown_struct = br#"{"Station": [{"id": 0, "struct_id": 0, "name": "S", "guid": "01", "values": {}}]}"#;
+        assert!(importer.import_datacore(unknown_struct.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_import_struct_sharing_a_name() {
+        let mut builder = DataCoreBuilder::new();
+        builder.add_struct("Ship", None, &[("mass", DataType::Double)]);
+        builder.add_struct("Ship", None, &[("hull", DataType::UInt32)]);
+        let schema = builder.build();
+        let importer = JsonImporter::with_schema(&schema);
+
+        let second = br#"{"Ship": [{"id": 0, "struct_id": 1, "name": "Ship.B", "guid": "01",
+            "values": {"hull": 3}}]}"#;
+        let imported = importer.import_datacore(second.as_slice()).unwrap();
+        assert!(matches!(imported.records[0].get("hull"), Some(RecordValue::UInt32(3))));
+
+        // Properties are still checked against the struct the id points at
+        let first_props = br#"{"Ship": [{"id": 0, "struct_id": 1, "name": "Ship.B", "guid": "01",
+            "values": {"mass": 1.0}}]}"#;
+        assert!(importer.import_datacore(first_props.as_slice()).is_err());
+
+        let out_of_range = br#"{"Ship": [{"id": 0, "struct_id": 2, "name": "Ship.C", "guid": "01", "values": {}}]}"#;
+        assert!(importer.import_datacore(out_of_range.as_slice()).is_err());
+    }
+}
@@ -1,16 +1,23 @@
 //! JSON data export for game assets
 //!
-//! Exports DCB records, CGF metadata, and P4K indices to JSON format.
+//! Exports DCB records, CGF metadata, and P4K indices to JSON format, and
+//! imports DCB records back from it.
 
-use starbreaker_parsers::dcb::DataCore;
+use starbreaker_parsers::dcb::{DataCore, LazyDataCore, Record, StructDef};
+use starbreaker_parsers::glob::glob_match_ignore_case;
 use starbreaker_parsers::p4k::P4kArchive;
 use serde_json::json;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::Path;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 use thiserror::Error;
 
+mod import;
+
+pub use import::JsonImporter;
+
 /// JSON export errors
 #[derive(Error, Debug)]
 pub enum JsonError {
@@ -19,6 +26,9 @@ pub enum JsonError {
     
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+
+    #[error("Parse error: {0}")]
+    Parse(#[from] starbreaker_parsers::ParseError),
     
     #[error("Export failed: {0}")]
     ExportFailed(String),
@@ -35,8 +45,52 @@ pub struct JsonExportOptions {
     /// Include metadata (file info, counts, etc.)
     pub include_metadata: bool,
     
-    /// Maximum nesting depth for arrays/objects
+    /// Maximum nesting depth for inline struct values
     pub max_depth: usize,
+
+    /// Round floats to this many decimal places (`None` keeps full precision)
+    pub float_decimals: Option<u8>,
+
+    /// Only export these record properties (`None` exports all)
+    ///
+    /// Names match case-insensitively; a trailing `*` matches by prefix.
+    pub include_props: Option<Vec<String>>,
+
+    /// Never export these record properties, same matching as `include_props`
+    pub exclude_props: Vec<String>,
+
+    /// Only export records of these structs (`None` exports all)
+    ///
+    /// Names match case-insensitively and may use `*` and `?` wildcards.
+    /// Other records are skipped before they are serialized.
+    pub struct_filter: Option<Vec<String>>,
+}
+
+impl JsonExportOptions {
+    /// Check whether a record property passes the include/exclude filters
+    pub fn includes_property(&self, name: &str) -> bool {
+        let matches = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => name.len() >= prefix.len()
+                && name.is_char_boundary(prefix.len())
+                && name[..prefix.len()].eq_ignore_ascii_case(prefix),
+            None => name.eq_ignore_ascii_case(pattern),
+        };
+
+        let included = self.include_props.as_ref()
+            .is_none_or(|include| include.iter().any(matches));
+        included && !self.exclude_props.iter().any(matches)
+    }
+
+    /// Check whether records of a struct pass `struct_filter`
+    pub fn includes_struct(&self, struct_name: &str) -> bool {
+        self.struct_filter.as_ref()
+            .is_none_or(|patterns| patterns.iter().any(|pattern| glob_match_ignore_case(pattern, struct_name)))
+    }
+
+    /// Check whether any property filter is configured
+    fn filters_properties(&self) -> bool {
+        self.include_props.is_some() || !self.exclude_props.is_empty()
+    }
 }
 
 impl Default for JsonExportOptions {
@@ -45,6 +99,10 @@ impl Default for JsonExportOptions {
             pretty: true,
             include_metadata: true,
             max_depth: 10,
+            float_decimals: None,
+            include_props: None,
+            exclude_props: Vec::new(),
+            struct_filter: None,
         }
     }
 }
@@ -74,13 +132,15 @@ impl JsonExporter {
         let mut by_struct: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
         
         for record in &datacore.records {
-            let struct_name = datacore.structs.get(record.struct_id as usize)
-                .map(|s| s.name.clone())
-                .unwrap_or_else(|| format!("Unknown_{:08X}", record.struct_id));
+            let struct_name = struct_name(&datacore.structs, record.struct_id);
+            if !self.options.includes_struct(&struct_name) {
+                continue;
+            }
             
-            let record_json = record.to_json();
+            let mut record_json = record.to_json_limited(self.options.max_depth);
+            self.filter_properties(&mut record_json);
             
-            by_struct.entry(struct_name)
+            by_struct.entry(struct_name.into_owned())
                 .or_insert_with(Vec::new)
                 .push(record_json);
         }
@@ -104,6 +164,105 @@ impl JsonExporter {
         Ok(())
     }
     
+    /// Stream DataCore records as newline-delimited JSON, one record per line
+    ///
+    /// Unlike [`export_datacore`](Self::export_datacore) nothing is grouped or
+    /// buffered: each record is serialized and written before the next is
+    /// touched, so the output side needs no more than one record's JSON at a
+    /// time. The records themselves are all in memory already; use
+    /// [`export_lazy_datacore_ndjson`](Self::export_lazy_datacore_ndjson) to
+    /// keep that flat too. `pretty` is ignored since NDJSON needs one object
+    /// per line. Returns the number of records written.
+    pub fn export_datacore_ndjson<W: Write>(&self, datacore: &DataCore, writer: W) -> JsonResult<usize> {
+        self.write_records_ndjson(&datacore.records, &datacore.structs, writer)
+    }
+
+    /// Stream a lazily parsed DataCore as NDJSON, loading one record at a time
+    ///
+    /// Records skipped by the struct filter are never loaded, and each
+    /// exported record's values are dropped again once written unless they
+    /// were already cached beforehand. Output matches
+    /// [`export_datacore_ndjson`](Self::export_datacore_ndjson).
+    pub fn export_lazy_datacore_ndjson<W: Write>(&self, datacore: &LazyDataCore, writer: W) -> JsonResult<usize> {
+        let mut writer = BufWriter::new(writer);
+        let mut count = 0;
+
+        for lazy in &datacore.records {
+            let struct_name = struct_name(&datacore.structs, lazy.struct_id);
+            if !self.options.includes_struct(&struct_name) {
+                continue;
+            }
+
+            let was_loaded = lazy.is_loaded();
+            let record = Record {
+                id: lazy.id,
+                struct_id: lazy.struct_id,
+                name: lazy.name.clone(),
+                guid: lazy.guid,
+                values: datacore.load_record(lazy)?,
+            };
+            if !was_loaded {
+                lazy.unload();
+            }
+
+            self.write_ndjson_line(&mut writer, &record, &struct_name)?;
+            count += 1;
+        }
+
+        writer.flush()?;
+        Ok(count)
+    }
+
+    /// Write records as NDJSON, tagging each with its struct name
+    fn write_records_ndjson<'a, W: Write>(
+        &self,
+        records: impl IntoIterator<Item = &'a Record>,
+        structs: &[StructDef],
+        writer: W,
+    ) -> JsonResult<usize> {
+        let mut writer = BufWriter::new(writer);
+        let mut count = 0;
+
+        for record in records {
+            let struct_name = struct_name(structs, record.struct_id);
+            if !self.options.includes_struct(&struct_name) {
+                continue;
+            }
+
+            self.write_ndjson_line(&mut writer, record, &struct_name)?;
+            count += 1;
+        }
+
+        writer.flush()?;
+        Ok(count)
+    }
+
+    /// Serialize one record as a single NDJSON line
+    fn write_ndjson_line<W: Write>(&self, writer: &mut W, record: &Record, struct_name: &str) -> JsonResult<()> {
+        let mut record_json = record.to_json_limited(self.options.max_depth);
+        self.filter_properties(&mut record_json);
+        if let Some(map) = record_json.as_object_mut() {
+            map.insert("struct".to_string(), json!(struct_name));
+        }
+        if let Some(decimals) = self.options.float_decimals {
+            round_json_floats(&mut record_json, decimals);
+        }
+
+        serde_json::to_writer(&mut *writer, &record_json)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Drop record values rejected by the include/exclude options
+    fn filter_properties(&self, record_json: &mut serde_json::Value) {
+        if !self.options.filters_properties() {
+            return;
+        }
+        if let Some(values) = record_json.get_mut("values").and_then(|v| v.as_object_mut()) {
+            values.retain(|name, _| self.options.includes_property(name));
+        }
+    }
+    
     /*
     /// Export CGF mesh metadata to JSON
     /// Includes vertex counts, material info, bounding boxes
@@ -151,7 +310,7 @@ impl JsonExporter {
     pub fn export_p4k_index(&self, archive: &P4kArchive, output_path: impl AsRef<Path>) -> JsonResult<()> {
         let mut entries = Vec::new();
         
-        for entry in &archive.entries {
+        for entry in archive.entries_iter() {
             entries.push(json!({
                 "path": entry.path,
                 "uncompressed_size": entry.uncompressed_size,
@@ -185,6 +344,17 @@ impl JsonExporter {
     fn write_json(&self, value: &serde_json::Value, output_path: impl AsRef<Path>) -> JsonResult<()> {
         let file = File::create(output_path)?;
         let writer = BufWriter::new(file);
+
+        let rounded;
+        let value = match self.options.float_decimals {
+            Some(decimals) => {
+                let mut copy = value.clone();
+                round_json_floats(&mut copy, decimals);
+                rounded = copy;
+                &rounded
+            }
+            None => value,
+        };
         
         if self.options.pretty {
             serde_json::to_writer_pretty(writer, value)?;
@@ -196,8 +366,242 @@ impl JsonExporter {
     }
 }
 
+/// Name of struct `struct_id`, or a placeholder naming the id if it's out of range
+///
+/// Borrowed from the struct table, so records the struct filter skips never
+/// copy their struct's name.
+fn struct_name(structs: &[StructDef], struct_id: u32) -> Cow<'_, str> {
+    structs.get(struct_id as usize)
+        .map(|s| Cow::Borrowed(s.name.as_str()))
+        .unwrap_or_else(|| Cow::Owned(format!("Unknown_{:08X}", struct_id)))
+}
+
+/// Round a float to `decimals` decimal places
+pub(crate) fn round_float(value: f64, decimals: u8) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    let rounded = (value * scale).round() / scale;
+    // Huge values overflow the scaled intermediate; leave those untouched
+    if rounded.is_finite() { rounded } else { value }
+}
+
+/// Round every non-integer number in a JSON tree, including inside arrays
+/// (Vec3/Vec4 values) and objects
+pub fn round_json_floats(value: &mut serde_json::Value, decimals: u8) {
+    match value {
+        serde_json::Value::Number(n) if n.is_f64() => {
+            if let Some(rounded) = n.as_f64()
+                .map(|v| round_float(v, decimals))
+                .and_then(serde_json::Number::from_f64)
+            {
+                *n = rounded;
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                round_json_floats(item, decimals);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                round_json_floats(item, decimals);
+            }
+        }
+        _ => {}
+    }
+}
+
 impl Default for JsonExporter {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_json_floats() {
+        let sum = 0.1f32 + 0.2f32;
+        let mut value = json!({
+            "scalar": sum,
+            "vec3": [sum, 1.0f32 / 3.0, 2.0],
+            "count": 7,
+        });
+
+        round_json_floats(&mut value, 3);
+
+        assert_eq!(value["scalar"].to_string(), "0.3");
+        assert_eq!(value["vec3"].to_string(), "[0.3,0.333,2.0]");
+        assert_eq!(value["count"].to_string(), "7");
+    }
+
+    #[test]
+    fn test_records_ndjson() {
+        use starbreaker_parsers::dcb::RecordValue;
+
+        let structs = vec![StructDef {
+            id: 0,
+            name: "Ship".to_string(),
+            parent_id: None,
+            property_start: 0,
+            property_count: 1,
+            size: 4,
+            flags: 0,
+        }];
+        let records: Vec<Record> = (0..3)
+            .map(|i| Record {
+                id: i,
+                struct_id: if i == 2 { 7 } else { 0 },
+                name: format!("record_{}", i),
+                guid: 0x1000 + i as u64,
+                values: HashMap::from([("mass".to_string(), RecordValue::Float(i as f32 * 1.5))]),
+            })
+            .collect();
+
+        let mut out = Vec::new();
+        let count = JsonExporter::new().write_records_ndjson(&records, &structs, &mut out).unwrap();
+        assert_eq!(count, 3);
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<serde_json::Value> = text.lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert!(text.ends_with('\n'));
+        assert_eq!(lines[0]["struct"], "Ship");
+        assert_eq!(lines[1]["name"], "record_1");
+        assert_eq!(lines[1]["values"]["mass"], 1.5);
+        assert_eq!(lines[2]["struct"], "Unknown_00000007");
+    }
+
+    #[test]
+    fn test_property_include_filter() {
+        use starbreaker_parsers::dcb::RecordValue;
+
+        let record = Record {
+            id: 0,
+            struct_id: 0,
+            name: "ship".to_string(),
+            guid: 0x1000,
+            values: HashMap::from([
+                ("Mass".to_string(), RecordValue::Float(100.0)),
+                ("shieldHealth".to_string(), RecordValue::Float(50.0)),
+                ("shieldRegen".to_string(), RecordValue::Float(5.0)),
+                ("description".to_string(), RecordValue::String("hauler".to_string())),
+            ]),
+        };
+
+        let exporter = JsonExporter::with_options(JsonExportOptions {
+            include_props: Some(vec!["mass".to_string(), "Shield*".to_string()]),
+            exclude_props: vec!["shieldregen".to_string()],
+            ..Default::default()
+        });
+
+        let mut out = Vec::new();
+        exporter.write_records_ndjson([&record], &[], &mut out).unwrap();
+        let line: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        let mut names: Vec<&str> = line["values"].as_object().unwrap().keys().map(String::as_str).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["Mass", "shieldHealth"]);
+    }
+
+    #[test]
+    fn test_struct_filter() {
+        use starbreaker_parsers::dcb::{DataType, RecordValue};
+        use starbreaker_parsers::testutil::DataCoreBuilder;
+
+        let mut builder = DataCoreBuilder::new();
+        let struct_ids: Vec<u32> = ["Ship", "Thruster", "ShipLoadout"].iter()
+            .map(|name| builder.add_struct(name, None, &[("mass", DataType::Float)]))
+            .collect();
+        for (i, &struct_index) in [0, 1, 0, 2].iter().enumerate() {
+            builder.add_record(struct_ids[struct_index], &format!("record_{}", i), 0x1000 + i as u64, [
+                ("mass", RecordValue::Float(1.0)),
+            ]);
+        }
+        let datacore = builder.build();
+
+        let exporter = JsonExporter::with_options(JsonExportOptions {
+            include_metadata: false,
+            struct_filter: Some(vec!["SHIP".to_string()]),
+            ..Default::default()
+        });
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("Game.json");
+        exporter.export_datacore(&datacore, &path).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+
+        let exported = json.as_object().unwrap();
+        assert_eq!(exported.keys().collect::<Vec<_>>(), vec!["Ship"]);
+        let names: Vec<&str> = exported["Ship"].as_array().unwrap().iter().map(|r| r["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["record_0", "record_2"]);
+
+        // Wildcards, and the streaming exporter skips the same way
+        let exporter = JsonExporter::with_options(JsonExportOptions {
+            struct_filter: Some(vec!["ship*".to_string(), "thr?ster".to_string()]),
+            ..Default::default()
+        });
+        let mut out = Vec::new();
+        assert_eq!(exporter.export_datacore_ndjson(&datacore, &mut out).unwrap(), 4);
+        let exporter = JsonExporter::with_options(JsonExportOptions {
+            struct_filter: Some(vec!["*Loadout".to_string()]),
+            ..Default::default()
+        });
+        let mut out = Vec::new();
+        assert_eq!(exporter.export_datacore_ndjson(&datacore, &mut out).unwrap(), 1);
+        assert!(String::from_utf8(out).unwrap().contains("\"struct\":\"ShipLoadout\""));
+    }
+
+    #[test]
+    fn test_lazy_ndjson_matches_eager() {
+        use starbreaker_parsers::dcb::{DataType, DcbParser, RecordValue};
+        use starbreaker_parsers::testutil::DataCoreBuilder;
+        use starbreaker_parsers::Parser;
+
+        let mut builder = DataCoreBuilder::new();
+        let ship = builder.add_struct("Ship", None, &[("mass", DataType::Float), ("crew", DataType::Int32)]);
+        let thruster = builder.add_struct("Thruster", None, &[("thrust", DataType::Float)]);
+        builder.add_record(ship, "Ship.Hauler", 1, [("mass", RecordValue::Float(1250.5)), ("crew", RecordValue::Int32(4))]);
+        builder.add_record(thruster, "Thruster.Main", 2, [("thrust", RecordValue::Float(9.81))]);
+        builder.add_record(ship, "Ship.Fighter", 3, [("mass", RecordValue::Float(80.25)), ("crew", RecordValue::Int32(1))]);
+
+        let datacore = builder.build();
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("Game.dcb");
+        let mut file = std::fs::File::create(&path).unwrap();
+        datacore.write(&mut file).unwrap();
+        drop(file);
+
+        let parser = DcbParser::new();
+        let eager = parser.parse_file(&path).unwrap();
+        let lazy = parser.parse_lazy(&path).unwrap();
+
+        let exporter = JsonExporter::with_options(JsonExportOptions {
+            struct_filter: Some(vec!["Ship".to_string()]),
+            ..Default::default()
+        });
+        let mut expected = Vec::new();
+        assert_eq!(exporter.export_datacore_ndjson(&eager, &mut expected).unwrap(), 2);
+        let mut out = Vec::new();
+        assert_eq!(exporter.export_lazy_datacore_ndjson(&lazy, &mut out).unwrap(), 2);
+        assert_eq!(out, expected);
+
+        // Nothing stays cached once written
+        assert!(lazy.records.iter().all(|r| !r.is_loaded()));
+    }
+
+    #[test]
+    fn test_parse_error_converts() {
+        use starbreaker_parsers::ParseError;
+        use std::error::Error;
+
+        let err = JsonError::from(ParseError::MissingField("record table".to_string()));
+        assert!(matches!(err, JsonError::Parse(ParseError::MissingField(_))));
+        assert!(err.source().is_some_and(|s| s.is::<ParseError>()));
+    }
+}
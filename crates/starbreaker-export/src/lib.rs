@@ -12,5 +12,5 @@ pub mod json;
 pub mod textures;
 
 pub use gltf::{GltfExporter, GltfExportOptions};
-pub use json::{JsonExporter, JsonExportOptions};
+pub use json::{JsonExporter, JsonExportOptions, JsonImporter};
 pub use textures::{TextureConverter, TextureConvertOptions, ImageFormat};
@@ -0,0 +1,157 @@
+//! Texture previews straight from a P4K archive
+//!
+//! Extracts a DDS entry (and any `.dds.N` split parts) into memory, decodes
+//! a preview-sized mip and optionally encodes it as PNG, without touching
+//! the disk.
+
+use std::io::{Cursor, Read, Seek};
+
+use image::{DynamicImage, ImageFormat as ImgFormat, RgbaImage};
+use starbreaker_parsers::dds::DdsCombiner;
+use starbreaker_parsers::p4k::{P4kArchive, P4kEntry, P4kParser};
+
+use crate::textures::{TextureConverter, TextureError, TextureResult};
+
+/// Decode a texture from `archive` and return a PNG no larger than `max_dim`
+///
+/// See [`preview_image_from_archive`] for how `path` is resolved.
+pub fn preview_texture_from_archive<R: Read + Seek>(
+    parser: &P4kParser,
+    archive: &P4kArchive,
+    reader: &mut R,
+    path: &str,
+    max_dim: u32,
+) -> TextureResult<Vec<u8>> {
+    let img = preview_image_from_archive(parser, archive, reader, path, max_dim)?;
+
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(img).write_to(&mut Cursor::new(&mut png), ImgFormat::Png)?;
+    Ok(png)
+}
+
+/// Decode a texture from `archive` into an RGBA image no larger than `max_dim`
+///
+/// `path` may name either the base `.dds` entry or one of its split parts;
+/// all parts present in the archive are combined before decoding.
+pub fn preview_image_from_archive<R: Read + Seek>(
+    parser: &P4kParser,
+    archive: &P4kArchive,
+    reader: &mut R,
+    path: &str,
+    max_dim: u32,
+) -> TextureResult<RgbaImage> {
+    let base_path = if DdsCombiner::is_split_file(path) {
+        DdsCombiner::get_base_path(path).to_string_lossy().into_owned()
+    } else {
+        path.to_string()
+    };
+
+    let base = archive
+        .get(&base_path)
+        .ok_or_else(|| TextureError::Parse(format!("Entry not found: {}", base_path)))?;
+
+    let mut parts = vec![read_entry(parser, reader, base)?];
+    for entry in split_entries(archive, &base_path) {
+        parts.push(read_entry(parser, reader, entry)?);
+    }
+
+    let texture = DdsCombiner::new().combine_from_bytes(&parts)?;
+    TextureConverter::new().preview(&texture, max_dim)
+}
+
+/// Split parts of `base_path` in combine order (`.1`, `.2`, `.3a`, `.3b`, ...)
+fn split_entries<'a>(archive: &'a P4kArchive, base_path: &str) -> Vec<&'a P4kEntry> {
+    let mut parts = Vec::new();
+
+    for i in 1..=99 {
+        if let Some(entry) = archive.get(&format!("{}.{}", base_path, i)) {
+            parts.push(entry);
+            continue;
+        }
+
+        if let Some(entry) = archive.get(&format!("{}.{}a", base_path, i)) {
+            parts.push(entry);
+            if let Some(b) = archive.get(&format!("{}.{}b", base_path, i)) {
+                parts.push(b);
+            }
+        }
+    }
+
+    parts
+}
+
+/// Decompress one entry into memory, checking its CRC
+fn read_entry<R: Read + Seek>(
+    parser: &P4kParser,
+    reader: &mut R,
+    entry: &P4kEntry,
+) -> TextureResult<Vec<u8>> {
+    let mut data = Vec::with_capacity(entry.uncompressed_size as usize);
+    let crc32 = parser.extract_entry_to(reader, entry, &mut data)?;
+
+    if crc32 != entry.crc32 {
+        return Err(TextureError::Parse(format!(
+            "CRC mismatch for {}: expected {:08x}, got {:08x}",
+            entry.path, entry.crc32, crc32
+        )));
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starbreaker_parsers::testutil::{bc3_texture, dds_file, stored_archive};
+
+    /// A square BC3 texture with a full mip chain, as file bytes
+    fn make_dds(size: u32, mips: u32) -> Vec<u8> {
+        let texture = bc3_texture(size, mips);
+        dds_file(&texture.header, &texture.data)
+    }
+
+    fn assert_png(bytes: &[u8], expected_dim: u32) {
+        assert!(bytes.starts_with(b"\x89PNG"));
+        let img = image::load_from_memory_with_format(bytes, ImgFormat::Png).unwrap();
+        assert_eq!((img.width(), img.height()), (expected_dim, expected_dim));
+    }
+
+    #[test]
+    fn test_preview_single_and_split_texture() {
+        let single = make_dds(16, 3);
+
+        // Split layout: header plus the first 64 data bytes, then the rest
+        let split = make_dds(16, 3);
+        let (split_base, split_rest) = split.split_at(128 + 64);
+
+        let (buf, archive) = stored_archive(&[
+            ("Data/Textures/hull.dds", &single),
+            ("Data/Textures/panel.dds", split_base),
+            ("Data/Textures/panel.dds.1", split_rest),
+        ]);
+        let parser = P4kParser::new();
+        let mut reader = Cursor::new(buf);
+
+        let png = preview_texture_from_archive(&parser, &archive, &mut reader, "Data/Textures/hull.dds", 8).unwrap();
+        assert_png(&png, 8);
+
+        // Asking for a split part resolves to the combined texture
+        let png = preview_texture_from_archive(&parser, &archive, &mut reader, "Data/Textures/panel.dds.1", 16).unwrap();
+        assert_png(&png, 16);
+
+        assert!(preview_texture_from_archive(&parser, &archive, &mut reader, "Data/missing.dds", 8).is_err());
+    }
+
+    #[test]
+    fn test_parse_error_keeps_source() {
+        use starbreaker_parsers::ParseError;
+        use std::error::Error;
+
+        let (buf, archive) = stored_archive(&[("Data/Textures/bad.dds", b"not a texture")]);
+        let err = preview_texture_from_archive(&P4kParser::new(), &archive, &mut Cursor::new(buf), "Data/Textures/bad.dds", 8)
+            .unwrap_err();
+
+        assert!(matches!(err, TextureError::ParseFailed(ParseError::InvalidMagic { .. })));
+        assert!(err.source().is_some_and(|s| s.is::<ParseError>()));
+    }
+}
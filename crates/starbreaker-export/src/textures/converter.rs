@@ -242,6 +242,59 @@ impl TextureConverter {
         Ok(())
     }
 
+    /// Decode a preview image no larger than `max_dim` on either side
+    ///
+    /// Decodes the mip chosen by [`DdsTexture::preview_mip_level`] instead of
+    /// the full-resolution level, and downscales it when the texture has too
+    /// few mips to reach `max_dim` (including single-mip textures).
+    pub fn preview(&self, texture: &DdsTexture, max_dim: u32) -> TextureResult<RgbaImage> {
+        let level = texture.preview_mip_level(max_dim);
+        let mip_data = texture.get_mipmap(level).ok_or(TextureError::InvalidMipLevel {
+            level,
+            max: texture.mipmap_count().saturating_sub(1),
+        })?;
+
+        let width = (texture.width() >> level).max(1);
+        let height = (texture.height() >> level).max(1);
+
+        let rgba_data = decompressor::decompress_bc(
+            &texture.format,
+            mip_data,
+            width,
+            height,
+        )?;
+
+        let mut img = RgbaImage::from_raw(width, height, rgba_data)
+            .ok_or(TextureError::DecompressionFailed(
+                "Failed to create image from preview mip".to_string()
+            ))?;
+
+        let max_dim = max_dim.max(1);
+        if width > max_dim || height > max_dim {
+            let scale = max_dim as f32 / width.max(height) as f32;
+            let new_width = ((width as f32 * scale).round() as u32).max(1);
+            let new_height = ((height as f32 * scale).round() as u32).max(1);
+            img = image::imageops::resize(&img, new_width, new_height, image::imageops::FilterType::Triangle);
+        }
+
+        if self.options.flip_y {
+            image::imageops::flip_vertical_in_place(&mut img);
+        }
+
+        Ok(img)
+    }
+
+    /// Write a preview image (see [`preview`](Self::preview)) to disk
+    pub fn save_preview(
+        &self,
+        texture: &DdsTexture,
+        max_dim: u32,
+        output_path: impl AsRef<Path>,
+    ) -> TextureResult<()> {
+        let img = self.preview(texture, max_dim)?;
+        self.write_image(&img, output_path.as_ref())
+    }
+
     /// Get texture information without converting
     pub fn get_info(texture: &DdsTexture) -> TextureInfo {
         TextureInfo {
@@ -20,7 +20,8 @@ pub fn decompress_bc(
     let mut output = vec![0u8; pixel_count * 4]; // RGBA8
 
     match format {
-        TextureFormat::BC1 => decompress_bc1(data, width, height, &mut output)?,
+        TextureFormat::BC1 => decompress_bc1(data, width, height, false, &mut output)?,
+        TextureFormat::BC1A => decompress_bc1(data, width, height, true, &mut output)?,
         TextureFormat::BC2 => decompress_bc2(data, width, height, &mut output)?,
         TextureFormat::BC3 => decompress_bc3(data, width, height, &mut output)?,
         TextureFormat::BC4 => decompress_bc4(data, width, height, &mut output)?,
@@ -62,8 +63,17 @@ pub fn decompress_bc(
 }
 
 /// Decompress BC1 (DXT1)
-fn decompress_bc1(data: &[u8], width: u32, height: u32, output: &mut [u8]) -> TextureResult<()> {
+///
+/// Blocks with `color0 <= color1` use three colors plus transparent black.
+/// Without `alpha` that fourth entry is opaque black instead, as DXT1
+/// textures without the alpha pixel-format flag are meant to be read.
+fn decompress_bc1(data: &[u8], width: u32, height: u32, alpha: bool, output: &mut [u8]) -> TextureResult<()> {
     texpresso::Format::Bc1.decompress(data, width as usize, height as usize, output);
+    if !alpha {
+        for pixel in output.chunks_exact_mut(4) {
+            pixel[3] = 255;
+        }
+    }
     Ok(())
 }
 
@@ -107,3 +117,32 @@ fn decompress_bc7(_data: &[u8], _width: u32, _height: u32, _output: &mut [u8]) -
         "BC7 decompression not yet supported".to_string()
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three-color block (color0 black <= color1 white): texel 0 uses
+    /// index 3, every other texel index 1
+    fn make_three_color_block() -> Vec<u8> {
+        let mut block = Vec::new();
+        block.extend_from_slice(&0x0000u16.to_le_bytes());
+        block.extend_from_slice(&0xFFFFu16.to_le_bytes());
+        block.extend_from_slice(&0x5555_5557u32.to_le_bytes());
+        block
+    }
+
+    #[test]
+    fn test_bc1_punch_through_alpha() {
+        let block = make_three_color_block();
+
+        let rgba = decompress_bc(&TextureFormat::BC1A, &block, 4, 4).unwrap();
+        assert_eq!(rgba[3], 0);
+        assert_eq!(&rgba[4..8], &[255, 255, 255, 255]);
+
+        // Opaque BC1 reads the same index as black
+        let rgba = decompress_bc(&TextureFormat::BC1, &block, 4, 4).unwrap();
+        assert_eq!(&rgba[0..4], &[0, 0, 0, 255]);
+        assert!(rgba.chunks_exact(4).all(|pixel| pixel[3] == 255));
+    }
+}
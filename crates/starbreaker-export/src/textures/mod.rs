@@ -4,9 +4,11 @@
 
 mod decompressor;
 mod converter;
+mod archive;
 
 pub use converter::{TextureConverter, TextureConvertOptions, ImageFormat};
 pub use decompressor::decompress_bc;
+pub use archive::{preview_image_from_archive, preview_texture_from_archive};
 
 use thiserror::Error;
 
@@ -21,6 +23,9 @@ pub enum TextureError {
     
     #[error("Parse error: {0}")]
     Parse(String),
+
+    #[error("Parse error: {0}")]
+    ParseFailed(#[from] starbreaker_parsers::ParseError),
     
     #[error("Unsupported format: {0}")]
     UnsupportedFormat(String),
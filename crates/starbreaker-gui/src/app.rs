@@ -105,6 +105,11 @@ impl eframe::App for StarBreakerApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Handle shortcuts
         self.handle_shortcuts(ctx);
+
+        // Keep repainting while the archive indexes so progress stays live
+        if self.file_browser.poll_indexing(&mut self.debug_console) {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
         
         // Menu bar
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
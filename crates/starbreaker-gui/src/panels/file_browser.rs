@@ -1,6 +1,6 @@
 //! File browser panel
 
-use crate::state::AppState;
+use crate::state::{AppState, IndexingStatus};
 use crate::widgets::{TreeNode, TreeView};
 use crate::panels::DebugConsolePanel;
 use eframe::egui;
@@ -35,28 +35,43 @@ impl FileBrowserPanel {
             let path_str = path.display().to_string();
             debug_console.info(format!("Selected file: {}", path_str));
             
+            // Parse on a worker thread; poll_indexing picks up the result
             let mut state = self.state.write();
-            if let Err(e) = state.open_archive(path) {
+            if let Err(e) = state.start_indexing(path, None) {
                 let error_msg = format!("Error opening archive: {}", e);
                 state.set_status(error_msg.clone());
                 debug_console.error(&error_msg);
                 eprintln!("[ERROR] {}", error_msg);
             } else {
-                debug_console.info(format!("Successfully opened archive: {}", path_str));
-                eprintln!("[INFO] Successfully opened archive: {}", path_str);
-                // Build tree from VFS
-                drop(state); // Release write lock
-                
+                debug_console.info(format!("Indexing archive: {}", path_str));
+            }
+        } else {
+            debug_console.debug("File dialog cancelled");
+        }
+    }
+
+    /// Pick up a finished background index, rebuilding the tree once it's done
+    ///
+    /// Returns whether indexing is still running, so the caller can keep repainting.
+    pub fn poll_indexing(&mut self, debug_console: &mut DebugConsolePanel) -> bool {
+        let finished = self.state.write().poll_indexing();
+        match finished {
+            Some(IndexingStatus::Done(archive)) => {
+                debug_console.info(format!("Successfully opened archive ({} files)", archive.entry_count()));
+
                 debug_console.debug("Building tree from archive...");
                 self.rebuild_tree();
-                
+
                 let entry_count = self.tree_root.as_ref().map(|t| t.children.len()).unwrap_or(0);
                 debug_console.info(format!("Tree rebuilt with {} root entries", entry_count));
-                eprintln!("[DEBUG] Tree rebuilt with {} entries", entry_count);
             }
-        } else {
-            debug_console.debug("File dialog cancelled");
+            Some(IndexingStatus::Failed(e)) => {
+                debug_console.error(format!("Error opening archive: {}", e));
+            }
+            Some(IndexingStatus::Cancelled) => debug_console.info("Indexing cancelled"),
+            Some(_) | None => {}
         }
+        self.state.read().indexing_status().is_running()
     }
     
     /// Rebuild tree from current VFS
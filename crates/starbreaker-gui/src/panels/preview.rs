@@ -2,9 +2,16 @@
 
 use crate::state::AppState;
 use eframe::egui;
+use starbreaker_export::textures::preview_image_from_archive;
+use starbreaker_parsers::P4kParser;
+use std::fs::File;
+use std::io::BufReader;
 use std::sync::Arc;
 use parking_lot::RwLock;
 
+/// Longest side of a decoded texture preview, in pixels
+const PREVIEW_MAX_DIM: u32 = 512;
+
 /// File preview mode
 enum PreviewMode {
     Text(String),
@@ -18,6 +25,8 @@ enum PreviewMode {
 pub struct PreviewPanel {
     state: Arc<RwLock<AppState>>,
     current_preview: Option<PreviewMode>,
+    /// Decoded texture for the selected file, keyed by its path
+    texture: Option<(String, Result<egui::TextureHandle, String>)>,
 }
 
 impl PreviewPanel {
@@ -26,8 +35,28 @@ impl PreviewPanel {
         Self {
             state,
             current_preview: None,
+            texture: None,
         }
     }
+
+    /// Decode a preview-sized mip of a DDS texture from the open archive
+    fn load_texture(
+        ctx: &egui::Context,
+        state: &AppState,
+        file_path: &str,
+    ) -> Result<egui::TextureHandle, String> {
+        let (Some(archive), Some(p4k_path)) = (&state.archive, &state.last_p4k_path) else {
+            return Err("No archive is open".to_string());
+        };
+
+        let mut reader = BufReader::new(File::open(p4k_path).map_err(|e| e.to_string())?);
+        let img = preview_image_from_archive(&P4kParser::new(), archive, &mut reader, file_path, PREVIEW_MAX_DIM)
+            .map_err(|e| e.to_string())?;
+
+        let size = [img.width() as usize, img.height() as usize];
+        let image = egui::ColorImage::from_rgba_unmultiplied(size, img.as_raw());
+        Ok(ctx.load_texture(file_path, image, egui::TextureOptions::LINEAR))
+    }
     
     /// Determine preview mode from file extension
     fn get_preview_mode(file_path: &str) -> PreviewMode {
@@ -78,8 +107,25 @@ impl PreviewPanel {
                 PreviewMode::Image => {
                     ui.heading("Image Preview");
                     ui.separator();
-                    ui.label("TODO: Load and display image");
-                    ui.label("(DDS decompression will be implemented)");
+
+                    if file_path.to_lowercase().ends_with(".dds") {
+                        if self.texture.as_ref().is_none_or(|(path, _)| path != file_path) {
+                            let texture = Self::load_texture(ui.ctx(), &state, file_path);
+                            self.texture = Some((file_path.clone(), texture));
+                        }
+
+                        match self.texture.as_ref().map(|(_, texture)| texture) {
+                            Some(Ok(texture)) => {
+                                ui.image((texture.id(), texture.size_vec2()));
+                            }
+                            Some(Err(e)) => {
+                                ui.colored_label(egui::Color32::RED, format!("Failed to decode texture: {}", e));
+                            }
+                            None => {}
+                        }
+                    } else {
+                        ui.label("TODO: Load and display image");
+                    }
                 }
                 PreviewMode::Model => {
                     ui.heading("3D Model Preview");
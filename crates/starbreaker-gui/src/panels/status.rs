@@ -21,6 +21,22 @@ impl StatusPanel {
         ui.horizontal(|ui| {
             let state = self.state.read();
             ui.label(&state.status_message);
+
+            // Show indexing progress with a way out
+            let indexing = state.indexing_status();
+            if indexing.is_running() {
+                ui.separator();
+                let bar = match indexing.fraction() {
+                    Some(fraction) => egui::ProgressBar::new(fraction).show_percentage(),
+                    None => egui::ProgressBar::new(0.0).animate(true),
+                };
+                ui.add(bar.desired_width(160.0));
+                if ui.button("Cancel").clicked() {
+                    drop(state);
+                    self.state.write().cancel_indexing();
+                    return;
+                }
+            }
             
             // Show archive info if loaded
             if let Some(path) = &state.last_p4k_path {
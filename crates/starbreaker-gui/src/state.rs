@@ -1,9 +1,184 @@
 //! Application state management
 
+use parking_lot::RwLock;
 use starbreaker_vfs::VfsTree;
-use starbreaker_parsers::P4kArchive;
-use std::path::PathBuf;
+use starbreaker_parsers::traits::{ParseOptions, ParseProgress, Parser, ProgressCallback};
+use starbreaker_parsers::{P4kArchive, P4kParser};
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+
+/// Cooperative cancellation flag shared with a worker thread
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the worker to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// The underlying flag, for [`ParseOptions::cancel`]
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.0.clone()
+    }
+}
+
+/// State of the background archive index, as shown to panels
+#[derive(Debug, Clone, Default)]
+pub enum IndexingStatus {
+    /// No indexing has been started
+    #[default]
+    Idle,
+    /// Parsing, with the latest progress event if one has arrived
+    InProgress(Option<ParseProgress>),
+    /// Parsing finished
+    Done(Arc<P4kArchive>),
+    /// Parsing failed with this message
+    Failed(String),
+    /// The user cancelled before parsing finished
+    Cancelled,
+}
+
+impl IndexingStatus {
+    /// Whether the worker is still running
+    pub fn is_running(&self) -> bool {
+        matches!(self, IndexingStatus::InProgress(_))
+    }
+
+    /// Fraction of the central directory read so far, if known
+    pub fn fraction(&self) -> Option<f32> {
+        match self {
+            IndexingStatus::InProgress(Some(progress)) => progress.total_items
+                .filter(|&total| total > 0)
+                .map(|total| progress.items_processed as f32 / total as f32),
+            IndexingStatus::Done(_) => Some(1.0),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a P4K directory on a worker thread so the UI stays responsive
+///
+/// Cancelling stops progress updates immediately and the parser before its
+/// next central directory entry.
+pub struct IndexingTask {
+    path: Option<PathBuf>,
+    status: Arc<RwLock<IndexingStatus>>,
+    token: CancellationToken,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl IndexingTask {
+    /// Start indexing the archive at `path`
+    pub fn start(path: &Path, on_progress: Option<ProgressCallback>) -> std::io::Result<Self> {
+        let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut task = Self::start_with_reader(reader, on_progress);
+        task.path = Some(path.to_path_buf());
+        Ok(task)
+    }
+
+    /// Start indexing an archive from any reader
+    ///
+    /// `on_progress` sees every progress event after the task's own status
+    /// has been updated, e.g. to request a repaint.
+    pub fn start_with_reader<R>(reader: R, on_progress: Option<ProgressCallback>) -> Self
+    where
+        R: Read + Seek + Send + 'static,
+    {
+        let status = Arc::new(RwLock::new(IndexingStatus::InProgress(None)));
+        let token = CancellationToken::new();
+
+        let handle = {
+            let status = status.clone();
+            let token = token.clone();
+            std::thread::spawn(move || {
+                let progress: ProgressCallback = {
+                    let status = status.clone();
+                    let token = token.clone();
+                    Box::new(move |progress: ParseProgress| {
+                        if token.is_cancelled() {
+                            return;
+                        }
+                        *status.write() = IndexingStatus::InProgress(Some(progress.clone()));
+                        if let Some(cb) = &on_progress {
+                            cb(progress);
+                        }
+                    })
+                };
+
+                let options = ParseOptions { cancel: Some(token.flag()), ..Default::default() };
+                let result = P4kParser::new().parse_with_options(reader, &options, Some(progress));
+                *status.write() = match result {
+                    _ if token.is_cancelled() => IndexingStatus::Cancelled,
+                    Ok(archive) => IndexingStatus::Done(Arc::new(archive)),
+                    Err(e) => IndexingStatus::Failed(e.to_string()),
+                };
+            })
+        };
+
+        Self {
+            path: None,
+            status,
+            token,
+            handle: Some(handle),
+        }
+    }
+
+    /// Path of the archive being indexed, if started from a file
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Current status
+    pub fn status(&self) -> IndexingStatus {
+        self.status.read().clone()
+    }
+
+    /// Ask the worker to stop
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Token shared with the worker
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Whether the worker has stopped
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(|h| h.is_finished())
+    }
+
+    /// Block until the worker stops and return its final status
+    pub fn wait(mut self) -> IndexingStatus {
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                return IndexingStatus::Failed("Indexing thread panicked".to_string());
+            }
+        }
+        self.status()
+    }
+}
+
+impl Drop for IndexingTask {
+    fn drop(&mut self) {
+        // Nobody is left to read the result
+        self.token.cancel();
+    }
+}
 
 /// Application state
 pub struct AppState {
@@ -21,6 +196,9 @@ pub struct AppState {
     
     /// Status message
     pub status_message: String,
+
+    /// Archive being indexed in the background
+    pub indexing: Option<IndexingTask>,
 }
 
 impl AppState {
@@ -32,15 +210,12 @@ impl AppState {
             selected_file: None,
             last_p4k_path: None,
             status_message: "Ready".to_string(),
+            indexing: None,
         }
     }
     
     /// Open a P4K archive
     pub fn open_archive(&mut self, path: PathBuf) -> anyhow::Result<()> {
-        use starbreaker_parsers::traits::Parser;
-        use starbreaker_parsers::P4kParser;
-        use starbreaker_vfs::mount::P4kMount;
-        
         // Parse the P4K archive
         eprintln!("[DEBUG] Loading P4K: {}", path.display());
         self.status_message = format!("Loading {}...", path.display());
@@ -48,10 +223,72 @@ impl AppState {
         
         eprintln!("[DEBUG] Parsing archive...");
         let archive = parser.parse_file(&path)?;
-        eprintln!("[INFO] Parsed {} entries", archive.entries.len());
-        
-        let archive = Arc::new(archive);
+        eprintln!("[INFO] Parsed {} entries", archive.entry_count());
         
+        self.install_archive(path, Arc::new(archive));
+        Ok(())
+    }
+
+    /// Start parsing a P4K archive on a worker thread
+    ///
+    /// Any indexing already running is cancelled. Call
+    /// [`poll_indexing`](Self::poll_indexing) each frame to pick up the result.
+    pub fn start_indexing(&mut self, path: PathBuf, on_progress: Option<ProgressCallback>) -> anyhow::Result<()> {
+        if let Some(previous) = self.indexing.take() {
+            previous.cancel();
+        }
+        self.indexing = Some(IndexingTask::start(&path, on_progress)?);
+        self.status_message = format!("Indexing {}...", path.display());
+        Ok(())
+    }
+
+    /// Cancel the running indexing task, if any
+    pub fn cancel_indexing(&mut self) {
+        if let Some(task) = &self.indexing {
+            task.cancel();
+        }
+    }
+
+    /// Status of the current indexing task
+    pub fn indexing_status(&self) -> IndexingStatus {
+        self.indexing.as_ref().map(IndexingTask::status).unwrap_or_default()
+    }
+
+    /// Finish a completed indexing task
+    ///
+    /// Returns the final status once, when the worker has stopped; a parsed
+    /// archive is mounted as if opened with [`open_archive`](Self::open_archive).
+    pub fn poll_indexing(&mut self) -> Option<IndexingStatus> {
+        if !self.indexing.as_ref()?.is_finished() {
+            if let IndexingStatus::InProgress(Some(progress)) = self.indexing_status() {
+                self.status_message = format!(
+                    "Indexing... {} / {} entries",
+                    progress.items_processed,
+                    progress.total_items.unwrap_or_default()
+                );
+            }
+            return None;
+        }
+
+        let task = self.indexing.take()?;
+        let path = task.path().map(Path::to_path_buf);
+        let status = task.wait();
+        match &status {
+            IndexingStatus::Done(archive) => match path {
+                Some(path) => self.install_archive(path, archive.clone()),
+                None => self.archive = Some(archive.clone()),
+            },
+            IndexingStatus::Failed(e) => self.status_message = format!("Error opening archive: {}", e),
+            IndexingStatus::Cancelled => self.status_message = "Indexing cancelled".to_string(),
+            IndexingStatus::Idle | IndexingStatus::InProgress(_) => {}
+        }
+        Some(status)
+    }
+
+    /// Mount a parsed archive and make it the current one
+    fn install_archive(&mut self, path: PathBuf, archive: Arc<P4kArchive>) {
+        use starbreaker_vfs::mount::P4kMount;
+
         // Create VFS mount
         let vfs = VfsTree::new();
         let mount = P4kMount::new(0, "game", &path, archive.clone());
@@ -62,9 +299,7 @@ impl AppState {
         self.last_p4k_path = Some(path.clone());
         self.status_message = format!("Opened: {} ({} files)", 
             path.display(), 
-            archive.entries.len());
-        
-        Ok(())
+            archive.entry_count());
     }
     
     /// Select a file in the VFS
@@ -88,3 +323,99 @@ impl Default for AppState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use starbreaker_parsers::testutil::p4k_file;
+    use starbreaker_parsers::traits::ParsePhase;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_indexing_task_progress_then_done() {
+        let bytes = p4k_file(&[("Data/game.xml", b"<Game/>"), ("Data/ship.xml", b"<Ship/>")]);
+        let phases = Arc::new(Mutex::new(Vec::new()));
+        let recorder = phases.clone();
+
+        let task = IndexingTask::start_with_reader(
+            Cursor::new(bytes),
+            Some(Box::new(move |p: ParseProgress| recorder.lock().push(p.phase))),
+        );
+        assert!(task.path().is_none());
+
+        let status = task.wait();
+        let IndexingStatus::Done(archive) = &status else {
+            panic!("expected Done, got {:?}", status);
+        };
+        assert_eq!(archive.entry_count(), 2);
+        assert_eq!(status.fraction(), Some(1.0));
+        assert!(!status.is_running());
+
+        let phases = phases.lock();
+        assert_eq!(phases.first(), Some(&ParsePhase::ReadingHeader));
+        assert!(phases.contains(&ParsePhase::Indexing));
+        assert_eq!(phases.last(), Some(&ParsePhase::Complete));
+    }
+
+    /// Blocks its first read until `gate` fires, then counts bytes read
+    struct GatedReader {
+        inner: Cursor<Vec<u8>>,
+        gate: Option<std::sync::mpsc::Receiver<()>>,
+        read_from: Arc<Mutex<Vec<u64>>>,
+    }
+
+    impl Read for GatedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if let Some(gate) = self.gate.take() {
+                gate.recv().ok();
+            }
+            self.read_from.lock().push(self.inner.position());
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for GatedReader {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_indexing_task_cancel_stops_parser() {
+        let files: &[(&str, &[u8])] = &[("Data/game.xml", b"<Game/>"), ("Data/ship.xml", b"<Ship/>")];
+        let cd_offset = starbreaker_parsers::testutil::stored_archive(files).0.len() as u64;
+        let (open, gate) = std::sync::mpsc::channel();
+        let read_from = Arc::new(Mutex::new(Vec::new()));
+
+        let task = IndexingTask::start_with_reader(
+            GatedReader { inner: Cursor::new(p4k_file(files)), gate: Some(gate), read_from: read_from.clone() },
+            None,
+        );
+        task.cancel();
+        open.send(()).unwrap();
+
+        assert!(matches!(task.wait(), IndexingStatus::Cancelled));
+        // The parser stopped before reading a single central directory entry
+        assert!(!read_from.lock().contains(&cd_offset));
+    }
+
+    #[test]
+    fn test_indexing_task_failure_and_poll() {
+        let task = IndexingTask::start_with_reader(Cursor::new(b"not an archive".to_vec()), None);
+        let mut state = AppState::new();
+        state.indexing = Some(task);
+
+        let status = loop {
+            if let Some(status) = state.poll_indexing() {
+                break status;
+            }
+            std::thread::yield_now();
+        };
+        assert!(matches!(status, IndexingStatus::Failed(_)));
+        assert!(state.status_message.starts_with("Error opening archive"));
+        assert!(state.indexing.is_none());
+        assert!(matches!(state.indexing_status(), IndexingStatus::Idle));
+        assert!(state.archive.is_none());
+    }
+}
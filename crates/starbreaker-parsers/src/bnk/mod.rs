@@ -0,0 +1,267 @@
+//! Wwise soundbank (BNK) index parser
+//!
+//! Soundbanks embed WEM audio streams by id. This parser only indexes the
+//! bank and carves streams out of it; audio is never decoded.
+//!
+//! # Layout
+//! A bank is a sequence of sections, each a 4-byte tag and a little-endian
+//! `u32` payload size:
+//! - `BKHD`: bank header (version, bank id)
+//! - `DIDX`: 12-byte records of (WEM id, offset into DATA, size)
+//! - `DATA`: the concatenated WEM streams
+//!
+//! Other sections (`HIRC`, `STID`, ...) are skipped.
+
+use std::io::{Read, Seek, SeekFrom};
+use crate::traits::{Parser, ParseResult, ParseError, ParseOptions, ProgressCallback};
+
+/// Bank header section tag
+const BKHD_TAG: [u8; 4] = *b"BKHD";
+/// Data index section tag
+const DIDX_TAG: [u8; 4] = *b"DIDX";
+/// Data section tag
+const DATA_TAG: [u8; 4] = *b"DATA";
+
+/// Size of one DIDX record
+const DIDX_ENTRY_SIZE: usize = 12;
+
+/// A WEM stream embedded in a soundbank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BnkEntry {
+    /// WEM id
+    pub id: u32,
+    /// Offset from the start of the DATA payload
+    pub offset: u32,
+    /// Stream size in bytes
+    pub size: u32,
+}
+
+/// Index of the WEM streams in a soundbank
+#[derive(Debug, Clone, Default)]
+pub struct BnkIndex {
+    /// Bank format version from BKHD
+    pub version: u32,
+    /// Bank id from BKHD
+    pub bank_id: u32,
+    /// Embedded streams in DIDX order
+    pub entries: Vec<BnkEntry>,
+    /// Absolute file offset of the DATA payload (`None` if the bank has no DATA)
+    pub data_offset: Option<u64>,
+    /// Size of the DATA payload
+    pub data_size: u64,
+}
+
+impl BnkIndex {
+    /// Find a stream by WEM id
+    pub fn get(&self, id: u32) -> Option<&BnkEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// Carve a WEM stream out of the bank it was parsed from
+    pub fn extract_wem<R: Read + Seek>(&self, reader: &mut R, id: u32) -> ParseResult<Vec<u8>> {
+        let entry = self.get(id)
+            .ok_or_else(|| ParseError::MissingField(format!("WEM {} not in bank", id)))?;
+        let data_offset = self.data_offset
+            .ok_or_else(|| ParseError::MissingField("DATA section".to_string()))?;
+
+        reader.seek(SeekFrom::Start(data_offset + entry.offset as u64))?;
+        let mut data = vec![0u8; entry.size as usize];
+        reader.read_exact(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// Wwise soundbank parser
+pub struct BnkParser;
+
+impl BnkParser {
+    /// Create a new BNK parser
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Decode the DIDX payload into entries
+    fn parse_didx(payload: &[u8], section_offset: u64) -> ParseResult<Vec<BnkEntry>> {
+        if !payload.len().is_multiple_of(DIDX_ENTRY_SIZE) {
+            return Err(ParseError::CorruptedData {
+                offset: section_offset,
+                message: format!("DIDX size {} is not a multiple of {}", payload.len(), DIDX_ENTRY_SIZE),
+            });
+        }
+
+        Ok(payload.chunks_exact(DIDX_ENTRY_SIZE)
+            .map(|record| BnkEntry {
+                id: u32::from_le_bytes([record[0], record[1], record[2], record[3]]),
+                offset: u32::from_le_bytes([record[4], record[5], record[6], record[7]]),
+                size: u32::from_le_bytes([record[8], record[9], record[10], record[11]]),
+            })
+            .collect())
+    }
+}
+
+impl Default for BnkParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for BnkParser {
+    type Output = BnkIndex;
+
+    fn extensions(&self) -> &[&str] {
+        &["bnk"]
+    }
+
+    fn magic_bytes(&self) -> Option<&[u8]> {
+        Some(&BKHD_TAG)
+    }
+
+    fn name(&self) -> &str {
+        "Wwise Soundbank Parser"
+    }
+
+    fn parse_with_options<R: Read + Seek>(
+        &self,
+        mut reader: R,
+        _options: &ParseOptions,
+        _progress: Option<ProgressCallback>,
+    ) -> ParseResult<Self::Output> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut index = BnkIndex::default();
+        let mut pos = 0u64;
+
+        while pos + 8 <= file_len {
+            let mut section = [0u8; 8];
+            reader.read_exact(&mut section)?;
+            let tag = [section[0], section[1], section[2], section[3]];
+            let size = u32::from_le_bytes([section[4], section[5], section[6], section[7]]) as u64;
+            let payload_start = pos + 8;
+
+            if pos == 0 && tag != BKHD_TAG {
+                return Err(ParseError::InvalidMagic {
+                    expected: BKHD_TAG.to_vec(),
+                    found: tag.to_vec(),
+                });
+            }
+            if payload_start + size > file_len {
+                return Err(ParseError::CorruptedData {
+                    offset: pos,
+                    message: format!(
+                        "section {} size {} runs past end of file",
+                        String::from_utf8_lossy(&tag), size
+                    ),
+                });
+            }
+
+            match tag {
+                BKHD_TAG if size >= 8 => {
+                    let mut header = [0u8; 8];
+                    reader.read_exact(&mut header)?;
+                    index.version = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+                    index.bank_id = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+                }
+                DIDX_TAG => {
+                    let mut payload = vec![0u8; size as usize];
+                    reader.read_exact(&mut payload)?;
+                    index.entries = Self::parse_didx(&payload, pos)?;
+                }
+                DATA_TAG => {
+                    index.data_offset = Some(payload_start);
+                    index.data_size = size;
+                }
+                _ => {}
+            }
+
+            pos = payload_start + size;
+            reader.seek(SeekFrom::Start(pos))?;
+        }
+
+        // Every indexed stream has to fit inside DATA
+        if let Some(entry) = index.entries.iter()
+            .find(|e| e.offset as u64 + e.size as u64 > index.data_size)
+        {
+            return Err(ParseError::InvalidStructure(format!(
+                "WEM {} ({} bytes at {}) lies outside the {}-byte DATA section",
+                entry.id, entry.size, entry.offset, index.data_size
+            )));
+        }
+
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn section(tag: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut buf = tag.to_vec();
+        buf.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    /// Bank with two WEMs: id 100 (16 bytes of 0xAA) and id 200 (7 bytes of 0xBB)
+    fn make_bank() -> Vec<u8> {
+        let mut header = 0x8Cu32.to_le_bytes().to_vec();
+        header.extend_from_slice(&0x1234u32.to_le_bytes());
+
+        let mut didx = Vec::new();
+        for (id, offset, size) in [(100u32, 0u32, 16u32), (200, 16, 7)] {
+            didx.extend_from_slice(&id.to_le_bytes());
+            didx.extend_from_slice(&offset.to_le_bytes());
+            didx.extend_from_slice(&size.to_le_bytes());
+        }
+
+        let mut data = vec![0xAA; 16];
+        data.extend_from_slice(&[0xBB; 7]);
+
+        let mut bank = section(&BKHD_TAG, &header);
+        bank.extend(section(&DIDX_TAG, &didx));
+        bank.extend(section(&DATA_TAG, &data));
+        bank.extend(section(b"HIRC", &[0; 4]));
+        bank
+    }
+
+    #[test]
+    fn test_index_and_extract() {
+        let bank = make_bank();
+        let index = BnkParser::new().parse(Cursor::new(&bank)).unwrap();
+
+        assert_eq!(index.version, 0x8C);
+        assert_eq!(index.bank_id, 0x1234);
+        assert_eq!(index.entries, vec![
+            BnkEntry { id: 100, offset: 0, size: 16 },
+            BnkEntry { id: 200, offset: 16, size: 7 },
+        ]);
+
+        let wem = index.extract_wem(&mut Cursor::new(&bank), 200).unwrap();
+        assert_eq!(wem, vec![0xBB; 7]);
+        assert!(index.extract_wem(&mut Cursor::new(&bank), 300).is_err());
+    }
+
+    #[test]
+    fn test_rejects_entry_outside_data() {
+        let mut bank = make_bank();
+        // Grow the second entry's size past the end of DATA
+        let size_pos = 16 + 8 + DIDX_ENTRY_SIZE + 8;
+        bank[size_pos..size_pos + 4].copy_from_slice(&64u32.to_le_bytes());
+
+        assert!(matches!(
+            BnkParser::new().parse(Cursor::new(&bank)),
+            Err(ParseError::InvalidStructure(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_missing_header() {
+        let bank = section(b"DATA", &[0; 4]);
+        assert!(matches!(
+            BnkParser::new().parse(Cursor::new(&bank)),
+            Err(ParseError::InvalidMagic { .. })
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+// starbreaker-parsers/src/cgf/animation.rs
+//! Bone animation tracks and pose sampling
+
+use serde::{Deserialize, Serialize};
+
+use super::bones::{matrix_to_quaternion, quaternion_to_matrix, Skeleton};
+use super::chunks::BoneKey;
+
+/// Quaternion dot product above which slerp falls back to normalized lerp
+const SLERP_LINEAR_THRESHOLD: f32 = 0.9995;
+
+/// Keyframes driving a single bone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoneTrack {
+    /// Index of the animated bone in the skeleton
+    pub bone_index: usize,
+    /// Keys sorted by time; each key may set any of position, rotation and scale
+    pub keys: Vec<BoneKey>,
+}
+
+/// A named animation made of per-bone tracks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Animation {
+    /// Animation name
+    pub name: String,
+    /// Whether sampling past the end wraps back to the start
+    pub looping: bool,
+    /// One track per animated bone
+    pub tracks: Vec<BoneTrack>,
+}
+
+impl Animation {
+    /// Create an empty animation
+    pub fn new(name: impl Into<String>, looping: bool) -> Self {
+        Self {
+            name: name.into(),
+            looping,
+            tracks: Vec::new(),
+        }
+    }
+
+    /// Time of the last key in any track
+    pub fn duration(&self) -> f32 {
+        self.tracks
+            .iter()
+            .flat_map(|t| t.keys.iter().map(|k| k.time))
+            .fold(0.0, f32::max)
+    }
+
+    /// Evaluate local bone transforms at `time`
+    ///
+    /// Returns one matrix per skeleton bone. Positions and scales are
+    /// interpolated linearly, rotations with slerp. Components a track has
+    /// no keys for, and bones without a track, keep their bind pose
+    /// (`Bone::local_transform`). Looping animations wrap `time` into
+    /// `0..duration`; others clamp to the first and last keys.
+    pub fn sample(&self, time: f32, skeleton: &Skeleton) -> Vec<[[f32; 4]; 4]> {
+        let duration = self.duration();
+        let time = if self.looping && duration > 0.0 {
+            time.rem_euclid(duration)
+        } else {
+            time.clamp(0.0, duration)
+        };
+
+        let mut pose: Vec<[[f32; 4]; 4]> = skeleton.bones.iter().map(|b| b.local_transform).collect();
+
+        for track in &self.tracks {
+            let Some(local) = pose.get_mut(track.bone_index) else {
+                continue;
+            };
+            let (bind_position, bind_rotation, bind_scale) = decompose(local);
+
+            let position = sample_channel(&track.keys, time, |k| k.position, lerp3).unwrap_or(bind_position);
+            let rotation = sample_channel(&track.keys, time, |k| k.rotation, slerp).unwrap_or(bind_rotation);
+            let scale = sample_channel(&track.keys, time, |k| k.scale, lerp3).unwrap_or(bind_scale);
+
+            *local = compose(position, rotation, scale);
+        }
+
+        pose
+    }
+}
+
+/// Interpolate the keys that set one component, or `None` if none do
+fn sample_channel<T: Copy>(
+    keys: &[BoneKey],
+    time: f32,
+    value: impl Fn(&BoneKey) -> Option<T>,
+    interpolate: impl Fn(T, T, f32) -> T,
+) -> Option<T> {
+    let mut previous: Option<(f32, T)> = None;
+
+    for key in keys {
+        let Some(current) = value(key) else {
+            continue;
+        };
+        if key.time >= time {
+            return Some(match previous {
+                Some((t0, v0)) if key.time > t0 => interpolate(v0, current, (time - t0) / (key.time - t0)),
+                _ => current,
+            });
+        }
+        previous = Some((key.time, current));
+    }
+
+    previous.map(|(_, v)| v)
+}
+
+fn lerp3(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+}
+
+/// Spherical interpolation along the shortest arc
+fn slerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    let mut dot = a.iter().zip(&b).map(|(x, y)| x * y).sum::<f32>();
+    let b = if dot < 0.0 {
+        dot = -dot;
+        b.map(|c| -c)
+    } else {
+        b
+    };
+
+    let (wa, wb) = if dot > SLERP_LINEAR_THRESHOLD {
+        (1.0 - t, t)
+    } else {
+        let theta = dot.acos();
+        let sin = theta.sin();
+        (((1.0 - t) * theta).sin() / sin, (t * theta).sin() / sin)
+    };
+
+    let q = [0, 1, 2, 3].map(|i| a[i] * wa + b[i] * wb);
+    let len = q.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if len > 0.0 { q.map(|c| c / len) } else { [0.0, 0.0, 0.0, 1.0] }
+}
+
+/// Split a local transform into translation, rotation quaternion and per-axis scale
+fn decompose(m: &[[f32; 4]; 4]) -> ([f32; 3], [f32; 4], [f32; 3]) {
+    let position = [m[3][0], m[3][1], m[3][2]];
+    let scale = [0, 1, 2].map(|i| (m[i][0] * m[i][0] + m[i][1] * m[i][1] + m[i][2] * m[i][2]).sqrt());
+
+    let mut rotation = *m;
+    for (row, &s) in rotation.iter_mut().zip(&scale) {
+        if s > 0.0 {
+            row[..3].iter_mut().for_each(|c| *c /= s);
+        }
+    }
+
+    (position, matrix_to_quaternion(rotation), scale)
+}
+
+/// Inverse of [`decompose`]
+fn compose(position: [f32; 3], rotation: [f32; 4], scale: [f32; 3]) -> [[f32; 4]; 4] {
+    let mut m = quaternion_to_matrix(rotation);
+    for (row, &s) in m.iter_mut().zip(&scale) {
+        row[..3].iter_mut().for_each(|c| *c *= s);
+    }
+    m[3] = [position[0], position[1], position[2], 1.0];
+    m
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cgf::Bone;
+
+    fn key(time: f32, position: Option<[f32; 3]>, rotation: Option<[f32; 4]>) -> BoneKey {
+        BoneKey { time, position, rotation, scale: None }
+    }
+
+    fn skeleton() -> Skeleton {
+        let mut skeleton = Skeleton::new();
+        let root = skeleton.add_bone(Bone::new("root"));
+        let mut child = Bone::new("child");
+        child.parent_index = Some(root);
+        child.set_position([0.0, 5.0, 0.0]);
+        skeleton.add_bone(child);
+        skeleton
+    }
+
+    fn animation(looping: bool) -> Animation {
+        let mut animation = Animation::new("walk", looping);
+        animation.tracks.push(BoneTrack {
+            bone_index: 0,
+            keys: vec![
+                key(0.0, Some([0.0, 0.0, 0.0]), Some([0.0, 0.0, 0.0, 1.0])),
+                key(2.0, Some([4.0, 2.0, -6.0]), Some([0.0, 0.0, 1.0, 0.0])),
+            ],
+        });
+        animation
+    }
+
+    fn translation(m: &[[f32; 4]; 4]) -> [f32; 3] {
+        [m[3][0], m[3][1], m[3][2]]
+    }
+
+    #[test]
+    fn test_sample_midpoint_interpolates() {
+        let skeleton = skeleton();
+        let pose = animation(false).sample(1.0, &skeleton);
+
+        assert_eq!(pose.len(), 2);
+        assert_eq!(translation(&pose[0]), [2.0, 1.0, -3.0]);
+
+        // Halfway between identity and 180 degrees about Z is 90 degrees about Z
+        let q = matrix_to_quaternion(pose[0]);
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((q[2].abs() - half).abs() < 1e-4 && (q[3].abs() - half).abs() < 1e-4, "{:?}", q);
+
+        // Unanimated bones keep their bind pose
+        assert_eq!(pose[1], skeleton.bones[1].local_transform);
+    }
+
+    #[test]
+    fn test_sample_clamps_and_loops() {
+        let skeleton = skeleton();
+
+        let clamped = animation(false);
+        assert_eq!(translation(&clamped.sample(-1.0, &skeleton)[0]), [0.0, 0.0, 0.0]);
+        assert_eq!(translation(&clamped.sample(5.0, &skeleton)[0]), [4.0, 2.0, -6.0]);
+
+        let looped = animation(true);
+        assert_eq!(translation(&looped.sample(3.0, &skeleton)[0]), [2.0, 1.0, -3.0]);
+        assert_eq!(translation(&looped.sample(-1.0, &skeleton)[0]), [2.0, 1.0, -3.0]);
+    }
+}
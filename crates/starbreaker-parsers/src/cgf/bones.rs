@@ -107,6 +107,35 @@ impl Skeleton {
         result
     }
 
+    /// Rename bones, e.g. to match another engine's naming convention
+    ///
+    /// Bones whose name is a key in `map` take the mapped name; others keep
+    /// theirs. Bone order is unchanged, so vertex bone indices stay valid.
+    /// Returns the number of bones renamed.
+    pub fn remap_bone_names(&mut self, map: &HashMap<String, String>) -> usize {
+        let mut renamed = 0;
+        for bone in &mut self.bones {
+            if let Some(new_name) = map.get(&bone.name) {
+                bone.name = new_name.clone();
+                renamed += 1;
+            }
+        }
+
+        self.rebuild_bone_map();
+        renamed
+    }
+
+    /// Rebuild the name to index lookup from the current bone names
+    ///
+    /// Needed after editing `Bone::name` directly. If two bones share a
+    /// name the last one wins, matching what `add_bone` does.
+    pub fn rebuild_bone_map(&mut self) {
+        self.bone_map.clear();
+        for (idx, bone) in self.bones.iter().enumerate() {
+            self.bone_map.insert(bone.name.clone(), idx);
+        }
+    }
+
     /// Get all bone names
     pub fn bone_names(&self) -> Vec<&str> {
         self.bones.iter().map(|b| b.name.as_str()).collect()
@@ -198,8 +227,10 @@ impl Bone {
     }
 
     /// Calculate inverse bind pose from bind pose
+    ///
+    /// A singular bind pose falls back to the identity matrix.
     pub fn calculate_inverse_bind_pose(&mut self) {
-        self.inverse_bind_pose = invert_matrix(self.bind_pose);
+        self.inverse_bind_pose = invert_matrix(self.bind_pose).unwrap_or(IDENTITY_MATRIX);
     }
 }
 
@@ -284,12 +315,47 @@ fn multiply_matrices(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
     result
 }
 
-/// Invert a 4x4 matrix (assuming it's a valid transform matrix)
-fn invert_matrix(m: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
-    // For transform matrices, we can use a simplified approach:
-    // The upper 3x3 is rotation (orthogonal), the last row is [0,0,0,1]
-    // and translation is in column 3
-    
+/// Tolerance used when checking whether the upper 3x3 is orthonormal
+const RIGID_EPSILON: f32 = 1e-4;
+
+/// Invert a 4x4 transform matrix
+///
+/// Rigid transforms (orthonormal rotation plus translation) take a fast
+/// transpose path; anything with scale or shear goes through a general
+/// Gauss-Jordan elimination. Returns `None` for singular matrices.
+pub fn invert_matrix(m: [[f32; 4]; 4]) -> Option<[[f32; 4]; 4]> {
+    if is_rigid(&m) {
+        Some(invert_rigid(m))
+    } else {
+        invert_general(m)
+    }
+}
+
+/// Check if the matrix is a rotation plus translation with no projection
+fn is_rigid(m: &[[f32; 4]; 4]) -> bool {
+    if m[0][3].abs() > RIGID_EPSILON
+        || m[1][3].abs() > RIGID_EPSILON
+        || m[2][3].abs() > RIGID_EPSILON
+        || (m[3][3] - 1.0).abs() > RIGID_EPSILON
+    {
+        return false;
+    }
+
+    for i in 0..3 {
+        for j in 0..3 {
+            let dot = m[i][0] * m[j][0] + m[i][1] * m[j][1] + m[i][2] * m[j][2];
+            let expected = if i == j { 1.0 } else { 0.0 };
+            if (dot - expected).abs() > RIGID_EPSILON {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Invert a rigid transform by transposing the rotation and back-rotating the translation
+fn invert_rigid(m: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
     // Transpose the 3x3 rotation part
     let mut result = IDENTITY_MATRIX;
     
@@ -311,6 +377,60 @@ fn invert_matrix(m: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
     result
 }
 
+/// Invert an arbitrary 4x4 matrix with Gauss-Jordan elimination and partial pivoting
+fn invert_general(m: [[f32; 4]; 4]) -> Option<[[f32; 4]; 4]> {
+    // Work in f64 so ill-conditioned bind poses don't lose too much precision
+    let mut a = [[0.0f64; 4]; 4];
+    let mut inv = [[0.0f64; 4]; 4];
+    for i in 0..4 {
+        for j in 0..4 {
+            a[i][j] = m[i][j] as f64;
+        }
+        inv[i][i] = 1.0;
+    }
+
+    for col in 0..4 {
+        // Pick the row with the largest pivot to keep the elimination stable
+        let pivot = (col..4)
+            .max_by(|&x, &y| a[x][col].abs().total_cmp(&a[y][col].abs()))
+            .unwrap_or(col);
+
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+
+        let scale = 1.0 / a[col][col];
+        for j in 0..4 {
+            a[col][j] *= scale;
+            inv[col][j] *= scale;
+        }
+
+        for row in 0..4 {
+            if row != col {
+                let factor = a[row][col];
+                if factor != 0.0 {
+                    for j in 0..4 {
+                        a[row][j] -= factor * a[col][j];
+                        inv[row][j] -= factor * inv[col][j];
+                    }
+                }
+            }
+        }
+    }
+
+    let mut result = [[0.0f32; 4]; 4];
+    for i in 0..4 {
+        for j in 0..4 {
+            result[i][j] = inv[i][j] as f32;
+        }
+    }
+
+    Some(result)
+}
+
 /// Convert quaternion to rotation matrix
 pub fn quaternion_to_matrix(q: [f32; 4]) -> [[f32; 4]; 4] {
     let [x, y, z, w] = q;
@@ -401,6 +521,33 @@ mod tests {
         assert!(skeleton.find_bone("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_remap_bone_names() {
+        let mut skeleton = Skeleton::new();
+        let root = skeleton.add_bone(Bone::new("Bip01"));
+        let mut spine = Bone::new("Bip01 Spine");
+        spine.parent_index = Some(root);
+        skeleton.add_bone(spine);
+        skeleton.add_bone(Bone::new("weapon_attach"));
+
+        let map = HashMap::from([
+            ("Bip01".to_string(), "Hips".to_string()),
+            ("Bip01 Spine".to_string(), "Spine".to_string()),
+        ]);
+        assert_eq!(skeleton.remap_bone_names(&map), 2);
+
+        assert_eq!(skeleton.find_bone_index("Hips"), Some(0));
+        assert_eq!(skeleton.find_bone("Spine").unwrap().parent_index, Some(0));
+        assert_eq!(skeleton.find_bone_index("weapon_attach"), Some(2));
+        assert!(skeleton.find_bone("Bip01").is_none());
+        assert!(skeleton.find_bone("Bip01 Spine").is_none());
+
+        // A rename collision resolves to the later bone, as with add_bone
+        let map = HashMap::from([("weapon_attach".to_string(), "Spine".to_string())]);
+        assert_eq!(skeleton.remap_bone_names(&map), 1);
+        assert_eq!(skeleton.find_bone_index("Spine"), Some(2));
+    }
+
     #[test]
     fn test_bone_chain_to_root() {
         let mut skeleton = Skeleton::new();
@@ -432,6 +579,60 @@ mod tests {
         }
     }
 
+    fn assert_identity(m: [[f32; 4]; 4]) {
+        for i in 0..4 {
+            for j in 0..4 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((m[i][j] - expected).abs() < 1e-4, "m[{}][{}] = {}", i, j, m[i][j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invert_rigid_matches_general() {
+        // 90 degree rotation about Z plus a translation
+        let mut m = quaternion_to_matrix([0.0, 0.0, std::f32::consts::FRAC_1_SQRT_2, std::f32::consts::FRAC_1_SQRT_2]);
+        m[3] = [1.0, 2.0, 3.0, 1.0];
+
+        assert!(is_rigid(&m));
+        let fast = invert_rigid(m);
+        let general = invert_general(m).unwrap();
+
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((fast[i][j] - general[i][j]).abs() < 1e-4);
+            }
+        }
+        assert_identity(multiply_matrices(m, invert_matrix(m).unwrap()));
+    }
+
+    #[test]
+    fn test_invert_scaled_and_sheared() {
+        let m = [
+            [2.0, 0.0, 0.0, 0.0],
+            [0.5, 3.0, 0.0, 0.0],
+            [0.0, 0.0, 0.25, 0.0],
+            [4.0, -1.0, 6.0, 1.0],
+        ];
+
+        assert!(!is_rigid(&m));
+        let inverse = invert_matrix(m).unwrap();
+        assert_identity(multiply_matrices(m, inverse));
+        assert_identity(multiply_matrices(inverse, m));
+    }
+
+    #[test]
+    fn test_invert_singular() {
+        let mut m = IDENTITY_MATRIX;
+        m[2] = [0.0, 0.0, 0.0, 0.0];
+        assert!(invert_matrix(m).is_none());
+
+        let mut bone = Bone::new("flat");
+        bone.bind_pose = m;
+        bone.calculate_inverse_bind_pose();
+        assert_eq!(bone.inverse_bind_pose, IDENTITY_MATRIX);
+    }
+
     #[test]
     fn test_quaternion_to_matrix_identity() {
         let q = [0.0, 0.0, 0.0, 1.0]; // Identity quaternion
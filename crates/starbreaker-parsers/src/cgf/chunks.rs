@@ -12,6 +12,8 @@ pub enum ChunkType {
     Timing,
     /// MTL Name chunk (materials)
     MtlName,
+    /// MTL Name chunk under its old 0x0002 id
+    MtlNameLegacy,
     /// Export flags
     ExportFlags,
     /// Mesh data
@@ -44,6 +46,14 @@ pub enum ChunkType {
     CompiledMorphTargets,
     /// Compiled mesh
     CompiledMesh,
+    /// Compiled bones under the pre-Ivo 0x1021 id
+    CompiledBonesLegacy,
+    /// Compiled physical bones under the pre-Ivo 0x1022 id
+    CompiledPhysicalBonesLegacy,
+    /// Compiled morph targets under the pre-Ivo 0x1023 id
+    CompiledMorphTargetsLegacy,
+    /// Compiled mesh under the pre-Ivo 0x1024 id
+    CompiledMeshLegacy,
     /// Compiled physics geometry
     CompiledPhysicsGeometry,
     /// Compiled int skinning data
@@ -70,11 +80,16 @@ pub enum ChunkType {
 
 impl ChunkType {
     /// Convert from raw u32 chunk type ID
+    ///
+    /// Every id maps to a distinct variant, so `to_u32` gives back the exact
+    /// id read from the file. Use [`ChunkType::canonical`] to fold legacy ids
+    /// onto the variant they share a layout with.
     pub fn from_u32(value: u32) -> Self {
         match value {
             0x0000 => ChunkType::SourceInfo,
             0x0001 => ChunkType::Timing,
-            0x0002 | 0x0014 => ChunkType::MtlName,
+            0x0002 => ChunkType::MtlNameLegacy,
+            0x0014 => ChunkType::MtlName,
             0x0003 => ChunkType::ExportFlags,
             0x1000 => ChunkType::Mesh,
             0x1001 => ChunkType::MeshSubsets,
@@ -87,10 +102,14 @@ impl ChunkType {
             0x101A => ChunkType::Helper,
             0x101B => ChunkType::MorphTargets,
             0x101C => ChunkType::Controller,
-            0x1021 | 0xACDC0000 => ChunkType::CompiledBones,
-            0x1022 | 0xACDC0001 => ChunkType::CompiledPhysicalBones,
-            0x1023 | 0xACDC0002 => ChunkType::CompiledMorphTargets,
-            0x1024 | 0xCCCC0000 => ChunkType::CompiledMesh,
+            0x1021 => ChunkType::CompiledBonesLegacy,
+            0x1022 => ChunkType::CompiledPhysicalBonesLegacy,
+            0x1023 => ChunkType::CompiledMorphTargetsLegacy,
+            0x1024 => ChunkType::CompiledMeshLegacy,
+            0xACDC0000 => ChunkType::CompiledBones,
+            0xACDC0001 => ChunkType::CompiledPhysicalBones,
+            0xACDC0002 => ChunkType::CompiledMorphTargets,
+            0xCCCC0000 => ChunkType::CompiledMesh,
             0x1025 => ChunkType::CompiledPhysicsGeometry,
             0x1026 => ChunkType::CompiledIntSkinVertices,
             0x1027 => ChunkType::CompiledExtToIntMap,
@@ -111,6 +130,7 @@ impl ChunkType {
             ChunkType::SourceInfo => 0x0000,
             ChunkType::Timing => 0x0001,
             ChunkType::MtlName => 0x0014,
+            ChunkType::MtlNameLegacy => 0x0002,
             ChunkType::ExportFlags => 0x0003,
             ChunkType::Mesh => 0x1000,
             ChunkType::MeshSubsets => 0x1001,
@@ -127,6 +147,10 @@ impl ChunkType {
             ChunkType::CompiledPhysicalBones => 0xACDC0001,
             ChunkType::CompiledMorphTargets => 0xACDC0002,
             ChunkType::CompiledMesh => 0xCCCC0000,
+            ChunkType::CompiledBonesLegacy => 0x1021,
+            ChunkType::CompiledPhysicalBonesLegacy => 0x1022,
+            ChunkType::CompiledMorphTargetsLegacy => 0x1023,
+            ChunkType::CompiledMeshLegacy => 0x1024,
             ChunkType::CompiledPhysicsGeometry => 0x1025,
             ChunkType::CompiledIntSkinVertices => 0x1026,
             ChunkType::CompiledExtToIntMap => 0x1027,
@@ -141,14 +165,26 @@ impl ChunkType {
         }
     }
 
+    /// Fold legacy ids onto the variant whose data layout they share
+    pub fn canonical(&self) -> Self {
+        match self {
+            ChunkType::MtlNameLegacy => ChunkType::MtlName,
+            ChunkType::CompiledBonesLegacy => ChunkType::CompiledBones,
+            ChunkType::CompiledPhysicalBonesLegacy => ChunkType::CompiledPhysicalBones,
+            ChunkType::CompiledMorphTargetsLegacy => ChunkType::CompiledMorphTargets,
+            ChunkType::CompiledMeshLegacy => ChunkType::CompiledMesh,
+            other => *other,
+        }
+    }
+
     /// Check if this chunk contains mesh geometry
     pub fn is_mesh(&self) -> bool {
-        matches!(self, ChunkType::Mesh | ChunkType::MeshSubsets | ChunkType::CompiledMesh)
+        matches!(self.canonical(), ChunkType::Mesh | ChunkType::MeshSubsets | ChunkType::CompiledMesh)
     }
 
     /// Check if this chunk contains bone/animation data
     pub fn is_bone(&self) -> bool {
-        matches!(self, 
+        matches!(self.canonical(),
             ChunkType::BoneAnim | ChunkType::BoneNameList | ChunkType::BoneInitialPos |
             ChunkType::BoneMesh | ChunkType::CompiledBones | ChunkType::CompiledPhysicalBones
         )
@@ -170,6 +206,58 @@ pub struct ChunkHeader {
     pub size: u32,
 }
 
+/// A chunk table entry and whether it made it into the model
+#[derive(Debug, Clone)]
+pub struct ChunkRecord {
+    /// Header from the chunk table
+    pub header: ChunkHeader,
+    /// Whether the chunk's data was parsed; `false` for unsupported types
+    /// and chunks that failed to parse
+    pub parsed: bool,
+}
+
+/// Per-type chunk counts, see [`super::CgfModel::chunk_coverage`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChunkCoverage {
+    /// Chunk type
+    pub chunk_type: ChunkType,
+    /// Chunks of this type in the chunk table
+    pub present: usize,
+    /// Chunks whose data was parsed
+    pub parsed: usize,
+    /// Chunks that were unsupported or failed to parse
+    pub skipped: usize,
+}
+
+/// How much of a file's chunk table was parsed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CoverageReport {
+    /// One entry per chunk type present, ordered by type id
+    pub chunk_types: Vec<ChunkCoverage>,
+}
+
+impl CoverageReport {
+    /// Total chunks in the chunk table
+    pub fn present(&self) -> usize {
+        self.chunk_types.iter().map(|c| c.present).sum()
+    }
+
+    /// Total chunks whose data was parsed
+    pub fn parsed(&self) -> usize {
+        self.chunk_types.iter().map(|c| c.parsed).sum()
+    }
+
+    /// Total chunks that were unsupported or failed to parse
+    pub fn skipped(&self) -> usize {
+        self.chunk_types.iter().map(|c| c.skipped).sum()
+    }
+
+    /// Check if every chunk was parsed
+    pub fn is_complete(&self) -> bool {
+        self.skipped() == 0
+    }
+}
+
 /// Parsed chunk with data
 #[derive(Debug)]
 pub enum CgfChunk {
@@ -210,7 +298,7 @@ pub enum CgfChunk {
 }
 
 /// Animation key for bones
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BoneKey {
     /// Time in ticks
     pub time: f32,
@@ -276,6 +364,7 @@ impl From<u32> for DataStreamType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_chunk_type_roundtrip() {
@@ -293,6 +382,42 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn test_chunk_type_raw_id_roundtrip(id in any::<u32>()) {
+            prop_assert_eq!(ChunkType::from_u32(id).to_u32(), id);
+        }
+    }
+
+    #[test]
+    fn test_chunk_type_known_ids_roundtrip() {
+        // Random ids rarely land on a known one, so check those and their neighbours
+        let known = [
+            0x0000, 0x0001, 0x0002, 0x0003, 0x0014, 0x1000, 0x1001, 0x100B, 0x100C,
+            0x1016, 0x1017, 0x1018, 0x1019, 0x101A, 0x101B, 0x101C, 0x1021, 0x1022,
+            0x1023, 0x1024, 0x1025, 0x1026, 0x1027, 0x1028, 0x1029, 0x102A, 0x102B,
+            0x102C, 0x102D, 0x102E, 0xACDC0000, 0xACDC0001, 0xACDC0002, 0xCCCC0000,
+        ];
+        let neighbours = known.iter().flat_map(|&id: &u32| [id.wrapping_sub(1), id, id.wrapping_add(1)]);
+        let edges = [0, u32::MAX, 0x8000_0000, 0xFFFF];
+
+        for id in neighbours.chain(edges) {
+            assert_eq!(ChunkType::from_u32(id).to_u32(), id, "chunk type {:#X} did not round-trip", id);
+        }
+
+        assert_eq!(ChunkType::from_u32(0xBEEF), ChunkType::Unknown(0xBEEF));
+    }
+
+    #[test]
+    fn test_chunk_type_canonical() {
+        assert_eq!(ChunkType::from_u32(0x1024).canonical(), ChunkType::CompiledMesh);
+        assert_eq!(ChunkType::from_u32(0x1021).canonical(), ChunkType::CompiledBones);
+        assert_eq!(ChunkType::from_u32(0x0002).canonical(), ChunkType::MtlName);
+        assert_eq!(ChunkType::Mesh.canonical(), ChunkType::Mesh);
+        assert!(ChunkType::CompiledMeshLegacy.is_mesh());
+        assert!(ChunkType::CompiledBonesLegacy.is_bone());
+    }
+
     #[test]
     fn test_chunk_type_is_mesh() {
         assert!(ChunkType::Mesh.is_mesh());
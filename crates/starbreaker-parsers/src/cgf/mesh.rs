@@ -4,6 +4,12 @@
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
+use thiserror::Error;
+
+use crate::traits::sanitize_f32s;
+
+use super::bones::invert_matrix;
+
 /// A 3D mesh from CGF file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mesh {
@@ -94,6 +100,188 @@ impl Mesh {
         self.bounding_box = Some(BoundingBox { min, max });
     }
 
+    /// Check faces and positions for problems that break normals and exporters
+    ///
+    /// Flags face indices past the end of the vertex list, degenerate faces
+    /// that repeat a vertex index, and vertices with NaN/Inf positions.
+    pub fn validate(&self) -> Result<(), MeshError> {
+        let vertex_count = self.vertices.len();
+        let mut error = MeshError {
+            mesh: self.name.clone(),
+            vertex_count,
+            out_of_range: Vec::new(),
+            degenerate_faces: Vec::new(),
+            non_finite_vertices: Vec::new(),
+        };
+
+        for (face_idx, face) in self.faces.iter().enumerate() {
+            for &index in &face.indices {
+                if index as usize >= vertex_count {
+                    error.out_of_range.push((face_idx, index));
+                }
+            }
+
+            let [a, b, c] = face.indices;
+            if a == b || b == c || a == c {
+                error.degenerate_faces.push(face_idx);
+            }
+        }
+
+        for (vertex_idx, vertex) in self.vertices.iter().enumerate() {
+            if vertex.position.iter().any(|v| !v.is_finite()) {
+                error.non_finite_vertices.push(vertex_idx);
+            }
+        }
+
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Replace NaN/Inf vertex attributes with 0.0
+    ///
+    /// Returns the number of vertices that had at least one value replaced.
+    pub fn sanitize_floats(&mut self) -> usize {
+        self.vertices.iter_mut().map(|v| v.sanitize_floats()).filter(|&changed| changed).count()
+    }
+
+    /// Bake a transform into the vertex data
+    ///
+    /// `m` uses the same layout as bone transforms (translation in `m[3]`).
+    /// Positions get the full matrix, tangents its linear part, and normals
+    /// the inverse-transpose so they stay perpendicular under non-uniform
+    /// scale. A mirroring matrix also reverses face winding and flips the
+    /// tangent handedness. The bounding box is recomputed afterwards.
+    pub fn transform(&mut self, m: [[f32; 4]; 4]) {
+        // Rows of the inverse are the columns of the inverse-transpose.
+        // A singular matrix has no meaningful normal transform; fall back to
+        // the linear part so normals at least follow the rotation.
+        let normal_matrix = invert_matrix(m)
+            .map(|inv| {
+                let mut nm = [[0.0f32; 3]; 3];
+                for (i, row) in nm.iter_mut().enumerate() {
+                    for (j, value) in row.iter_mut().enumerate() {
+                        *value = inv[j][i];
+                    }
+                }
+                nm
+            })
+            .unwrap_or([
+                [m[0][0], m[0][1], m[0][2]],
+                [m[1][0], m[1][1], m[1][2]],
+                [m[2][0], m[2][1], m[2][2]],
+            ]);
+
+        let linear = |v: [f32; 3], rows: [[f32; 3]; 3]| -> [f32; 3] {
+            [
+                v[0] * rows[0][0] + v[1] * rows[1][0] + v[2] * rows[2][0],
+                v[0] * rows[0][1] + v[1] * rows[1][1] + v[2] * rows[2][1],
+                v[0] * rows[0][2] + v[1] * rows[1][2] + v[2] * rows[2][2],
+            ]
+        };
+        let linear_part = [
+            [m[0][0], m[0][1], m[0][2]],
+            [m[1][0], m[1][1], m[1][2]],
+            [m[2][0], m[2][1], m[2][2]],
+        ];
+
+        let det = linear_part[0][0] * (linear_part[1][1] * linear_part[2][2] - linear_part[1][2] * linear_part[2][1])
+            - linear_part[0][1] * (linear_part[1][0] * linear_part[2][2] - linear_part[1][2] * linear_part[2][0])
+            + linear_part[0][2] * (linear_part[1][0] * linear_part[2][1] - linear_part[1][1] * linear_part[2][0]);
+        let mirrored = det < 0.0;
+
+        for vertex in &mut self.vertices {
+            let p = linear(vertex.position, linear_part);
+            vertex.position = [p[0] + m[3][0], p[1] + m[3][1], p[2] + m[3][2]];
+
+            vertex.normal = normalize(linear(vertex.normal, normal_matrix));
+
+            if let Some(t) = &mut vertex.tangent {
+                let [x, y, z] = normalize(linear([t[0], t[1], t[2]], linear_part));
+                let w = if mirrored { -t[3] } else { t[3] };
+                *t = [x, y, z, w];
+            }
+        }
+
+        if mirrored {
+            for face in &mut self.faces {
+                face.indices.swap(1, 2);
+            }
+        }
+
+        self.calculate_bounding_box();
+    }
+
+    /// Check bone indices and weights against a skeleton of `bone_count` bones
+    pub fn validate_skin(&self, bone_count: usize) -> Vec<SkinError> {
+        let mut errors = Vec::new();
+
+        for (vertex_idx, vertex) in self.vertices.iter().enumerate() {
+            if let Some(indices) = vertex.bone_indices {
+                for index in indices {
+                    if index as usize >= bone_count {
+                        errors.push(SkinError::BoneIndexOutOfRange {
+                            mesh: self.name.clone(),
+                            vertex: vertex_idx,
+                            index,
+                            bone_count,
+                        });
+                    }
+                }
+            }
+
+            if let Some(weights) = vertex.bone_weights {
+                let sum: f32 = weights.iter().sum();
+                if (sum - 1.0).abs() > SKIN_WEIGHT_TOLERANCE {
+                    errors.push(SkinError::WeightSum {
+                        mesh: self.name.clone(),
+                        vertex: vertex_idx,
+                        sum,
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// Clamp bone indices to the last bone of a `bone_count` skeleton
+    ///
+    /// Returns the number of vertices that were changed.
+    pub fn clamp_bone_indices(&mut self, bone_count: usize) -> usize {
+        let max = bone_count.saturating_sub(1).min(u8::MAX as usize) as u8;
+        let mut changed = 0;
+
+        for indices in self.vertices.iter_mut().filter_map(|v| v.bone_indices.as_mut()) {
+            if indices.iter().any(|&i| i > max) {
+                for i in indices.iter_mut() {
+                    *i = (*i).min(max);
+                }
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+
+    /// Translate bone indices from external to internal (skeleton) space
+    ///
+    /// `map[external]` is the internal bone index. Indices past the end of
+    /// the map, or mapped past `u8::MAX`, are left as they are. Returns the
+    /// number of indices that couldn't be translated.
+    pub fn remap_bone_indices(&mut self, map: &[u16]) -> usize {
+        let mut untranslated = 0;
+        for index in self.vertices.iter_mut().filter_map(|v| v.bone_indices.as_mut()).flatten() {
+            match map.get(*index as usize).and_then(|&i| u8::try_from(i).ok()) {
+                Some(internal) => *index = internal,
+                None => untranslated += 1,
+            }
+        }
+        untranslated
+    }
+
     /// Flip normals (reverse face winding)
     pub fn flip_normals(&mut self) {
         // Flip vertex normals
@@ -109,6 +297,261 @@ impl Mesh {
         }
     }
 
+    /// Recompute vertex normals from face geometry
+    ///
+    /// With `smooth`, each vertex averages the normals of the faces around it
+    /// that share a smoothing group. A vertex used by faces from different
+    /// groups is duplicated once per extra group, so hard edges between
+    /// groups keep a distinct normal on each side. Without `smooth`, every
+    /// face gets its own vertices and a flat normal. Faces with out-of-range
+    /// indices are left untouched.
+    pub fn recalculate_normals(&mut self, smooth: bool) {
+        let vertex_count = self.vertices.len();
+        let mut slots: std::collections::HashMap<(u32, u32), u32> = std::collections::HashMap::new();
+        let mut claimed = vec![false; vertex_count];
+        let mut sums: Vec<[f32; 3]> = vec![[0.0; 3]; vertex_count];
+
+        for face_idx in 0..self.faces.len() {
+            let face = &self.faces[face_idx];
+            if face.indices.iter().any(|&i| i as usize >= vertex_count) {
+                continue;
+            }
+            let normal = face.calculate_normal(&self.vertices);
+            // Flat shading gives every face its own key, so nothing is shared
+            let group = if smooth { face.smoothing_group } else { face_idx as u32 };
+
+            let mut indices = face.indices;
+            for index in &mut indices {
+                let original = *index;
+                let slot = *slots.entry((original, group)).or_insert_with(|| {
+                    // The first group to reach a vertex keeps it in place
+                    if !claimed[original as usize] {
+                        claimed[original as usize] = true;
+                        original
+                    } else {
+                        self.vertices.push(self.vertices[original as usize].clone());
+                        sums.push([0.0; 3]);
+                        (self.vertices.len() - 1) as u32
+                    }
+                });
+
+                let sum = &mut sums[slot as usize];
+                for axis in 0..3 {
+                    sum[axis] += normal[axis];
+                }
+                *index = slot;
+            }
+            self.faces[face_idx].indices = indices;
+        }
+
+        for (vertex, sum) in self.vertices.iter_mut().zip(&sums) {
+            if *sum != [0.0; 3] {
+                vertex.normal = normalize(*sum);
+            }
+        }
+    }
+
+    /// Compute per-vertex tangents from UV channel 0 for normal mapping
+    ///
+    /// Face tangents and bitangents are accumulated per vertex, then the
+    /// tangent is orthogonalized against the vertex normal and the bitangent's
+    /// side stored as handedness in W. With `split_on_seams`, a vertex whose
+    /// faces disagree on handedness or whose face tangents diverge past
+    /// [`TANGENT_SPLIT_COS`] is duplicated, so each side of a UV seam or
+    /// mirror gets a consistent basis. Duplicates keep all other attributes.
+    /// Faces with out-of-range indices or degenerate UVs don't contribute,
+    /// and vertices only they use keep their existing tangent.
+    pub fn compute_tangents(&mut self, split_on_seams: bool) {
+        struct Basis {
+            slot: u32,
+            tangent: [f32; 3],
+            bitangent: [f32; 3],
+            flipped: bool,
+        }
+
+        let vertex_count = self.vertices.len();
+        // Bases gathered per original vertex; the first one keeps it in place
+        let mut bases: Vec<Vec<Basis>> = (0..vertex_count).map(|_| Vec::new()).collect();
+
+        for face_idx in 0..self.faces.len() {
+            let face = &self.faces[face_idx];
+            if face.indices.iter().any(|&i| i as usize >= vertex_count) {
+                continue;
+            }
+            let Some((tangent, bitangent)) = face_tangent(face, &self.vertices) else {
+                continue;
+            };
+            let flipped = dot(cross(face.calculate_normal(&self.vertices), tangent), bitangent) < 0.0;
+            let direction = normalize(tangent);
+
+            let mut indices = face.indices;
+            for index in &mut indices {
+                let candidates = &mut bases[*index as usize];
+                let matching = candidates.iter().position(|b| {
+                    !split_on_seams
+                        || (b.flipped == flipped && dot(normalize(b.tangent), direction) >= TANGENT_SPLIT_COS)
+                });
+                let basis = match matching {
+                    Some(i) => &mut candidates[i],
+                    None => {
+                        let slot = if candidates.is_empty() {
+                            *index
+                        } else {
+                            self.vertices.push(self.vertices[*index as usize].clone());
+                            (self.vertices.len() - 1) as u32
+                        };
+                        candidates.push(Basis { slot, tangent: [0.0; 3], bitangent: [0.0; 3], flipped });
+                        candidates.last_mut().unwrap()
+                    }
+                };
+
+                for axis in 0..3 {
+                    basis.tangent[axis] += tangent[axis];
+                    basis.bitangent[axis] += bitangent[axis];
+                }
+                *index = basis.slot;
+            }
+            self.faces[face_idx].indices = indices;
+        }
+
+        for basis in bases.iter().flatten() {
+            let vertex = &mut self.vertices[basis.slot as usize];
+            let n = vertex.normal;
+            let d = dot(n, basis.tangent);
+            let t = normalize([
+                basis.tangent[0] - n[0] * d,
+                basis.tangent[1] - n[1] * d,
+                basis.tangent[2] - n[2] * d,
+            ]);
+            // Tangent parallel to the normal; there's no basis to give
+            if dot(t, t) < 0.5 {
+                continue;
+            }
+            let w = if dot(cross(n, t), basis.bitangent) < 0.0 { -1.0 } else { 1.0 };
+            vertex.tangent = Some([t[0], t[1], t[2], w]);
+        }
+    }
+
+    /// Merge vertices whose attributes are all bit-for-bit identical
+    ///
+    /// Faces are remapped to the first copy of each vertex; out-of-range
+    /// indices stay out of range. Subsets keep their index ranges and get
+    /// their vertex ranges recomputed. Returns the number of vertices removed.
+    pub fn weld_vertices(&mut self) -> usize {
+        let mut first_of: std::collections::HashMap<Vec<u32>, u32> = std::collections::HashMap::new();
+        let mut remap = Vec::with_capacity(self.vertices.len());
+        let mut welded = Vec::with_capacity(self.vertices.len());
+
+        for vertex in self.vertices.drain(..) {
+            let next = welded.len() as u32;
+            let index = *first_of.entry(vertex_key(&vertex)).or_insert(next);
+            if index == next {
+                welded.push(vertex);
+            }
+            remap.push(index);
+        }
+
+        let removed = remap.len() - welded.len();
+        self.vertices = welded;
+        for index in self.faces.iter_mut().flat_map(|f| f.indices.iter_mut()) {
+            if let Some(&new) = remap.get(*index as usize) {
+                *index = new;
+            }
+        }
+
+        for subset in &mut self.subsets {
+            let first_face = (subset.first_index / 3) as usize;
+            let end_face = ((subset.first_index + subset.num_indices) / 3) as usize;
+            let range = self.faces.get(first_face..end_face.min(self.faces.len())).unwrap_or_default();
+            let used = range.iter().flat_map(|f| f.indices).filter(|&i| (i as usize) < self.vertices.len());
+            if let (Some(min), Some(max)) = (used.clone().min(), used.max()) {
+                subset.first_vertex = min;
+                subset.num_vertices = max - min + 1;
+            }
+        }
+
+        removed
+    }
+
+    /// Expand into a flat triangle list with three unshared vertices per face
+    ///
+    /// Face `i` uses vertices `3i..3i + 3`, carrying every attribute of the
+    /// original. Faces with out-of-range indices are dropped and subsets are
+    /// shifted to match. See [`Mesh::to_indexed`] for the inverse.
+    pub fn to_non_indexed(&self) -> Mesh {
+        let mut mesh = Mesh::new(self.name.clone());
+        mesh.bounding_box = self.bounding_box;
+
+        // Number of kept faces before each face, for shifting subsets
+        let mut kept_before = Vec::with_capacity(self.faces.len() + 1);
+        for face in &self.faces {
+            kept_before.push(mesh.faces.len() as u32);
+            let Some(vertices) = face.indices.iter()
+                .map(|&i| self.vertices.get(i as usize).cloned())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+
+            let base = mesh.vertices.len() as u32;
+            mesh.vertices.extend(vertices);
+            mesh.faces.push(Face {
+                indices: [base, base + 1, base + 2],
+                ..face.clone()
+            });
+        }
+        kept_before.push(mesh.faces.len() as u32);
+
+        let kept_at = |index: u32| kept_before[((index / 3) as usize).min(self.faces.len())] * 3;
+        mesh.subsets = self.subsets.iter()
+            .map(|subset| {
+                let first = kept_at(subset.first_index);
+                let count = kept_at(subset.first_index + subset.num_indices) - first;
+                MeshSubset {
+                    first_index: first,
+                    num_indices: count,
+                    first_vertex: first,
+                    num_vertices: count,
+                    ..subset.clone()
+                }
+            })
+            .collect();
+
+        mesh
+    }
+
+    /// Re-share identical vertices, undoing [`Mesh::to_non_indexed`]
+    ///
+    /// Delegates to [`Mesh::weld_vertices`].
+    pub fn to_indexed(&mut self) {
+        self.weld_vertices();
+    }
+
+    /// Set each face's `material_id` from the subset whose index range
+    /// holds the face's first index
+    ///
+    /// Faces outside every subset keep their material. Returns how many
+    /// faces that was.
+    pub fn assign_subset_materials(&mut self) -> usize {
+        let mut unassigned = 0;
+        for i in 0..self.faces.len() {
+            match self.subset_for_face(i).map(|s| s.material_id) {
+                Some(material_id) => self.faces[i].material_id = material_id,
+                None => unassigned += 1,
+            }
+        }
+        unassigned
+    }
+
+    /// Find the subset whose index range holds the face's first index
+    pub fn subset_for_face(&self, face_index: usize) -> Option<&MeshSubset> {
+        let first_index = face_index as u64 * 3;
+        self.subsets.iter().find(|s| {
+            let start = s.first_index as u64;
+            (start..start + s.num_indices as u64).contains(&first_index)
+        })
+    }
+
     /// Get all unique material IDs used by faces
     pub fn material_ids(&self) -> Vec<u32> {
         let mut ids: Vec<u32> = self.faces.iter()
@@ -206,6 +649,26 @@ impl Mesh {
     }
 }
 
+/// Bit pattern of every vertex attribute, for exact duplicate detection
+fn vertex_key(vertex: &Vertex) -> Vec<u32> {
+    let mut key: Vec<u32> = vertex.position.iter()
+        .chain(&vertex.normal)
+        .chain(vertex.uv.iter().flatten())
+        .map(|f| f.to_bits())
+        .collect();
+    // Channel count keeps [a, b] + [] apart from [a] + [b] style collisions
+    key.push(vertex.uv.len() as u32);
+    key.extend(vertex.color.map(u32::from_le_bytes));
+    key.push(vertex.color.is_some() as u32);
+    key.extend(vertex.tangent.iter().flatten().map(|f| f.to_bits()));
+    key.push(vertex.tangent.is_some() as u32);
+    key.extend(vertex.bone_weights.iter().flatten().map(|f| f.to_bits()));
+    key.push(vertex.bone_weights.is_some() as u32);
+    key.extend(vertex.bone_indices.map(u32::from_le_bytes));
+    key.push(vertex.bone_indices.is_some() as u32);
+    key
+}
+
 /// A single vertex with all attributes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vertex {
@@ -215,8 +678,8 @@ pub struct Vertex {
     pub normal: [f32; 3],
     /// UV coordinates (multiple channels)
     pub uv: SmallVec<[f32; 2]>,
-    /// Vertex color (RGBA)
-    pub color: SmallVec<[u8; 4]>,
+    /// Vertex color (RGBA, 0..255)
+    pub color: Option<[u8; 4]>,
     /// Tangent vector (with handedness in W)
     pub tangent: Option<[f32; 4]>,
     /// Bone weights (up to 4 influences)
@@ -248,6 +711,27 @@ impl Vertex {
         }
     }
 
+    /// Vertex color as RGBA floats normalized to 0..1
+    pub fn color_rgba_f32(&self) -> Option<[f32; 4]> {
+        self.color.map(|c| c.map(|channel| channel as f32 / 255.0))
+    }
+
+    /// Replace NaN/Inf attributes with 0.0, returning whether anything changed
+    pub fn sanitize_floats(&mut self) -> bool {
+        let mut changed = sanitize_f32s(&mut self.position);
+        changed |= sanitize_f32s(&mut self.normal);
+        for uv in self.uv.iter_mut() {
+            changed |= sanitize_f32s(uv);
+        }
+        if let Some(ref mut tangent) = self.tangent {
+            changed |= sanitize_f32s(tangent);
+        }
+        if let Some(ref mut weights) = self.bone_weights {
+            changed |= sanitize_f32s(weights);
+        }
+        changed
+    }
+
     /// Normalize bone weights to sum to 1.0
     pub fn normalize_bone_weights(&mut self) {
         if let Some(ref mut weights) = self.bone_weights {
@@ -309,6 +793,65 @@ impl Face {
     }
 }
 
+/// Problems found by [`Mesh::validate`]
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error(
+    "Mesh '{mesh}' is invalid: {} out-of-range indices, {} degenerate faces, {} non-finite positions",
+    out_of_range.len(), degenerate_faces.len(), non_finite_vertices.len()
+)]
+pub struct MeshError {
+    /// Name of the mesh
+    pub mesh: String,
+    /// Number of vertices in the mesh
+    pub vertex_count: usize,
+    /// `(face index, vertex index)` for each index past the end of the vertex list
+    pub out_of_range: Vec<(usize, u32)>,
+    /// Faces that repeat a vertex index
+    pub degenerate_faces: Vec<usize>,
+    /// Vertices with a NaN/Inf position component
+    pub non_finite_vertices: Vec<usize>,
+}
+
+impl MeshError {
+    /// Whether no problems were recorded
+    pub fn is_empty(&self) -> bool {
+        self.out_of_range.is_empty()
+            && self.degenerate_faces.is_empty()
+            && self.non_finite_vertices.is_empty()
+    }
+
+    /// Whether the mesh can't be exported as-is
+    ///
+    /// Out-of-range indices would read past the vertex buffer; degenerate
+    /// faces and bad positions only produce visual artifacts.
+    pub fn is_fatal(&self) -> bool {
+        !self.out_of_range.is_empty()
+    }
+}
+
+/// How far bone weights may stray from summing to 1.0
+pub const SKIN_WEIGHT_TOLERANCE: f32 = 0.01;
+
+/// A skinning problem found by [`CgfModel::validate_skin`](super::CgfModel::validate_skin)
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum SkinError {
+    #[error("Mesh '{mesh}' vertex {vertex}: bone index {index} out of range ({bone_count} bones)")]
+    BoneIndexOutOfRange {
+        mesh: String,
+        vertex: usize,
+        index: u8,
+        bone_count: usize,
+    },
+
+    #[error("Mesh '{mesh}' vertex {vertex}: bone weights sum to {sum}")]
+    WeightSum { mesh: String, vertex: usize, sum: f32 },
+}
+
+/// Reorder a BGRA color quad to RGBA
+pub fn bgra_to_rgba(color: [u8; 4]) -> [u8; 4] {
+    [color[2], color[1], color[0], color[3]]
+}
+
 /// Mesh subset (for multi-material meshes)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeshSubset {
@@ -404,6 +947,54 @@ pub struct SubMesh {
     pub material_index: u32,
 }
 
+/// Minimum cosine between face tangents sharing a vertex in
+/// [`Mesh::compute_tangents`] before the vertex is split (60 degrees)
+pub const TANGENT_SPLIT_COS: f32 = 0.5;
+
+/// Unnormalized tangent and bitangent of a face from UV channel 0
+///
+/// Returns `None` when a corner has no UVs or the UV triangle is degenerate.
+fn face_tangent(face: &Face, vertices: &[Vertex]) -> Option<([f32; 3], [f32; 3])> {
+    let [a, b, c] = face.indices.map(|i| &vertices[i as usize]);
+    let (uv_a, uv_b, uv_c) = (*a.uv.first()?, *b.uv.first()?, *c.uv.first()?);
+
+    let e1 = [b.position[0] - a.position[0], b.position[1] - a.position[1], b.position[2] - a.position[2]];
+    let e2 = [c.position[0] - a.position[0], c.position[1] - a.position[1], c.position[2] - a.position[2]];
+    let (du1, dv1) = (uv_b[0] - uv_a[0], uv_b[1] - uv_a[1]);
+    let (du2, dv2) = (uv_c[0] - uv_a[0], uv_c[1] - uv_a[1]);
+
+    let det = du1 * dv2 - du2 * dv1;
+    if det.abs() < f32::EPSILON {
+        return None;
+    }
+    let r = 1.0 / det;
+    let tangent = [0, 1, 2].map(|i| (e1[i] * dv2 - e2[i] * dv1) * r);
+    let bitangent = [0, 1, 2].map(|i| (e2[i] * du1 - e1[i] * du2) * r);
+    Some((tangent, bitangent))
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Normalize a vector, leaving zero-length vectors untouched
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len > f32::EPSILON {
+        [v[0] / len, v[1] / len, v[2] / len]
+    } else {
+        v
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -434,6 +1025,46 @@ mod tests {
         assert_eq!(mesh.triangle_count(), 2);
     }
 
+    #[test]
+    fn test_sanitize_floats() {
+        let mut mesh = make_test_mesh();
+        mesh.vertices[1].position[0] = f32::NAN;
+        mesh.vertices[2].normal[1] = f32::NEG_INFINITY;
+
+        assert_eq!(mesh.sanitize_floats(), 2);
+        assert_eq!(mesh.vertices[1].position, [0.0, 0.0, 0.0]);
+        assert_eq!(mesh.vertices[2].normal, [0.0, 0.0, 0.0]);
+        assert_eq!(mesh.sanitize_floats(), 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(make_test_mesh().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_out_of_range_index() {
+        let mut mesh = make_test_mesh();
+        mesh.faces.push(Face::new(0, 2, 7));
+
+        let err = mesh.validate().unwrap_err();
+        assert_eq!(err.out_of_range, vec![(2, 7)]);
+        assert!(err.degenerate_faces.is_empty());
+        assert!(err.is_fatal());
+    }
+
+    #[test]
+    fn test_validate_degenerate_face() {
+        let mut mesh = make_test_mesh();
+        mesh.faces.push(Face::new(1, 1, 3));
+        mesh.vertices[3].position[2] = f32::NAN;
+
+        let err = mesh.validate().unwrap_err();
+        assert_eq!(err.degenerate_faces, vec![2]);
+        assert_eq!(err.non_finite_vertices, vec![3]);
+        assert!(!err.is_fatal());
+    }
+
     #[test]
     fn test_bounding_box() {
         let mut mesh = make_test_mesh();
@@ -445,6 +1076,24 @@ mod tests {
         assert_eq!(bb.center(), [0.5, 0.5, 0.0]);
     }
 
+    #[test]
+    fn test_subset_for_face() {
+        let mut mesh = make_test_mesh();
+        let subset = |first_index, material_id| MeshSubset {
+            first_index,
+            num_indices: 3,
+            first_vertex: 0,
+            num_vertices: 4,
+            material_id,
+            bounding_box: None,
+        };
+        mesh.subsets = vec![subset(0, 7), subset(3, 9)];
+
+        assert_eq!(mesh.subset_for_face(0).map(|s| s.material_id), Some(7));
+        assert_eq!(mesh.subset_for_face(1).map(|s| s.material_id), Some(9));
+        assert!(mesh.subset_for_face(2).is_none());
+    }
+
     #[test]
     fn test_face_normal() {
         let mesh = make_test_mesh();
@@ -474,4 +1123,191 @@ mod tests {
         let sum: f32 = weights.iter().sum();
         assert!((sum - 1.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_bgra_color_normalizes_to_rgba() {
+        // Opaque orange stored as BGRA
+        let mut vertex = Vertex::new([0.0, 0.0, 0.0]);
+        vertex.color = Some(bgra_to_rgba([0, 128, 255, 255]));
+
+        let rgba = vertex.color_rgba_f32().unwrap();
+        assert_eq!(rgba, [1.0, 128.0 / 255.0, 0.0, 1.0]);
+
+        assert!(Vertex::new([0.0, 0.0, 0.0]).color_rgba_f32().is_none());
+    }
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        for i in 0..3 {
+            assert!((actual[i] - expected[i]).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_transform_rotate_translate() {
+        let mut mesh = make_test_mesh();
+        for vertex in &mut mesh.vertices {
+            vertex.normal = [0.0, 0.0, 1.0];
+        }
+
+        // 90 degrees about X (Y -> Z, Z -> -Y), then translate by (10, 0, 0)
+        mesh.transform([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, -1.0, 0.0, 0.0],
+            [10.0, 0.0, 0.0, 1.0],
+        ]);
+
+        assert_close(mesh.vertices[0].position, [10.0, 0.0, 0.0]);
+        assert_close(mesh.vertices[3].position, [11.0, 0.0, 1.0]);
+        for vertex in &mesh.vertices {
+            assert_close(vertex.normal, [0.0, -1.0, 0.0]);
+        }
+
+        let bbox = mesh.bounding_box.as_ref().unwrap();
+        assert_close(bbox.min, [10.0, 0.0, 0.0]);
+        assert_close(bbox.max, [11.0, 0.0, 1.0]);
+        assert_eq!(mesh.faces[0].indices, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_transform_non_uniform_scale_normals() {
+        let mut mesh = make_test_mesh();
+        let s = std::f32::consts::FRAC_1_SQRT_2;
+        mesh.vertices[0].normal = [s, s, 0.0];
+
+        // Stretch X by 2: the normal must lean towards Y to stay perpendicular
+        mesh.transform([
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        let len = (0.25f32 + 1.0).sqrt();
+        assert_close(mesh.vertices[0].normal, [0.5 / len, 1.0 / len, 0.0]);
+        assert_close(mesh.vertices[3].position, [2.0, 1.0, 0.0]);
+    }
+
+    /// Two triangles folded 90 degrees along the shared edge 1-2
+    fn make_folded_mesh(second_group: u32) -> Mesh {
+        let mut mesh = Mesh::new("folded");
+        mesh.vertices = vec![
+            Vertex::new([0.0, 0.0, 0.0]),
+            Vertex::new([1.0, 0.0, 0.0]),
+            Vertex::new([1.0, 1.0, 0.0]),
+            Vertex::new([1.0, 0.0, -1.0]),
+        ];
+        let mut second = Face::new(1, 3, 2);
+        second.smoothing_group = second_group;
+        mesh.faces = vec![Face::new(0, 1, 2), second];
+        mesh
+    }
+
+    #[test]
+    fn test_recalculate_normals_splits_smoothing_groups() {
+        let mut mesh = make_folded_mesh(1);
+        mesh.recalculate_normals(true);
+
+        // Vertices 1 and 2 are duplicated for the second group
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.faces[0].indices, [0, 1, 2]);
+        assert_eq!(mesh.faces[1].indices, [4, 3, 5]);
+        assert_eq!(mesh.vertices[4].position, mesh.vertices[1].position);
+        assert_eq!(mesh.vertices[5].position, mesh.vertices[2].position);
+
+        assert_close(mesh.vertices[1].normal, [0.0, 0.0, 1.0]);
+        assert_close(mesh.vertices[4].normal, [1.0, 0.0, 0.0]);
+        assert_close(mesh.vertices[5].normal, [1.0, 0.0, 0.0]);
+
+        // Same group: the shared edge is smoothed, nothing is split
+        let mut smooth = make_folded_mesh(0);
+        smooth.recalculate_normals(true);
+        assert_eq!(smooth.vertices.len(), 4);
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert_close(smooth.vertices[1].normal, [half, 0.0, half]);
+    }
+
+    #[test]
+    fn test_non_indexed_round_trip() {
+        let mut quad = make_test_mesh();
+        for (i, vertex) in quad.vertices.iter_mut().enumerate() {
+            vertex.uv[0] = [i as f32 * 0.25, 1.0];
+            vertex.color = Some([i as u8, 0, 0, 255]);
+            vertex.tangent = Some([1.0, 0.0, 0.0, -1.0]);
+        }
+        quad.faces[1].material_id = 3;
+        quad.subsets = vec![MeshSubset {
+            first_index: 3,
+            num_indices: 3,
+            first_vertex: 1,
+            num_vertices: 3,
+            material_id: 3,
+            bounding_box: None,
+        }];
+
+        let mut flat = quad.to_non_indexed();
+        assert_eq!(flat.vertices.len(), 6);
+        assert_eq!(flat.indices_flat(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(flat.faces[1].material_id, 3);
+        assert_eq!(flat.vertices[4].color, Some([3, 0, 0, 255]));
+        assert_eq!(flat.vertices[4].uv[0], [0.75, 1.0]);
+        assert_eq!((flat.subsets[0].first_vertex, flat.subsets[0].num_vertices), (3, 3));
+
+        flat.to_indexed();
+        assert_eq!(flat.vertices.len(), 4);
+        assert_eq!(flat.indices_flat(), quad.indices_flat());
+        assert_eq!(flat.vertices[3].color, Some([3, 0, 0, 255]));
+        assert_eq!(flat.vertices[3].tangent, Some([1.0, 0.0, 0.0, -1.0]));
+        assert_eq!((flat.subsets[0].first_vertex, flat.subsets[0].num_vertices), (1, 3));
+    }
+
+    #[test]
+    fn test_tangents_split_on_uv_seam() {
+        // The second face mirrors its UVs, making the shared edge a seam
+        let mut mesh = make_test_mesh();
+        for (vertex, uv) in mesh.vertices.iter_mut().zip([[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [0.0, 0.0]]) {
+            vertex.normal = [0.0, 0.0, 1.0];
+            vertex.uv[0] = uv;
+        }
+
+        let mut shared = mesh.clone();
+        shared.compute_tangents(false);
+        assert_eq!(shared.vertices.len(), 4);
+
+        mesh.compute_tangents(true);
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.faces[0].indices, [0, 1, 2]);
+        assert_eq!(mesh.faces[1].indices, [4, 3, 5]);
+        for (copy, original) in [(4, 1), (5, 2)] {
+            assert_eq!(mesh.vertices[copy].position, mesh.vertices[original].position);
+            assert_eq!(mesh.vertices[copy].normal, mesh.vertices[original].normal);
+        }
+        assert_eq!(mesh.vertices[1].tangent, Some([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(mesh.vertices[4].tangent, Some([0.0, -1.0, 0.0, -1.0]));
+
+        // Continuous UVs need no splitting
+        let mut plane = make_test_mesh();
+        for vertex in &mut plane.vertices {
+            vertex.normal = [0.0, 0.0, 1.0];
+            vertex.uv[0] = [vertex.position[0], vertex.position[1]];
+        }
+        plane.compute_tangents(true);
+        assert_eq!(plane.vertices.len(), 4);
+        assert!(plane.vertices.iter().all(|v| v.tangent == Some([1.0, 0.0, 0.0, 1.0])));
+    }
+
+    #[test]
+    fn test_weld_keeps_distinct_attributes() {
+        let mut mesh = make_test_mesh();
+        mesh.vertices.push(Vertex::new([1.0, 1.0, 0.0]));
+        let mut seam = Vertex::new([1.0, 1.0, 0.0]);
+        seam.uv[0] = [0.5, 0.5];
+        mesh.vertices.push(seam);
+        mesh.faces.push(Face::new(4, 5, 9));
+
+        assert_eq!(mesh.weld_vertices(), 1);
+        assert_eq!(mesh.vertices.len(), 5);
+        // The UV seam copy survives, the out-of-range index is left alone
+        assert_eq!(mesh.faces[2].indices, [3, 4, 9]);
+    }
 }
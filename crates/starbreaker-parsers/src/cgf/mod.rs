@@ -29,10 +29,12 @@
 mod chunks;
 mod mesh;
 mod bones;
+mod animation;
 
-pub use chunks::{ChunkType, ChunkHeader, CgfChunk};
-pub use mesh::{Mesh, Vertex, Face, SubMesh, MeshSubset};
-pub use bones::{Skeleton, Bone, BonePhysics};
+pub use chunks::{ChunkType, ChunkHeader, ChunkRecord, ChunkCoverage, CoverageReport, CgfChunk, BoneKey};
+pub use mesh::{Mesh, MeshError, SkinError, Vertex, Face, SubMesh, MeshSubset, BoundingBox, bgra_to_rgba, SKIN_WEIGHT_TOLERANCE, TANGENT_SPLIT_COS};
+pub use bones::{Skeleton, Bone, BonePhysics, invert_matrix, matrix_to_quaternion};
+pub use animation::{Animation, BoneTrack};
 
 use std::io::{Read, Seek, SeekFrom};
 use std::collections::HashMap;
@@ -41,13 +43,14 @@ use rayon::prelude::*;
 
 use crate::traits::{
     Parser, ParseResult, ParseError,
-    ParseOptions, ParseProgress, ParsePhase, ProgressCallback
+    ParseOptions, ParseProgress, ParsePhase, ProgressCallback,
+    ParseReport, ParseWarning,
 };
 
 /// CGF file magic signatures
-const CRYTEK_MAGIC: &[u8; 8] = b"CryTek\0\0";
-const IVO_MAGIC: &[u8; 4] = b"#ivo";
-const CRCH_MAGIC: &[u8; 4] = b"CrCh";
+pub(crate) const CRYTEK_MAGIC: &[u8; 8] = b"CryTek\0\0";
+pub(crate) const IVO_MAGIC: &[u8; 4] = b"#ivo";
+pub(crate) const CRCH_MAGIC: &[u8; 4] = b"CrCh";
 
 /// CGF file versions
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -67,6 +70,8 @@ pub struct CgfModel {
     pub version: CgfVersion,
     /// All chunks in the file
     pub chunks: Vec<CgfChunk>,
+    /// Every chunk table entry, including chunks that were skipped
+    pub chunk_records: Vec<ChunkRecord>,
     /// Mesh data (extracted from mesh chunks)
     pub meshes: Vec<Mesh>,
     /// Material references
@@ -79,6 +84,9 @@ pub struct CgfModel {
     pub morph_targets: Vec<MorphTarget>,
     /// Physics data
     pub physics: Option<PhysicsProxy>,
+    /// External-to-internal bone index map from the ExtToIntMap chunk
+    /// (empty if absent); parsing has already applied it to the meshes
+    pub ext_to_int_map: Vec<u16>,
 }
 
 impl CgfModel {
@@ -87,12 +95,14 @@ impl CgfModel {
         Self {
             version,
             chunks: Vec::new(),
+            chunk_records: Vec::new(),
             meshes: Vec::new(),
             materials: Vec::new(),
             skeleton: None,
             nodes: Vec::new(),
             morph_targets: Vec::new(),
             physics: None,
+            ext_to_int_map: Vec::new(),
         }
     }
 
@@ -106,11 +116,132 @@ impl CgfModel {
         self.meshes.iter().map(|m| m.faces.len()).sum()
     }
 
+    /// Collapse every mesh into one, keeping material assignment in subsets
+    ///
+    /// Meshes are concatenated with [`Mesh::merge`]. A mesh's own subsets are
+    /// carried over with their offsets shifted; meshes without subsets get
+    /// one subset per run of faces sharing a material id.
+    pub fn merge_meshes(&self) -> Mesh {
+        let mut merged = Mesh::new("merged");
+
+        for mesh in &self.meshes {
+            let vertex_offset = merged.vertices.len() as u32;
+            let index_offset = merged.faces.len() as u32 * 3;
+            merged.merge(mesh);
+
+            if !mesh.subsets.is_empty() {
+                merged.subsets.extend(mesh.subsets.iter().map(|subset| MeshSubset {
+                    first_index: subset.first_index + index_offset,
+                    first_vertex: subset.first_vertex + vertex_offset,
+                    ..subset.clone()
+                }));
+                continue;
+            }
+
+            let mut start = 0;
+            while start < mesh.faces.len() {
+                let material_id = mesh.faces[start].material_id;
+                let end = mesh.faces[start..].iter()
+                    .position(|f| f.material_id != material_id)
+                    .map_or(mesh.faces.len(), |offset| start + offset);
+
+                let mut bounds: Option<BoundingBox> = None;
+                for &idx in mesh.faces[start..end].iter().flat_map(|f| f.indices.iter()) {
+                    if let Some(vertex) = mesh.vertices.get(idx as usize) {
+                        match &mut bounds {
+                            Some(bbox) => bbox.expand(vertex.position),
+                            None => bounds = Some(BoundingBox::new(vertex.position, vertex.position)),
+                        }
+                    }
+                }
+
+                merged.subsets.push(MeshSubset {
+                    first_index: index_offset + start as u32 * 3,
+                    num_indices: (end - start) as u32 * 3,
+                    first_vertex: vertex_offset,
+                    num_vertices: mesh.vertices.len() as u32,
+                    material_id,
+                    bounding_box: bounds,
+                });
+                start = end;
+            }
+        }
+
+        merged
+    }
+
+    /// Get helper/dummy nodes (locators, hardpoints) in file order
+    pub fn helpers(&self) -> Vec<&Node> {
+        self.nodes.iter().filter(|n| n.is_helper()).collect()
+    }
+
     /// Check if model has skeletal animation data
     pub fn is_skinned(&self) -> bool {
         self.skeleton.is_some() && self.meshes.iter().any(|m| m.has_bone_weights())
     }
 
+    /// Check every mesh's bone indices and weights against the skeleton
+    ///
+    /// Reports indices at or past the skeleton's bone count and weights that
+    /// don't sum to ~1.0. Models without a skeleton report nothing.
+    pub fn validate_skin(&self) -> Vec<SkinError> {
+        let Some(skeleton) = &self.skeleton else {
+            return Vec::new();
+        };
+
+        self.meshes.iter()
+            .flat_map(|mesh| mesh.validate_skin(skeleton.bone_count()))
+            .collect()
+    }
+
+    /// Remap every mesh's bone indices through [`ext_to_int_map`](Self::ext_to_int_map)
+    ///
+    /// Parsing calls this once all chunks are read, so it only needs calling
+    /// again for meshes added afterwards. Does nothing without a map.
+    /// Returns the number of indices the map couldn't translate.
+    pub fn apply_ext_to_int_map(&mut self) -> usize {
+        if self.ext_to_int_map.is_empty() {
+            return 0;
+        }
+        let map = &self.ext_to_int_map;
+        self.meshes.iter_mut().map(|mesh| mesh.remap_bone_indices(map)).sum()
+    }
+
+    /// Count, per chunk type, how many chunks were present, parsed and skipped
+    pub fn chunk_coverage(&self) -> CoverageReport {
+        let mut by_type: HashMap<ChunkType, ChunkCoverage> = HashMap::new();
+        for record in &self.chunk_records {
+            let chunk_type = record.header.chunk_type;
+            let coverage = by_type.entry(chunk_type).or_insert(ChunkCoverage {
+                chunk_type,
+                present: 0,
+                parsed: 0,
+                skipped: 0,
+            });
+            coverage.present += 1;
+            if record.parsed {
+                coverage.parsed += 1;
+            } else {
+                coverage.skipped += 1;
+            }
+        }
+
+        let mut chunk_types: Vec<ChunkCoverage> = by_type.into_values().collect();
+        chunk_types.sort_by_key(|c| c.chunk_type.to_u32());
+        CoverageReport { chunk_types }
+    }
+
+    /// Name of the material a face's `material_id` refers to
+    ///
+    /// Material ids index the sub-materials of a multi-material, so a
+    /// material with sub-materials is searched through them rather than
+    /// matched by its own `index`.
+    pub fn material_name(&self, material_id: u32) -> Option<&str> {
+        self.materials.iter()
+            .find_map(|m| m.find_by_index(material_id))
+            .map(|m| m.name.as_str())
+    }
+
     /// Get all unique texture paths referenced by materials
     pub fn texture_paths(&self) -> Vec<&str> {
         let mut paths: Vec<&str> = self.materials.iter()
@@ -140,6 +271,14 @@ pub struct MaterialRef {
 }
 
 impl MaterialRef {
+    /// Find the leaf material with this `index`, descending into sub-materials
+    pub fn find_by_index(&self, index: u32) -> Option<&MaterialRef> {
+        if self.sub_materials.is_empty() {
+            return (self.index == index).then_some(self);
+        }
+        self.sub_materials.iter().find_map(|sub| sub.find_by_index(index))
+    }
+
     /// Get all texture paths
     pub fn texture_paths(&self) -> Vec<&str> {
         let mut paths = Vec::new();
@@ -211,6 +350,19 @@ impl Node {
     pub fn is_root(&self) -> bool {
         self.parent_id == 0
     }
+
+    /// Check if this is a helper/dummy node (a transform with no mesh)
+    pub fn is_helper(&self) -> bool {
+        self.mesh_index.is_none()
+    }
+
+    /// Check if this helper looks like a mount point (weapons, thrusters, ...)
+    ///
+    /// Star Citizen names these `hardpoint_*` or `hp_*`.
+    pub fn is_hardpoint(&self) -> bool {
+        let name = self.name.to_lowercase();
+        self.is_helper() && (name.starts_with("hardpoint") || name.starts_with("hp_"))
+    }
 }
 
 /// Morph target for facial animation
@@ -254,11 +406,6 @@ pub enum PhysicsProxyType {
 pub struct CgfParser;
 
 impl CgfParser {
-    fn parse_chunks_parallel(&self, chunks: &[ChunkHeader], data: &[u8]) -> ParseResult<Vec<Chunk>> {
-        chunks.par_iter()
-            .map(|header| self.parse_chunk(header, data))
-            .collect()
-    }
     /// Create a new CGF parser
     pub fn new() -> Self {
         Self
@@ -290,7 +437,7 @@ impl CgfParser {
             return Err(ParseError::InvalidMagic {
                 expected: CRYTEK_MAGIC.to_vec(),
                 found: magic.to_vec(),
-            });
+            }.at_offset(0));
         };
 
         // Read rest of header
@@ -678,24 +825,17 @@ impl CgfParser {
                 }
             }
 
-            // Calculate inverse bind pose (transpose for simplified inverse)
-            let mut inverse_bind_pose = [[0.0f32; 4]; 4];
-            for row in 0..4 {
-                for col in 0..4 {
-                    inverse_bind_pose[row][col] = bind_pose[col][row];
-                }
-            }
-
-            let bone = Bone {
+            let mut bone = Bone {
                 name,
                 parent_index,
                 controller_id,
                 local_transform,
                 bind_pose,
-                inverse_bind_pose,
+                inverse_bind_pose: bind_pose,
                 physics: None,
                 limits: None,
             };
+            bone.calculate_inverse_bind_pose();
 
             skeleton.add_bone(bone);
         }
@@ -707,10 +847,14 @@ impl CgfParser {
 
     /// Parse CompiledMesh chunk data (0xCCCC0000)
     /// This is the optimized runtime mesh format
+    ///
+    /// Colors are returned as RGBA; pass `bgra_colors` when the stream holds
+    /// CryEngine's native BGRA order.
     fn parse_compiled_mesh_chunk<R: Read + Seek>(
         &self,
         reader: &mut R,
         header: &ChunkHeader,
+        bgra_colors: bool,
     ) -> ParseResult<Mesh> {
         reader.seek(SeekFrom::Start(header.offset as u64))?;
 
@@ -782,12 +926,12 @@ impl CgfParser {
                     }
                 }
                 3 => {
-                    // Color stream (already in u8 RGBA format)
+                    // Color stream (u8 per channel, RGBA or BGRA)
                     colors.reserve(vert_count);
                     for _ in 0..vert_count {
                         let mut color_buf = [0u8; 4];
                         reader.read_exact(&mut color_buf)?;
-                        colors.push(color_buf);
+                        colors.push(if bgra_colors { bgra_to_rgba(color_buf) } else { color_buf });
                     }
                 }
                 12 => {
@@ -896,6 +1040,95 @@ impl CgfParser {
         })
     }
 
+    /// Parse CompiledIntSkinVertices chunk data (0x1026)
+    ///
+    /// Skins that keep skinning out of the compiled mesh store it here: a
+    /// 32-byte header, then 64 bytes per vertex holding two obsolete
+    /// positions around the real one, four `u16` bone ids, four weights and
+    /// a color. Only the bone ids and weights are kept.
+    fn parse_int_skin_vertices_chunk<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        header: &ChunkHeader,
+    ) -> ParseResult<Vec<IntSkinVertex>> {
+        const HEADER_SIZE: u32 = 32;
+        const VERTEX_SIZE: usize = 64;
+
+        if header.size < HEADER_SIZE {
+            return Err(ParseError::InvalidStructure(format!(
+                "IntSkinVertices chunk of {} bytes is smaller than its header",
+                header.size
+            )));
+        }
+
+        reader.seek(SeekFrom::Start(header.offset as u64 + HEADER_SIZE as u64))?;
+        let count = (header.size - HEADER_SIZE) as usize / VERTEX_SIZE;
+        let mut data = vec![0u8; count * VERTEX_SIZE];
+        reader.read_exact(&mut data)?;
+
+        Ok(data.chunks_exact(VERTEX_SIZE)
+            .map(|v| IntSkinVertex {
+                // Clamped to u8 like the compiled mesh skin stream
+                bone_indices: [0, 1, 2, 3].map(|i| u16::from_le_bytes([v[36 + i * 2], v[37 + i * 2]]).min(255) as u8),
+                bone_weights: [0, 1, 2, 3].map(|i| {
+                    f32::from_le_bytes([v[44 + i * 4], v[45 + i * 4], v[46 + i * 4], v[47 + i * 4]])
+                }),
+            })
+            .collect())
+    }
+
+    /// Copy weights from an IntSkinVertices chunk onto meshes that carry none
+    ///
+    /// Vertices pair up by index, so a mesh whose vertex count differs from
+    /// the chunk's is left unskinned and reported.
+    fn merge_int_skin_vertices(
+        model: &mut CgfModel,
+        skin: &[IntSkinVertex],
+        offset: u64,
+        warnings: &mut Vec<ParseWarning>,
+    ) {
+        for mesh in model.meshes.iter_mut().filter(|m| !m.vertices.is_empty() && !m.has_bone_weights()) {
+            if mesh.vertices.len() != skin.len() {
+                warnings.push(ParseWarning {
+                    offset,
+                    context: "IntSkinVertices".to_string(),
+                    reason: format!(
+                        "{} has {} vertices but the skin chunk has {}; left unskinned",
+                        mesh.name,
+                        mesh.vertices.len(),
+                        skin.len()
+                    ),
+                });
+                continue;
+            }
+            for (vertex, skin_vertex) in mesh.vertices.iter_mut().zip(skin) {
+                vertex.bone_indices = Some(skin_vertex.bone_indices);
+                vertex.bone_weights = Some(skin_vertex.bone_weights);
+            }
+        }
+    }
+
+    /// Parse the external-to-internal bone index map, one `u16` per external bone
+    fn parse_ext_to_int_map_chunk<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        header: &ChunkHeader,
+    ) -> ParseResult<Vec<u16>> {
+        if header.size == 0 {
+            return Err(ParseError::InvalidStructure(
+                "ExtToIntMap chunk has no size".to_string()
+            ));
+        }
+
+        reader.seek(SeekFrom::Start(header.offset as u64))?;
+        let mut data = vec![0u8; header.size as usize & !1];
+        reader.read_exact(&mut data)?;
+
+        Ok(data.chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .collect())
+    }
+
     /// Parse CompiledMorphTargets chunk data (0xACDC0002)
     /// Contains blend shape/morph target data for facial animation
     fn parse_compiled_morph_targets_chunk<R: Read + Seek>(
@@ -977,36 +1210,161 @@ impl CgfParser {
 
         Ok(morph_targets)
     }
-}
 
-impl Default for CgfParser {
-    fn default() -> Self {
-        Self::new()
+    /// Parse a model, collecting a warning for every chunk that fails
+    ///
+    /// Unlike `parse_with_options`, which silently drops broken chunks,
+    /// this reports each one with its offset and the reason it failed.
+    pub fn parse_with_report<R: Read + Seek>(
+        &self,
+        reader: R,
+        options: &ParseOptions,
+    ) -> ParseResult<ParseReport<CgfModel>> {
+        let mut warnings = Vec::new();
+        let output = self.parse_impl(reader, options, None, &mut warnings)?;
+        Ok(ParseReport { output, warnings })
     }
-}
 
-impl Parser for CgfParser {
-    type Output = CgfModel;
+    /// Apply `ParseOptions::sanitize_floats` to a freshly parsed mesh
+    fn sanitize_mesh(
+        mesh: &mut Mesh,
+        options: &ParseOptions,
+        chunk_header: &ChunkHeader,
+        warnings: &mut Vec<ParseWarning>,
+    ) {
+        if !options.sanitize_floats {
+            return;
+        }
 
-    fn extensions(&self) -> &[&str] {
-        &["cgf", "cga", "skin", "chr"]
+        let count = mesh.sanitize_floats();
+        if count > 0 {
+            warnings.push(ParseWarning::sanitized(
+                chunk_header.offset as u64,
+                format!("{:?} chunk {} ({} vertices)", chunk_header.chunk_type, chunk_header.id, count),
+            ));
+        }
     }
 
-    fn magic_bytes(&self) -> Option<&[u8]> {
-        // Can't return single magic - multiple formats supported
-        None
+    /// Decode one chunk from a reader holding the whole file
+    ///
+    /// Chunk types the model doesn't use decode to [`ChunkOutput::Unknown`]
+    /// or [`ChunkOutput::Skipped`]; the caller decides whether that is an error.
+    fn decode_chunk<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        header: &ChunkHeader,
+        options: &ParseOptions,
+    ) -> ParseResult<ChunkOutput> {
+        Ok(match header.chunk_type.canonical() {
+            ChunkType::Mesh | ChunkType::MeshSubsets => ChunkOutput::Mesh(self.parse_mesh_chunk(reader, header)?),
+            ChunkType::Node => ChunkOutput::Node(self.parse_node_chunk(reader, header)?),
+            ChunkType::Material => ChunkOutput::Material(self.parse_material_chunk(reader, header)?),
+            ChunkType::CompiledBones => ChunkOutput::Skeleton(self.parse_compiled_bones_chunk(reader, header)?),
+            ChunkType::CompiledMesh => ChunkOutput::CompiledMesh(
+                self.parse_compiled_mesh_chunk(reader, header, options.bgra_vertex_colors)?,
+            ),
+            ChunkType::CompiledMorphTargets => {
+                ChunkOutput::MorphTargets(self.parse_compiled_morph_targets_chunk(reader, header)?)
+            }
+            ChunkType::CompiledExtToIntMap => ChunkOutput::ExtToIntMap(self.parse_ext_to_int_map_chunk(reader, header)?),
+            ChunkType::CompiledIntSkinVertices if options.merge_int_skin_vertices => {
+                ChunkOutput::IntSkin(self.parse_int_skin_vertices_chunk(reader, header)?)
+            }
+            // Legacy bone data - skip for now
+            ChunkType::CompiledIntSkinVertices | ChunkType::BoneAnim | ChunkType::BoneNameList => ChunkOutput::Skipped,
+            _ => ChunkOutput::Unknown,
+        })
     }
 
-    fn name(&self) -> &str {
-        "CryEngine Geometry Parser"
+    /// Decode every chunk on a pool of `ParseOptions::thread_count` threads
+    ///
+    /// A count of `0` runs on rayon's global pool instead of building one
+    /// per file. Each worker reads its own slice of `data`, from the chunk's
+    /// offset to whichever is further of its declared end and the next
+    /// chunk's start, through a header rebased to that slice. Results come
+    /// back in chunk order; errors are per chunk, as in the serial loop.
+    fn decode_chunks_parallel(
+        &self,
+        chunk_headers: &[ChunkHeader],
+        data: &[u8],
+        options: &ParseOptions,
+    ) -> ParseResult<Vec<ParseResult<ChunkOutput>>> {
+        let mut starts: Vec<usize> = chunk_headers.iter().map(|h| h.offset as usize).collect();
+        starts.sort_unstable();
+        starts.dedup();
+
+        let decode_all = || {
+            chunk_headers.par_iter()
+                .map(|header| {
+                    let start = (header.offset as usize).min(data.len());
+                    let next = starts.get(starts.partition_point(|&s| s <= start)).copied().unwrap_or(data.len());
+                    let end = next.max(start + header.size as usize).min(data.len());
+
+                    let local = ChunkHeader { offset: 0, ..header.clone() };
+                    // A chunk starting past the end reads from an empty slice and fails like the serial read
+                    let slice = if header.offset as usize > data.len() { &[][..] } else { &data[start..end] };
+                    self.decode_chunk(&mut std::io::Cursor::new(slice), &local, options)
+                })
+                .collect()
+        };
+
+        if options.thread_count == 0 {
+            return Ok(decode_all());
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(options.thread_count)
+            .build()
+            .map_err(|e| ParseError::UnsupportedFeature(format!("Can't start chunk parsing threads: {}", e)))?;
+
+        Ok(pool.install(decode_all))
     }
 
-    fn parse_with_options<R: Read + Seek>(
+    /// Add a decoded chunk to the model, returning whether it was used
+    fn merge_chunk(
+        model: &mut CgfModel,
+        int_skin: &mut Option<(u64, Vec<IntSkinVertex>)>,
+        output: ChunkOutput,
+        chunk_header: &ChunkHeader,
+        options: &ParseOptions,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> bool {
+        match output {
+            ChunkOutput::Mesh(mut mesh) => {
+                Self::sanitize_mesh(&mut mesh, options, chunk_header, warnings);
+                model.meshes.push(mesh);
+            }
+            ChunkOutput::CompiledMesh(mut mesh) => {
+                Self::sanitize_mesh(&mut mesh, options, chunk_header, warnings);
+                let unassigned = if mesh.subsets.is_empty() { 0 } else { mesh.assign_subset_materials() };
+                if unassigned > 0 {
+                    warnings.push(ParseWarning {
+                        offset: chunk_header.offset as u64,
+                        context: format!("{:?} chunk {}", chunk_header.chunk_type, chunk_header.id),
+                        reason: format!("{} faces outside every subset were left at material 0", unassigned),
+                    });
+                }
+                model.meshes.push(mesh);
+            }
+            ChunkOutput::Node(node) => model.nodes.push(node),
+            ChunkOutput::Material(material) => model.materials.push(material),
+            ChunkOutput::Skeleton(skeleton) => model.skeleton = Some(skeleton),
+            ChunkOutput::MorphTargets(morph_targets) => model.morph_targets.extend(morph_targets),
+            ChunkOutput::ExtToIntMap(map) => model.ext_to_int_map = map,
+            ChunkOutput::IntSkin(skin) => *int_skin = Some((chunk_header.offset as u64, skin)),
+            ChunkOutput::Skipped | ChunkOutput::Unknown => return false,
+        }
+        true
+    }
+
+    /// Shared chunk loop for `parse_with_options` and `parse_with_report`
+    fn parse_impl<R: Read + Seek>(
         &self,
         mut reader: R,
         options: &ParseOptions,
         progress: Option<ProgressCallback>,
-    ) -> ParseResult<Self::Output> {
+        warnings: &mut Vec<ParseWarning>,
+    ) -> ParseResult<CgfModel> {
         // Report start
         if let Some(ref cb) = progress {
             cb(ParseProgress {
@@ -1025,10 +1383,20 @@ impl Parser for CgfParser {
         // Parse chunk table
         let chunk_headers = self.parse_chunk_table(&mut reader, &header, version)?;
 
-        // Create model
+        // Decode chunks, on worker threads if allowed, then merge in chunk order
+        let outputs = if options.thread_count != 1 && chunk_headers.len() > 1 {
+            let mut data = Vec::new();
+            reader.seek(SeekFrom::Start(0))?;
+            reader.read_to_end(&mut data)?;
+            self.decode_chunks_parallel(&chunk_headers, &data, options)?
+        } else {
+            Vec::new()
+        };
+        let mut outputs = outputs.into_iter();
+
         let mut model = CgfModel::new(version);
+        let mut int_skin = None;
 
-        // Parse each chunk
         for (idx, chunk_header) in chunk_headers.iter().enumerate() {
             if let Some(ref cb) = progress {
                 cb(ParseProgress {
@@ -1041,48 +1409,49 @@ impl Parser for CgfParser {
                 });
             }
 
-            match chunk_header.chunk_type {
-                ChunkType::Mesh | ChunkType::MeshSubsets => {
-                    if let Ok(mesh) = self.parse_mesh_chunk(&mut reader, chunk_header) {
-                        model.meshes.push(mesh);
-                    }
-                }
-                ChunkType::Node => {
-                    if let Ok(node) = self.parse_node_chunk(&mut reader, chunk_header) {
-                        model.nodes.push(node);
-                    }
-                }
-                ChunkType::Material => {
-                    if let Ok(material) = self.parse_material_chunk(&mut reader, chunk_header) {
-                        model.materials.push(material);
-                    }
-                }
-                ChunkType::CompiledBones => {
-                    if let Ok(skeleton) = self.parse_compiled_bones_chunk(&mut reader, chunk_header) {
-                        model.skeleton = Some(skeleton);
-                    }
-                }
-                ChunkType::CompiledMesh => {
-                    if let Ok(mesh) = self.parse_compiled_mesh_chunk(&mut reader, chunk_header) {
-                        model.meshes.push(mesh);
-                    }
-                }
-                ChunkType::CompiledMorphTargets => {
-                    if let Ok(morph_targets) = self.parse_compiled_morph_targets_chunk(&mut reader, chunk_header) {
-                        model.morph_targets.extend(morph_targets);
-                    }
-                }
-                ChunkType::BoneAnim | ChunkType::BoneNameList => {
-                    // Legacy bone data - skip for now
+            let output = match outputs.next() {
+                Some(output) => output,
+                None => self.decode_chunk(&mut reader, chunk_header, options),
+            };
+
+            let result = match output {
+                Ok(ChunkOutput::Unknown) if !options.skip_unknown_chunks => {
+                    return Err(ParseError::UnknownChunkType {
+                        chunk_type: chunk_header.chunk_type.to_u32(),
+                    });
                 }
-                _ => {
-                    if !options.skip_unknown_chunks {
-                        return Err(ParseError::UnknownChunkType {
-                            chunk_type: chunk_header.chunk_type.to_u32(),
-                        });
-                    }
+                Ok(output) => Ok(Self::merge_chunk(&mut model, &mut int_skin, output, chunk_header, options, warnings)),
+                Err(e) => Err(e),
+            };
+
+            let parsed = match result {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    warnings.push(ParseWarning::new(
+                        chunk_header.offset as u64,
+                        format!("{:?} chunk {}", chunk_header.chunk_type, chunk_header.id),
+                        &e,
+                    ));
+                    false
                 }
-            }
+            };
+            model.chunk_records.push(ChunkRecord { header: chunk_header.clone(), parsed });
+        }
+
+        // The skin chunk may also come after the meshes; merge before
+        // remapping so its bone ids go through the map like streamed ones
+        if let Some((offset, skin)) = int_skin {
+            Self::merge_int_skin_vertices(&mut model, &skin, offset, warnings);
+        }
+
+        // The map chunk may come after the meshes, so remap once all are read
+        let untranslated = model.apply_ext_to_int_map();
+        if untranslated > 0 {
+            warnings.push(ParseWarning {
+                offset: 0,
+                context: "ExtToIntMap".to_string(),
+                reason: format!("{} bone indices outside the map were left unchanged", untranslated),
+            });
         }
 
         // Report completion
@@ -1101,6 +1470,64 @@ impl Parser for CgfParser {
     }
 }
 
+impl Default for CgfParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for CgfParser {
+    type Output = CgfModel;
+
+    fn extensions(&self) -> &[&str] {
+        &["cgf", "cga", "skin", "chr"]
+    }
+
+    fn magic_bytes(&self) -> Option<&[u8]> {
+        // Can't return single magic - multiple formats supported
+        None
+    }
+
+    fn name(&self) -> &str {
+        "CryEngine Geometry Parser"
+    }
+
+    fn parse_with_options<R: Read + Seek>(
+        &self,
+        reader: R,
+        options: &ParseOptions,
+        progress: Option<ProgressCallback>,
+    ) -> ParseResult<Self::Output> {
+        // Chunks that fail to parse are skipped; use `parse_with_report` to see them
+        let mut warnings = Vec::new();
+        self.parse_impl(reader, options, progress, &mut warnings)
+    }
+}
+
+/// What one chunk decoded to, before it is merged into the model
+#[derive(Debug)]
+enum ChunkOutput {
+    Mesh(Mesh),
+    CompiledMesh(Mesh),
+    Node(Node),
+    Material(MaterialRef),
+    Skeleton(Skeleton),
+    MorphTargets(Vec<MorphTarget>),
+    ExtToIntMap(Vec<u16>),
+    IntSkin(Vec<IntSkinVertex>),
+    /// A known chunk the model doesn't use yet
+    Skipped,
+    /// A chunk type this parser doesn't handle
+    Unknown,
+}
+
+/// Skinning data of one vertex from a CompiledIntSkinVertices chunk
+#[derive(Debug, Clone, Copy)]
+struct IntSkinVertex {
+    bone_indices: [u8; 4],
+    bone_weights: [f32; 4],
+}
+
 /// CGF file header
 #[derive(Debug)]
 struct CgfHeader {
@@ -1111,12 +1538,194 @@ struct CgfHeader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::ivo_file;
 
     #[test]
     fn test_cgf_version() {
         assert_ne!(CgfVersion::Legacy(1), CgfVersion::Ivo(1));
     }
 
+    /// Ivo file with an empty mesh chunk and a mesh chunk pointing past the end
+    fn make_model_with_bad_chunk() -> Vec<u8> {
+        let good_offset: u32 = 16 + 2 * 20;
+        let mut data = ivo_file(&[(ChunkType::Mesh, 1, good_offset, 48), (ChunkType::Mesh, 2, 10_000, 48)]);
+
+        // Mesh header with no vertices or faces
+        data.extend_from_slice(&[0u8; 48]);
+        data
+    }
+
+    #[test]
+    fn test_parse_with_report_keeps_good_chunks() {
+        let parser = CgfParser::new();
+        let report = parser
+            .parse_with_report(std::io::Cursor::new(make_model_with_bad_chunk()), &ParseOptions::default())
+            .unwrap();
+
+        assert_eq!(report.output.meshes.len(), 1);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].offset, 10_000);
+        assert!(report.warnings[0].context.contains("Mesh"));
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_compiled_mesh_faces_take_subset_materials() {
+        let mut data = ivo_file(&[(ChunkType::CompiledMesh, 1, 36, 0)]);
+
+        // Header: 4 vertices, 9 indices, 2 subsets, 1 stream
+        for field in [0u32, 4, 9, 2, 1, 0, 0, 0] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(&0u32.to_le_bytes()); // position stream
+        data.extend_from_slice(&48u32.to_le_bytes());
+        data.extend_from_slice(&[0u8; 48]);
+        for index in [0u32, 1, 2, 1, 2, 3, 0, 2, 3] {
+            data.extend_from_slice(&index.to_le_bytes());
+        }
+        // Subsets (material, first index, index count, first vertex); the
+        // third face is in neither
+        for field in [2u32, 0, 3, 0, 5, 3, 3, 0] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+
+        let report = CgfParser::new()
+            .parse_with_report(std::io::Cursor::new(data), &ParseOptions::default())
+            .unwrap();
+        let materials: Vec<u32> = report.output.meshes[0].faces.iter().map(|f| f.material_id).collect();
+        assert_eq!(materials, vec![2, 5, 0]);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].reason.contains("1 faces"));
+    }
+
+    /// Ivo file with a three-vertex compiled mesh and a separate
+    /// IntSkinVertices chunk holding `skin_vertices` vertices
+    fn make_split_skin_model(skin_vertices: u32) -> Vec<u8> {
+        let mesh_offset: u32 = 16 + 2 * 20;
+        let mesh_size = 32 + 8 + 36 + 12;
+        let skin_size = 32 + skin_vertices * 64;
+        let mut data = ivo_file(&[
+            (ChunkType::CompiledMesh, 1, mesh_offset, 0),
+            (ChunkType::CompiledIntSkinVertices, 2, mesh_offset + mesh_size, skin_size),
+        ]);
+
+        // Mesh: 3 vertices, one face, positions only
+        for field in [0u32, 3, 3, 0, 1, 0, 0, 0] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(&0u32.to_le_bytes()); // position stream
+        data.extend_from_slice(&36u32.to_le_bytes());
+        data.extend_from_slice(&[0u8; 36]);
+        for index in [0u32, 1, 2] {
+            data.extend_from_slice(&index.to_le_bytes());
+        }
+
+        // Skin: header, then per vertex 36 bytes of positions, bone ids,
+        // weights and color
+        data.extend_from_slice(&[0u8; 32]);
+        for i in 0..skin_vertices as u16 {
+            data.extend_from_slice(&[0u8; 36]);
+            for bone in [i, i + 1, 0, 0] {
+                data.extend_from_slice(&bone.to_le_bytes());
+            }
+            for weight in [0.75f32, 0.25, 0.0, 0.0] {
+                data.extend_from_slice(&weight.to_le_bytes());
+            }
+            data.extend_from_slice(&[0xFF; 4]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_int_skin_vertices_merge_onto_compiled_mesh() {
+        let report = CgfParser::new()
+            .parse_with_report(std::io::Cursor::new(make_split_skin_model(3)), &ParseOptions::default())
+            .unwrap();
+        assert!(report.is_clean(), "{:?}", report.warnings);
+
+        let vertices = &report.output.meshes[0].vertices;
+        assert_eq!(vertices[0].bone_indices, Some([0, 1, 0, 0]));
+        assert_eq!(vertices[2].bone_indices, Some([2, 3, 0, 0]));
+        assert!(vertices.iter().all(|v| v.bone_weights == Some([0.75, 0.25, 0.0, 0.0])));
+
+        // Off, the chunk is skipped and the mesh stays unskinned
+        let options = ParseOptions { merge_int_skin_vertices: false, ..Default::default() };
+        let report = CgfParser::new()
+            .parse_with_report(std::io::Cursor::new(make_split_skin_model(3)), &options)
+            .unwrap();
+        assert!(!report.output.meshes[0].has_bone_weights());
+    }
+
+    #[test]
+    fn test_int_skin_vertices_count_mismatch_is_reported() {
+        let report = CgfParser::new()
+            .parse_with_report(std::io::Cursor::new(make_split_skin_model(2)), &ParseOptions::default())
+            .unwrap();
+
+        assert!(!report.output.meshes[0].has_bone_weights());
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].reason.contains("has 3 vertices but the skin chunk has 2"));
+    }
+
+    #[test]
+    fn test_parallel_parse_matches_serial() {
+        // An explicit pool size, and the global pool
+        for thread_count in [4, 0] {
+            let parallel = ParseOptions { thread_count, ..Default::default() };
+            for data in [make_split_skin_model(3), make_split_skin_model(2), make_model_with_bad_chunk()] {
+                let serial = CgfParser::new()
+                    .parse_with_report(std::io::Cursor::new(data.clone()), &ParseOptions::default())
+                    .unwrap();
+                let threaded = CgfParser::new()
+                    .parse_with_report(std::io::Cursor::new(data), &parallel)
+                    .unwrap();
+
+                assert_eq!(format!("{:?}", threaded.output), format!("{:?}", serial.output));
+                assert_eq!(format!("{:?}", threaded.warnings), format!("{:?}", serial.warnings));
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_with_options_skips_bad_chunks() {
+        let model = CgfParser::new()
+            .parse(std::io::Cursor::new(make_model_with_bad_chunk()))
+            .unwrap();
+
+        assert_eq!(model.meshes.len(), 1);
+    }
+
+    #[test]
+    fn test_chunk_coverage_counts() {
+        let mut data = make_model_with_bad_chunk();
+        // Grow the table to five entries: append a bone name list and two
+        // unknown chunks, all pointing at the empty mesh data
+        data[8..12].copy_from_slice(&5u32.to_le_bytes());
+        let mesh_data = data.split_off(16 + 2 * 20);
+        for (chunk_type, id) in [(ChunkType::BoneNameList.to_u32(), 3u32), (0xDEAD, 4), (0xDEAD, 5)] {
+            data.extend_from_slice(&chunk_type.to_le_bytes());
+            data.extend_from_slice(&0x800u32.to_le_bytes());
+            data.extend_from_slice(&(16u32 + 5 * 20).to_le_bytes());
+            data.extend_from_slice(&id.to_le_bytes());
+            data.extend_from_slice(&48u32.to_le_bytes());
+        }
+        data.extend_from_slice(&mesh_data);
+        // The good mesh chunk's offset moved with the data
+        data[16 + 8..16 + 12].copy_from_slice(&(16u32 + 5 * 20).to_le_bytes());
+
+        let model = CgfParser::new().parse(std::io::Cursor::new(data)).unwrap();
+        let report = model.chunk_coverage();
+
+        assert_eq!(report.chunk_types, vec![
+            ChunkCoverage { chunk_type: ChunkType::Mesh, present: 2, parsed: 1, skipped: 1 },
+            ChunkCoverage { chunk_type: ChunkType::BoneNameList, present: 1, parsed: 0, skipped: 1 },
+            ChunkCoverage { chunk_type: ChunkType::Unknown(0xDEAD), present: 2, parsed: 0, skipped: 2 },
+        ]);
+        assert_eq!((report.present(), report.parsed(), report.skipped()), (5, 1, 4));
+        assert!(!report.is_complete());
+        assert!(CgfModel::new(CgfVersion::Ivo(1)).chunk_coverage().is_complete());
+    }
+
     #[test]
     fn test_material_texture_paths() {
         let mat = MaterialRef {
@@ -1136,4 +1745,124 @@ mod tests {
         assert_eq!(paths.len(), 2);
         assert!(paths.contains(&"textures/diffuse.dds"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_material_name_resolves_sub_materials() {
+        let material = |name: &str, index: u32, sub_materials: Vec<MaterialRef>| MaterialRef {
+            name: name.into(),
+            index,
+            shader: "illum".into(),
+            textures: MaterialTextures::default(),
+            params: HashMap::new(),
+            sub_materials,
+        };
+
+        let mut model = CgfModel::new(CgfVersion::Ivo(1));
+        model.materials = vec![
+            material("decals", 3, Vec::new()),
+            material("hull_multi", 0, vec![
+                material("hull_paint", 0, Vec::new()),
+                material("hull_glass", 1, Vec::new()),
+            ]),
+        ];
+
+        assert_eq!(model.material_name(3), Some("decals"));
+        assert_eq!(model.material_name(0), Some("hull_paint"));
+        assert_eq!(model.material_name(1), Some("hull_glass"));
+        assert_eq!(model.material_name(2), None);
+    }
+
+    #[test]
+    fn test_merge_meshes_keeps_materials() {
+        let mut hull = Mesh::new("hull");
+        hull.vertices = vec![Vertex::new([0.0; 3]), Vertex::new([1.0, 0.0, 0.0]), Vertex::new([0.0, 1.0, 0.0])];
+        hull.faces = vec![Face::new(0, 1, 2)];
+
+        let mut glass = Mesh::new("glass");
+        glass.vertices = vec![
+            Vertex::new([0.0, 0.0, 2.0]),
+            Vertex::new([1.0, 0.0, 2.0]),
+            Vertex::new([0.0, 1.0, 2.0]),
+            Vertex::new([1.0, 1.0, 3.0]),
+        ];
+        glass.faces = vec![Face::new(0, 1, 2), Face::new(1, 3, 2)];
+        for face in &mut glass.faces {
+            face.material_id = 4;
+        }
+
+        let mut model = CgfModel::new(CgfVersion::Ivo(0x900));
+        model.meshes = vec![hull, glass];
+
+        let merged = model.merge_meshes();
+        assert_eq!(merged.vertex_count(), 7);
+        assert_eq!(merged.face_count(), 3);
+        assert_eq!(merged.faces[1].indices, [3, 4, 5]);
+
+        assert_eq!(merged.subsets.len(), 2);
+        let glass_subset = &merged.subsets[1];
+        assert_eq!(glass_subset.material_id, 4);
+        assert_eq!((glass_subset.first_index, glass_subset.num_indices), (3, 6));
+        assert_eq!((glass_subset.first_vertex, glass_subset.num_vertices), (3, 4));
+        assert_eq!(glass_subset.bounding_box.unwrap().max, [1.0, 1.0, 3.0]);
+
+        let bbox = merged.bounding_box.unwrap();
+        assert_eq!((bbox.min, bbox.max), ([0.0; 3], [1.0, 1.0, 3.0]));
+    }
+
+    #[test]
+    fn test_ext_to_int_map_remaps_bone_indices() {
+        // Ivo file with a single ExtToIntMap chunk: external 0..4 -> 3, 0, 2, 1
+        let mut data = ivo_file(&[(ChunkType::CompiledExtToIntMap, 1, 36, 8)]);
+        for internal in [3u16, 0, 2, 1] {
+            data.extend_from_slice(&internal.to_le_bytes());
+        }
+
+        let mut model = CgfParser::new().parse(std::io::Cursor::new(data)).unwrap();
+        assert_eq!(model.ext_to_int_map, vec![3, 0, 2, 1]);
+        assert!(model.chunk_coverage().is_complete());
+
+        let mut mesh = Mesh::new("body");
+        mesh.vertices = vec![Vertex::new([0.0; 3]), Vertex::new([1.0; 3]), Vertex::new([2.0; 3])];
+        mesh.vertices[0].bone_indices = Some([0, 1, 2, 3]);
+        mesh.vertices[1].bone_indices = Some([3, 9, 0, 0]);
+        model.meshes.push(mesh);
+
+        assert_eq!(model.apply_ext_to_int_map(), 1);
+        let vertices = &model.meshes[0].vertices;
+        assert_eq!(vertices[0].bone_indices, Some([3, 0, 2, 1]));
+        // Index 9 has no mapping and is kept
+        assert_eq!(vertices[1].bone_indices, Some([1, 9, 3, 3]));
+        assert_eq!(vertices[2].bone_indices, None);
+    }
+
+    #[test]
+    fn test_validate_skin_reports_bad_bone_index() {
+        let mut skeleton = Skeleton::new();
+        skeleton.add_bone(Bone::new("root"));
+        skeleton.add_bone(Bone::new("spine"));
+
+        let mut mesh = Mesh::new("body");
+        mesh.vertices = vec![Vertex::new([0.0; 3]), Vertex::new([1.0; 3]), Vertex::new([2.0; 3])];
+        mesh.vertices[0].bone_indices = Some([0, 1, 0, 0]);
+        mesh.vertices[0].bone_weights = Some([0.5, 0.5, 0.0, 0.0]);
+        mesh.vertices[1].bone_indices = Some([1, 7, 0, 0]);
+        mesh.vertices[1].bone_weights = Some([0.75, 0.25, 0.0, 0.0]);
+        mesh.vertices[2].bone_indices = Some([0, 0, 0, 0]);
+        mesh.vertices[2].bone_weights = Some([0.5, 0.0, 0.0, 0.0]);
+
+        let mut model = CgfModel::new(CgfVersion::Ivo(0x900));
+        model.meshes.push(mesh);
+        assert!(model.validate_skin().is_empty(), "no skeleton, nothing to check");
+
+        model.skeleton = Some(skeleton);
+        let errors = model.validate_skin();
+        assert_eq!(errors, vec![
+            SkinError::BoneIndexOutOfRange { mesh: "body".to_string(), vertex: 1, index: 7, bone_count: 2 },
+            SkinError::WeightSum { mesh: "body".to_string(), vertex: 2, sum: 0.5 },
+        ]);
+
+        assert_eq!(model.meshes[0].clamp_bone_indices(2), 1);
+        assert_eq!(model.meshes[0].vertices[1].bone_indices, Some([1, 1, 0, 0]));
+        assert_eq!(model.validate_skin().len(), 1);
+    }
+}
@@ -1,12 +1,13 @@
 //! DataCore container and header structures
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::{Read, Seek, SeekFrom};
 use std::path::PathBuf;
 use std::sync::Arc;
 use parking_lot::Mutex;
-use super::{StringTable, StructDef, PropertyDef, Record, LazyRecord, RecordValue, DataType};
-use crate::traits::{ParseResult, ParseError};
+use serde::Serialize;
+use super::{StringTable, StructDef, PropertyDef, Record, LazyRecord, RecordRef, RecordValue, DataType};
+use crate::traits::{ParseResult, ParseError, ParseOptions};
 
 /// DataCore file header
 #[derive(Debug, Clone)]
@@ -19,6 +20,29 @@ pub struct DataCoreHeader {
     pub struct_offset: u64,
     pub property_offset: u64,
     pub record_offset: u64,
+    /// Start of the separate values region (version 6+ headers only)
+    pub value_offset: Option<u64>,
+    /// Start of the enum options table (version 6+ headers only)
+    pub enum_offset: Option<u64>,
+    /// Encoding detected for the string table
+    pub string_encoding: StringEncoding,
+}
+
+/// Text encoding of a DataCore string table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StringEncoding {
+    /// Null-terminated UTF-8 (decoded lossily)
+    #[default]
+    Utf8,
+    /// Null-terminated UTF-16LE, signalled by a `FF FE` byte order mark
+    Utf16Le,
+}
+
+impl DataCoreHeader {
+    /// Whether this header uses the extended version 6+ layout
+    pub fn is_extended(&self) -> bool {
+        self.value_offset.is_some()
+    }
 }
 
 /// Parsed DataCore database
@@ -34,6 +58,38 @@ pub struct DataCore {
 }
 
 impl DataCore {
+    /// Assemble a DataCore from its tables, building the lookup indices
+    pub fn from_parts(
+        header: DataCoreHeader,
+        strings: StringTable,
+        structs: Vec<StructDef>,
+        properties: Vec<PropertyDef>,
+        records: Vec<Record>,
+    ) -> Self {
+        let mut struct_index = HashMap::new();
+        for (idx, s) in structs.iter().enumerate() {
+            struct_index.insert(s.name.clone(), idx);
+        }
+
+        let mut record_index = HashMap::new();
+        for (idx, r) in records.iter().enumerate() {
+            record_index.insert(r.guid, idx);
+            if !r.name.is_empty() {
+                record_index.insert(r.id as u64, idx);
+            }
+        }
+
+        Self {
+            header,
+            strings,
+            structs,
+            properties,
+            records,
+            struct_index,
+            record_index,
+        }
+    }
+
     /// Get a record by GUID
     pub fn get_record(&self, guid: u64) -> Option<&Record> {
         self.record_index.get(&guid).map(|&idx| &self.records[idx])
@@ -49,26 +105,427 @@ impl DataCore {
         self.struct_index.get(name).map(|&idx| &self.structs[idx])
     }
     
-    /// Find records by struct type
+    /// Get every struct definition with this name
+    ///
+    /// Names aren't unique across namespaces, and `struct_index` only keeps
+    /// one id per name, so this scans the struct table.
+    pub fn structs_by_name(&self, name: &str) -> Vec<&StructDef> {
+        self.structs.iter().filter(|s| s.name == name).collect()
+    }
+
+    /// Find records by struct type, across every struct sharing the name
     pub fn find_by_struct(&self, struct_name: &str) -> Vec<&Record> {
-        if let Some(&struct_idx) = self.struct_index.get(struct_name) {
-            self.records.iter()
-                .filter(|r| r.struct_id == struct_idx as u32)
-                .collect()
-        } else {
-            Vec::new()
-        }
+        let struct_ids = struct_ids_named(&self.structs, struct_name);
+        self.records.iter()
+            .filter(|r| struct_ids.contains(&r.struct_id))
+            .collect()
     }
     
     /// Get total record count
     pub fn record_count(&self) -> usize {
         self.records.len()
     }
+
+    /// Human-readable breadcrumb for a record, e.g. `Ship/Aurora/MLauncher`
+    ///
+    /// Composed of the parent struct name (when the record's struct inherits
+    /// from one), the struct name and the record name. Unnamed records fall
+    /// back to their canonical GUID.
+    pub fn record_path(&self, record: &Record) -> String {
+        let struct_name = |id: u32| {
+            self.structs.get(id as usize)
+                .map(|s| s.name.clone())
+                .unwrap_or_else(|| format!("struct_{}", id))
+        };
+
+        let mut parts = Vec::with_capacity(3);
+        if let Some(parent_id) = self.structs.get(record.struct_id as usize).and_then(|s| s.parent_id) {
+            parts.push(struct_name(parent_id));
+        }
+        parts.push(struct_name(record.struct_id));
+        parts.push(if record.name.is_empty() {
+            record.cig_guid().to_string()
+        } else {
+            record.name.clone()
+        });
+
+        parts.join("/")
+    }
+
+    /// Get a record by its position in the file
+    ///
+    /// Records are kept in the order they appear in the DCB, so indices are
+    /// stable across parses of the same file.
+    pub fn record_by_index(&self, index: usize) -> Option<&Record> {
+        self.records.get(index)
+    }
+
+    /// Iterate the records of one struct type in file order
+    pub fn records_of_struct(&self, struct_id: u32) -> impl Iterator<Item = &Record> + '_ {
+        self.records.iter().filter(move |r| r.struct_id == struct_id)
+    }
+
+    /// Count records per struct id
+    pub fn record_count_by_struct(&self) -> HashMap<u32, usize> {
+        let mut counts = HashMap::new();
+        for record in &self.records {
+            *counts.entry(record.struct_id).or_insert(0) += 1;
+        }
+        counts
+    }
     
     /// Get all struct names
     pub fn struct_names(&self) -> Vec<&str> {
         self.structs.iter().map(|s| s.name.as_str()).collect()
     }
+
+    /// Get the raw string pool
+    pub fn strings(&self) -> &StringTable {
+        &self.strings
+    }
+
+    /// Find string table entries containing `substr`, as `(id, string)` pairs
+    ///
+    /// Handy for locating asset path references (e.g. `".cgf"`) in the pool.
+    pub fn find_strings(&self, substr: &str) -> Vec<(usize, &str)> {
+        self.strings.iter()
+            .filter(|(_, s)| s.contains(substr))
+            .collect()
+    }
+
+    /// Count how often each string table entry is used, indexed by string id
+    ///
+    /// A use is a struct, property or record name, or a string/locale value
+    /// in a record (array elements included). Strings are matched by text,
+    /// so duplicate entries share a count.
+    pub fn string_usage(&self) -> Vec<usize> {
+        let mut uses: HashMap<&str, usize> = HashMap::new();
+
+        fn visit<'a>(value: &'a RecordValue, uses: &mut HashMap<&'a str, usize>) {
+            match value {
+                RecordValue::String(s) => *uses.entry(s.as_str()).or_default() += 1,
+                RecordValue::LocaleString { key, value } => {
+                    *uses.entry(key.as_str()).or_default() += 1;
+                    *uses.entry(value.as_str()).or_default() += 1;
+                }
+                RecordValue::Array { items, .. } => items.iter().for_each(|item| visit(item, uses)),
+                RecordValue::Struct(fields) => fields.values().for_each(|value| visit(value, uses)),
+                _ => {}
+            }
+        }
+
+        let names = self.structs.iter().map(|s| s.name.as_str())
+            .chain(self.properties.iter().map(|p| p.name.as_str()))
+            .chain(self.records.iter().map(|r| r.name.as_str()));
+        for name in names {
+            *uses.entry(name).or_default() += 1;
+        }
+        for record in &self.records {
+            for value in record.values.values() {
+                visit(value, &mut uses);
+            }
+        }
+
+        self.strings.iter()
+            .map(|(_, s)| uses.get(s).copied().unwrap_or(0))
+            .collect()
+    }
+
+    /// String table entries nothing refers to (dead pool entries)
+    pub fn unused_strings(&self) -> Vec<(usize, &str)> {
+        let usage = self.string_usage();
+        self.strings.iter()
+            .filter(|(id, _)| usage[*id] == 0)
+            .collect()
+    }
+    
+    /// Count property data types and unknown value type ids
+    ///
+    /// Useful when the DCB schema changes and new type ids start showing up
+    /// as `DataType::Unknown` / `RecordValue::Unknown`.
+    pub fn type_statistics(&self) -> TypeStats {
+        let mut stats = TypeStats::default();
+        
+        for property in &self.properties {
+            *stats.property_types.entry(property.data_type.clone()).or_default() += 1;
+        }
+        
+        fn visit(value: &RecordValue, stats: &mut TypeStats) {
+            stats.value_count += 1;
+            match value {
+                RecordValue::Unknown(id) => *stats.unknown_values.entry(*id).or_default() += 1,
+                RecordValue::Array { items, .. } => items.iter().for_each(|item| visit(item, stats)),
+                RecordValue::Struct(fields) => fields.values().for_each(|value| visit(value, stats)),
+                _ => {}
+            }
+        }
+        
+        for record in &self.records {
+            for value in record.values.values() {
+                visit(value, &mut stats);
+            }
+        }
+        
+        stats
+    }
+    
+    /// Resolve a reference to the record it points at
+    pub fn resolve_reference(&self, reference: &RecordRef) -> Option<&Record> {
+        if reference.is_null() {
+            return None;
+        }
+        self.records.get(reference.record_id as usize)
+    }
+    
+    /// Find references whose target record doesn't exist in this DataCore
+    ///
+    /// Null references are ignored. Anything else that fails to resolve is
+    /// reported, which usually means a cross-file reference or a truncated
+    /// extraction.
+    pub fn validate_references(&self) -> Vec<DanglingRef> {
+        let mut dangling = Vec::new();
+
+        for record in &self.records {
+            let mut names: Vec<&String> = record.values.keys().collect();
+            names.sort();
+
+            for name in names {
+                self.collect_dangling(record, name.clone(), &record.values[name], &mut dangling);
+            }
+        }
+
+        dangling
+    }
+
+    fn collect_dangling(&self, record: &Record, property: String, value: &RecordValue, out: &mut Vec<DanglingRef>) {
+        match value {
+            RecordValue::Reference(reference)
+                if !reference.is_null() && self.resolve_reference(reference).is_none() =>
+            {
+                out.push(DanglingRef {
+                    source_guid: record.guid,
+                    source_name: record.name.clone(),
+                    property,
+                    reference: *reference,
+                });
+            }
+            RecordValue::Array { items, .. } => {
+                for (i, item) in items.iter().enumerate() {
+                    self.collect_dangling(record, format!("{}[{}]", property, i), item, out);
+                }
+            }
+            RecordValue::Struct(fields) => {
+                let mut names: Vec<&String> = fields.keys().collect();
+                names.sort();
+
+                for name in names {
+                    self.collect_dangling(record, format!("{}.{}", property, name), &fields[name], out);
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    /// Build the reverse reference map
+    ///
+    /// Maps each referenced record's GUID to the GUIDs of the records that
+    /// reference it. A record referencing the same target several times is
+    /// only listed once.
+    pub fn reverse_references(&self) -> HashMap<u64, Vec<u64>> {
+        let mut reverse: HashMap<u64, Vec<u64>> = HashMap::new();
+        
+        for record in &self.records {
+            let mut targets: Vec<u64> = record.values.values()
+                .flat_map(RecordValue::references)
+                .filter_map(|r| self.resolve_reference(r))
+                .map(|target| target.guid)
+                .collect();
+            targets.sort_unstable();
+            targets.dedup();
+            
+            for target in targets {
+                reverse.entry(target).or_default().push(record.guid);
+            }
+        }
+        
+        reverse
+    }
+    
+    /// Count incoming references per record, most referenced first
+    pub fn reference_histogram(&self) -> Vec<(u64, usize)> {
+        let mut histogram: Vec<(u64, usize)> = self.reverse_references()
+            .into_iter()
+            .map(|(guid, referrers)| (guid, referrers.len()))
+            .collect();
+        histogram.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        histogram
+    }
+    
+    /// Count incoming references per struct type, most referenced first
+    pub fn struct_reference_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        for (guid, refcount) in self.reference_histogram() {
+            if let Some(record) = self.get_record(guid) {
+                *counts.entry(record.struct_id).or_default() += refcount;
+            }
+        }
+        
+        let mut counts: Vec<(String, usize)> = counts.into_iter()
+            .map(|(struct_id, refcount)| {
+                let name = self.structs.get(struct_id as usize)
+                    .map(|s| s.name.clone())
+                    .unwrap_or_else(|| format!("Unknown_{}", struct_id));
+                (name, refcount)
+            })
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// Fraction of a struct's records that hold a non-empty value per property
+    ///
+    /// A property counts as filled when present and not
+    /// [`RecordValue::is_empty_value`], which separates the fields a struct
+    /// actually uses from ones that are always left at zero. Sorted by fill
+    /// rate, highest first, then by name. Empty for unknown structs or
+    /// structs without records.
+    pub fn property_fill_rates(&self, struct_name: &str) -> Vec<(String, f32)> {
+        let records = self.find_by_struct(struct_name);
+        if records.is_empty() {
+            return Vec::new();
+        }
+
+        let mut filled: BTreeMap<&str, usize> = BTreeMap::new();
+        for record in &records {
+            for (name, value) in &record.values {
+                *filled.entry(name.as_str()).or_default() += !value.is_empty_value() as usize;
+            }
+        }
+
+        let mut rates: Vec<(String, f32)> = filled.into_iter()
+            .map(|(name, count)| (name.to_string(), count as f32 / records.len() as f32))
+            .collect();
+        rates.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        rates
+    }
+
+    /// List property values that changed since `baseline`
+    ///
+    /// Records are matched by GUID; records only present on one side are
+    /// not listed. Floats within [`CHANGELOG_FLOAT_EPSILON`] of each other
+    /// count as unchanged. Changes are sorted by struct, record and
+    /// property name, see [`group_changes_by_struct`].
+    pub fn changelog_against(&self, baseline: &DataCore) -> Vec<PropertyChange> {
+        let mut changes = Vec::new();
+
+        for record in &self.records {
+            let Some(old) = baseline.get_record(record.guid) else {
+                continue;
+            };
+
+            let properties: BTreeSet<&str> = record.values.keys()
+                .chain(old.values.keys())
+                .map(String::as_str)
+                .collect();
+
+            for property in properties {
+                let (old_value, new_value) = (old.values.get(property), record.values.get(property));
+                let unchanged = match (old_value, new_value) {
+                    (Some(a), Some(b)) => a.approx_eq(b, CHANGELOG_FLOAT_EPSILON),
+                    _ => false,
+                };
+                if unchanged {
+                    continue;
+                }
+
+                changes.push(PropertyChange {
+                    struct_name: self.structs.get(record.struct_id as usize)
+                        .map(|s| s.name.clone())
+                        .unwrap_or_else(|| format!("struct_{}", record.struct_id)),
+                    record_guid: record.guid,
+                    record_name: record.name.clone(),
+                    property: property.to_string(),
+                    old_value: old_value.cloned(),
+                    new_value: new_value.cloned(),
+                });
+            }
+        }
+
+        changes.sort_by(|a, b| {
+            (&a.struct_name, &a.record_name, &a.property).cmp(&(&b.struct_name, &b.record_name, &b.property))
+        });
+        changes
+    }
+}
+
+/// Float tolerance used by [`DataCore::changelog_against`]
+pub const CHANGELOG_FLOAT_EPSILON: f64 = 1e-6;
+
+/// A property whose value differs between two DataCores
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertyChange {
+    /// Struct type of the record
+    pub struct_name: String,
+    /// GUID of the record
+    pub record_guid: u64,
+    /// Name of the record
+    pub record_name: String,
+    /// Property name
+    pub property: String,
+    /// Value in the baseline (`None` if the property was added)
+    pub old_value: Option<RecordValue>,
+    /// Current value (`None` if the property was removed)
+    pub new_value: Option<RecordValue>,
+}
+
+/// Group changes by struct name for per-struct reports
+pub fn group_changes_by_struct(changes: &[PropertyChange]) -> BTreeMap<&str, Vec<&PropertyChange>> {
+    let mut groups: BTreeMap<&str, Vec<&PropertyChange>> = BTreeMap::new();
+    for change in changes {
+        groups.entry(change.struct_name.as_str()).or_default().push(change);
+    }
+    groups
+}
+
+/// A reference whose target record couldn't be found
+#[derive(Debug, Clone)]
+pub struct DanglingRef {
+    /// GUID of the record holding the reference
+    pub source_guid: u64,
+    /// Name of the record holding the reference
+    pub source_name: String,
+    /// Property path, with array indices (e.g. `parts[2]`)
+    pub property: String,
+    /// The unresolved reference
+    pub reference: RecordRef,
+}
+
+/// Data type distribution of a DataCore, for format research
+#[derive(Debug, Clone, Default)]
+pub struct TypeStats {
+    /// Number of property definitions using each data type
+    pub property_types: HashMap<DataType, usize>,
+    /// Occurrences of `RecordValue::Unknown` by raw type id
+    pub unknown_values: HashMap<u32, usize>,
+    /// Total number of values visited (array elements included)
+    pub value_count: usize,
+}
+
+impl TypeStats {
+    /// Property types sorted by usage, most common first
+    pub fn sorted_property_types(&self) -> Vec<(&DataType, usize)> {
+        let mut types: Vec<_> = self.property_types.iter().map(|(t, c)| (t, *c)).collect();
+        types.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| format!("{:?}", a.0).cmp(&format!("{:?}", b.0))));
+        types
+    }
+    
+    /// Number of property definitions with an unrecognised type id
+    pub fn unknown_property_count(&self) -> usize {
+        self.property_types.iter()
+            .filter(|(t, _)| matches!(t, DataType::Unknown(_)))
+            .map(|(_, c)| c)
+            .sum()
+    }
 }
 
 /// Lazy-loading DataCore that loads records on-demand
@@ -91,6 +548,9 @@ pub struct LazyDataCore {
     
     /// Shared file handle for lazy loading
     file_handle: Arc<Mutex<Option<std::fs::File>>>,
+
+    /// Options the file was opened with, applied to values loaded later
+    options: ParseOptions,
 }
 
 impl LazyDataCore {
@@ -117,8 +577,15 @@ impl LazyDataCore {
             file_handle: Arc::new(Mutex::new(
                 file_path.and_then(|p| std::fs::File::open(p).ok())
             )),
+            options: ParseOptions::default(),
         }
     }
+
+    /// Apply `options` to record values loaded from now on
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
     
     /// Get a lazy record by GUID
     pub fn get_record(&self, guid: u64) -> Option<&LazyRecord> {
@@ -243,19 +710,24 @@ impl LazyDataCore {
                 let w = f32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]);
                 RecordValue::Vec4([x, y, z, w])
             }
+            DataType::Blob => {
+                RecordValue::Bytes(super::read_blob(reader, self.options.decompression_memory_limit)?)
+            }
             _ => RecordValue::Unknown(0),
         })
     }
     
     /// Find records by struct type (returns lazy records)
     pub fn find_by_struct(&self, struct_name: &str) -> Vec<&LazyRecord> {
-        if let Some(&struct_idx) = self.struct_index.get(struct_name) {
-            self.records.iter()
-                .filter(|r| r.struct_id == struct_idx as u32)
-                .collect()
-        } else {
-            Vec::new()
-        }
+        let struct_ids = struct_ids_named(&self.structs, struct_name);
+        self.records.iter()
+            .filter(|r| struct_ids.contains(&r.struct_id))
+            .collect()
+    }
+
+    /// Get every struct definition with this name
+    pub fn structs_by_name(&self, name: &str) -> Vec<&StructDef> {
+        self.structs.iter().filter(|s| s.name == name).collect()
     }
     
     /// Get total record count
@@ -294,4 +766,336 @@ impl LazyDataCore {
             record.unload();
         }
     }
-}
\ No newline at end of file
+}
+
+/// Indices of every struct named `name`, matching `Record::struct_id`
+fn struct_ids_named(structs: &[StructDef], name: &str) -> Vec<u32> {
+    structs.iter()
+        .enumerate()
+        .filter(|(_, s)| s.name == name)
+        .map(|(idx, _)| idx as u32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lasso::ThreadedRodeo;
+
+    fn make_struct(id: u32, name: &str) -> StructDef {
+        StructDef {
+            id,
+            name: name.to_string(),
+            parent_id: None,
+            property_start: 0,
+            property_count: 0,
+            size: 0,
+            flags: 0,
+        }
+    }
+
+    fn make_record(id: u32, struct_id: u32, values: Vec<(&str, RecordValue)>) -> Record {
+        Record {
+            id,
+            struct_id,
+            name: format!("record_{}", id),
+            guid: 0x1000 + id as u64,
+            values: values.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+        }
+    }
+
+    fn reference(record_id: u32, struct_id: u32) -> RecordValue {
+        RecordValue::Reference(RecordRef { record_id, struct_id })
+    }
+
+    fn make_property(id: u32, name: &str, data_type: DataType) -> PropertyDef {
+        PropertyDef {
+            id,
+            name: name.to_string(),
+            data_type,
+            struct_id: None,
+            conversion: 0,
+        }
+    }
+
+    fn make_datacore(structs: Vec<StructDef>, records: Vec<Record>) -> DataCore {
+        let struct_index = structs.iter().enumerate().map(|(i, s)| (s.name.clone(), i)).collect();
+        let record_index = records.iter().enumerate().map(|(i, r)| (r.guid, i)).collect();
+
+        DataCore {
+            header: DataCoreHeader {
+                version: 1,
+                struct_count: structs.len() as u32,
+                property_count: 0,
+                record_count: records.len() as u32,
+                string_offset: 0,
+                struct_offset: 0,
+                property_offset: 0,
+                record_offset: 0,
+                value_offset: None,
+                enum_offset: None,
+                string_encoding: StringEncoding::Utf8,
+            },
+            strings: StringTable {
+                interner: Arc::new(ThreadedRodeo::default()),
+                spurs: Vec::new(),
+                by_offset: HashMap::new(),
+            },
+            structs,
+            properties: Vec::new(),
+            records,
+            struct_index,
+            record_index,
+        }
+    }
+
+    /// Record 1 (Manufacturer) is referenced by 2, 3 and 4; record 5 by 2 only
+    fn make_referenced_datacore() -> DataCore {
+        make_datacore(
+            vec![make_struct(0, "Ship"), make_struct(1, "Manufacturer")],
+            vec![
+                make_record(0, 0, vec![("null", reference(0xFFFFFFFF, 1))]),
+                make_record(1, 1, vec![]),
+                make_record(2, 0, vec![
+                    ("manufacturer", reference(1, 1)),
+                    ("parts", RecordValue::array(DataType::Reference, vec![reference(5, 0), reference(1, 1)])),
+                ]),
+                make_record(3, 0, vec![("manufacturer", reference(1, 1))]),
+                make_record(4, 0, vec![("manufacturer", reference(1, 1))]),
+                make_record(5, 0, vec![]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_find_by_struct_duplicate_names() {
+        let datacore = make_datacore(
+            vec![make_struct(0, "Loadout"), make_struct(1, "Ship"), make_struct(2, "Loadout")],
+            vec![make_record(0, 0, vec![]), make_record(1, 1, vec![]), make_record(2, 2, vec![])],
+        );
+
+        assert_eq!(datacore.structs_by_name("Loadout").len(), 2);
+        let ids: Vec<u32> = datacore.find_by_struct("Loadout").iter().map(|r| r.id).collect();
+        assert_eq!(ids, [0, 2]);
+        assert!(datacore.find_by_struct("Missing").is_empty());
+    }
+
+    #[test]
+    fn test_property_fill_rates() {
+        let records = (0..4)
+            .map(|i| make_record(i, 0, vec![
+                ("thrust", RecordValue::Float(100.0 + i as f32)),
+                ("unused", RecordValue::Int32(0)),
+                ("tag", RecordValue::String(if i == 0 { "boost".to_string() } else { String::new() })),
+            ]))
+            .chain([make_record(4, 1, vec![("other", RecordValue::Boolean(true))])])
+            .collect();
+        let datacore = make_datacore(vec![make_struct(0, "Thruster"), make_struct(1, "Shield")], records);
+
+        let rates = datacore.property_fill_rates("Thruster");
+        let names: Vec<&str> = rates.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["thrust", "tag", "unused"]);
+        assert!((rates[0].1 - 1.0).abs() < 1e-6);
+        assert!((rates[1].1 - 0.25).abs() < 1e-6);
+        assert!(rates[2].1.abs() < 1e-6);
+
+        assert!(datacore.property_fill_rates("Missing").is_empty());
+    }
+
+    #[test]
+    fn test_validate_references() {
+        let datacore = make_datacore(
+            vec![make_struct(0, "Ship"), make_struct(1, "Manufacturer")],
+            vec![
+                make_record(0, 1, vec![]),
+                make_record(1, 1, vec![]),
+                make_record(2, 0, vec![
+                    ("manufacturer", reference(1, 1)),
+                    ("null", reference(0xFFFFFFFF, 1)),
+                    ("parts", RecordValue::array(DataType::Reference, vec![reference(1, 1), reference(42, 0)])),
+                    ("loadout", RecordValue::Struct(HashMap::from([
+                        ("engine".to_string(), reference(7, 0)),
+                        ("shield".to_string(), reference(1, 1)),
+                    ]))),
+                ]),
+            ],
+        );
+
+        let dangling = datacore.validate_references();
+
+        assert_eq!(dangling.len(), 2);
+        assert_eq!(dangling[0].source_guid, 0x1002);
+        assert_eq!(dangling[0].property, "loadout.engine");
+        assert_eq!(dangling[0].reference.record_id, 7);
+        assert_eq!(dangling[1].property, "parts[1]");
+        assert_eq!(dangling[1].reference.record_id, 42);
+
+        // Same references as the walk used by reverse_references
+        let unresolved = datacore.records[2].values.values()
+            .flat_map(RecordValue::references)
+            .filter(|r| datacore.resolve_reference(r).is_none())
+            .count();
+        assert_eq!(unresolved, dangling.len());
+    }
+
+    #[test]
+    fn test_find_strings_and_usage() {
+        let mut datacore = make_datacore(
+            vec![make_struct(0, "Ship")],
+            vec![make_record(0, 0, vec![
+                ("geometry", RecordValue::String("Objects/Ships/hull.cgf".to_string())),
+            ])],
+        );
+        for s in ["Ship", "record_0", "Objects/Ships/hull.cgf", "Objects/Unused/prop.cgf"] {
+            let spur = datacore.strings.interner.get_or_intern(s);
+            datacore.strings.spurs.push(spur);
+        }
+
+        assert_eq!(datacore.strings().len(), 4);
+        assert_eq!(
+            datacore.find_strings(".cgf"),
+            vec![(2, "Objects/Ships/hull.cgf"), (3, "Objects/Unused/prop.cgf")]
+        );
+        assert_eq!(datacore.find_strings("hull"), vec![(2, "Objects/Ships/hull.cgf")]);
+
+        assert_eq!(datacore.string_usage(), vec![1, 1, 1, 0]);
+        assert_eq!(datacore.unused_strings(), vec![(3, "Objects/Unused/prop.cgf")]);
+    }
+
+    #[test]
+    fn test_reference_histogram() {
+        let datacore = make_referenced_datacore();
+        let histogram = datacore.reference_histogram();
+
+        assert_eq!(histogram, vec![(0x1001, 3), (0x1005, 1)]);
+    }
+
+    #[test]
+    fn test_reverse_references() {
+        let datacore = make_referenced_datacore();
+        let reverse = datacore.reverse_references();
+
+        let mut referrers = reverse[&0x1001].clone();
+        referrers.sort_unstable();
+        assert_eq!(referrers, vec![0x1002, 0x1003, 0x1004]);
+        assert!(!reverse.contains_key(&0x1000));
+    }
+
+    #[test]
+    fn test_struct_reference_counts() {
+        let datacore = make_referenced_datacore();
+        let counts = datacore.struct_reference_counts();
+
+        assert_eq!(counts, vec![("Manufacturer".to_string(), 3), ("Ship".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_type_statistics() {
+        let mut datacore = make_datacore(
+            vec![make_struct(0, "Ship")],
+            vec![
+                make_record(0, 0, vec![
+                    ("mass", RecordValue::Float(1.0)),
+                    ("mystery", RecordValue::Unknown(0x42)),
+                ]),
+                make_record(1, 0, vec![
+                    ("mass", RecordValue::Float(2.0)),
+                    ("mystery", RecordValue::array(DataType::Unknown(0x42), vec![RecordValue::Unknown(0x42)])),
+                ]),
+            ],
+        );
+        datacore.properties = vec![
+            make_property(0, "mass", DataType::Float),
+            make_property(1, "health", DataType::Float),
+            make_property(2, "name", DataType::String),
+            make_property(3, "mystery", DataType::Unknown(0x42)),
+        ];
+
+        let stats = datacore.type_statistics();
+
+        assert_eq!(stats.property_types[&DataType::Float], 2);
+        assert_eq!(stats.property_types[&DataType::String], 1);
+        assert_eq!(stats.unknown_property_count(), 1);
+        assert_eq!(stats.unknown_values.len(), 1);
+        assert_eq!(stats.unknown_values[&0x42], 2);
+        assert_eq!(stats.value_count, 5);
+        assert_eq!(stats.sorted_property_types()[0], (&DataType::Float, 2));
+    }
+
+    #[test]
+    fn test_records_of_struct_in_file_order() {
+        let datacore = make_referenced_datacore();
+
+        let ships: Vec<u32> = datacore.records_of_struct(0).map(|r| r.id).collect();
+        assert_eq!(ships, vec![0, 2, 3, 4, 5]);
+        assert_eq!(datacore.records_of_struct(7).count(), 0);
+
+        assert_eq!(datacore.record_by_index(1).map(|r| r.id), Some(1));
+        assert!(datacore.record_by_index(6).is_none());
+
+        let counts = datacore.record_count_by_struct();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&0], 5);
+        assert_eq!(counts[&1], 1);
+    }
+
+    #[test]
+    fn test_record_path() {
+        let mut launcher = make_struct(1, "MLauncher");
+        launcher.parent_id = Some(0);
+        let mut unnamed = make_record(2, 1, vec![]);
+        unnamed.name.clear();
+
+        let datacore = make_datacore(
+            vec![make_struct(0, "Ship"), launcher],
+            vec![make_record(0, 0, vec![]), make_record(1, 1, vec![]), unnamed],
+        );
+
+        assert_eq!(datacore.record_path(&datacore.records[0]), "Ship/record_0");
+        assert_eq!(datacore.records[1].display_label(&datacore), "Ship/MLauncher/record_1");
+
+        let label = datacore.records[2].display_label(&datacore);
+        assert_eq!(label, format!("Ship/MLauncher/{}", datacore.records[2].cig_guid()));
+    }
+
+    #[test]
+    fn test_changelog_against() {
+        let structs = || vec![make_struct(0, "Ship"), make_struct(1, "Weapon")];
+        let baseline = make_datacore(structs(), vec![
+            make_record(0, 0, vec![("health", RecordValue::Int32(1000)), ("mass", RecordValue::Float(1.5))]),
+            make_record(1, 1, vec![("damage", RecordValue::Float(20.0))]),
+        ]);
+
+        // Float noise only: just the int change is reported
+        let patched = make_datacore(structs(), vec![
+            make_record(0, 0, vec![("health", RecordValue::Int32(1200)), ("mass", RecordValue::Float(1.5000001))]),
+            make_record(1, 1, vec![("damage", RecordValue::Float(20.0))]),
+            make_record(2, 1, vec![("damage", RecordValue::Float(5.0))]),
+        ]);
+        let changes = patched.changelog_against(&baseline);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].struct_name, "Ship");
+        assert_eq!(changes[0].record_guid, 0x1000);
+        assert_eq!(changes[0].property, "health");
+        assert!(matches!(changes[0].old_value, Some(RecordValue::Int32(1000))));
+        assert!(matches!(changes[0].new_value, Some(RecordValue::Int32(1200))));
+
+        // A real float change, plus a property added to another struct
+        let patched = make_datacore(structs(), vec![
+            make_record(0, 0, vec![("health", RecordValue::Int32(1200)), ("mass", RecordValue::Float(2.0))]),
+            make_record(1, 1, vec![("damage", RecordValue::Float(20.0)), ("range", RecordValue::Int32(900))]),
+        ]);
+        let changes = patched.changelog_against(&baseline);
+        let properties: Vec<_> = changes.iter().map(|c| c.property.as_str()).collect();
+        assert_eq!(properties, vec!["health", "mass", "range"]);
+        assert!(changes[2].old_value.is_none());
+
+        let groups = group_changes_by_struct(&changes);
+        assert_eq!(groups["Ship"].len(), 2);
+        assert_eq!(groups["Weapon"].len(), 1);
+
+        let json = serde_json::to_value(&changes[1]).unwrap();
+        assert_eq!(json["record_name"], "record_0");
+        assert!(patched.changelog_against(&patched).is_empty());
+    }
+}
@@ -43,10 +43,14 @@ mod datacore;
 mod records;
 mod structs;
 mod cryxml;
+mod typed;
+mod writer;
 
-pub use datacore::{DataCore, DataCoreHeader, LazyDataCore};
-pub use records::{Record, RecordValue, RecordRef, LazyRecord};
+pub use datacore::{DataCore, DataCoreHeader, LazyDataCore, DanglingRef, TypeStats, StringEncoding};
+pub use datacore::{PropertyChange, group_changes_by_struct, CHANGELOG_FLOAT_EPSILON};
+pub use records::{Record, RecordValue, RecordRef, LazyRecord, CigGuid, MAX_VALUE_DEPTH};
 pub use structs::{StructDef, PropertyDef, DataType};
+pub use typed::{FromRecord, FromRecordValue};
 
 use std::io::{Read, Seek, SeekFrom, BufReader};
 use std::collections::HashMap;
@@ -56,17 +60,58 @@ use lasso::{ThreadedRodeo, Spur};
 
 use crate::traits::{
     Parser, ParseResult, ParseError,
-    ParseOptions, ParseProgress, ParsePhase, ProgressCallback
+    ParseOptions, ParseProgress, ParsePhase, ProgressCallback,
+    ParseReport, ParseWarning,
 };
 
 /// DCB file magic bytes
-const DCB_MAGIC: &[u8] = &[0x44, 0x43, 0x42, 0x31]; // "DCB1"
+pub(crate) const DCB_MAGIC: &[u8] = &[0x44, 0x43, 0x42, 0x31]; // "DCB1"
+
+/// Byte order mark introducing UTF-16LE string data
+const UTF16LE_BOM: [u8; 2] = [0xFF, 0xFE];
+
+/// First version using the extended 44-byte header
+const DCB_EXTENDED_VERSION: u32 = 6;
 
 /// Alternate CryXml magic (for older formats)
-const CRYXML_MAGIC: &[u8] = &[0x43, 0x72, 0x79, 0x58]; // "CryX"
+pub(crate) const CRYXML_MAGIC: &[u8] = &[0x43, 0x72, 0x79, 0x58]; // "CryX"
 
 /// Binary XML magic
-const BINXML_MAGIC: u32 = 0x4D584C42; // "BXLM"
+pub(crate) const BINXML_MAGIC: u32 = 0x4D584C42; // "BXLM"
+
+/// Read a u32 length-prefixed blob, refusing lengths above `max_len`
+pub(crate) fn read_blob<R: Read>(reader: &mut R, max_len: usize) -> ParseResult<Vec<u8>> {
+    let mut len_buf = [0u8; 4];
+    reader.read_exact(&mut len_buf)?;
+    let len = u32::from_le_bytes(len_buf) as usize;
+
+    if len > max_len {
+        return Err(ParseError::BufferOverflow { requested: len, available: max_len });
+    }
+
+    let mut data = vec![0u8; len];
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// Skip past one value without decoding it
+///
+/// Consumes exactly the bytes [`DcbParser::read_value`] would: blobs by their
+/// length prefix, arrays by their element count alone.
+fn skip_value<R: Read + Seek>(reader: &mut R, data_type: &DataType) -> ParseResult<()> {
+    let len = match data_type {
+        DataType::Blob => {
+            let mut len_buf = [0u8; 4];
+            reader.read_exact(&mut len_buf)?;
+            u32::from_le_bytes(len_buf) as i64
+        }
+        DataType::Array(_) | DataType::LocaleString => 4,
+        DataType::Unknown(_) => 0,
+        fixed => fixed.size().unwrap_or(0) as i64,
+    };
+    reader.seek(SeekFrom::Current(len))?;
+    Ok(())
+}
 
 /// DataCore Binary parser
 pub struct DcbParser {
@@ -130,7 +175,7 @@ impl DcbParser {
         let file = std::fs::File::open(path)?;
         let mut reader = BufReader::new(file);
         
-        let datacore = self.parse_lazy_impl(&mut reader, Some(path))?;
+        let datacore = self.parse_lazy_impl(&mut reader, Some(path), &ParseOptions::default())?;
         let arc = Arc::new(datacore);
         
         // Cache result
@@ -142,17 +187,33 @@ impl DcbParser {
         Ok(arc)
     }
     
+    /// Parse file with lazy loading, applying `options` to values loaded later
+    ///
+    /// Like [`parse_lazy`](Self::parse_lazy), but the returned DataCore keeps
+    /// `options` for its on-demand reads, so limits such as
+    /// `decompression_memory_limit` hold for every record. The result isn't
+    /// cached, since the cache is keyed by path alone.
+    pub fn parse_lazy_with_options(&self, path: &Path, options: &ParseOptions) -> ParseResult<Arc<LazyDataCore>> {
+        let file = std::fs::File::open(path)?;
+        let mut reader = BufReader::new(file);
+
+        Ok(Arc::new(self.parse_lazy_impl(&mut reader, Some(path), options)?))
+    }
+    
     /// Internal lazy parsing implementation
     fn parse_lazy_impl<R: Read + Seek>(
         &self,
         reader: &mut R,
         file_path: Option<&Path>,
+        options: &ParseOptions,
     ) -> ParseResult<LazyDataCore> {
+        phase_span!("dcb.parse_lazy");
+
         // Parse header
-        let header = self.parse_header(reader)?;
+        let mut header = self.parse_header(reader)?;
         
         // Parse string table
-        let strings = self.parse_string_table(reader, header.string_offset)?;
+        let strings = self.parse_string_table(reader, &mut header)?;
         
         // Parse struct definitions
         let structs = self.parse_struct_definitions(
@@ -170,6 +231,8 @@ impl DcbParser {
             reader,
             &header,
             &strings,
+            &structs,
+            &properties,
         )?;
         
         // Build indices
@@ -195,9 +258,40 @@ impl DcbParser {
             struct_index,
             record_index,
             file_path.map(|p| p.to_path_buf()),
-        ))
+        ).with_options(options.clone()))
     }
     
+    /// Parse a DataCore file, turning per-record failures into warnings
+    ///
+    /// A record whose values can't be read is kept with no values and the
+    /// parser skips ahead to the next record, instead of aborting the whole
+    /// file as `parse_with_options` does.
+    pub fn parse_with_report<R: Read + Seek>(
+        &self,
+        mut reader: R,
+        options: &ParseOptions,
+    ) -> ParseResult<ParseReport<DataCore>> {
+        let mut warnings = Vec::new();
+
+        let mut header = self.parse_header(&mut reader)?;
+        let strings = self.parse_string_table(&mut reader, &mut header)?;
+        let structs = self.parse_struct_definitions(&mut reader, &header, &strings, None)?;
+        let properties = self.parse_property_definitions(&mut reader, &header, &strings)?;
+        let records = self.parse_records(
+            &mut reader,
+            &header,
+            &strings,
+            &structs,
+            &properties,
+            None,
+            options,
+            Some(&mut warnings),
+        )?;
+
+        let output = DataCore::from_parts(header, strings, structs, properties, records);
+        Ok(ParseReport { output, warnings })
+    }
+
     /// Parse the file header
     fn parse_header<R: Read + Seek>(&self, reader: &mut R) -> ParseResult<DataCoreHeader> {
         let mut header_data = [0u8; 36];
@@ -218,7 +312,7 @@ impl DcbParser {
             return Err(ParseError::InvalidMagic {
                 expected: DCB_MAGIC.to_vec(),
                 found: magic.to_vec(),
-            });
+            }.at_offset(0));
         }
         
         let version = u32::from_le_bytes([
@@ -252,6 +346,18 @@ impl DcbParser {
         let record_offset = u32::from_le_bytes([
             header_data[32], header_data[33], header_data[34], header_data[35]
         ]) as u64;
+
+        // Version 6 appends the values and enum section offsets
+        let (value_offset, enum_offset) = if version >= DCB_EXTENDED_VERSION {
+            let mut extended = [0u8; 8];
+            reader.read_exact(&mut extended)?;
+            (
+                Some(u32::from_le_bytes([extended[0], extended[1], extended[2], extended[3]]) as u64),
+                Some(u32::from_le_bytes([extended[4], extended[5], extended[6], extended[7]]) as u64),
+            )
+        } else {
+            (None, None)
+        };
         
         Ok(DataCoreHeader {
             version,
@@ -262,6 +368,9 @@ impl DcbParser {
             struct_offset,
             property_offset,
             record_offset,
+            value_offset,
+            enum_offset,
+            string_encoding: StringEncoding::Utf8,
         })
     }
     
@@ -292,15 +401,24 @@ impl DcbParser {
             struct_offset: 0,
             property_offset: 0,
             record_offset: 0,
+            value_offset: None,
+            enum_offset: None,
+            string_encoding: StringEncoding::Utf8,
         })
     }
     
     /// Parse the string table
+    ///
+    /// String data starting with a UTF-16LE byte order mark is decoded as
+    /// UTF-16; anything else is lossy UTF-8. The detected encoding is
+    /// recorded on `header.string_encoding`.
     fn parse_string_table<R: Read + Seek>(
         &self,
         reader: &mut R,
-        offset: u64,
+        header: &mut DataCoreHeader,
     ) -> ParseResult<StringTable> {
+        let offset = header.string_offset;
+        phase_span!("dcb.string_table", offset = offset);
         reader.seek(SeekFrom::Start(offset))?;
         
         // Read string count
@@ -320,6 +438,12 @@ impl DcbParser {
         let _data_start = reader.stream_position()?;
         let mut string_data = Vec::new();
         reader.read_to_end(&mut string_data)?;
+
+        header.string_encoding = if string_data.starts_with(&UTF16LE_BOM) {
+            StringEncoding::Utf16Le
+        } else {
+            StringEncoding::Utf8
+        };
         
         // Build string map using interner to deduplicate values
         let interner = Arc::new(ThreadedRodeo::default());
@@ -327,21 +451,14 @@ impl DcbParser {
         let mut by_offset = HashMap::new();
         
         for &str_offset in offsets.iter() {
-            let start = str_offset as usize;
-            
-            // Find null terminator
-            let end = string_data[start..]
-                .iter()
-                .position(|&b| b == 0)
-                .map(|p| start + p)
-                .unwrap_or(string_data.len());
-            
-            let s = String::from_utf8_lossy(&string_data[start..end]);
+            let start = (str_offset as usize).min(string_data.len());
+            let s = decode_string(&string_data[start..], header.string_encoding);
             let spur = interner.get_or_intern(s.as_ref());
             by_offset.insert(str_offset, spur);
             spurs.push(spur);
         }
         
+        phase_event!("string table parsed", strings = count, bytes = string_data.len());
         Ok(StringTable { interner, spurs, by_offset })
     }
     
@@ -353,6 +470,7 @@ impl DcbParser {
         strings: &StringTable,
         progress: Option<&ProgressCallback>,
     ) -> ParseResult<Vec<StructDef>> {
+        phase_span!("dcb.structs", count = header.struct_count, offset = header.struct_offset);
         reader.seek(SeekFrom::Start(header.struct_offset))?;
         
         let mut structs = Vec::with_capacity(header.struct_count as usize);
@@ -423,6 +541,7 @@ impl DcbParser {
         header: &DataCoreHeader,
         strings: &StringTable,
     ) -> ParseResult<Vec<PropertyDef>> {
+        phase_span!("dcb.properties", count = header.property_count, offset = header.property_offset);
         reader.seek(SeekFrom::Start(header.property_offset))?;
         
         let mut properties = Vec::with_capacity(header.property_count as usize);
@@ -469,7 +588,10 @@ impl DcbParser {
         reader: &mut R,
         header: &DataCoreHeader,
         strings: &StringTable,
+        structs: &[StructDef],
+        properties: &[PropertyDef],
     ) -> ParseResult<Vec<LazyRecord>> {
+        phase_span!("dcb.records", count = header.record_count, offset = header.record_offset, lazy = true);
         reader.seek(SeekFrom::Start(header.record_offset))?;
         
         let mut records = Vec::with_capacity(header.record_count as usize);
@@ -501,8 +623,12 @@ impl DcbParser {
             
             let guid = ((guid_hi as u64) << 32) | (guid_lo as u64);
             
-            // Store current position for lazy loading later
+            // Store current position for lazy loading later, then skip the
+            // values to reach the next record header
             let data_offset = reader.stream_position()?;
+            if let Some(sd) = structs.get(struct_id as usize) {
+                self.skip_record_values(reader, sd, properties)?;
+            }
             
             records.push(LazyRecord::new(
                 i,
@@ -525,7 +651,10 @@ impl DcbParser {
         structs: &[StructDef],
         properties: &[PropertyDef],
         progress: Option<&ProgressCallback>,
+        options: &ParseOptions,
+        mut warnings: Option<&mut Vec<ParseWarning>>,
     ) -> ParseResult<Vec<Record>> {
+        phase_span!("dcb.records", count = header.record_count, offset = header.record_offset, lazy = false);
         reader.seek(SeekFrom::Start(header.record_offset))?;
         
         let mut records = Vec::with_capacity(header.record_count as usize);
@@ -552,7 +681,7 @@ impl DcbParser {
             ]);
             
             let name = strings.get_by_offset(name_offset)
-                .cloned()
+                .map(str::to_owned)
                 .unwrap_or_default();
             
             let guid = ((guid_hi as u64) << 32) | (guid_lo as u64);
@@ -562,7 +691,35 @@ impl DcbParser {
             
             // Parse property values based on struct definition
             let values = if let Some(sd) = struct_def {
-                self.parse_record_values(reader, sd, properties, strings)?
+                let values_offset = reader.stream_position()?;
+                match (self.parse_record_values(reader, sd, properties, strings, options), warnings.as_deref_mut()) {
+                    (Ok(mut values), warnings) => {
+                        if options.sanitize_floats {
+                            let mut sanitized: Vec<_> = values.iter_mut()
+                                .filter_map(|(prop, value)| value.sanitize_floats().then_some(prop.as_str()))
+                                .collect();
+                            sanitized.sort_unstable();
+
+                            if let Some(warnings) = warnings {
+                                for prop in sanitized {
+                                    warnings.push(ParseWarning::sanitized(
+                                        values_offset,
+                                        format!("Record {} ({}).{}", i, name, prop),
+                                    ));
+                                }
+                            }
+                        }
+                        values
+                    }
+                    (Err(e), Some(warnings)) => {
+                        // Skip to the next record and keep going
+                        warnings.push(ParseWarning::new(values_offset, format!("Record {} ({})", i, name), &e));
+                        reader.seek(SeekFrom::Start(values_offset))?;
+                        self.skip_record_values(reader, sd, properties)?;
+                        HashMap::new()
+                    }
+                    (Err(e), None) => return Err(e),
+                }
             } else {
                 HashMap::new()
             };
@@ -599,6 +756,7 @@ impl DcbParser {
         struct_def: &StructDef,
         properties: &[PropertyDef],
         strings: &StringTable,
+        options: &ParseOptions,
     ) -> ParseResult<HashMap<String, RecordValue>> {
         let mut values = HashMap::new();
         
@@ -607,7 +765,7 @@ impl DcbParser {
         
         for i in start..end {
             if let Some(prop) = properties.get(i) {
-                let value = self.read_value(reader, &prop.data_type, strings)?;
+                let value = self.read_value(reader, &prop.data_type, strings, options.decompression_memory_limit)?;
                 values.insert(prop.name.clone(), value);
             }
         }
@@ -615,12 +773,30 @@ impl DcbParser {
         Ok(values)
     }
     
+    /// Skip past a record's values without decoding them
+    fn skip_record_values<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        struct_def: &StructDef,
+        properties: &[PropertyDef],
+    ) -> ParseResult<()> {
+        let start = struct_def.property_start as usize;
+        let end = start + struct_def.property_count as usize;
+
+        for prop in (start..end).filter_map(|i| properties.get(i)) {
+            skip_value(reader, &prop.data_type)?;
+        }
+
+        Ok(())
+    }
+
     /// Read a single value based on type
     fn read_value<R: Read>(
         &self,
         reader: &mut R,
         data_type: &DataType,
         strings: &StringTable,
+        max_blob_size: usize,
     ) -> ParseResult<RecordValue> {
         Ok(match data_type {
             DataType::Boolean => {
@@ -743,15 +919,17 @@ impl DcbParser {
                 reader.read_exact(&mut buf)?;
                 RecordValue::Enum(u32::from_le_bytes(buf))
             }
+
+            DataType::Blob => RecordValue::Bytes(read_blob(reader, max_blob_size)?),
             
-            DataType::Array(_) => {
+            DataType::Array(elem_type) => {
                 // Array handling - read count first
                 let mut count_buf = [0u8; 4];
                 reader.read_exact(&mut count_buf)?;
                 let _count = u32::from_le_bytes(count_buf) as usize;
                 
                 // For now, return as bytes
-                RecordValue::Array(vec![])
+                RecordValue::array((**elem_type).clone(), vec![])
             }
             
             DataType::Unknown(type_id) => {
@@ -785,9 +963,11 @@ impl Parser for DcbParser {
     fn parse_with_options<R: Read + Seek>(
         &self,
         mut reader: R,
-        _options: &ParseOptions,
+        options: &ParseOptions,
         progress: Option<ProgressCallback>,
     ) -> ParseResult<Self::Output> {
+        phase_span!("dcb.parse");
+
         // Report start
         if let Some(ref cb) = progress {
             cb(ParseProgress {
@@ -801,10 +981,10 @@ impl Parser for DcbParser {
         }
         
         // Parse header
-        let header = self.parse_header(&mut reader)?;
+        let mut header = self.parse_header(&mut reader)?;
         
         // Parse string table
-        let strings = self.parse_string_table(&mut reader, header.string_offset)?;
+        let strings = self.parse_string_table(&mut reader, &mut header)?;
         
         // Parse struct definitions
         let structs = self.parse_struct_definitions(
@@ -824,23 +1004,11 @@ impl Parser for DcbParser {
             &strings,
             &structs,
             &properties,
-            progress.as_ref()
+            progress.as_ref(),
+            options,
+            None,
         )?;
         
-        // Build indices
-        let mut struct_index = HashMap::new();
-        for (idx, s) in structs.iter().enumerate() {
-            struct_index.insert(s.name.clone(), idx);
-        }
-        
-        let mut record_index = HashMap::new();
-        for (idx, r) in records.iter().enumerate() {
-            record_index.insert(r.guid, idx);
-            if !r.name.is_empty() {
-                record_index.insert(r.id as u64, idx);
-            }
-        }
-        
         // Report completion
         if let Some(ref cb) = progress {
             cb(ParseProgress {
@@ -853,15 +1021,25 @@ impl Parser for DcbParser {
             });
         }
         
-        Ok(DataCore {
-            header,
-            strings,
-            structs,
-            properties,
-            records,
-            struct_index,
-            record_index,
-        })
+        Ok(DataCore::from_parts(header, strings, structs, properties, records))
+    }
+}
+
+/// Decode one null-terminated string from the start of `data`
+fn decode_string(data: &[u8], encoding: StringEncoding) -> std::borrow::Cow<'_, str> {
+    match encoding {
+        StringEncoding::Utf8 => {
+            let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+            String::from_utf8_lossy(&data[..end])
+        }
+        StringEncoding::Utf16Le => {
+            let units: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .take_while(|&unit| unit != 0)
+                .collect();
+            String::from_utf16_lossy(&units).into()
+        }
     }
 }
 
@@ -886,6 +1064,31 @@ impl StringTable {
     pub fn get_by_offset(&self, offset: u32) -> Option<&str> {
         self.by_offset.get(&offset).map(|spur| self.interner.resolve(spur))
     }
+
+    /// Number of entries in the table (duplicates included)
+    pub fn len(&self) -> usize {
+        self.spurs.len()
+    }
+
+    /// Check if the table has no entries
+    pub fn is_empty(&self) -> bool {
+        self.spurs.is_empty()
+    }
+
+    /// Iterate over `(id, string)` pairs in table order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
+        self.spurs.iter().enumerate().map(|(id, spur)| (id, self.interner.resolve(spur)))
+    }
+}
+
+impl Default for StringTable {
+    fn default() -> Self {
+        Self {
+            interner: Arc::new(ThreadedRodeo::default()),
+            spurs: Vec::new(),
+            by_offset: HashMap::new(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -898,4 +1101,225 @@ mod tests {
         assert_eq!(DataType::from_u32(4), DataType::Int32);
         assert_eq!(DataType::from_u32(8), DataType::Float);
     }
-}
\ No newline at end of file
+
+    /// One record of a struct with a float and a vec3 property
+    fn parse_nan_record(sanitize_floats: bool, warnings: Option<&mut Vec<ParseWarning>>) -> Record {
+        let mut data = vec![0u8; 16]; // struct 0, no name, guid 0
+        data.extend_from_slice(&f32::NAN.to_le_bytes());
+        data.extend_from_slice(&1.0f32.to_le_bytes());
+        data.extend_from_slice(&f32::INFINITY.to_le_bytes());
+        data.extend_from_slice(&3.0f32.to_le_bytes());
+
+        let header = DataCoreHeader {
+            version: 1,
+            struct_count: 1,
+            property_count: 2,
+            record_count: 1,
+            string_offset: 0,
+            struct_offset: 0,
+            property_offset: 0,
+            record_offset: 0,
+            value_offset: None,
+            enum_offset: None,
+            string_encoding: StringEncoding::Utf8,
+        };
+        let strings = StringTable {
+            interner: Arc::new(ThreadedRodeo::default()),
+            spurs: Vec::new(),
+            by_offset: HashMap::new(),
+        };
+        let structs = vec![StructDef {
+            id: 0,
+            name: "Thruster".to_string(),
+            parent_id: None,
+            property_start: 0,
+            property_count: 2,
+            size: 16,
+            flags: 0,
+        }];
+        let properties = vec![
+            PropertyDef { id: 0, name: "thrust".to_string(), data_type: DataType::Float, struct_id: None, conversion: 0 },
+            PropertyDef { id: 1, name: "offset".to_string(), data_type: DataType::Vec3, struct_id: None, conversion: 0 },
+        ];
+
+        let mut records = DcbParser::new().parse_records(
+            &mut std::io::Cursor::new(data),
+            &header,
+            &strings,
+            &structs,
+            &properties,
+            None,
+            &ParseOptions { sanitize_floats, ..Default::default() },
+            warnings,
+        ).unwrap();
+
+        records.remove(0)
+    }
+
+    #[test]
+    fn test_sanitize_floats() {
+        let mut warnings = Vec::new();
+        let record = parse_nan_record(true, Some(&mut warnings));
+
+        assert!(matches!(record.values["thrust"], RecordValue::Float(v) if v == 0.0));
+        assert!(matches!(record.values["offset"], RecordValue::Vec3(v) if v == [1.0, 0.0, 3.0]));
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].context.ends_with(".offset"));
+        assert!(warnings[1].context.ends_with(".thrust"));
+
+        // Left untouched by default
+        let record = parse_nan_record(false, None);
+        assert!(matches!(record.values["thrust"], RecordValue::Float(v) if v.is_nan()));
+    }
+
+    /// DataCore file bytes holding one `Signature { payload: Blob, seq: UInt32 }` record per payload
+    fn blob_datacore(payloads: &[&[u8]]) -> Vec<u8> {
+        let mut builder = crate::testutil::DataCoreBuilder::new();
+        let signature = builder.add_struct("Signature", None, &[("payload", DataType::Blob), ("seq", DataType::UInt32)]);
+        for (seq, payload) in payloads.iter().enumerate() {
+            builder.add_record(signature, &format!("Signature.{}", seq), seq as u64 + 1, [
+                ("payload", RecordValue::Bytes(payload.to_vec())),
+                ("seq", RecordValue::UInt32(seq as u32)),
+            ]);
+        }
+
+        let mut data = std::io::Cursor::new(Vec::new());
+        builder.build().write(&mut data).unwrap();
+        data.into_inner()
+    }
+
+    #[test]
+    fn test_blob_property() {
+        let payload = b"\x00\x01starbreaker\xff";
+        let data = blob_datacore(&[payload]);
+        let parse = |options: &ParseOptions| {
+            DcbParser::new().parse_with_options(std::io::Cursor::new(&data), options, None)
+        };
+
+        let datacore = parse(&ParseOptions::default()).unwrap();
+        let value = &datacore.records[0].values["payload"];
+        assert!(matches!(value, RecordValue::Bytes(b) if b.as_slice() == payload));
+        assert_eq!(value.to_json(), serde_json::json!("AAFzdGFyYnJlYWtlcv8="));
+        assert_eq!(value.as_string().unwrap(), "000173746172627265616b6572ff");
+
+        // Blob longer than the allocation bound is rejected
+        let tight = ParseOptions { decompression_memory_limit: 4, ..Default::default() };
+        assert!(matches!(parse(&tight), Err(ParseError::BufferOverflow { requested: 14, available: 4 })));
+    }
+
+    #[test]
+    fn test_records_after_blob_start_at_the_right_offset() {
+        let data = blob_datacore(&[b"\x00\x01starbreaker\xff", b"ok"]);
+
+        // The oversized first blob is skipped by its length prefix
+        let tight = ParseOptions { decompression_memory_limit: 4, ..Default::default() };
+        let report = DcbParser::new().parse_with_report(std::io::Cursor::new(&data), &tight).unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.output.records[0].values.is_empty());
+        assert!(matches!(&report.output.records[1].values["payload"], RecordValue::Bytes(b) if b.as_slice() == b"ok"));
+        assert!(matches!(report.output.records[1].values["seq"], RecordValue::UInt32(1)));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("Game.dcb");
+        std::fs::write(&path, &data).unwrap();
+
+        let lazy = DcbParser::new().parse_lazy(&path).unwrap();
+        let values = lazy.load_record(&lazy.records[1]).unwrap();
+        assert!(matches!(&values["payload"], RecordValue::Bytes(b) if b.as_slice() == b"ok"));
+        assert!(matches!(values["seq"], RecordValue::UInt32(1)));
+    }
+
+    #[test]
+    fn test_lazy_blob_uses_open_options() {
+        let payload = b"\x00\x01starbreaker\xff";
+        let mut builder = crate::testutil::DataCoreBuilder::new();
+        let signature = builder.add_struct("Signature", None, &[("payload", DataType::Blob)]);
+        builder.add_record(signature, "Signature.Default", 1, [("payload", RecordValue::Bytes(payload.to_vec()))]);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("Game.dcb");
+        builder.build().write(&mut std::fs::File::create(&path).unwrap()).unwrap();
+
+        let parser = DcbParser::new();
+        let lazy = parser.parse_lazy(&path).unwrap();
+        let values = lazy.load_record(&lazy.records[0]).unwrap();
+        assert!(matches!(&values["payload"], RecordValue::Bytes(b) if b.as_slice() == payload));
+
+        // The limit given at open time still applies when the record is loaded
+        let tight = ParseOptions { decompression_memory_limit: 4, ..Default::default() };
+        let lazy = parser.parse_lazy_with_options(&path, &tight).unwrap();
+        assert!(matches!(
+            lazy.load_record(&lazy.records[0]),
+            Err(ParseError::BufferOverflow { requested: 14, available: 4 })
+        ));
+    }
+
+    /// Header bytes: magic, version, 3 counts, 4 section offsets, then `extra`
+    fn make_header(version: u32, extra: &[u32]) -> Vec<u8> {
+        let mut data = DCB_MAGIC.to_vec();
+        for value in [version, 2, 3, 4, 100, 200, 300, 400].iter().chain(extra) {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_extended_v6_header() {
+        let parser = DcbParser::new();
+
+        let header = parser.parse_header(&mut std::io::Cursor::new(make_header(6, &[500, 600]))).unwrap();
+        assert_eq!(header.version, 6);
+        assert_eq!(header.record_count, 4);
+        assert_eq!(header.string_offset, 100);
+        assert_eq!(header.record_offset, 400);
+        assert_eq!(header.value_offset, Some(500));
+        assert_eq!(header.enum_offset, Some(600));
+        assert!(header.is_extended());
+
+        // Older versions keep the 36-byte layout and never read past it
+        let mut cursor = std::io::Cursor::new(make_header(5, &[500, 600]));
+        let header = parser.parse_header(&mut cursor).unwrap();
+        assert_eq!(header.record_offset, 400);
+        assert_eq!(header.value_offset, None);
+        assert!(!header.is_extended());
+        assert_eq!(cursor.position(), 36);
+
+        // A truncated v6 header is an error rather than garbage offsets
+        assert!(parser.parse_header(&mut std::io::Cursor::new(make_header(6, &[]))).is_err());
+    }
+
+    #[test]
+    fn test_utf16_string_table() {
+        let names = ["Straße", "日本語"];
+
+        // BOM, then each string as UTF-16LE with a 16-bit terminator
+        let mut string_data = UTF16LE_BOM.to_vec();
+        let mut offsets = Vec::new();
+        for name in names {
+            offsets.push(string_data.len() as u32);
+            for unit in name.encode_utf16().chain(std::iter::once(0)) {
+                string_data.extend_from_slice(&unit.to_le_bytes());
+            }
+        }
+
+        let mut data = (offsets.len() as u32).to_le_bytes().to_vec();
+        for offset in &offsets {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data.extend_from_slice(&string_data);
+
+        let mut header = DcbParser::new()
+            .parse_header(&mut std::io::Cursor::new(make_header(1, &[])))
+            .unwrap();
+        header.string_offset = 0;
+
+        let strings = DcbParser::new()
+            .parse_string_table(&mut std::io::Cursor::new(data), &mut header)
+            .unwrap();
+
+        assert_eq!(header.string_encoding, StringEncoding::Utf16Le);
+        assert_eq!(strings.get(0), Some("Straße"));
+        assert_eq!(strings.get(1), Some("日本語"));
+        assert_eq!(strings.get_by_offset(offsets[1]), Some("日本語"));
+    }
+}
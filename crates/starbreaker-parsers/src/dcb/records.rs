@@ -6,6 +6,7 @@ use std::sync::Arc;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 
+use super::structs::DataType;
 use crate::traits::ParseResult;
 
 /// A single data record from the DCB file
@@ -24,6 +25,16 @@ pub struct Record {
 }
 
 impl Record {
+    /// Get the record GUID in CryEngine's canonical form
+    pub fn cig_guid(&self) -> CigGuid {
+        CigGuid::from(self.guid)
+    }
+
+    /// Friendly label for UIs and logs (see [`DataCore::record_path`](super::DataCore::record_path))
+    pub fn display_label(&self, datacore: &super::DataCore) -> String {
+        datacore.record_path(self)
+    }
+
     /// Get a value by property name
     pub fn get(&self, name: &str) -> Option<&RecordValue> {
         self.values.get(name)
@@ -98,27 +109,37 @@ impl Record {
     
     /// Convert to a JSON value for serialization
     pub fn to_json(&self) -> serde_json::Value {
+        self.to_json_limited(MAX_VALUE_DEPTH)
+    }
+
+    /// Convert to JSON, cutting off structs nested more than `max_depth`
+    /// levels deep (see [`RecordValue::to_json_limited`])
+    pub fn to_json_limited(&self, max_depth: usize) -> serde_json::Value {
         serde_json::json!({
             "id": self.id,
             "struct_id": self.struct_id,
             "name": self.name,
             "guid": format!("{:016X}", self.guid),
-            "values": self.values_to_json()
+            "values": self.values_to_json(max_depth)
         })
     }
     
     /// Convert values to JSON
-    fn values_to_json(&self) -> serde_json::Value {
+    fn values_to_json(&self, max_depth: usize) -> serde_json::Value {
         let mut map = serde_json::Map::new();
         
         for (name, value) in &self.values {
-            map.insert(name.clone(), value.to_json());
+            map.insert(name.clone(), value.to_json_limited(max_depth));
         }
         
         serde_json::Value::Object(map)
     }
 }
 
+/// Deepest struct nesting rendered by [`RecordValue::to_json`] and
+/// [`RecordValue::as_string`]
+pub const MAX_VALUE_DEPTH: usize = 32;
+
 /// Value types for record properties
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RecordValue {
@@ -148,17 +169,61 @@ pub enum RecordValue {
     Vec4([f32; 4]),
     /// Enumeration value
     Enum(u32),
-    /// Array of values
-    Array(Vec<RecordValue>),
+    /// Array of values, tagged with the element type so empty arrays keep it
+    Array { elem_type: DataType, items: Vec<RecordValue> },
     /// Locale string with key
     LocaleString { key: String, value: String },
+    /// Opaque binary blob
+    Bytes(Vec<u8>),
     /// Unknown/unparsed data
     Unknown(u32),
+    /// Inline struct (class) instance, by property name
+    Struct(HashMap<String, RecordValue>),
 }
 
 impl RecordValue {
+    /// Build an array value
+    pub fn array(elem_type: DataType, items: Vec<RecordValue>) -> Self {
+        RecordValue::Array { elem_type, items }
+    }
+
+    /// The DCB type this value was read as, `None` for inline structs
+    ///
+    /// Narrow integers were widened on read, so they report the 32-bit type.
+    pub fn data_type(&self) -> Option<DataType> {
+        Some(match self {
+            RecordValue::Boolean(_) => DataType::Boolean,
+            RecordValue::Int32(_) => DataType::Int32,
+            RecordValue::Int64(_) => DataType::Int64,
+            RecordValue::UInt32(_) => DataType::UInt32,
+            RecordValue::UInt64(_) => DataType::UInt64,
+            RecordValue::Float(_) => DataType::Float,
+            RecordValue::Double(_) => DataType::Double,
+            RecordValue::String(_) => DataType::String,
+            RecordValue::Guid(_) => DataType::Guid,
+            RecordValue::Reference(_) => DataType::Reference,
+            RecordValue::Vec3(_) => DataType::Vec3,
+            RecordValue::Vec4(_) => DataType::Vec4,
+            RecordValue::Enum(_) => DataType::Enum,
+            RecordValue::Array { elem_type, .. } => DataType::Array(Box::new(elem_type.clone())),
+            RecordValue::LocaleString { .. } => DataType::LocaleString,
+            RecordValue::Bytes(_) => DataType::Blob,
+            RecordValue::Unknown(type_id) => DataType::Unknown(*type_id),
+            RecordValue::Struct(_) => return None,
+        })
+    }
+
     /// Convert to JSON value
+    ///
+    /// Empty arrays are written as `{"element_type": ..., "items": []}` so
+    /// their type survives a round trip; other arrays are plain JSON arrays.
     pub fn to_json(&self) -> serde_json::Value {
+        self.to_json_limited(MAX_VALUE_DEPTH)
+    }
+
+    /// Convert to JSON value, writing structs nested more than `max_depth`
+    /// levels deep as `null`
+    pub fn to_json_limited(&self, max_depth: usize) -> serde_json::Value {
         match self {
             RecordValue::Boolean(v) => serde_json::Value::Bool(*v),
             RecordValue::Int32(v) => serde_json::Value::Number((*v).into()),
@@ -188,20 +253,60 @@ impl RecordValue {
                 "w": v[3]
             }),
             RecordValue::Enum(v) => serde_json::Value::Number((*v).into()),
-            RecordValue::Array(arr) => {
-                serde_json::Value::Array(arr.iter().map(|v| v.to_json()).collect())
+            RecordValue::Array { elem_type, items } if items.is_empty() => serde_json::json!({
+                "element_type": elem_type.type_name(),
+                "items": []
+            }),
+            RecordValue::Array { items, .. } => {
+                serde_json::Value::Array(items.iter().map(|v| v.to_json_limited(max_depth)).collect())
             }
             RecordValue::LocaleString { key, value } => serde_json::json!({
                 "key": key,
                 "value": value
             }),
+            RecordValue::Bytes(bytes) => {
+                use base64::Engine;
+                serde_json::Value::String(base64::engine::general_purpose::STANDARD.encode(bytes))
+            }
             RecordValue::Unknown(type_id) => serde_json::json!({
                 "unknown_type": type_id
             }),
+            RecordValue::Struct(_) if max_depth == 0 => serde_json::Value::Null,
+            RecordValue::Struct(fields) => serde_json::Value::Object(
+                fields.iter()
+                    .map(|(name, value)| (name.clone(), value.to_json_limited(max_depth - 1)))
+                    .collect()
+            ),
+        }
+    }
+    
+    /// Collect every non-null reference in this value, descending into arrays and structs
+    pub fn references(&self) -> Vec<&RecordRef> {
+        let mut refs = Vec::new();
+        self.collect_references(&mut refs);
+        refs
+    }
+
+    fn collect_references<'a>(&'a self, out: &mut Vec<&'a RecordRef>) {
+        match self {
+            RecordValue::Reference(r) if !r.is_null() => out.push(r),
+            RecordValue::Array { items, .. } => {
+                for item in items {
+                    item.collect_references(out);
+                }
+            }
+            RecordValue::Struct(fields) => {
+                for value in fields.values() {
+                    value.collect_references(out);
+                }
+            }
+            _ => {}
         }
     }
     
     /// Get as string, with conversion
+    ///
+    /// Structs render as an indented block of `name: value` lines.
     pub fn as_string(&self) -> Option<String> {
         match self {
             RecordValue::String(s) => Some(s.clone()),
@@ -214,10 +319,131 @@ impl RecordValue {
             RecordValue::Double(v) => Some(v.to_string()),
             RecordValue::Enum(v) => Some(v.to_string()),
             RecordValue::LocaleString { value, .. } => Some(value.clone()),
+            RecordValue::Bytes(bytes) => Some(bytes.iter().map(|b| format!("{:02x}", b)).collect()),
+            RecordValue::Struct(fields) => {
+                let mut block = String::new();
+                Self::write_struct_block(fields, &mut block, 0);
+                Some(block.trim_end().to_string())
+            }
             _ => None,
         }
     }
+
+    /// Append one line per field in name order, nested structs indented
+    /// beneath their name; values without a text form show their type
+    fn write_struct_block(fields: &HashMap<String, RecordValue>, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        if depth >= MAX_VALUE_DEPTH {
+            out.push_str(&format!("{}...\n", indent));
+            return;
+        }
+
+        let mut names: Vec<&String> = fields.keys().collect();
+        names.sort_unstable();
+        for name in names {
+            match &fields[name] {
+                RecordValue::Struct(inner) => {
+                    out.push_str(&format!("{}{}:\n", indent, name));
+                    Self::write_struct_block(inner, out, depth + 1);
+                }
+                value => {
+                    let text = value.as_string().unwrap_or_else(|| format!("<{}>", value.type_name()));
+                    out.push_str(&format!("{}{}: {}\n", indent, name, text));
+                }
+            }
+        }
+    }
     
+    /// Replace NaN/Inf floats with 0.0, returning whether anything changed
+    pub fn sanitize_floats(&mut self) -> bool {
+        match self {
+            RecordValue::Float(v) => crate::traits::sanitize_f32s(std::slice::from_mut(v)),
+            RecordValue::Double(v) if !v.is_finite() => {
+                *v = 0.0;
+                true
+            }
+            RecordValue::Vec3(v) => crate::traits::sanitize_f32s(v),
+            RecordValue::Vec4(v) => crate::traits::sanitize_f32s(v),
+            RecordValue::Array { items, .. } => {
+                items.iter_mut().fold(false, |changed, item| item.sanitize_floats() | changed)
+            }
+            RecordValue::Struct(fields) => {
+                fields.values_mut().fold(false, |changed, value| value.sanitize_floats() | changed)
+            }
+            _ => false,
+        }
+    }
+
+    /// Compare values, letting floats differ by up to `epsilon`
+    ///
+    /// The tolerance is absolute below 1.0 and relative to the larger
+    /// magnitude above it. Vectors, arrays and structs compare element-wise; values
+    /// of different variants are never equal.
+    pub fn approx_eq(&self, other: &RecordValue, epsilon: f64) -> bool {
+        let close = |a: f64, b: f64| a == b || (a - b).abs() <= epsilon * a.abs().max(b.abs()).max(1.0);
+        let close_all = |a: &[f32], b: &[f32]| a.iter().zip(b).all(|(a, b)| close(*a as f64, *b as f64));
+
+        match (self, other) {
+            (RecordValue::Float(a), RecordValue::Float(b)) => close(*a as f64, *b as f64),
+            (RecordValue::Double(a), RecordValue::Double(b)) => close(*a, *b),
+            (RecordValue::Vec3(a), RecordValue::Vec3(b)) => close_all(a, b),
+            (RecordValue::Vec4(a), RecordValue::Vec4(b)) => close_all(a, b),
+            (RecordValue::Array { elem_type: a_type, items: a }, RecordValue::Array { elem_type: b_type, items: b }) => {
+                a_type == b_type
+                    && a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.approx_eq(b, epsilon))
+            }
+            (RecordValue::Boolean(a), RecordValue::Boolean(b)) => a == b,
+            (RecordValue::Int32(a), RecordValue::Int32(b)) => a == b,
+            (RecordValue::Int64(a), RecordValue::Int64(b)) => a == b,
+            (RecordValue::UInt32(a), RecordValue::UInt32(b)) => a == b,
+            (RecordValue::UInt64(a), RecordValue::UInt64(b)) => a == b,
+            (RecordValue::String(a), RecordValue::String(b)) => a == b,
+            (RecordValue::Guid(a), RecordValue::Guid(b)) => a == b,
+            (RecordValue::Reference(a), RecordValue::Reference(b)) => {
+                a.record_id == b.record_id && a.struct_id == b.struct_id
+            }
+            (RecordValue::Enum(a), RecordValue::Enum(b)) => a == b,
+            (
+                RecordValue::LocaleString { key: a, value: a_value },
+                RecordValue::LocaleString { key: b, value: b_value },
+            ) => a == b && a_value == b_value,
+            (RecordValue::Bytes(a), RecordValue::Bytes(b)) => a == b,
+            (RecordValue::Unknown(a), RecordValue::Unknown(b)) => a == b,
+            (RecordValue::Struct(a), RecordValue::Struct(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(name, a)| b.get(name).is_some_and(|b| a.approx_eq(b, epsilon)))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether this is a zero/empty value: `false`, 0, an empty string,
+    /// array or blob, a zero GUID or vector, or a null reference
+    ///
+    /// Unparsed values count as empty, and so do structs whose fields all are.
+    pub fn is_empty_value(&self) -> bool {
+        match self {
+            RecordValue::Boolean(v) => !v,
+            RecordValue::Int32(v) => *v == 0,
+            RecordValue::Int64(v) => *v == 0,
+            RecordValue::UInt32(v) => *v == 0,
+            RecordValue::UInt64(v) => *v == 0,
+            RecordValue::Float(v) => *v == 0.0,
+            RecordValue::Double(v) => *v == 0.0,
+            RecordValue::String(v) => v.is_empty(),
+            RecordValue::Guid(v) => v.iter().all(|&b| b == 0),
+            RecordValue::Reference(r) => r.is_null(),
+            RecordValue::Vec3(v) => v.iter().all(|&c| c == 0.0),
+            RecordValue::Vec4(v) => v.iter().all(|&c| c == 0.0),
+            RecordValue::Enum(v) => *v == 0,
+            RecordValue::Array { items, .. } => items.is_empty(),
+            RecordValue::LocaleString { key, value } => key.is_empty() && value.is_empty(),
+            RecordValue::Bytes(bytes) => bytes.is_empty(),
+            RecordValue::Unknown(_) => true,
+            RecordValue::Struct(fields) => fields.values().all(RecordValue::is_empty_value),
+        }
+    }
+
     /// Get the type name of this value
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -234,15 +460,63 @@ impl RecordValue {
             RecordValue::Vec3(_) => "vec3",
             RecordValue::Vec4(_) => "vec4",
             RecordValue::Enum(_) => "enum",
-            RecordValue::Array(_) => "array",
+            RecordValue::Array { .. } => "array",
             RecordValue::LocaleString { .. } => "locale_string",
+            RecordValue::Bytes(_) => "bytes",
             RecordValue::Unknown(_) => "unknown",
+            RecordValue::Struct(_) => "struct",
         }
     }
 }
 
+/// CryEngine GUID with the game's canonical formatting
+///
+/// Stored as the 16 bytes found in the file. The game formats them as two
+/// little-endian 64-bit halves, each printed most significant byte first,
+/// which is the `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` string other Star
+/// Citizen data tools use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CigGuid(pub [u8; 16]);
+
+impl CigGuid {
+    /// Raw bytes as stored in the file
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl From<[u8; 16]> for CigGuid {
+    fn from(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+}
+
+/// Widen a record's `u64` GUID; the upper half of the 16 bytes is zero
+impl From<u64> for CigGuid {
+    fn from(guid: u64) -> Self {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&guid.to_le_bytes());
+        Self(bytes)
+    }
+}
+
+impl std::fmt::Display for CigGuid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let b = &self.0;
+        write!(
+            f,
+            "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+            b[7], b[6], b[5], b[4],
+            b[3], b[2],
+            b[1], b[0],
+            b[15], b[14],
+            b[13], b[12], b[11], b[10], b[9], b[8],
+        )
+    }
+}
+
 /// Reference to another record
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RecordRef {
     /// Target record ID
     pub record_id: u32,
@@ -397,6 +671,69 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_cig_guid_display() {
+        let bytes: [u8; 16] = std::array::from_fn(|i| i as u8);
+        assert_eq!(CigGuid::from(bytes).to_string(), "07060504-0302-0100-0f0e-0d0c0b0a0908");
+
+        let record = make_test_record();
+        assert_eq!(record.cig_guid().to_string(), "12345678-9abc-def0-0000-000000000000");
+    }
+
+    #[test]
+    fn test_nested_struct_value() {
+        let engine = RecordValue::Struct(HashMap::from([
+            ("thrust".to_string(), RecordValue::Float(9.5)),
+            ("nozzle".to_string(), RecordValue::Struct(HashMap::from([
+                ("angle".to_string(), RecordValue::Int32(15)),
+            ]))),
+        ]));
+        let mut record = make_test_record();
+        record.values.insert("engine".to_string(), engine.clone());
+
+        let json = record.to_json();
+        assert_eq!(json["values"]["engine"]["thrust"], 9.5);
+        assert_eq!(json["values"]["engine"]["nozzle"]["angle"], 15);
+        assert_eq!(json["values"]["name"], "Test Ship");
+
+        assert_eq!(engine.to_json_limited(1)["nozzle"], serde_json::Value::Null);
+        assert_eq!(engine.type_name(), "struct");
+        assert_eq!(engine.as_string().unwrap(), "nozzle:\n  angle: 15\nthrust: 9.5");
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        assert!(RecordValue::Float(1.0).approx_eq(&RecordValue::Float(1.0 + 1e-7), 1e-6));
+        assert!(!RecordValue::Float(1.0).approx_eq(&RecordValue::Float(1.1), 1e-6));
+        // Relative above 1.0
+        assert!(RecordValue::Double(50000.0).approx_eq(&RecordValue::Double(50000.01), 1e-6));
+        assert!(!RecordValue::Float(f32::NAN).approx_eq(&RecordValue::Float(f32::NAN), 1e-6));
+
+        let a = RecordValue::array(DataType::Unknown(0), vec![RecordValue::Vec3([1.0, 2.0, 3.0]), RecordValue::Int32(4)]);
+        let b = RecordValue::array(DataType::Unknown(0), vec![RecordValue::Vec3([1.0, 2.0, 3.000001]), RecordValue::Int32(4)]);
+        assert!(a.approx_eq(&b, 1e-5));
+        assert!(!a.approx_eq(&RecordValue::array(DataType::Unknown(0), vec![]), 1e-5));
+        assert!(!RecordValue::array(DataType::Int32, vec![]).approx_eq(&RecordValue::array(DataType::String, vec![]), 1e-5));
+
+        assert!(!RecordValue::Int32(1).approx_eq(&RecordValue::Int64(1), 1e-6));
+        assert!(!RecordValue::Int32(1).approx_eq(&RecordValue::Int32(2), 1e-6));
+    }
+
+    #[test]
+    fn test_empty_array_keeps_element_type() {
+        let ints = RecordValue::array(DataType::Int32, vec![]);
+        let strings = RecordValue::array(DataType::String, vec![]);
+
+        assert_eq!(ints.to_json(), serde_json::json!({"element_type": "i32", "items": []}));
+        assert_eq!(strings.to_json()["element_type"], "string");
+        assert_ne!(ints.to_json(), strings.to_json());
+        assert_eq!(ints.data_type(), Some(DataType::Array(Box::new(DataType::Int32))));
+
+        // Non-empty arrays stay plain JSON arrays
+        let filled = RecordValue::array(DataType::Int32, vec![RecordValue::Int32(3)]);
+        assert_eq!(filled.to_json(), serde_json::json!([3]));
+    }
+
     #[test]
     fn test_get_string() {
         let record = make_test_record();
@@ -64,7 +64,7 @@ impl PropertyDef {
 }
 
 /// Data types supported by DCB
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DataType {
     /// Boolean value (1 byte)
     Boolean,
@@ -102,6 +102,8 @@ pub enum DataType {
     Vec4,
     /// Enumeration value
     Enum,
+    /// Opaque binary blob (u32 length prefix followed by the bytes)
+    Blob,
     /// Array of elements
     Array(Box<DataType>),
     /// Unknown type
@@ -130,6 +132,7 @@ impl DataType {
             16 => DataType::Vec3,
             17 => DataType::Vec4,
             18 => DataType::Enum,
+            19 => DataType::Blob,
             // Array types have high bit set
             v if v & 0x80000000 != 0 => {
                 let inner_type = v & 0x7FFFFFFF;
@@ -139,6 +142,33 @@ impl DataType {
         }
     }
     
+    /// Convert back to the raw u32 type ID (inverse of [`from_u32`](Self::from_u32))
+    pub fn to_u32(&self) -> u32 {
+        match self {
+            DataType::Boolean => 0,
+            DataType::Int8 => 1,
+            DataType::Int16 => 2,
+            DataType::Int32 => 4,
+            DataType::Int64 => 5,
+            DataType::UInt8 => 6,
+            DataType::UInt16 => 7,
+            DataType::UInt32 => 8,
+            DataType::UInt64 => 9,
+            DataType::Float => 10,
+            DataType::Double => 11,
+            DataType::String => 12,
+            DataType::Guid => 13,
+            DataType::LocaleString => 14,
+            DataType::Reference => 15,
+            DataType::Vec3 => 16,
+            DataType::Vec4 => 17,
+            DataType::Enum => 18,
+            DataType::Blob => 19,
+            DataType::Array(inner) => 0x80000000 | inner.to_u32(),
+            DataType::Unknown(value) => *value,
+        }
+    }
+
     /// Get the size in bytes for this type
     pub fn size(&self) -> Option<usize> {
         Some(match self {
@@ -152,7 +182,7 @@ impl DataType {
             DataType::Vec4 => 16,
             DataType::Guid => 16,
             DataType::LocaleString => 8, // offset + hash
-            DataType::Array(_) => return None, // Variable size
+            DataType::Array(_) | DataType::Blob => return None, // Variable size
             DataType::Unknown(_) => return None,
         })
     }
@@ -200,10 +230,40 @@ impl DataType {
             DataType::Vec3 => "vec3",
             DataType::Vec4 => "vec4",
             DataType::Enum => "enum",
+            DataType::Blob => "blob",
             DataType::Array(_) => "array",
             DataType::Unknown(_) => "unknown",
         }
     }
+
+    /// Parse a name written by [`type_name`](Self::type_name)
+    ///
+    /// Returns `None` for `"array"` and `"unknown"`, whose names don't carry
+    /// the inner type or raw id.
+    pub fn from_type_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "bool" => DataType::Boolean,
+            "i8" => DataType::Int8,
+            "i16" => DataType::Int16,
+            "i32" => DataType::Int32,
+            "i64" => DataType::Int64,
+            "u8" => DataType::UInt8,
+            "u16" => DataType::UInt16,
+            "u32" => DataType::UInt32,
+            "u64" => DataType::UInt64,
+            "f32" => DataType::Float,
+            "f64" => DataType::Double,
+            "string" => DataType::String,
+            "guid" => DataType::Guid,
+            "locale_string" => DataType::LocaleString,
+            "reference" => DataType::Reference,
+            "vec3" => DataType::Vec3,
+            "vec4" => DataType::Vec4,
+            "enum" => DataType::Enum,
+            "blob" => DataType::Blob,
+            _ => return None,
+        })
+    }
 }
 
 /// Flag definitions for struct flags
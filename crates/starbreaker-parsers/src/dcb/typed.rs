@@ -0,0 +1,270 @@
+// starbreaker-parsers/src/dcb/typed.rs
+//! Typed access to DataCore records
+//!
+//! [`FromRecordValue`] converts a single property value into a Rust type and
+//! [`FromRecord`] maps a whole record onto a hand-written struct:
+//!
+//! ```rust,ignore
+//! struct Ship {
+//!     name: String,
+//!     mass: f32,
+//! }
+//!
+//! impl FromRecord for Ship {
+//!     fn from_record(record: &Record) -> ParseResult<Self> {
+//!         Ok(Self {
+//!             name: record.require("name")?,
+//!             mass: record.require("mass")?,
+//!         })
+//!     }
+//! }
+//!
+//! let ship: Ship = datacore.deserialize(record)?;
+//! ```
+
+use super::{DataCore, Record, RecordRef, RecordValue};
+use crate::traits::{ParseError, ParseResult};
+
+/// Conversion from a single record property value
+pub trait FromRecordValue: Sized {
+    /// Convert the value, returning `None` if the type doesn't fit
+    fn from_record_value(value: &RecordValue) -> Option<Self>;
+}
+
+/// Conversion from a whole record into a typed struct
+pub trait FromRecord: Sized {
+    /// Build `Self` from the record's property values
+    fn from_record(record: &Record) -> ParseResult<Self>;
+}
+
+impl FromRecordValue for bool {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        match value {
+            RecordValue::Boolean(v) => Some(*v),
+            RecordValue::Int32(v) => Some(*v != 0),
+            RecordValue::UInt32(v) => Some(*v != 0),
+            _ => None,
+        }
+    }
+}
+
+/// Integer conversions go through `i128` so out-of-range values fail instead of wrapping
+macro_rules! impl_from_record_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl FromRecordValue for $ty {
+                fn from_record_value(value: &RecordValue) -> Option<Self> {
+                    let wide: i128 = match value {
+                        RecordValue::Int32(v) => (*v).into(),
+                        RecordValue::Int64(v) => (*v).into(),
+                        RecordValue::UInt32(v) => (*v).into(),
+                        RecordValue::UInt64(v) => (*v).into(),
+                        RecordValue::Enum(v) => (*v).into(),
+                        RecordValue::Boolean(v) => (*v).into(),
+                        _ => return None,
+                    };
+                    <$ty>::try_from(wide).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_record_value_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl FromRecordValue for f32 {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        f64::from_record_value(value).map(|v| v as f32)
+    }
+}
+
+impl FromRecordValue for f64 {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        match value {
+            RecordValue::Float(v) => Some(*v as f64),
+            RecordValue::Double(v) => Some(*v),
+            RecordValue::Int32(v) => Some(*v as f64),
+            RecordValue::Int64(v) => Some(*v as f64),
+            RecordValue::UInt32(v) => Some(*v as f64),
+            RecordValue::UInt64(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+}
+
+impl FromRecordValue for String {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        match value {
+            RecordValue::String(s) => Some(s.clone()),
+            RecordValue::LocaleString { value, .. } => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromRecordValue for [f32; 3] {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        match value {
+            RecordValue::Vec3(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl FromRecordValue for [f32; 4] {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        match value {
+            RecordValue::Vec4(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl FromRecordValue for RecordRef {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        match value {
+            RecordValue::Reference(r) => Some(*r),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromRecordValue> FromRecordValue for Vec<T> {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        match value {
+            RecordValue::Array { items, .. } => items.iter().map(T::from_record_value).collect(),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromRecordValue> FromRecordValue for Option<T> {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        Some(T::from_record_value(value))
+    }
+}
+
+impl FromRecordValue for RecordValue {
+    fn from_record_value(value: &RecordValue) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl Record {
+    /// Get a property converted to `T`
+    ///
+    /// Returns `None` if the property is missing or has an incompatible type.
+    pub fn get_typed<T: FromRecordValue>(&self, name: &str) -> Option<T> {
+        self.values.get(name).and_then(T::from_record_value)
+    }
+
+    /// Get a property converted to `T`, failing if it is missing or mistyped
+    pub fn require<T: FromRecordValue>(&self, name: &str) -> ParseResult<T> {
+        let value = self.values.get(name)
+            .ok_or_else(|| ParseError::MissingField(format!("{}.{}", self.name, name)))?;
+
+        T::from_record_value(value).ok_or_else(|| {
+            ParseError::InvalidStructure(format!(
+                "Property '{}' of record '{}' has type {} which does not convert to {}",
+                name,
+                self.name,
+                value.type_name(),
+                std::any::type_name::<T>(),
+            ))
+        })
+    }
+}
+
+impl DataCore {
+    /// Deserialize a record into a typed struct
+    pub fn deserialize<T: FromRecord>(&self, record: &Record) -> ParseResult<T> {
+        T::from_record(record).map_err(|e| e.with_context(format!("record '{}'", record.name)))
+    }
+
+    /// Deserialize every record of the given struct type
+    pub fn deserialize_all<T: FromRecord>(&self, struct_name: &str) -> ParseResult<Vec<T>> {
+        self.find_by_struct(struct_name)
+            .into_iter()
+            .map(|record| self.deserialize(record))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dcb::DataType;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq)]
+    struct Ship {
+        name: String,
+        mass: f32,
+        crew: u8,
+        spawn: [f32; 3],
+        hardpoints: Vec<u32>,
+        manufacturer: Option<RecordRef>,
+    }
+
+    impl FromRecord for Ship {
+        fn from_record(record: &Record) -> ParseResult<Self> {
+            Ok(Self {
+                name: record.require("name")?,
+                mass: record.require("mass")?,
+                crew: record.require("crew")?,
+                spawn: record.require("spawn")?,
+                hardpoints: record.require("hardpoints")?,
+                manufacturer: record.get_typed("manufacturer"),
+            })
+        }
+    }
+
+    fn make_ship_record() -> Record {
+        let mut values = HashMap::new();
+        values.insert("name".to_string(), RecordValue::String("Aurora MR".to_string()));
+        values.insert("mass".to_string(), RecordValue::Double(26_000.5));
+        values.insert("crew".to_string(), RecordValue::Int32(1));
+        values.insert("spawn".to_string(), RecordValue::Vec3([1.0, 2.0, 3.0]));
+        values.insert(
+            "hardpoints".to_string(),
+            RecordValue::array(DataType::UInt32, vec![RecordValue::UInt32(4), RecordValue::UInt32(7)]),
+        );
+
+        Record {
+            id: 0,
+            struct_id: 0,
+            name: "RSI_Aurora_MR".to_string(),
+            guid: 0xABCD,
+            values,
+        }
+    }
+
+    #[test]
+    fn test_from_record_ship() {
+        let ship = Ship::from_record(&make_ship_record()).unwrap();
+
+        assert_eq!(ship, Ship {
+            name: "Aurora MR".to_string(),
+            mass: 26_000.5,
+            crew: 1,
+            spawn: [1.0, 2.0, 3.0],
+            hardpoints: vec![4, 7],
+            manufacturer: None,
+        });
+    }
+
+    #[test]
+    fn test_require_errors() {
+        let record = make_ship_record();
+
+        assert!(matches!(record.require::<u32>("missing"), Err(ParseError::MissingField(_))));
+        assert!(matches!(record.require::<u32>("name"), Err(ParseError::InvalidStructure(_))));
+    }
+
+    #[test]
+    fn test_integer_range_checked() {
+        assert_eq!(u8::from_record_value(&RecordValue::Int32(255)), Some(255));
+        assert_eq!(u8::from_record_value(&RecordValue::Int32(256)), None);
+        assert_eq!(u32::from_record_value(&RecordValue::Int32(-1)), None);
+    }
+}
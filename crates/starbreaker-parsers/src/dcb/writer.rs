@@ -0,0 +1,553 @@
+// starbreaker-parsers/src/dcb/writer.rs
+//! DataCore Binary writer
+//!
+//! Re-emits a parsed [`DataCore`] in the layout [`DcbParser`](super::DcbParser)
+//! reads, so parse → write → parse round trips can catch field ordering and
+//! width bugs. Only the parts of the format the parser understands are
+//! written: structs, properties, and records of scalar values. The string
+//! table goes last because the parser reads string data up to end of file.
+//!
+//! The writer also backs [`Exportable`] for [`DataCore`], next to the JSON
+//! and XML dumps.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::io::{Cursor, Seek, SeekFrom, Write};
+
+use super::{DataCore, DataType, RecordValue, DCB_MAGIC, DCB_EXTENDED_VERSION};
+use crate::traits::{Exportable, ParseError, ParseResult};
+
+/// Marker for an absent parent or struct id
+const NO_ID: u32 = 0xFFFFFFFF;
+
+/// Deduplicated UTF-8 string table under construction
+#[derive(Default)]
+struct StringTableBuilder {
+    offsets: HashMap<String, u32>,
+    order: Vec<u32>,
+    data: Vec<u8>,
+}
+
+impl StringTableBuilder {
+    /// Add a string if new, returning its offset into the string data
+    fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&offset) = self.offsets.get(s) {
+            return offset;
+        }
+
+        let offset = self.data.len() as u32;
+        self.data.extend_from_slice(s.as_bytes());
+        self.data.push(0);
+        self.offsets.insert(s.to_string(), offset);
+        self.order.push(offset);
+        offset
+    }
+
+    /// Offset of a string added earlier
+    fn offset(&self, s: &str) -> u32 {
+        self.offsets[s]
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> ParseResult<()> {
+        writer.write_all(&(self.order.len() as u32).to_le_bytes())?;
+        for offset in &self.order {
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+        writer.write_all(&self.data)?;
+        Ok(())
+    }
+}
+
+impl DataCore {
+    /// Write this DataCore back out in DCB binary layout
+    ///
+    /// Structs, properties and records are written in order so ids and
+    /// indices are preserved. Strings are always written as UTF-8. Record
+    /// values must match their property's type; arrays, locale strings and
+    /// unknown types are not supported yet and return
+    /// [`ParseError::UnsupportedFeature`]. Version 6+ headers get zeroed
+    /// value and enum offsets since those sections aren't written.
+    pub fn write<W: Write + Seek>(&self, writer: &mut W) -> ParseResult<()> {
+        let mut strings = StringTableBuilder::default();
+        for s in &self.structs {
+            strings.intern(&s.name);
+        }
+        for p in &self.properties {
+            strings.intern(&p.name);
+        }
+        for r in &self.records {
+            strings.intern(&r.name);
+            for value in r.values.values() {
+                if let RecordValue::String(s) = value {
+                    strings.intern(s);
+                }
+            }
+        }
+
+        let extended = self.header.version >= DCB_EXTENDED_VERSION;
+        let start = writer.stream_position()?;
+        let header_size = if extended { 44 } else { 36 };
+        writer.write_all(&vec![0u8; header_size])?;
+
+        let struct_offset = writer.stream_position()? - start;
+        for s in &self.structs {
+            for field in [
+                strings.offset(&s.name),
+                s.parent_id.unwrap_or(NO_ID),
+                s.property_start,
+                s.property_count,
+                s.size,
+                s.flags,
+            ] {
+                writer.write_all(&field.to_le_bytes())?;
+            }
+        }
+
+        let property_offset = writer.stream_position()? - start;
+        for p in &self.properties {
+            for field in [
+                strings.offset(&p.name),
+                p.data_type.to_u32(),
+                p.struct_id.unwrap_or(NO_ID),
+                p.conversion,
+            ] {
+                writer.write_all(&field.to_le_bytes())?;
+            }
+        }
+
+        let record_offset = writer.stream_position()? - start;
+        for record in &self.records {
+            writer.write_all(&record.struct_id.to_le_bytes())?;
+            writer.write_all(&strings.offset(&record.name).to_le_bytes())?;
+            writer.write_all(&(record.guid as u32).to_le_bytes())?;
+            writer.write_all(&((record.guid >> 32) as u32).to_le_bytes())?;
+
+            let Some(struct_def) = self.structs.get(record.struct_id as usize) else {
+                continue;
+            };
+            for prop in struct_def.property_indices().filter_map(|i| self.properties.get(i)) {
+                let value = record.values.get(&prop.name).ok_or_else(|| {
+                    ParseError::MissingField(format!("{}.{}", record.name, prop.name))
+                })?;
+                write_value(writer, &prop.data_type, value, &strings).map_err(|e| {
+                    e.with_context(format!("Record {} ({}).{}", record.id, record.name, prop.name))
+                })?;
+            }
+        }
+
+        let string_offset = writer.stream_position()? - start;
+        strings.write(writer)?;
+        let end = writer.stream_position()?;
+
+        // Fill in the header now that every section has been placed
+        writer.seek(SeekFrom::Start(start))?;
+        writer.write_all(DCB_MAGIC)?;
+        for field in [
+            self.header.version,
+            self.structs.len() as u32,
+            self.properties.len() as u32,
+            self.records.len() as u32,
+            string_offset as u32,
+            struct_offset as u32,
+            property_offset as u32,
+            record_offset as u32,
+        ] {
+            writer.write_all(&field.to_le_bytes())?;
+        }
+        if extended {
+            writer.write_all(&[0u8; 8])?;
+        }
+        writer.seek(SeekFrom::Start(end))?;
+
+        Ok(())
+    }
+}
+
+/// Write one value with the width `data_type` calls for
+fn write_value<W: Write>(
+    writer: &mut W,
+    data_type: &DataType,
+    value: &RecordValue,
+    strings: &StringTableBuilder,
+) -> ParseResult<()> {
+    let out_of_range = || ParseError::InvalidStructure(format!("{:?} out of range for {:?}", value, data_type));
+
+    match (data_type, value) {
+        (DataType::Boolean, RecordValue::Boolean(v)) => writer.write_all(&[*v as u8])?,
+        (DataType::Int8, RecordValue::Int32(v)) => {
+            writer.write_all(&i8::try_from(*v).map_err(|_| out_of_range())?.to_le_bytes())?
+        }
+        (DataType::Int16, RecordValue::Int32(v)) => {
+            writer.write_all(&i16::try_from(*v).map_err(|_| out_of_range())?.to_le_bytes())?
+        }
+        (DataType::Int32, RecordValue::Int32(v)) => writer.write_all(&v.to_le_bytes())?,
+        (DataType::Int64, RecordValue::Int64(v)) => writer.write_all(&v.to_le_bytes())?,
+        (DataType::UInt8, RecordValue::UInt32(v)) => {
+            writer.write_all(&u8::try_from(*v).map_err(|_| out_of_range())?.to_le_bytes())?
+        }
+        (DataType::UInt16, RecordValue::UInt32(v)) => {
+            writer.write_all(&u16::try_from(*v).map_err(|_| out_of_range())?.to_le_bytes())?
+        }
+        (DataType::UInt32, RecordValue::UInt32(v)) => writer.write_all(&v.to_le_bytes())?,
+        (DataType::UInt64, RecordValue::UInt64(v)) => writer.write_all(&v.to_le_bytes())?,
+        (DataType::Float, RecordValue::Float(v)) => writer.write_all(&v.to_le_bytes())?,
+        (DataType::Double, RecordValue::Double(v)) => writer.write_all(&v.to_le_bytes())?,
+        (DataType::String, RecordValue::String(s)) => writer.write_all(&strings.offset(s).to_le_bytes())?,
+        (DataType::Guid, RecordValue::Guid(bytes)) => writer.write_all(bytes)?,
+        (DataType::Reference, RecordValue::Reference(r)) => {
+            writer.write_all(&r.record_id.to_le_bytes())?;
+            writer.write_all(&r.struct_id.to_le_bytes())?;
+        }
+        (DataType::Vec3, RecordValue::Vec3(v)) => {
+            for c in v {
+                writer.write_all(&c.to_le_bytes())?;
+            }
+        }
+        (DataType::Vec4, RecordValue::Vec4(v)) => {
+            for c in v {
+                writer.write_all(&c.to_le_bytes())?;
+            }
+        }
+        (DataType::Enum, RecordValue::Enum(v)) => writer.write_all(&v.to_le_bytes())?,
+        (DataType::Blob, RecordValue::Bytes(bytes)) => {
+            writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+            writer.write_all(bytes)?;
+        }
+        (DataType::Array(_) | DataType::LocaleString | DataType::Unknown(_), _) => {
+            return Err(ParseError::UnsupportedFeature(format!("Writing {:?} values", data_type)));
+        }
+        _ => {
+            return Err(ParseError::InvalidStructure(format!(
+                "{:?} value stored in a {:?} property",
+                value, data_type
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+impl DataCore {
+    /// Name of a struct by id, with a placeholder for ids out of range
+    fn struct_name_or_unknown(&self, struct_id: u32) -> String {
+        self.structs.get(struct_id as usize)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|| format!("Unknown_{:08X}", struct_id))
+    }
+}
+
+impl Exportable for DataCore {
+    /// Records grouped by struct name, with a metadata header
+    ///
+    /// Same layout as the JSON exporter's DataCore dump, with structs in
+    /// name order and records in file order.
+    fn export_json(&self, pretty: bool) -> ParseResult<String> {
+        let mut by_struct: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+        for record in &self.records {
+            by_struct.entry(self.struct_name_or_unknown(record.struct_id))
+                .or_default()
+                .push(record.to_json());
+        }
+
+        let output = serde_json::json!({
+            "metadata": {
+                "version": self.header.version,
+                "record_count": self.records.len(),
+                "struct_count": self.structs.len(),
+            },
+            "structs": by_struct,
+        });
+        let json = if pretty {
+            serde_json::to_string_pretty(&output)
+        } else {
+            serde_json::to_string(&output)
+        };
+        json.map_err(|e| ParseError::InvalidStructure(format!("JSON serialization failed: {}", e)))
+    }
+
+    /// scXML dump: one element per record, named after its struct
+    ///
+    /// Scalar values become attributes; vectors, references and arrays
+    /// become child elements. Properties are written in name order.
+    fn export_xml(&self) -> ParseResult<String> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        let _ = writeln!(xml, "<DataCore version=\"{}\">", self.header.version);
+
+        for record in &self.records {
+            let tag = xml_name(&self.struct_name_or_unknown(record.struct_id));
+            let _ = write!(
+                xml,
+                "  <{} __name=\"{}\" __ref=\"{}\"",
+                tag,
+                xml_escape(&record.name),
+                record.cig_guid()
+            );
+
+            let mut properties: Vec<(&String, &RecordValue)> = record.values.iter().collect();
+            properties.sort_by_key(|(name, _)| *name);
+            let (attributes, children): (Vec<_>, Vec<_>) = properties.into_iter()
+                .partition(|(_, value)| xml_attribute(value).is_some());
+
+            for (name, value) in attributes {
+                let text = xml_attribute(value).unwrap_or_default();
+                let _ = write!(xml, " {}=\"{}\"", xml_name(name), xml_escape(&text));
+            }
+            if children.is_empty() {
+                xml.push_str("/>\n");
+                continue;
+            }
+
+            xml.push_str(">\n");
+            for (name, value) in children {
+                write_xml_element(&mut xml, &xml_name(name), value, 2);
+            }
+            let _ = writeln!(xml, "  </{}>", tag);
+        }
+
+        xml.push_str("</DataCore>\n");
+        Ok(xml)
+    }
+
+    /// Re-packed DCB bytes, see [`DataCore::write`]
+    ///
+    /// Fails with [`ParseError::UnsupportedFeature`] for value kinds the
+    /// writer can't serialize yet (arrays, locale strings, unknown types).
+    fn export_binary(&self) -> ParseResult<Vec<u8>> {
+        let mut buffer = Cursor::new(Vec::new());
+        self.write(&mut buffer)?;
+        Ok(buffer.into_inner())
+    }
+}
+
+/// Text of a value that fits in an XML attribute, `None` for compound values
+fn xml_attribute(value: &RecordValue) -> Option<String> {
+    match value {
+        RecordValue::Guid(bytes) => Some(super::CigGuid(*bytes).to_string()),
+        RecordValue::Reference(r) if r.is_null() => Some("null".to_string()),
+        RecordValue::Reference(_) | RecordValue::Array { .. } | RecordValue::Struct(_) => None,
+        other => other.as_string(),
+    }
+}
+
+/// Write `value` as an element named `name`, recursing into arrays and structs
+fn write_xml_element(xml: &mut String, name: &str, value: &RecordValue, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let axes = ["x", "y", "z", "w"];
+
+    let _ = write!(xml, "{}<{}", indent, name);
+    match value {
+        RecordValue::Vec3(v) => axes.iter().zip(v).for_each(|(axis, c)| {
+            let _ = write!(xml, " {}=\"{}\"", axis, c);
+        }),
+        RecordValue::Vec4(v) => axes.iter().zip(v).for_each(|(axis, c)| {
+            let _ = write!(xml, " {}=\"{}\"", axis, c);
+        }),
+        RecordValue::Reference(r) if !r.is_null() => {
+            let _ = write!(xml, " record_id=\"{}\" struct_id=\"{}\"", r.record_id, r.struct_id);
+        }
+        RecordValue::Unknown(type_id) => {
+            let _ = write!(xml, " unknown_type=\"{}\"", type_id);
+        }
+        RecordValue::Array { items, .. } if !items.is_empty() => {
+            xml.push_str(">\n");
+            for item in items {
+                write_xml_element(xml, "item", item, depth + 1);
+            }
+            let _ = writeln!(xml, "{}</{}>", indent, name);
+            return;
+        }
+        RecordValue::Struct(fields) if !fields.is_empty() => {
+            xml.push_str(">\n");
+            let mut names: Vec<&String> = fields.keys().collect();
+            names.sort_unstable();
+            for field in names {
+                write_xml_element(xml, &xml_name(field), &fields[field], depth + 1);
+            }
+            let _ = writeln!(xml, "{}</{}>", indent, name);
+            return;
+        }
+        RecordValue::Array { .. } | RecordValue::Struct(_) => {}
+        scalar => {
+            let _ = write!(xml, " value=\"{}\"", xml_escape(&xml_attribute(scalar).unwrap_or_default()));
+        }
+    }
+    xml.push_str("/>\n");
+}
+
+/// Turn a struct or property name into a valid XML element/attribute name
+fn xml_name(name: &str) -> String {
+    let mut out: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') { c } else { '_' })
+        .collect();
+    if !out.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        out.insert(0, '_');
+    }
+    out
+}
+
+/// Escape text for use in XML attributes and content
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dcb::{DcbParser, RecordRef};
+    use crate::testutil::DataCoreBuilder;
+    use crate::traits::Parser;
+    use std::io::Cursor;
+
+    /// Two structs, the second inheriting from the first, with one record each
+    fn make_datacore() -> DataCore {
+        let mut builder = DataCoreBuilder::new();
+        let item = builder.add_struct("Item", None, &[
+            ("enabled", DataType::Boolean),
+            ("mass", DataType::Double),
+            ("slot", DataType::Int16),
+        ]);
+        let thruster = builder.add_struct("Thruster", Some(item), &[
+            ("thrust", DataType::Float),
+            ("offset", DataType::Vec3),
+            ("label", DataType::String),
+            ("parent", DataType::Reference),
+            ("mode", DataType::Enum),
+            ("tuning", DataType::Blob),
+        ]);
+
+        builder.add_record(item, "Item.Default", 0x1122_3344_5566_7788, [
+            ("enabled", RecordValue::Boolean(true)),
+            ("mass", RecordValue::Double(1250.5)),
+            ("slot", RecordValue::Int32(-3)),
+        ]);
+        builder.add_record(thruster, "Thruster.Main", 42, [
+            ("thrust", RecordValue::Float(9.81)),
+            ("offset", RecordValue::Vec3([1.0, -2.0, 0.5])),
+            ("label", RecordValue::String("Main Engine".to_string())),
+            ("parent", RecordValue::Reference(RecordRef { record_id: 1, struct_id: 1 })),
+            ("mode", RecordValue::Enum(3)),
+            ("tuning", RecordValue::Bytes(vec![1, 2, 3])),
+        ]);
+
+        let mut datacore = builder.build();
+        // Layout fields the builder leaves at zero still have to round-trip
+        datacore.structs[0].size = 13;
+        datacore.structs[1].flags = 2;
+        datacore
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let original = make_datacore();
+
+        let mut buffer = Cursor::new(Vec::new());
+        original.write(&mut buffer).unwrap();
+        let parsed = DcbParser::new().parse(Cursor::new(buffer.into_inner())).unwrap();
+
+        assert_eq!(parsed.header.version, 5);
+        assert_eq!(parsed.structs.len(), 2);
+        for (a, b) in original.structs.iter().zip(&parsed.structs) {
+            assert_eq!(
+                (a.id, &a.name, a.parent_id, a.property_start, a.property_count, a.size, a.flags),
+                (b.id, &b.name, b.parent_id, b.property_start, b.property_count, b.size, b.flags)
+            );
+        }
+        for (a, b) in original.properties.iter().zip(&parsed.properties) {
+            assert_eq!((a.id, &a.name, &a.data_type, a.struct_id), (b.id, &b.name, &b.data_type, b.struct_id));
+        }
+
+        assert_eq!(parsed.records.len(), 2);
+        for (a, b) in original.records.iter().zip(&parsed.records) {
+            assert_eq!((a.id, a.struct_id, &a.name, a.guid), (b.id, b.struct_id, &b.name, b.guid));
+            assert_eq!(
+                serde_json::to_value(&a.values).unwrap(),
+                serde_json::to_value(&b.values).unwrap(),
+                "values of {}",
+                a.name
+            );
+        }
+        assert_eq!(parsed.get_record(42).map(|r| r.name.as_str()), Some("Thruster.Main"));
+    }
+
+    #[test]
+    fn test_exportable_json_and_xml() {
+        let mut datacore = make_datacore();
+        datacore.records[1].values.insert("label".to_string(), RecordValue::String("<Main & \"Aux\">".to_string()));
+
+        let json: serde_json::Value = serde_json::from_str(&datacore.export_json(true).unwrap()).unwrap();
+        assert_eq!(json["metadata"]["record_count"], 2);
+        assert_eq!(json["structs"]["Thruster"][0]["name"], "Thruster.Main");
+        assert_eq!(json["structs"]["Thruster"][0]["values"]["label"], "<Main & \"Aux\">");
+        assert!(!datacore.export_json(false).unwrap().contains('\n'));
+
+        let xml = datacore.export_xml().unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<Item __name=\"Item.Default\""));
+        assert!(xml.contains("label=\"&lt;Main &amp; &quot;Aux&quot;&gt;\""));
+        assert!(xml.contains("<offset x=\"1\" y=\"-2\" z=\"0.5\"/>"));
+        assert!(xml.contains("<parent record_id=\"1\" struct_id=\"1\"/>"));
+        assert!(xml.contains("</Thruster>"));
+
+        // Every element opened is closed, in order
+        let mut open = Vec::new();
+        for tag in xml.split('<').skip(2).map(|t| t.split('>').next().unwrap()) {
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(open.pop(), Some(name));
+            } else if !tag.ends_with('/') {
+                open.push(tag.split(' ').next().unwrap());
+            }
+        }
+        assert!(open.is_empty());
+    }
+
+    #[test]
+    fn test_exportable_binary_round_trip() {
+        let original = make_datacore();
+        let bytes = original.export_binary().unwrap();
+        let parsed = DcbParser::new().parse(Cursor::new(bytes)).unwrap();
+        assert_eq!(parsed.records.len(), 2);
+        assert_eq!(parsed.export_json(false).unwrap(), original.export_json(false).unwrap());
+
+        let mut datacore = make_datacore();
+        datacore.properties[5].data_type = DataType::Array(Box::new(DataType::String));
+        assert!(matches!(
+            datacore.export_binary(),
+            Err(ParseError::Nested { ref source, .. }) if matches!(**source, ParseError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_write_rejects_unsupported_values() {
+        let mut datacore = make_datacore();
+        datacore.properties[2].data_type = DataType::LocaleString;
+        datacore.records[0].values.insert(
+            "slot".to_string(),
+            RecordValue::LocaleString { key: "0".to_string(), value: "slot".to_string() },
+        );
+        let err = datacore.write(&mut Cursor::new(Vec::new())).unwrap_err();
+        assert!(matches!(err, ParseError::Nested { ref source, .. } if matches!(**source, ParseError::UnsupportedFeature(_))));
+
+        // A value that doesn't fit its property's width is refused, not truncated
+        let mut datacore = make_datacore();
+        datacore.records[0].values.insert("slot".to_string(), RecordValue::Int32(70_000));
+        assert!(datacore.write(&mut Cursor::new(Vec::new())).is_err());
+
+        let mut datacore = make_datacore();
+        datacore.records[1].values.remove("mode");
+        assert!(matches!(
+            datacore.write(&mut Cursor::new(Vec::new())),
+            Err(ParseError::MissingField(_))
+        ));
+    }
+}
@@ -176,6 +176,92 @@ impl DdsCombiner {
         })
     }
 
+    /// Combine split parts that are already in memory
+    ///
+    /// `parts[0]` is the base file (magic, headers and the first block of
+    /// data); every following part is raw data appended in order. Used when
+    /// the parts come straight out of an archive rather than from disk.
+    pub fn combine_from_bytes(&self, parts: &[Vec<u8>]) -> ParseResult<DdsTexture> {
+        let (first, rest) = parts.split_first().ok_or_else(|| {
+            ParseError::InvalidStructure("No parts provided to combine".to_string())
+        })?;
+
+        let mut texture = self.parser.parse_with_options(
+            std::io::Cursor::new(first),
+            &crate::traits::ParseOptions::default(),
+            None,
+        )?;
+
+        for part in rest {
+            texture.data.extend_from_slice(part);
+        }
+        texture.was_split = !rest.is_empty();
+
+        Ok(texture)
+    }
+
+    /// Combine split parts only until mip levels `0..=max_mip` are complete
+    ///
+    /// `read_part` is called with the base path and then each split part
+    /// name (`base.1`, `base.2`, `base.3a`, `base.3b`, ...) in combine order,
+    /// returning `Ok(None)` for parts that don't exist. Reading stops as soon
+    /// as the requested levels are covered, so later parts are never fetched.
+    /// The result is trimmed to those levels and `mipmap_count` updated; pair
+    /// it with [`DdsTexture::truncate_to_mip`] to drop the larger levels too.
+    pub fn combine_up_to_mip<F>(&self, mut read_part: F, base_path: &str, max_mip: u32) -> ParseResult<DdsTexture>
+    where
+        F: FnMut(&str) -> ParseResult<Option<Vec<u8>>>,
+    {
+        let base = read_part(base_path)?.ok_or_else(|| {
+            ParseError::InvalidStructure(format!("Base texture not found: {}", base_path))
+        })?;
+
+        let mut texture = self.parser.parse_with_options(
+            std::io::Cursor::new(base),
+            &crate::traits::ParseOptions::default(),
+            None,
+        )?;
+
+        let levels = (max_mip + 1).min(texture.mipmap_count().max(1));
+        let needed = texture.mip_chain_size(levels);
+
+        'parts: for i in 1..=99 {
+            if texture.data.len() >= needed {
+                break;
+            }
+
+            if let Some(part) = read_part(&format!("{}.{}", base_path, i))? {
+                texture.data.extend_from_slice(&part);
+                texture.was_split = true;
+                continue;
+            }
+
+            for suffix in ["a", "b"] {
+                if texture.data.len() >= needed {
+                    break 'parts;
+                }
+                match read_part(&format!("{}.{}{}", base_path, i, suffix))? {
+                    Some(part) => {
+                        texture.data.extend_from_slice(&part);
+                        texture.was_split = true;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        if texture.data.len() < needed {
+            return Err(ParseError::InvalidStructure(format!(
+                "Split parts hold {} bytes, {} needed for {} mip levels",
+                texture.data.len(), needed, levels
+            )));
+        }
+
+        texture.data.truncate(needed);
+        texture.header.mipmap_count = levels;
+        Ok(texture)
+    }
+
     /// Combine split files from a list of paths
     /// 
     /// Useful when you already know the split file paths
@@ -270,6 +356,41 @@ mod tests {
         assert!(!DdsCombiner::is_split_file("texture"));
     }
 
+    #[test]
+    fn test_combine_up_to_mip_skips_unneeded_parts() {
+        use crate::testutil::{dds_file, dds_header};
+        use std::collections::HashMap;
+
+        // 16x16 BC3 chain: 256 + 64 + 16 bytes, one level per file
+        let base = dds_file(&dds_header(*b"DXT5", 16, 3), &[1u8; 256]);
+
+        let files = HashMap::from([
+            ("tex.dds".to_string(), base),
+            ("tex.dds.1".to_string(), vec![2u8; 64]),
+            ("tex.dds.2".to_string(), vec![3u8; 16]),
+        ]);
+
+        let mut requested = Vec::new();
+        let texture = DdsCombiner::new()
+            .combine_up_to_mip(|path| {
+                requested.push(path.to_string());
+                Ok(files.get(path).cloned())
+            }, "tex.dds", 1)
+            .unwrap();
+
+        assert_eq!(requested, ["tex.dds", "tex.dds.1"]);
+        assert_eq!(texture.mipmap_count(), 2);
+        assert_eq!(texture.data_size(), 256 + 64);
+        assert!(texture.was_split);
+
+        let result = DdsCombiner::new().combine_up_to_mip(
+            |path| Ok(files.get(path).filter(|_| path == "tex.dds").cloned()),
+            "tex.dds",
+            2,
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_get_base_path() {
         assert_eq!(
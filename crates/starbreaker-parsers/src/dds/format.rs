@@ -56,8 +56,10 @@ impl DxgiFormat {
 /// Detected texture format
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TextureFormat {
-    /// Block Compressed 1 (DXT1)
+    /// Block Compressed 1 (DXT1), opaque
     BC1,
+    /// Block Compressed 1 with 1-bit punch-through alpha
+    BC1A,
     /// Block Compressed 2 (DXT3)
     BC2,
     /// Block Compressed 3 (DXT5)
@@ -93,7 +95,8 @@ impl TextureFormat {
     /// Detect from DXGI format
     fn from_dxgi_format(format: u32) -> Self {
         match DxgiFormat::from_u32(format) {
-            DxgiFormat::BC1Unorm | DxgiFormat::BC1UnormSrgb => TextureFormat::BC1,
+            // DXGI BC1 always decodes three-color blocks with transparency
+            DxgiFormat::BC1Unorm | DxgiFormat::BC1UnormSrgb => TextureFormat::BC1A,
             DxgiFormat::BC2Unorm | DxgiFormat::BC2UnormSrgb => TextureFormat::BC2,
             DxgiFormat::BC3Unorm | DxgiFormat::BC3UnormSrgb => TextureFormat::BC3,
             DxgiFormat::BC4Unorm | DxgiFormat::BC4Snorm => TextureFormat::BC4,
@@ -110,6 +113,7 @@ impl TextureFormat {
     fn from_fourcc(pf: &PixelFormat) -> Self {
         if pf.flags & pf_flags::FOURCC != 0 {
             match &pf.fourcc {
+                b"DXT1" if pf.flags & pf_flags::ALPHAPIXELS != 0 => TextureFormat::BC1A,
                 b"DXT1" => TextureFormat::BC1,
                 b"DXT2" | b"DXT3" => TextureFormat::BC2,
                 b"DXT4" | b"DXT5" => TextureFormat::BC3,
@@ -132,20 +136,38 @@ impl TextureFormat {
         }
     }
 
-    /// Get block size for compressed formats
-    pub fn block_size(&self) -> Option<usize> {
+    /// Get `(block_width, block_height, bytes_per_block)`
+    ///
+    /// Uncompressed formats are 1x1 blocks of a single pixel; unknown
+    /// formats are assumed to be 4 bytes per pixel.
+    pub fn block_dim(&self) -> (u32, u32, u32) {
         match self {
-            TextureFormat::BC1 | TextureFormat::BC4 => Some(8),
-            TextureFormat::BC2 | TextureFormat::BC3 | TextureFormat::BC5 
-            | TextureFormat::BC6H | TextureFormat::BC7 => Some(16),
-            _ => None,
+            TextureFormat::BC1 | TextureFormat::BC1A | TextureFormat::BC4 => (4, 4, 8),
+            TextureFormat::BC2 | TextureFormat::BC3 | TextureFormat::BC5
+            | TextureFormat::BC6H | TextureFormat::BC7 => (4, 4, 16),
+            TextureFormat::RGBA8 | TextureFormat::BGRA8 | TextureFormat::Unknown => (1, 1, 4),
         }
     }
 
+    /// Get bytes per block (per pixel for uncompressed formats)
+    pub fn bytes_per_block(&self) -> u32 {
+        self.block_dim().2
+    }
+
+    /// Get block size for compressed formats
+    pub fn block_size(&self) -> Option<usize> {
+        self.is_compressed().then(|| self.bytes_per_block() as usize)
+    }
+
+    /// Check if decoded texels can be transparent
+    pub fn has_alpha(&self) -> bool {
+        !matches!(self, TextureFormat::BC1 | TextureFormat::BC4 | TextureFormat::BC5 | TextureFormat::BC6H)
+    }
+
     /// Check if format is block-compressed
     pub fn is_compressed(&self) -> bool {
         matches!(self, 
-            TextureFormat::BC1 | TextureFormat::BC2 | TextureFormat::BC3 |
+            TextureFormat::BC1 | TextureFormat::BC1A | TextureFormat::BC2 | TextureFormat::BC3 |
             TextureFormat::BC4 | TextureFormat::BC5 | TextureFormat::BC6H |
             TextureFormat::BC7
         )
@@ -1,6 +1,6 @@
 //! DDS header structures
 
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, Write};
 use crate::traits::{ParseResult, ParseError};
 
 /// DDS header flags
@@ -123,6 +123,27 @@ impl DdsHeader {
         })
     }
 
+    /// Write the 124-byte header (without the "DDS " magic)
+    pub fn write<W: Write>(&self, writer: &mut W) -> ParseResult<()> {
+        writer.write_all(&self.size.to_le_bytes())?;
+        writer.write_all(&self.flags.to_le_bytes())?;
+        writer.write_all(&self.height.to_le_bytes())?;
+        writer.write_all(&self.width.to_le_bytes())?;
+        writer.write_all(&self.pitch_or_linear_size.to_le_bytes())?;
+        writer.write_all(&self.depth.to_le_bytes())?;
+        writer.write_all(&self.mipmap_count.to_le_bytes())?;
+        for value in &self.reserved1 {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        self.pixel_format.write(writer)?;
+        writer.write_all(&self.caps.to_le_bytes())?;
+        writer.write_all(&self.caps2.to_le_bytes())?;
+        writer.write_all(&self.caps3.to_le_bytes())?;
+        writer.write_all(&self.caps4.to_le_bytes())?;
+        writer.write_all(&self.reserved2.to_le_bytes())?;
+        Ok(())
+    }
+
     /// Check if this DDS has a DX10 extended header
     pub fn has_dx10_header(&self) -> bool {
         self.pixel_format.fourcc == *b"DX10"
@@ -182,6 +203,19 @@ impl PixelFormat {
         })
     }
 
+    /// Write the 32-byte pixel format
+    pub fn write<W: Write>(&self, writer: &mut W) -> ParseResult<()> {
+        writer.write_all(&self.size.to_le_bytes())?;
+        writer.write_all(&self.flags.to_le_bytes())?;
+        writer.write_all(&self.fourcc)?;
+        writer.write_all(&self.rgb_bit_count.to_le_bytes())?;
+        writer.write_all(&self.r_bit_mask.to_le_bytes())?;
+        writer.write_all(&self.g_bit_mask.to_le_bytes())?;
+        writer.write_all(&self.b_bit_mask.to_le_bytes())?;
+        writer.write_all(&self.a_bit_mask.to_le_bytes())?;
+        Ok(())
+    }
+
     /// Get FourCC as string
     pub fn fourcc_string(&self) -> String {
         String::from_utf8_lossy(&self.fourcc).to_string()
@@ -218,4 +252,14 @@ impl DX10Header {
             misc_flags2,
         })
     }
+
+    /// Write the 20-byte DX10 header
+    pub fn write<W: Write>(&self, writer: &mut W) -> ParseResult<()> {
+        writer.write_all(&self.dxgi_format.to_le_bytes())?;
+        writer.write_all(&self.resource_dimension.to_le_bytes())?;
+        writer.write_all(&self.misc_flag.to_le_bytes())?;
+        writer.write_all(&self.array_size.to_le_bytes())?;
+        writer.write_all(&self.misc_flags2.to_le_bytes())?;
+        Ok(())
+    }
 }
@@ -13,12 +13,14 @@ mod combiner;
 pub use header::{DdsHeader, DX10Header, PixelFormat};
 pub use format::{DxgiFormat, TextureFormat};
 pub use combiner::DdsCombiner;
+#[cfg(any(test, feature = "test-util"))]
+pub(crate) use header::pf_flags;
 
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, Write};
 use crate::traits::{Parser, ParseResult, ParseError, ParseOptions, ProgressCallback};
 
 /// DDS file magic number "DDS "
-const DDS_MAGIC: u32 = 0x20534444;
+pub(crate) const DDS_MAGIC: u32 = 0x20534444;
 
 /// Parsed DDS texture
 #[derive(Debug)]
@@ -97,65 +99,146 @@ impl DdsTexture {
 
     /// Calculate the size of a mip level in bytes
     fn calculate_mip_size(&self, width: u32, height: u32) -> usize {
-        match &self.format {
-            TextureFormat::BC1 => {
-                // BC1: 8 bytes per 4x4 block
-                let block_width = (width + 3) / 4;
-                let block_height = (height + 3) / 4;
-                (block_width * block_height * 8) as usize
-            }
-            TextureFormat::BC2 | TextureFormat::BC3 => {
-                // BC2/BC3: 16 bytes per 4x4 block
-                let block_width = (width + 3) / 4;
-                let block_height = (height + 3) / 4;
-                (block_width * block_height * 16) as usize
-            }
-            TextureFormat::BC4 => {
-                // BC4: 8 bytes per 4x4 block (single channel)
-                let block_width = (width + 3) / 4;
-                let block_height = (height + 3) / 4;
-                (block_width * block_height * 8) as usize
-            }
-            TextureFormat::BC5 => {
-                // BC5: 16 bytes per 4x4 block (two channels)
-                let block_width = (width + 3) / 4;
-                let block_height = (height + 3) / 4;
-                (block_width * block_height * 16) as usize
-            }
-            TextureFormat::BC6H | TextureFormat::BC7 => {
-                // BC6H/BC7: 16 bytes per 4x4 block
-                let block_width = (width + 3) / 4;
-                let block_height = (height + 3) / 4;
-                (block_width * block_height * 16) as usize
-            }
-            TextureFormat::RGBA8 | TextureFormat::BGRA8 => {
-                // Uncompressed RGBA/BGRA: 4 bytes per pixel
-                (width * height * 4) as usize
-            }
-            TextureFormat::Unknown => {
-                // Fallback: assume RGBA
-                (width * height * 4) as usize
-            }
-        }
+        let (block_width, block_height, bytes_per_block) = self.format.block_dim();
+        let blocks_wide = width.div_ceil(block_width) as usize;
+        let blocks_high = height.div_ceil(block_height) as usize;
+        blocks_wide * blocks_high * bytes_per_block as usize
     }
 
-    /// Extract all mipmap levels
-    /// Returns a vector of (level, width, height, data) tuples
-    pub fn extract_mipmaps(&self) -> Vec<(u32, u32, u32, Vec<u8>)> {
-        let mut mipmaps = Vec::new();
+    /// Total size in bytes of the first `levels` mip levels
+    fn mip_chain_size(&self, levels: u32) -> usize {
         let mut width = self.width();
         let mut height = self.height();
+        let mut size = 0;
 
-        for level in 0..self.mipmap_count() {
-            if let Some(mip_data) = self.get_mipmap(level) {
-                mipmaps.push((level, width, height, mip_data.to_vec()));
-            }
-            
+        for _ in 0..levels {
+            size += self.calculate_mip_size(width, height);
             width = (width / 2).max(1);
             height = (height / 2).max(1);
         }
 
-        mipmaps
+        size
+    }
+
+    /// Drop mip levels above `level` so it becomes the new full-resolution level
+    ///
+    /// Width, height and `mipmap_count` are updated to match. Useful for
+    /// thumbnails and previews that never need the larger levels. Returns
+    /// `false` and leaves the texture untouched if `level` doesn't exist or
+    /// the data doesn't reach it.
+    pub fn truncate_to_mip(&mut self, level: u32) -> bool {
+        let Some((width, height)) = self.get_mipmap_dimensions(level) else {
+            return false;
+        };
+
+        let skip = self.mip_chain_size(level);
+        if skip >= self.data.len() {
+            return false;
+        }
+
+        self.data.drain(..skip);
+        self.header.width = width;
+        self.header.height = height;
+        self.header.mipmap_count -= level;
+        true
+    }
+
+    /// Iterate over mipmap levels without copying their data
+    ///
+    /// Stops early if the data is shorter than the header claims, so a
+    /// header-only texture yields nothing.
+    pub fn iter_mipmaps(&self) -> MipIter<'_> {
+        MipIter {
+            texture: self,
+            level: 0,
+            width: self.width(),
+            height: self.height(),
+            offset: 0,
+        }
+    }
+
+    /// Extract all mipmap levels
+    /// Returns a vector of (level, width, height, data) tuples
+    pub fn extract_mipmaps(&self) -> Vec<(u32, u32, u32, Vec<u8>)> {
+        self.iter_mipmaps()
+            .map(|mip| (mip.level, mip.width, mip.height, mip.data.to_vec()))
+            .collect()
+    }
+
+    /// Write the texture back out as a DDS file
+    ///
+    /// Emits the "DDS " magic, a header whose flags, caps and pitch/linear
+    /// size are recomputed from the format and dimensions, the DX10 header
+    /// when the format requires one, and finally the texture data.
+    pub fn write<W: Write>(&self, mut writer: W) -> ParseResult<()> {
+        let dx10_header = self.dx10_header.clone().or_else(|| self.synthesize_dx10_header());
+
+        let mut header = self.header.clone();
+        header.size = 124;
+        header.pixel_format.size = 32;
+
+        header.flags = header::flags::CAPS
+            | header::flags::HEIGHT
+            | header::flags::WIDTH
+            | header::flags::PIXEL_FORMAT;
+        header.caps = header::caps::TEXTURE;
+
+        if self.format.is_compressed() {
+            header.flags |= header::flags::LINEAR_SIZE;
+            header.pitch_or_linear_size = self.calculate_mip_size(self.width(), self.height()) as u32;
+        } else {
+            header.flags |= header::flags::PITCH;
+            let bits_per_pixel = match header.pixel_format.rgb_bit_count {
+                0 => 32,
+                bits => bits,
+            };
+            header.pitch_or_linear_size = (self.width() * bits_per_pixel).div_ceil(8);
+        }
+
+        if self.mipmap_count() > 1 {
+            header.flags |= header::flags::MIPMAP_COUNT;
+            header.caps |= header::caps::COMPLEX | header::caps::MIPMAP;
+        }
+
+        if header.depth > 1 {
+            header.flags |= header::flags::DEPTH;
+            header.caps |= header::caps::COMPLEX;
+        }
+
+        if self.is_cubemap() {
+            header.caps |= header::caps::COMPLEX;
+        }
+
+        if dx10_header.is_some() {
+            header.pixel_format.flags |= header::pf_flags::FOURCC;
+            header.pixel_format.fourcc = *b"DX10";
+        }
+
+        writer.write_all(&DDS_MAGIC.to_le_bytes())?;
+        header.write(&mut writer)?;
+        if let Some(dx10) = &dx10_header {
+            dx10.write(&mut writer)?;
+        }
+        writer.write_all(&self.data)?;
+
+        Ok(())
+    }
+
+    /// Build a DX10 header for formats that have no legacy FourCC
+    fn synthesize_dx10_header(&self) -> Option<DX10Header> {
+        let dxgi_format = match self.format {
+            TextureFormat::BC6H => DxgiFormat::BC6HUf16,
+            TextureFormat::BC7 => DxgiFormat::BC7Unorm,
+            _ => return None,
+        };
+
+        Some(DX10Header {
+            dxgi_format: dxgi_format as u32,
+            resource_dimension: 3, // D3D10_RESOURCE_DIMENSION_TEXTURE2D
+            misc_flag: if self.is_cubemap() { 0x4 } else { 0 },
+            array_size: 1,
+            misc_flags2: 0,
+        })
     }
 
     /// Get the dimensions of a specific mipmap level
@@ -174,6 +257,72 @@ impl DdsTexture {
 
         Some((width, height))
     }
+
+    /// Pick the mip level to decode for a preview of at most `max_dim` pixels
+    ///
+    /// Returns the largest level (lowest index) whose width and height both fit
+    /// within `max_dim`, so thumbnails never decode more data than they show.
+    /// If even the smallest level is too big, the last level is returned and
+    /// the caller is expected to downscale it.
+    pub fn preview_mip_level(&self, max_dim: u32) -> u32 {
+        let last = self.mipmap_count().saturating_sub(1);
+        (0..=last)
+            .find(|&level| {
+                self.get_mipmap_dimensions(level)
+                    .map(|(w, h)| w <= max_dim && h <= max_dim)
+                    .unwrap_or(false)
+            })
+            .unwrap_or(last)
+    }
+}
+
+/// A single mipmap level borrowed from a texture's data
+#[derive(Debug, Clone, Copy)]
+pub struct MipView<'a> {
+    /// Mip level (0 is full resolution)
+    pub level: u32,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Raw level data
+    pub data: &'a [u8],
+}
+
+/// Iterator over the mipmap levels of a [`DdsTexture`]
+pub struct MipIter<'a> {
+    texture: &'a DdsTexture,
+    level: u32,
+    width: u32,
+    height: u32,
+    offset: usize,
+}
+
+impl<'a> Iterator for MipIter<'a> {
+    type Item = MipView<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.level >= self.texture.mipmap_count() {
+            return None;
+        }
+
+        let size = self.texture.calculate_mip_size(self.width, self.height);
+        let data = self.texture.data.get(self.offset..self.offset + size)?;
+
+        let view = MipView {
+            level: self.level,
+            width: self.width,
+            height: self.height,
+            data,
+        };
+
+        self.level += 1;
+        self.offset += size;
+        self.width = (self.width / 2).max(1);
+        self.height = (self.height / 2).max(1);
+
+        Some(view)
+    }
 }
 
 /// DDS Parser
@@ -251,3 +400,155 @@ impl Parser for DdsParser {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::bc3_texture;
+    use std::io::Cursor;
+
+    fn make_bc3_texture() -> DdsTexture {
+        bc3_texture(16, 3)
+    }
+
+    #[test]
+    fn test_preview_mip_level() {
+        // 256, 128, 64, 32, 16
+        let texture = bc3_texture(256, 5);
+
+        assert_eq!(texture.preview_mip_level(256), 0);
+        assert_eq!(texture.preview_mip_level(100), 2);
+        assert_eq!(texture.preview_mip_level(64), 2);
+        assert_eq!(texture.preview_mip_level(20), 4);
+
+        // Nothing fits: fall back to the smallest level
+        assert_eq!(texture.preview_mip_level(8), 4);
+        assert_eq!(bc3_texture(256, 1).preview_mip_level(64), 0);
+    }
+
+    #[test]
+    fn test_truncate_to_mip() {
+        // 16x16 (256 bytes), 8x8 (64), 4x4 (16)
+        let mut texture = make_bc3_texture();
+        let smallest = texture.get_mipmap(2).unwrap().to_vec();
+
+        assert!(texture.truncate_to_mip(1));
+        assert_eq!(texture.mipmap_count(), 2);
+        assert_eq!((texture.width(), texture.height()), (8, 8));
+        assert_eq!(texture.data_size(), 64 + 16);
+        assert_eq!(texture.get_mipmap(1), Some(smallest.as_slice()));
+
+        assert!(!texture.truncate_to_mip(2));
+        assert_eq!(texture.mipmap_count(), 2);
+    }
+
+    #[test]
+    fn test_iter_mipmaps_borrows_levels() {
+        let texture = make_bc3_texture();
+        let mips: Vec<_> = texture.iter_mipmaps().collect();
+
+        assert_eq!(mips.len(), 3);
+        for mip in &mips {
+            assert_eq!(mip.data.len(), texture.calculate_mip_size(mip.width, mip.height));
+            assert_eq!(Some((mip.width, mip.height)), texture.get_mipmap_dimensions(mip.level));
+            assert_eq!(Some(mip.data), texture.get_mipmap(mip.level));
+        }
+        assert_eq!(mips[2].data.as_ptr(), texture.data[320..].as_ptr());
+    }
+
+    #[test]
+    fn test_iter_mipmaps_header_only() {
+        let mut texture = make_bc3_texture();
+        texture.data.clear();
+
+        assert_eq!(texture.iter_mipmaps().count(), 0);
+        assert!(texture.extract_mipmaps().is_empty());
+    }
+
+    #[test]
+    fn test_write_round_trip_bc3() {
+        let texture = make_bc3_texture();
+
+        let mut buffer = Vec::new();
+        texture.write(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), 4 + 124 + texture.data.len());
+
+        let parsed = DdsParser::new().parse(Cursor::new(buffer)).unwrap();
+        assert_eq!(parsed.width(), 16);
+        assert_eq!(parsed.height(), 16);
+        assert_eq!(parsed.mipmap_count(), 3);
+        assert_eq!(parsed.format, TextureFormat::BC3);
+        assert!(parsed.dx10_header.is_none());
+        assert!(parsed.has_mipmaps());
+        assert_eq!(parsed.header.pitch_or_linear_size, 256);
+        assert_ne!(parsed.header.flags & header::flags::LINEAR_SIZE, 0);
+        assert_eq!(parsed.get_mipmap(2), texture.get_mipmap(2));
+        assert_eq!(parsed.data, texture.data);
+    }
+
+    #[test]
+    fn test_write_synthesizes_dx10_for_bc7() {
+        let mut texture = make_bc3_texture();
+        texture.format = TextureFormat::BC7;
+
+        let mut buffer = Vec::new();
+        texture.write(&mut buffer).unwrap();
+
+        let parsed = DdsParser::new().parse(Cursor::new(buffer)).unwrap();
+        assert_eq!(parsed.format, TextureFormat::BC7);
+        assert_eq!(parsed.dx10_header.as_ref().map(|h| h.dxgi_format), Some(DxgiFormat::BC7Unorm as u32));
+        assert_eq!(parsed.mipmap_count(), 3);
+    }
+
+    #[test]
+    fn test_block_dim() {
+        assert_eq!(TextureFormat::BC1.block_dim(), (4, 4, 8));
+        assert_eq!(TextureFormat::BC7.block_dim(), (4, 4, 16));
+        assert_eq!(TextureFormat::RGBA8.block_dim(), (1, 1, 4));
+        assert_eq!(TextureFormat::BC4.block_size(), Some(8));
+        assert_eq!(TextureFormat::BGRA8.block_size(), None);
+    }
+
+    #[test]
+    fn test_bc1_alpha_detection() {
+        let mut dds_header = make_bc3_texture().header;
+        dds_header.pixel_format.fourcc = *b"DXT1";
+        assert_eq!(TextureFormat::from_header(&dds_header, None), TextureFormat::BC1);
+
+        dds_header.pixel_format.flags |= header::pf_flags::ALPHAPIXELS;
+        assert_eq!(TextureFormat::from_header(&dds_header, None), TextureFormat::BC1A);
+        assert!(TextureFormat::BC1A.has_alpha() && !TextureFormat::BC1.has_alpha());
+
+        let dx10 = DX10Header {
+            dxgi_format: DxgiFormat::BC1Unorm as u32,
+            resource_dimension: 3,
+            misc_flag: 0,
+            array_size: 1,
+            misc_flags2: 0,
+        };
+        assert_eq!(TextureFormat::from_header(&dds_header, Some(&dx10)), TextureFormat::BC1A);
+    }
+
+    #[test]
+    fn test_mip_size_matches_block_math() {
+        let mut texture = bc3_texture(16, 1);
+        let formats = [
+            (TextureFormat::BC1, 8), (TextureFormat::BC1A, 8), (TextureFormat::BC2, 16), (TextureFormat::BC3, 16),
+            (TextureFormat::BC4, 8), (TextureFormat::BC5, 16), (TextureFormat::BC6H, 16),
+            (TextureFormat::BC7, 16),
+        ];
+
+        for (format, bytes) in formats {
+            texture.format = format;
+            for (w, h) in [(1u32, 1u32), (3, 5), (4, 4), (17, 8), (256, 128)] {
+                let expected = (w.div_ceil(4) * h.div_ceil(4) * bytes) as usize;
+                assert_eq!(texture.calculate_mip_size(w, h), expected, "{:?} {}x{}", texture.format, w, h);
+            }
+        }
+
+        for format in [TextureFormat::RGBA8, TextureFormat::BGRA8, TextureFormat::Unknown] {
+            texture.format = format;
+            assert_eq!(texture.calculate_mip_size(3, 5), 60);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// starbreaker-parsers/src/detect.rs
+//! Cheap format detection from leading magic bytes
+//!
+//! Answers "what is this file" without constructing a parser. Only the first
+//! few bytes are read, plus the ZIP trailer when telling P4K archives apart
+//! from SOCPAK packages.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::cgf::{CRCH_MAGIC, CRYTEK_MAGIC, IVO_MAGIC};
+use crate::dcb::{BINXML_MAGIC, CRYXML_MAGIC, DCB_MAGIC};
+use crate::dds::DDS_MAGIC;
+use crate::p4k::{
+    CompressionMethod, EOCD_SIGNATURE, EOCD_SIZE, P4K_MAGIC, ZIP64_EOCD_LOCATOR_SIGNATURE,
+};
+
+/// Size of the ZIP64 end of central directory locator
+const ZIP64_LOCATOR_SIZE: usize = 20;
+
+/// File formats recognizable from their signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatKind {
+    /// P4K game archive (ZIP64 or CryEngine-specific compression)
+    P4k,
+    /// SOCPAK scene package (plain ZIP)
+    Socpak,
+    /// DataCore binary database
+    Dcb,
+    /// CryXml binary XML
+    CryXml,
+    /// Binary XML ("BXLM")
+    BinXml,
+    /// CryEngine geometry (CryTek, #ivo or CrCh container)
+    Cgf,
+    /// DirectDraw Surface texture
+    Dds,
+}
+
+/// Identify a file from its leading bytes
+///
+/// Returns `None` for unrecognized or unreadable input. The reader is left
+/// at an unspecified position.
+pub fn detect_format<R: Read + Seek>(mut reader: R) -> Option<FormatKind> {
+    reader.seek(SeekFrom::Start(0)).ok()?;
+
+    let mut magic = [0u8; 8];
+    let len = read_up_to(&mut reader, &mut magic).ok()?;
+    let magic = &magic[..len];
+
+    if magic.starts_with(CRYTEK_MAGIC) || magic.starts_with(IVO_MAGIC) || magic.starts_with(CRCH_MAGIC) {
+        return Some(FormatKind::Cgf);
+    }
+    if magic.starts_with(&DDS_MAGIC.to_le_bytes()) {
+        return Some(FormatKind::Dds);
+    }
+    if magic.starts_with(DCB_MAGIC) {
+        return Some(FormatKind::Dcb);
+    }
+    if magic.starts_with(CRYXML_MAGIC) {
+        return Some(FormatKind::CryXml);
+    }
+    if magic.starts_with(&BINXML_MAGIC.to_le_bytes()) {
+        return Some(FormatKind::BinXml);
+    }
+    if magic.starts_with(P4K_MAGIC) || magic.starts_with(&EOCD_SIGNATURE.to_le_bytes()) {
+        return Some(classify_zip(&mut reader));
+    }
+
+    None
+}
+
+/// Tell a P4K archive from a SOCPAK
+///
+/// P4K archives use ZIP64 and CryEngine's Zstd/LZ4 method ids; SOCPAKs are
+/// ordinary ZIPs. Anything unreadable past the magic counts as a SOCPAK.
+fn classify_zip<R: Read + Seek>(reader: &mut R) -> FormatKind {
+    // Compression method of the first local header
+    let mut method = [0u8; 2];
+    if reader.seek(SeekFrom::Start(8)).is_ok()
+        && reader.read_exact(&mut method).is_ok()
+        && matches!(
+            CompressionMethod::from(u16::from_le_bytes(method)),
+            CompressionMethod::Zstd | CompressionMethod::Lz4
+        )
+    {
+        return FormatKind::P4k;
+    }
+
+    if has_zip64_locator(reader).unwrap_or(false) {
+        FormatKind::P4k
+    } else {
+        FormatKind::Socpak
+    }
+}
+
+/// Whether a ZIP64 EOCD locator sits directly in front of the EOCD record
+fn has_zip64_locator<R: Read + Seek>(reader: &mut R) -> std::io::Result<bool> {
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    let tail_len = file_size.min((u16::MAX as usize + EOCD_SIZE + ZIP64_LOCATOR_SIZE) as u64);
+    reader.seek(SeekFrom::Start(file_size - tail_len))?;
+
+    let mut tail = vec![0u8; tail_len as usize];
+    reader.read_exact(&mut tail)?;
+
+    let sig = EOCD_SIGNATURE.to_le_bytes();
+    let Some(eocd) = (0..=tail.len().saturating_sub(EOCD_SIZE))
+        .rev()
+        .find(|&pos| tail.len() >= EOCD_SIZE && tail[pos..pos + 4] == sig)
+    else {
+        return Ok(false);
+    };
+
+    Ok(eocd >= ZIP64_LOCATOR_SIZE
+        && tail[eocd - ZIP64_LOCATOR_SIZE..eocd - ZIP64_LOCATOR_SIZE + 4]
+            == ZIP64_EOCD_LOCATOR_SIGNATURE.to_le_bytes())
+}
+
+/// Fill as much of `buf` as the reader allows
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn detect(bytes: &[u8]) -> Option<FormatKind> {
+        detect_format(Cursor::new(bytes))
+    }
+
+    /// Local header with the given method, then an EOCD, optionally preceded by a ZIP64 locator
+    fn make_zip(method: u16, zip64: bool) -> Vec<u8> {
+        let mut data = P4K_MAGIC.to_vec();
+        data.extend_from_slice(&[20, 0, 0, 0]); // version, flags
+        data.extend_from_slice(&method.to_le_bytes());
+        data.extend_from_slice(&[0u8; 20]);
+
+        if zip64 {
+            data.extend_from_slice(&ZIP64_EOCD_LOCATOR_SIGNATURE.to_le_bytes());
+            data.extend_from_slice(&[0u8; 16]);
+        }
+        data.extend_from_slice(&EOCD_SIGNATURE.to_le_bytes());
+        data.extend_from_slice(&[0u8; 18]);
+        data
+    }
+
+    #[test]
+    fn test_detect_single_magic_formats() {
+        assert_eq!(detect(b"DDS \x7c\x00\x00\x00"), Some(FormatKind::Dds));
+        assert_eq!(detect(b"DCB1\x05\x00\x00\x00"), Some(FormatKind::Dcb));
+        assert_eq!(detect(b"CryXmlB\0"), Some(FormatKind::CryXml));
+        assert_eq!(detect(b"BLXM\x01\x00\x00\x00"), Some(FormatKind::BinXml));
+    }
+
+    #[test]
+    fn test_detect_cgf_magics() {
+        assert_eq!(detect(b"CryTek\0\0\x45\x07\x00\x00"), Some(FormatKind::Cgf));
+        assert_eq!(detect(b"#ivo\x01\x00\x00\x00"), Some(FormatKind::Cgf));
+        assert_eq!(detect(b"CrCh\x46\x07\x00\x00"), Some(FormatKind::Cgf));
+    }
+
+    #[test]
+    fn test_detect_zip_flavours() {
+        assert_eq!(detect(&make_zip(0, true)), Some(FormatKind::P4k));
+        assert_eq!(detect(&make_zip(93, false)), Some(FormatKind::P4k));
+        assert_eq!(detect(&make_zip(99, false)), Some(FormatKind::P4k));
+        assert_eq!(detect(&make_zip(8, false)), Some(FormatKind::Socpak));
+    }
+
+    #[test]
+    fn test_detect_unknown() {
+        assert_eq!(detect(b""), None);
+        assert_eq!(detect(b"PK"), None);
+        assert_eq!(detect(b"<?xml version"), None);
+    }
+}
@@ -0,0 +1,70 @@
+//! Shell-style wildcard matching
+//!
+//! `*` matches any run of characters (including `/`) and `?` exactly one.
+//! There are no character classes or escapes; archive paths and struct
+//! names never need them.
+
+/// Check whether `text` matches `pattern`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches(&pattern, &text)
+}
+
+/// Check whether `text` matches `pattern`, ignoring case
+pub fn glob_match_ignore_case(pattern: &str, text: &str) -> bool {
+    glob_match(&pattern.to_lowercase(), &text.to_lowercase())
+}
+
+fn matches(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            // Let the last `*` swallow one more character and retry
+            _ => match backtrack {
+                Some((star, from)) => {
+                    backtrack = Some((star, from + 1));
+                    p = star + 1;
+                    t = from + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.xml", "Data/ship.xml"));
+        assert!(glob_match("ship?.xml", "ship1.xml"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(glob_match("**", ""));
+        assert!(!glob_match("ship?.xml", "ship.xml"));
+        assert!(!glob_match("*.XML", "ship.xml"));
+
+        // `?` is one character, not one byte
+        assert!(glob_match("?.xml", "é.xml"));
+    }
+
+    #[test]
+    fn test_glob_match_ignore_case() {
+        assert!(glob_match_ignore_case("SHIP*", "ShipLoadout"));
+        assert!(glob_match_ignore_case("thr?ster", "Thruster"));
+        assert!(!glob_match_ignore_case("ship", "ShipLoadout"));
+    }
+}
@@ -16,6 +16,7 @@
 //! | SKIN   | `.skin`   | Skinned Mesh |
 //! | MTL    | `.mtl`    | Material Definition |
 //! | DDS    | `.dds`    | DirectDraw Surface Texture |
+//! | BNK    | `.bnk`    | Wwise Soundbank (index only) |
 //! 
 //! # Example
 //! 
@@ -28,29 +29,44 @@
 //! println!("Found {} entires", archive.entry_county())
 //! ```
 
+#[macro_use]
+pub mod logging;
 pub mod traits;
 pub mod registry;
+pub mod detect;
+pub mod glob;
 pub mod p4k;
 pub mod dcb;
 pub mod cgf;
 pub mod dds;
+pub mod bnk;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testutil;
 
 // Re-export main types
 pub use traits::{
     Parser, StreamingParser, RandomAccessParser, HierarchicalParser,
     ParseError, ParseResult, ParseOptions, ParseProgress, ParsePhase,
-    ProgressCallback
+    ProgressCallback, ProgressThrottle, ProgressEstimator, ProgressEstimate,
+    EstimatedProgressCallback, ParseReport, ParseWarning,
 };
 
+pub use detect::{detect_format, FormatKind};
+
 pub use registry::{
     ParserRegistry, ParserRegistration, ParserRegistrationBuilder,
     ParserInfo, RegistryError, AnyParser, GLOBAL_REGISTRY,
 };
 
-pub use p4k::{P4kParser, P4kArchive, P4kEntry, P4kEntryInfo, P4kCompression, CompressionMethod};
-pub use dcb::{DcbParser, DataCore, DataCoreHeader, Record, RecordValue, RecordRef, StructDef, PropertyDef, DataType};
-pub use cgf::{CgfParser, CgfModel, Mesh, Vertex, Face, Skeleton, Bone};
-pub use dds::{DdsParser, DdsTexture, DdsCombiner, DdsHeader, TextureFormat};
+pub use p4k::{P4kParser, P4kArchive, P4kEntry, P4kEntryInfo, P4kRawEntry, P4kCompression, CompressionMethod, CrcWriter};
+pub use p4k::{ContentType, ExtractLayout, ExtractionManifest, MultiVolumeReader, ParallelExtractOptions, ResumeProgress};
+pub use dcb::{
+    DcbParser, DataCore, DataCoreHeader, Record, RecordValue, RecordRef, CigGuid, StructDef, PropertyDef, DataType,
+    FromRecord, FromRecordValue,
+};
+pub use cgf::{CgfParser, CgfModel, Mesh, MeshError, Vertex, Face, Skeleton, Bone, Animation};
+pub use dds::{DdsParser, DdsTexture, DdsCombiner, DdsHeader, TextureFormat, MipView};
+pub use bnk::{BnkParser, BnkIndex, BnkEntry};
 
 /// Initialize the global parser registry with all built-in parsers
 pub fn init_registry() {
@@ -80,6 +96,19 @@ pub fn init_registry() {
             .build()
             .unwrap()
     );
+
+    // Register BNK Parser
+    let _ = GLOBAL_REGISTRY.register(
+        ParserRegistrationBuilder::new()
+            .id("bnk")
+            .name("Wwise Soundbank Parser")
+            .description("Indexes WEM streams embedded in Wwise .bnk soundbanks")
+            .extensions(&["bnk"])
+            .priority(100)
+            .factory(bnk::BnkParser::new)
+            .build()
+            .unwrap()
+    );
 }
 
 /// Version information
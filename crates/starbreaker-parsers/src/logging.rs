@@ -6,7 +6,7 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Whether tracing has been initialized
-static TACING_INTIALIZED: AtomicBool = AtomicBool::new(false);
+static TRACING_INITIALIZED: AtomicBool = AtomicBool::new(false);
 
 /// Initialize the default tracing subscriber
 /// 
@@ -19,12 +19,12 @@ pub fn init_default() {
             use tracing_subscriber::{fmt, EnvFilter, prelude::*};
 
             let filter = EnvFilter::try_from_default_env()
-                .unwrapt_or_else(|_| EnvFilter::new("warn, starbreaker=info"));
+                .unwrap_or_else(|_| EnvFilter::new("warn, starbreaker=info"));
 
             tracing_subscriber::registry()
                 .with(fmt::layer())
                 .with(filter)
-                .inti();
+                .init();
         }
     }
 }
@@ -54,13 +54,13 @@ pub fn init_with_config(config: TracingConfig) {
 /// Configuration for tracing initialization
 #[derive(Debug, Clone)]
 pub struct TracingConfig {
-    /// Defualt log level filter (e.g., "info", "debug", "warn")
+    /// Default log level filter (e.g., "info", "debug", "warn")
     pub default_level: String,
     /// Show the target (module path) in log output
     pub show_target: bool,
     /// Show thread IDs in log output
     pub show_thread_ids: bool,
-    /// Show soure file in log output
+    /// Show source file in log output
     pub show_file: bool,
     /// Show line number in log output
     pub show_line_number: bool,
@@ -95,7 +95,7 @@ macro_rules! log_parse_complete {
     ($parser:expr, $duration:expr, $items:expr) => {
         tracing::info!(
             parser = %$parser,
-            duratioin_ms = %$duration.as_millis(),
+            duration_ms = %$duration.as_millis(),
             items = %$items,
             "Parse complete"
         );
@@ -113,6 +113,27 @@ macro_rules! log_parse_error {
     };
 }
 
+/// Enter an `info`-level span for a parser phase
+///
+/// The span stays entered until the end of the enclosing block. Expands to
+/// nothing when the `tracing` feature is disabled.
+macro_rules! phase_span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        let _phase_span = tracing::info_span!($name $(, $field = $value)*).entered();
+    };
+}
+
+/// Emit a `debug`-level event inside the current parser phase
+///
+/// Expands to nothing when the `tracing` feature is disabled.
+macro_rules! phase_event {
+    ($msg:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($field = $value,)* $msg);
+    };
+}
+
 /// Instrument a parsing operation with timing
 #[cfg(feature = "tracing")]
 pub fn instrument_parse<T, F>(name: &str, f: F) -> T
@@ -124,7 +145,7 @@ where
 
     let start = std::time::Instant::now();
     let result = f();
-    let durationi = start.elapsed();
+    let duration = start.elapsed();
 
     tracing::debug!(duration_ms = %duration.as_millis(), "Parse operation complete");
 
@@ -141,7 +162,7 @@ where
 
 /// Create a span for tracking progress through a large operation
 #[cfg(feature = "tracing")]
-pub fn progress_span(operation: &std, total: usize) -> tracing::Span {
+pub fn progress_span(operation: &str, total: usize) -> tracing::Span {
     tracing::info_span!("progress", operation = %operation, total = %total)
 }
 
@@ -155,7 +176,7 @@ pub fn progress_span(_operation: &str, _total: usize) -> () {
 pub fn log_progress(current: usize, total: usize) {
     if current % 1000 == 0 || current == total {
         let percent = (current as f64 / total as f64 * 100.0) as u32;
-        tracing::debug!(current = %current, total = %total, percent = %perceent, "Progress");
+        tracing::debug!(current = %current, total = %total, percent = %percent, "Progress");
     }
 }
 
@@ -179,4 +200,82 @@ mod tests {
         let result = instrument_parse("test", || 42);
         assert_eq!(result, 42);
     }
+
+    #[cfg(feature = "tracing")]
+    mod spans {
+        use std::io::Cursor;
+        use std::sync::{Arc, Mutex};
+
+        use tracing::span::{Attributes, Id};
+        use tracing::Subscriber;
+        use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+        use tracing_subscriber::registry::LookupSpan;
+
+        use crate::dcb::DcbParser;
+        use crate::p4k::P4kParser;
+        use crate::traits::Parser;
+
+        /// Layer that records the name of every span created
+        #[derive(Clone, Default)]
+        struct SpanCapture(Arc<Mutex<Vec<String>>>);
+
+        impl<S> Layer<S> for SpanCapture
+        where
+            S: Subscriber + for<'a> LookupSpan<'a>,
+        {
+            fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+                self.0.lock().unwrap().push(attrs.metadata().name().to_string());
+            }
+        }
+
+        fn capture<F: FnOnce()>(f: F) -> Vec<String> {
+            let capture = SpanCapture::default();
+            let subscriber = tracing_subscriber::registry().with(capture.clone());
+            tracing::subscriber::with_default(subscriber, f);
+            let names = capture.0.lock().unwrap().clone();
+            names
+        }
+
+        /// Local header magic followed by an empty end of central directory
+        fn empty_p4k() -> Vec<u8> {
+            let mut data = vec![0x50, 0x4B, 0x03, 0x04];
+            data.extend_from_slice(&0x06054B50u32.to_le_bytes());
+            data.extend_from_slice(&[0u8; 8]); // disk numbers and entry counts
+            data.extend_from_slice(&0u32.to_le_bytes()); // cd size
+            data.extend_from_slice(&4u32.to_le_bytes()); // cd offset
+            data.extend_from_slice(&0u16.to_le_bytes()); // comment length
+            data
+        }
+
+        /// DCB header with every section empty and the string table right after it
+        fn empty_dcb() -> Vec<u8> {
+            let mut data = b"DCB1".to_vec();
+            data.extend_from_slice(&1u32.to_le_bytes());
+            data.extend_from_slice(&[0u8; 12]); // struct, property, record counts
+            for _ in 0..4 {
+                data.extend_from_slice(&36u32.to_le_bytes());
+            }
+            data.extend_from_slice(&0u32.to_le_bytes()); // string count
+            data
+        }
+
+        #[test]
+        fn test_p4k_parse_spans() {
+            let spans = capture(|| {
+                P4kParser::new().parse(Cursor::new(empty_p4k())).unwrap();
+            });
+            assert_eq!(spans, ["p4k.parse", "p4k.eocd_scan", "p4k.central_directory"]);
+        }
+
+        #[test]
+        fn test_dcb_parse_spans() {
+            let spans = capture(|| {
+                DcbParser::new().parse(Cursor::new(empty_dcb())).unwrap();
+            });
+            assert_eq!(
+                spans,
+                ["dcb.parse", "dcb.string_table", "dcb.structs", "dcb.properties", "dcb.records"]
+            );
+        }
+    }
 }
\ No newline at end of file
@@ -7,11 +7,24 @@ use std::fs::File;
 use std::io::BufReader;
 
 use memmap2::Mmap;
+use serde::Serialize;
 
-use super::entry::P4kEntry;
+use super::entry::{normalize_entry_path, P4kEntry};
 
 use crate::ParseResult;
 
+pub(super) const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// Continue an FNV-1a hash over `bytes`
+pub(super) fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
 /// Parsed P4K archive structure
 #[derive(Debug)]
 pub struct P4kArchive {
@@ -19,6 +32,9 @@ pub struct P4kArchive {
     pub entries: Vec<P4kEntry>,
     /// Path to entry index mapping for fast lookup
     pub path_index: HashMap<String, usize>,
+    /// Directory path (no trailing slash, "" for the root) to the entry
+    /// indices of its direct children; built on demand by [`build_dir_index`](Self::build_dir_index)
+    pub dir_index: Option<HashMap<String, Vec<usize>>>,
 }
 
 impl P4kArchive {
@@ -27,6 +43,7 @@ impl P4kArchive {
         Self {
             entries: Vec::new(),
             path_index: HashMap::new(),
+            dir_index: None,
         }
     }
 
@@ -35,6 +52,14 @@ impl P4kArchive {
         self.entries.len()
     }
 
+    /// Iterate over every entry in central directory order
+    ///
+    /// Prefer this over reaching into `entries`, which may stop being a
+    /// plain `Vec` once storage becomes lazy or indexed.
+    pub fn entries_iter(&self) -> impl Iterator<Item = &P4kEntry> + '_ {
+        self.entries.iter()
+    }
+
     /// Get number of files (non-directories)
     pub fn file_count(&self) -> usize {
         self.entries.iter().filter(|e| !e.is_directory).count()
@@ -57,20 +82,51 @@ impl P4kArchive {
 
     /// Get an entry by path
     pub fn get (&self, path: &str) -> Option<&P4kEntry> {
-        self.path_index.get(path).map(|idx| &self.entries[*idx])
+        self.index_of(path).map(|idx| &self.entries[idx])
     }
 
     /// Check if path exists in archive
     pub fn contains(&self, path: &str) -> bool {
-        self.path_index.contains_key(path)
+        self.index_of(path).is_some()
+    }
+
+    /// Look up an entry index, accepting `\\` separators in `path`
+    fn index_of(&self, path: &str) -> Option<usize> {
+        if path.contains('\\') {
+            self.path_index.get(&normalize_entry_path(path)).copied()
+        } else {
+            self.path_index.get(path).copied()
+        }
     }
 
     /// Find entries matching a pattern (glob-like)
     pub fn find(&self, pattern: &str) -> Vec<&P4kEntry> {
+        self.find_iter(pattern).collect()
+    }
+
+    /// Find one page of entries matching a pattern, plus the total match count
+    ///
+    /// Returns up to `limit` matches starting at match number `offset`, in
+    /// the same order as [`find`](Self::find). Matches outside the window
+    /// are only counted, so broad patterns don't build a full result list.
+    pub fn find_paged(&self, pattern: &str, offset: usize, limit: usize) -> (Vec<&P4kEntry>, usize) {
+        let mut page = Vec::with_capacity(limit.min(1024));
+        let mut total = 0;
+        for entry in self.find_iter(pattern) {
+            if total >= offset && page.len() < limit {
+                page.push(entry);
+            }
+            total += 1;
+        }
+        (page, total)
+    }
+
+    /// Lazily match entries against a glob-like pattern
+    fn find_iter<'a>(&'a self, pattern: &str) -> impl Iterator<Item = &'a P4kEntry> + 'a {
         let pattern = pattern.to_lowercase();
-        let parts: Vec<&str> = pattern.split('*').collect();
+        let parts: Vec<String> = pattern.split('*').map(str::to_string).collect();
 
-        self.entries.iter().filter(|entry| {
+        self.entries.iter().filter(move |entry| {
             let path = entry.path.to_lowercase();
 
             if parts.len() == 1 {
@@ -86,18 +142,18 @@ impl P4kArchive {
 
                     if i == 0 {
                         // Must start with first part
-                        if !path.starts_with(*part) {
+                        if !path.starts_with(part.as_str()) {
                             return false;
                         }
                         pos = part.len();
                     } else if i == parts.len() - 1 {
                         // Must end with last part
-                        if !path.ends_with(*part) {
+                        if !path.ends_with(part.as_str()) {
                             return false;
                         }
                     } else {
                         // Must contain middle part
-                        if let Some(idx) = path[pos..].find(*part) {
+                        if let Some(idx) = path[pos..].find(part.as_str()) {
                             pos += idx + part.len();
                         } else {
                             return false;
@@ -106,10 +162,14 @@ impl P4kArchive {
                 }
                 true
             }
-        }).collect()
+        })
     }
 
     /// Find entries by extension
+    ///
+    /// This matches the final extension only, so split texture parts such as
+    /// `ship.dds.1` are not returned for `"dds"`; use
+    /// [`find_by_base_extension`](Self::find_by_base_extension) for those.
     pub fn find_by_extension(&self, ext: &str) -> Vec<&P4kEntry> {
         let ext = ext.trim_start_matches('.').to_lowercase();
         self.entries.iter()
@@ -121,17 +181,43 @@ impl P4kArchive {
             .collect()
     }
 
+    /// Find entries by extension, including numbered split parts
+    ///
+    /// `find_by_base_extension("dds")` returns `ship.dds` as well as
+    /// `ship.dds.1`, `ship.dds.2`, ...
+    pub fn find_by_base_extension(&self, ext: &str) -> Vec<&P4kEntry> {
+        let ext = ext.trim_start_matches('.').to_lowercase();
+        self.entries.iter()
+            .filter(|e| {
+                e.base_extension()
+                    .map(|e| e.to_lowercase() == ext)
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
     /// List entries in a directory
+    ///
+    /// Uses the directory index when [`build_dir_index`](Self::build_dir_index)
+    /// has been called, otherwise scans every entry.
     pub fn list_directory(&self, path: &str) -> Vec<&P4kEntry> {
         let path = path.trim_end_matches('/');
+
+        if let Some(dir_index) = &self.dir_index {
+            return dir_index.get(path)
+                .map(|children| children.iter().map(|&idx| &self.entries[idx]).collect())
+                .unwrap_or_default();
+        }
+
         let prefix = if path.is_empty() { String::new() } else { format!("{}/", path) };
 
         self.entries.iter()
             .filter(|entry| {
                 if entry.path.starts_with(&prefix) {
                     let remainder = &entry.path[prefix.len()..];
-                    // Only direct children (no additional slashes, or just trailing slash)
-                    !remainder.trim_end_matches('/').contains('/')
+                    // Only direct children (no additional slashes, or just
+                    // trailing slash), not the directory's own entry
+                    !remainder.is_empty() && !remainder.trim_end_matches('/').contains('/')
                 } else {
                     false
                 }
@@ -139,6 +225,104 @@ impl P4kArchive {
             .collect()
     }
 
+    /// Keep only the entries matching the predicate and rebuild the path index
+    pub fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&P4kEntry) -> bool,
+    {
+        self.entries.retain(predicate);
+        self.rebuild_index();
+    }
+
+    /// Rebuild the path index after the entry list has been modified
+    ///
+    /// The directory index is rebuilt too if it had been built.
+    pub fn rebuild_index(&mut self) {
+        self.path_index = self.entries.iter()
+            .enumerate()
+            .map(|(idx, entry)| (entry.path.clone(), idx))
+            .collect();
+
+        if self.dir_index.is_some() {
+            self.build_dir_index();
+        }
+    }
+
+    /// Build the directory-to-children index used by [`list_directory`](Self::list_directory)
+    ///
+    /// Makes directory listings O(children) instead of O(entries).
+    pub fn build_dir_index(&mut self) {
+        let mut dir_index: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let trimmed = entry.path.trim_end_matches('/');
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let parent = entry.parent().unwrap_or("");
+            dir_index.entry(parent.to_string()).or_default().push(idx);
+        }
+
+        self.dir_index = Some(dir_index);
+    }
+
+    /// Fingerprint the archive contents for change detection
+    ///
+    /// Hashes the sorted `(path, crc32, size)` of every entry, so two archives
+    /// with the same contents match regardless of entry order or layout. Uses
+    /// FNV-1a so values are stable across builds and can be stored.
+    pub fn fingerprint(&self) -> u64 {
+        let mut entries: Vec<&P4kEntry> = self.entries.iter().collect();
+        entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        let mut hash = FNV_OFFSET_BASIS;
+        for entry in entries {
+            hash = fnv1a(hash, entry.path.as_bytes());
+            hash = fnv1a(hash, &[0]); // path terminator
+            hash = fnv1a(hash, &entry.crc32.to_le_bytes());
+            hash = fnv1a(hash, &entry.uncompressed_size.to_le_bytes());
+        }
+        hash
+    }
+
+    /// Compare this archive (old) against `other` (new)
+    ///
+    /// Entries are matched by path; a matched entry is modified when its CRC32
+    /// or uncompressed size differ. All lists are sorted by path.
+    pub fn diff(&self, other: &P4kArchive) -> ArchiveDiff {
+        let mut diff = ArchiveDiff::default();
+
+        for old_entry in &self.entries {
+            match other.get(&old_entry.path) {
+                None => diff.removed.push(old_entry.path.clone()),
+                Some(new_entry) => {
+                    if old_entry.crc32 != new_entry.crc32
+                        || old_entry.uncompressed_size != new_entry.uncompressed_size
+                    {
+                        diff.modified.push(ModifiedEntry {
+                            path: old_entry.path.clone(),
+                            old_size: old_entry.uncompressed_size,
+                            new_size: new_entry.uncompressed_size,
+                            old_crc32: old_entry.crc32,
+                            new_crc32: new_entry.crc32,
+                        });
+                    }
+                }
+            }
+        }
+
+        diff.added = other.entries.iter()
+            .filter(|e| !self.contains(&e.path))
+            .map(|e| e.path.clone())
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.modified.sort_by(|a, b| a.path.cmp(&b.path));
+        diff
+    }
+
     /// Get all top-level directories
     pub fn root_directories(&self) -> Vec<String> {
         let mut roots: std::collections::HashSet<String> = std::collections::HashSet::new();
@@ -159,7 +343,7 @@ impl P4kArchive {
         let mut root = DirectoryNode::new("".to_string());
 
         for entry in &self.entries {
-            root.insert(&entry.path, entry.is_directory);
+            root.insert_entry(entry);
         }
 
         root
@@ -209,6 +393,10 @@ pub struct DirectoryNode {
     pub is_file: bool,
     /// Child nodes
     pub children: HashMap<String, DirectoryNode>,
+    /// Uncompressed size for files inserted from an entry (0 otherwise)
+    pub size: u64,
+    /// CRC32 for files inserted from an entry (0 otherwise)
+    pub crc32: u32,
 }
 
 impl DirectoryNode {
@@ -218,16 +406,24 @@ impl DirectoryNode {
             name,
             is_file: false,
             children: HashMap::new(),
+            size: 0,
+            crc32: 0,
         }
     }
 
     /// Insert a path into the tree
     pub fn insert(&mut self, path: &str, is_directory: bool) {
         let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
-        self.insert_parts(&parts, !is_directory);
+        self.insert_parts(&parts, !is_directory, 0, 0);
+    }
+
+    /// Insert an archive entry, keeping its size and CRC for diffing
+    pub fn insert_entry(&mut self, entry: &P4kEntry) {
+        let parts: Vec<&str> = entry.path.split('/').filter(|s| !s.is_empty()).collect();
+        self.insert_parts(&parts, !entry.is_directory, entry.uncompressed_size, entry.crc32);
     }
 
-    fn insert_parts(&mut self, parts: &[&str], is_file: bool) {
+    fn insert_parts(&mut self, parts: &[&str], is_file: bool, size: u64, crc32: u32) {
         if parts.is_empty() {
             return;
         }
@@ -241,11 +437,29 @@ impl DirectoryNode {
 
         if is_leaf {
             child.is_file = is_file;
+            child.size = size;
+            child.crc32 = crc32;
         } else {
-            child.insert_parts(&parts[1..], is_file);
+            child.insert_parts(&parts[1..], is_file, size, crc32);
         }
     }
 
+    /// Compare this tree (old) against `other` (new)
+    ///
+    /// Every node in either tree gets a marker: files are modified when their
+    /// size or CRC differ, directories when anything below them changed.
+    /// Runs in time linear in the union of both trees.
+    pub fn diff(&self, other: &DirectoryNode) -> TreeDiff {
+        TreeDiff::between(Some(self), Some(other), &other.name)
+    }
+
+    /// Find the node at a `/`-separated path below this one
+    pub fn find(&self, path: &str) -> Option<&DirectoryNode> {
+        path.split('/')
+            .filter(|s| !s.is_empty())
+            .try_fold(self, |node, part| node.children.get(part))
+    }
+
     /// Get sorted child names
     pub fn sorted_children(&self) -> Vec<&str> {
         let mut names: Vec<&str> = self.children.keys().map(|s| s.as_str()).collect();
@@ -264,6 +478,136 @@ impl DirectoryNode {
     }
 }
 
+/// Change marker for one node of a [`TreeDiff`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffMarker {
+    /// Only present in the new tree
+    Added,
+    /// Only present in the old tree
+    Removed,
+    /// Present in both with different contents
+    Modified,
+    /// Present in both and identical
+    Unchanged,
+}
+
+/// Per-node result of [`DirectoryNode::diff`], shaped like the union of both trees
+#[derive(Debug, Clone)]
+pub struct TreeDiff {
+    /// Node name
+    pub name: String,
+    /// Whether this is a file (taken from the new tree when present)
+    pub is_file: bool,
+    /// Change marker
+    pub marker: DiffMarker,
+    /// Child diffs
+    pub children: HashMap<String, TreeDiff>,
+}
+
+impl TreeDiff {
+    fn between(old: Option<&DirectoryNode>, new: Option<&DirectoryNode>, name: &str) -> Self {
+        let mut children = HashMap::new();
+        for (child_name, child) in new.iter().flat_map(|n| &n.children) {
+            let old_child = old.and_then(|o| o.children.get(child_name));
+            children.insert(child_name.clone(), Self::between(old_child, Some(child), child_name));
+        }
+        for (child_name, child) in old.iter().flat_map(|o| &o.children) {
+            if !children.contains_key(child_name) {
+                children.insert(child_name.clone(), Self::between(Some(child), None, child_name));
+            }
+        }
+
+        let marker = match (old, new) {
+            (None, _) => DiffMarker::Added,
+            (Some(_), None) => DiffMarker::Removed,
+            (Some(a), Some(b)) => {
+                let file_changed = a.is_file != b.is_file
+                    || (b.is_file && (a.size != b.size || a.crc32 != b.crc32));
+                if file_changed || children.values().any(|c| c.marker != DiffMarker::Unchanged) {
+                    DiffMarker::Modified
+                } else {
+                    DiffMarker::Unchanged
+                }
+            }
+        };
+
+        Self {
+            name: name.to_string(),
+            is_file: new.or(old).map(|n| n.is_file).unwrap_or(false),
+            marker,
+            children,
+        }
+    }
+
+    /// Look up the diff node at a `/`-separated path below this one
+    pub fn find(&self, path: &str) -> Option<&TreeDiff> {
+        path.split('/')
+            .filter(|s| !s.is_empty())
+            .try_fold(self, |node, part| node.children.get(part))
+    }
+
+    /// Sorted paths of changed files with their markers
+    pub fn changed_files(&self) -> Vec<(String, DiffMarker)> {
+        let mut changes = Vec::new();
+        self.collect_changes("", &mut changes);
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        changes
+    }
+
+    fn collect_changes(&self, prefix: &str, changes: &mut Vec<(String, DiffMarker)>) {
+        for (name, child) in &self.children {
+            let path = if prefix.is_empty() { name.clone() } else { format!("{}/{}", prefix, name) };
+            if child.is_file {
+                if child.marker != DiffMarker::Unchanged {
+                    changes.push((path, child.marker));
+                }
+            } else if child.marker != DiffMarker::Unchanged {
+                child.collect_changes(&path, changes);
+            }
+        }
+    }
+}
+
+/// Entry-level result of [`P4kArchive::diff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ArchiveDiff {
+    /// Paths only present in the new archive
+    pub added: Vec<String>,
+    /// Paths only present in the old archive
+    pub removed: Vec<String>,
+    /// Entries present in both with a different CRC32 or size
+    pub modified: Vec<ModifiedEntry>,
+}
+
+impl ArchiveDiff {
+    /// Check if the archives have identical contents
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// An entry whose contents changed between two archives
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModifiedEntry {
+    /// Entry path
+    pub path: String,
+    /// Uncompressed size in the old archive
+    pub old_size: u64,
+    /// Uncompressed size in the new archive
+    pub new_size: u64,
+    /// CRC32 in the old archive
+    pub old_crc32: u32,
+    /// CRC32 in the new archive
+    pub new_crc32: u32,
+}
+
+impl ModifiedEntry {
+    /// Change in uncompressed size (negative when the entry shrank)
+    pub fn size_delta(&self) -> i64 {
+        self.new_size as i64 - self.old_size as i64
+    }
+}
+
 /// Archive statistics
 #[derive(Debug, Default)]
 pub struct ArchiveStatistics {
@@ -329,6 +673,7 @@ mod tests {
         let entries: Vec<_> = entries.iter().map(|(path, is_dir)| {
             crate::p4k::P4kEntry {
                 path: path.to_string(),
+                original_path: None,
                 compression: CompressionMethod::Store,
                 crc32: 0,
                 compressed_size: 100,
@@ -347,7 +692,31 @@ mod tests {
             path_index.insert(entry.path.clone(), idx);
         }
 
-        P4kArchive { entries, path_index }
+        P4kArchive { entries, path_index, dir_index: None }
+    }
+
+    #[test]
+    fn test_entries_iter() {
+        let archive = make_test_archive();
+        assert_eq!(archive.entries_iter().count(), archive.entry_count());
+        assert_eq!(archive.entries_iter().next().unwrap().path, "Data/");
+    }
+
+    #[test]
+    fn test_find_paged() {
+        let archive = make_test_archive();
+        let all: Vec<&str> = archive.find("config").iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(all.len(), 3);
+
+        let (page, total) = archive.find_paged("config", 0, 2);
+        assert_eq!(total, 3);
+        assert_eq!(page.iter().map(|e| e.path.as_str()).collect::<Vec<_>>(), all[..2]);
+
+        let (page, total) = archive.find_paged("config", 2, 2);
+        assert_eq!((page.len(), total), (1, 3));
+        assert_eq!(page[0].path, all[2]);
+        let (page, total) = archive.find_paged("*.xml", 5, 10);
+        assert_eq!((page.len(), total), (0, 2));
     }
 
     #[test]
@@ -362,6 +731,87 @@ mod tests {
         assert_eq!(xml_files.len(), 2);
     }
 
+    #[test]
+    fn test_find_by_base_extension() {
+        let mut archive = make_test_archive();
+        let mut split = archive.get("Data/Textures/ship.dds").unwrap().clone();
+        split.path = "Data/Textures/ship.dds.1".to_string();
+        archive.entries.push(split);
+        archive.rebuild_index();
+
+        let paths: Vec<_> = archive.find_by_base_extension("dds").iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["Data/Textures/ship.dds", "Data/Textures/ship.dds.1"]);
+
+        // The strict lookup is unchanged
+        assert_eq!(archive.find_by_extension("dds").len(), 1);
+    }
+
+    #[test]
+    fn test_tree_diff() {
+        let old = make_test_archive();
+        let mut new = make_test_archive();
+        new.entries.iter_mut()
+            .find(|e| e.path == "Data/Objects/ship.cgf")
+            .unwrap()
+            .uncompressed_size = 200;
+        let mut added = new.entries[3].clone();
+        added.path = "Data/Libs/Config/newprofile.xml".to_string();
+        new.entries.push(added);
+        new.rebuild_index();
+
+        let diff = old.build_tree().diff(&new.build_tree());
+
+        assert_eq!(diff.changed_files(), vec![
+            ("Data/Libs/Config/newprofile.xml".to_string(), DiffMarker::Added),
+            ("Data/Objects/ship.cgf".to_string(), DiffMarker::Modified),
+        ]);
+        assert_eq!(diff.find("Data/Libs").unwrap().marker, DiffMarker::Modified);
+        assert_eq!(diff.find("Data/Textures").unwrap().marker, DiffMarker::Unchanged);
+        assert_eq!(diff.find("Data/Libs/Config/defaultprofile.xml").unwrap().marker, DiffMarker::Unchanged);
+
+        // Swapping sides turns additions into removals
+        let reverse = new.build_tree().diff(&old.build_tree());
+        assert_eq!(reverse.find("Data/Libs/Config/newprofile.xml").unwrap().marker, DiffMarker::Removed);
+    }
+
+    #[test]
+    fn test_archive_diff() {
+        let old = make_test_archive();
+        let mut new = make_test_archive();
+
+        let ship = new.entries.iter_mut().find(|e| e.path == "Data/Objects/ship.cgf").unwrap();
+        ship.crc32 = 0x1234;
+        let profile = new.entries.iter_mut().find(|e| e.path == "Data/Libx/Config/profiles.xml").unwrap();
+        profile.uncompressed_size = 40;
+        let mut added = new.entries[3].clone();
+        added.path = "Data/Libs/Config/newprofile.xml".to_string();
+        new.entries.push(added);
+        new.retain(|e| e.path != "Data/Textures/ship.dds");
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec!["Data/Libs/Config/newprofile.xml"]);
+        assert_eq!(diff.removed, vec!["Data/Textures/ship.dds"]);
+        assert_eq!(diff.modified, vec![
+            ModifiedEntry {
+                path: "Data/Libx/Config/profiles.xml".to_string(),
+                old_size: 100,
+                new_size: 40,
+                old_crc32: 0,
+                new_crc32: 0,
+            },
+            ModifiedEntry {
+                path: "Data/Objects/ship.cgf".to_string(),
+                old_size: 100,
+                new_size: 100,
+                old_crc32: 0,
+                new_crc32: 0x1234,
+            },
+        ]);
+        assert_eq!(diff.modified[0].size_delta(), -60);
+
+        assert!(old.diff(&make_test_archive()).is_empty());
+    }
+
     #[test]
     fn test_list_directory() {
         let archive = make_test_archive();
@@ -374,6 +824,31 @@ mod tests {
         assert_eq!(config.len(), 2);
     }
 
+    #[test]
+    fn test_dir_index_matches_scan() {
+        let scanned = make_test_archive();
+        let mut indexed = make_test_archive();
+        indexed.build_dir_index();
+
+        let paths = |entries: Vec<&P4kEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.path.clone()).collect()
+        };
+
+        for dir in ["", "Data", "Data/", "Data/Libs", "Data/Libs/Config", "Data/Libx/Config", "Data/Textures", "Missing"] {
+            assert_eq!(
+                paths(indexed.list_directory(dir)),
+                paths(scanned.list_directory(dir)),
+                "listing of {:?}",
+                dir
+            );
+        }
+
+        // The index follows later edits to the entry list
+        indexed.retain(|e| !e.path.ends_with(".dds"));
+        assert!(indexed.dir_index.is_some());
+        assert!(paths(indexed.list_directory("Data/Textures")).iter().all(|p| !p.ends_with(".dds")));
+    }
+
     #[test]
     fn test_find_pattern() {
         let archive = make_test_archive();
@@ -385,6 +860,31 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[test]
+    fn test_retain_rebuilds_index() {
+        let mut archive = make_test_archive();
+        archive.retain(|e| !e.path.ends_with(".xml"));
+
+        assert_eq!(archive.entry_count(), 7);
+        assert!(!archive.contains("Data/Libs/Config/defaultprofile.xml"));
+        assert_eq!(archive.get("Data/Objects/ship.cgf").map(|e| e.path.as_str()), Some("Data/Objects/ship.cgf"));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let a = make_test_archive();
+        let mut b = make_test_archive();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+
+        // Entry order doesn't matter
+        b.entries.reverse();
+        b.rebuild_index();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+
+        b.entries[0].crc32 = 0xDEADBEEF;
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
+
     #[test]
     fn test_build_tree() {
         let archive = make_test_archive();
@@ -7,19 +7,94 @@
 //! - ZStd (Zstandard)
 //! - LZ4 (custom implementation)
 
+use std::io::{self, Read, Write};
+
 use crate::traits::{ParseError, ParseResult};
 use super::CompressionMethod;
 
+/// Writer adapter that computes a CRC32 of everything written through it
+///
+/// Lets streaming extraction verify an entry without a second pass over the
+/// output.
+pub struct CrcWriter<W> {
+    inner: W,
+    hasher: crc32fast::Hasher,
+    bytes_written: u64,
+}
+
+impl<W: Write> CrcWriter<W> {
+    /// Wrap a writer
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: crc32fast::Hasher::new(),
+            bytes_written: 0,
+        }
+    }
+
+    /// CRC32 of the bytes written so far
+    pub fn crc32(&self) -> u32 {
+        self.hasher.clone().finalize()
+    }
+
+    /// Number of bytes written so far
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Unwrap the writer, returning it with the final CRC32
+    pub fn into_inner(self) -> (W, u32) {
+        (self.inner, self.hasher.finalize())
+    }
+}
+
+impl<W: Write> Write for CrcWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.bytes_written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Largest expansion an LZ4 block can encode, used to cap the growable decode
+const LZ4_MAX_RATIO: usize = 255;
+
+/// Turn a declared uncompressed size into a usable size hint
+///
+/// Streamed entries and archives written before their ZIP64 fixup declare
+/// `0` or `0xFFFFFFFF`; those sizes are ignored and the output is grown as
+/// it decodes instead. Empty entries come out empty either way.
+fn size_hint(expected_size: usize) -> Option<usize> {
+    match expected_size {
+        0 => None,
+        size if size as u64 == u32::MAX as u64 || size == usize::MAX => None,
+        size => Some(size),
+    }
+}
+
 /// Handles compression and decompression for P4K archives
 pub struct P4kCompression;
 
 impl P4kCompression {
     /// Decompress data using the specified compression method
+    ///
+    /// `expected_size` pre-sizes the output and is checked against it; an
+    /// absent size (`0` or `0xFFFFFFFF`) falls back to a growable decode.
     pub fn decompress(
         data: &[u8],
         method: CompressionMethod,
         expected_size: usize,
     ) -> ParseResult<Vec<u8>> {
+        // Zero-length entries store no stream at all, not even a frame header
+        if data.is_empty() && !matches!(method, CompressionMethod::Unknown(_)) {
+            return Ok(Vec::new());
+        }
+
         match method {
             CompressionMethod::Store => {
                 // No compression, return as-is
@@ -46,19 +121,78 @@ impl P4kCompression {
         }
     }
 
+    /// Decompress a stream into `writer` without buffering the whole output
+    ///
+    /// Store, Deflate and Zstd are decoded incrementally. LZ4 entries use the
+    /// block format, which can't be streamed, so they are decoded in memory
+    /// first. Returns the number of bytes written.
+    pub fn decompress_to<R: Read, W: Write>(
+        mut reader: R,
+        method: CompressionMethod,
+        expected_size: usize,
+        writer: &mut W,
+    ) -> ParseResult<u64> {
+        let written = match method {
+            CompressionMethod::Store => io::copy(&mut reader, writer)?,
+
+            CompressionMethod::Deflate => {
+                let mut decoder = flate2::read::DeflateDecoder::new(reader);
+                io::copy(&mut decoder, writer)
+                    .map_err(|e| ParseError::DecompressionFailed(
+                        format!("DEFLATE decompression failed: {}", e)
+                    ))?
+            }
+
+            CompressionMethod::Zstd => {
+                let mut decoder = zstd::stream::read::Decoder::new(reader)
+                    .map_err(|e| ParseError::DecompressionFailed(
+                        format!("ZSTD decompression failed: {}", e)
+                    ))?;
+                io::copy(&mut decoder, writer)
+                    .map_err(|e| ParseError::DecompressionFailed(
+                        format!("ZSTD decompression failed: {}", e)
+                    ))?
+            }
+
+            CompressionMethod::Lz4 => {
+                let mut data = Vec::new();
+                reader.read_to_end(&mut data)?;
+                let output = Self::decompress_lz4(&data, expected_size)?;
+                writer.write_all(&output)?;
+                output.len() as u64
+            }
+
+            CompressionMethod::Unknown(method) => {
+                return Err(ParseError::UnsupportedFeature(
+                    format!("Unknown compression method: {}", method)
+                ));
+            }
+        };
+
+        if size_hint(expected_size).is_some() && written != expected_size as u64 {
+            return Err(ParseError::DecompressionFailed(
+                format!(
+                    "Streamed size mismatch: expected {}, got {}",
+                    expected_size, written
+                )
+            ));
+        }
+
+        Ok(written)
+    }
+
     /// Decompress using DEFLATE algorithm
     fn decompress_deflate(data: &[u8], expected_size: usize) -> ParseResult<Vec<u8>> {
-        use std::io::Read;
-
+        let hint = size_hint(expected_size);
         let mut decoder = flate2::read::DeflateDecoder::new(data);
-        let mut output = Vec::with_capacity(expected_size);
+        let mut output = Vec::with_capacity(hint.unwrap_or(data.len()));
 
         decoder.read_to_end(&mut output)
             .map_err(|e| ParseError::DecompressionFailed(
                 format!("DEFLATE decompression failed: {}", e)
             ))?;
 
-        if output.len() != expected_size {
+        if hint.is_some() && output.len() != expected_size {
             return Err(ParseError::DecompressionFailed(
                 format!(
                     "DEFLATE size mismatch: expected {}, got {}",
@@ -77,7 +211,7 @@ impl P4kCompression {
                 format!("ZSTD decompression failed: {}", e)
             ))?;
 
-        if output.len() != expected_size {
+        if size_hint(expected_size).is_some() && output.len() != expected_size {
             return Err(ParseError::DecompressionFailed(
                 format!(
                     "ZSTD size mismatch: expected {}, got {}",
@@ -109,10 +243,8 @@ impl P4kCompression {
 
     /// Decompress LZ4 frame format
     fn decompress_lz4_frame(data: &[u8], expected_size: usize) -> ParseResult<Vec<u8>> {
-        use std::io::Read;
-
         let mut decoder = lz4_flex::frame::FrameDecoder::new(data);
-        let mut output = Vec::with_capacity(expected_size);
+        let mut output = Vec::with_capacity(size_hint(expected_size).unwrap_or(data.len()));
 
         decoder.read_to_end(&mut output)
             .map_err(|e| ParseError::DecompressionFailed(
@@ -123,11 +255,34 @@ impl P4kCompression {
     }
 
     /// Decompress LZ4 block format (raw)
+    ///
+    /// Blocks don't record their decoded size, so without a hint the output
+    /// buffer is doubled until the block fits (up to LZ4's maximum ratio).
     fn decompress_lz4_block(data: &[u8], expected_size: usize) -> ParseResult<Vec<u8>> {
-        lz4_flex::decompress(data, expected_size)
-            .map_err(|e| ParseError::DecompressionFailed(
-                format!("LZ4 block decompression failed: {}", e)
-            ))
+        if let Some(size) = size_hint(expected_size) {
+            return lz4_flex::decompress(data, size)
+                .map_err(|e| ParseError::DecompressionFailed(
+                    format!("LZ4 block decompression failed: {}", e)
+                ));
+        }
+
+        let max_size = data.len().saturating_mul(LZ4_MAX_RATIO).max(64);
+        let mut capacity = data.len().saturating_mul(4).clamp(64, max_size);
+        loop {
+            let mut output = vec![0u8; capacity];
+            match lz4_flex::block::decompress_into(data, &mut output) {
+                Ok(len) => {
+                    output.truncate(len);
+                    return Ok(output);
+                }
+                Err(e) if capacity >= max_size => {
+                    return Err(ParseError::DecompressionFailed(
+                        format!("LZ4 block decompression failed: {}", e)
+                    ));
+                }
+                Err(_) => capacity = capacity.saturating_mul(2).min(max_size),
+            }
+        }
     }
 
     /// Compress data using the specified method
@@ -258,6 +413,29 @@ mod tests {
         assert_eq!(data.as_slice(), decompressed.as_slice());
     }
 
+    #[test]
+    fn test_decompress_without_size_hint() {
+        let original = b"streamed entry with no declared size ".repeat(64);
+
+        for method in [CompressionMethod::Deflate, CompressionMethod::Zstd, CompressionMethod::Lz4] {
+            let compressed = P4kCompression::compress(&original, method).unwrap();
+
+            for declared in [0, u32::MAX as usize] {
+                let decompressed = P4kCompression::decompress(&compressed, method, declared).unwrap();
+                assert_eq!(decompressed, original, "{:?} with declared size {}", method, declared);
+
+                let mut streamed = Vec::new();
+                P4kCompression::decompress_to(&compressed[..], method, declared, &mut streamed).unwrap();
+                assert_eq!(streamed, original);
+            }
+
+            // A valid hint is still checked against the output
+            if method != CompressionMethod::Lz4 {
+                assert!(P4kCompression::decompress(&compressed, method, original.len() + 1).is_err());
+            }
+        }
+    }
+
     #[test]
     fn test_crc32() {
         let data = b"Hello, World!";
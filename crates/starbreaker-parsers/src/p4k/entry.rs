@@ -2,13 +2,39 @@
 //! P4K archive entry structures
 
 use super::CompressionMethod;
+use crate::detect::{detect_format, FormatKind};
 use serde::{Deserialize, Serialize};
 
+/// Extensions of entries stored as plain text
+const TEXT_EXTENSIONS: &[&str] = &[
+    "xml", "cfg", "json", "txt", "ini", "lua", "mtl", "chrparams", "cdf", "adb",
+    "entxml", "animevents", "csv", "log",
+];
+
+/// Extensions of CryEngine geometry, including the split `*m` mesh streams
+const MODEL_EXTENSIONS: &[&str] = &[
+    "cgf", "cga", "chr", "skin", "cgfm", "cgam", "chrm", "skinm",
+];
+
+/// Rough kind of an entry's contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentType {
+    /// Human-readable text (XML, config, JSON, scripts)
+    Text,
+    /// CryEngine geometry
+    Model,
+    /// Anything else, including binary XML and textures
+    Binary,
+}
+
 /// Represents a single file entry in a P4K archive
 #[derive(Debug, Clone)]
 pub struct P4kEntry {
-    /// Full path within the archive
+    /// Full path within the archive, always `/`-separated
     pub path: String,
+    /// Name exactly as stored in the archive, when it differs from `path`
+    /// (e.g. it used Windows `\\` separators)
+    pub original_path: Option<String>,
     /// Compression method used
     pub compression: CompressionMethod,
     /// CRC32 checksum of uncompressed data
@@ -32,12 +58,17 @@ pub struct P4kEntry {
 }
 
 impl P4kEntry {
+    /// Get the name exactly as stored in the archive
+    pub fn raw_path(&self) -> &str {
+        self.original_path.as_deref().unwrap_or(&self.path)
+    }
+
     /// Get the filename without path
     pub fn filename(&self) -> &str {
-        self.path
-            .rsplit('/')
+        let path = self.path.trim_end_matches('/');
+        path.rsplit('/')
             .next()
-            .unwrap_or(&self.path)
+            .unwrap_or(path)
     }
 
     /// Get the parent directory path
@@ -56,6 +87,24 @@ impl P4kEntry {
         filename.rfind('.').map(|idx| &filename[idx + 1..])
     }
 
+    /// Get the extension ignoring a trailing numeric split suffix
+    ///
+    /// Split textures are stored as `ship.dds`, `ship.dds.1`, `ship.dds.2`...;
+    /// for those `extension()` returns `"1"` while this returns `"dds"`.
+    pub fn base_extension(&self) -> Option<&str> {
+        let ext = self.extension()?;
+        if !ext.bytes().all(|b| b.is_ascii_digit()) {
+            return Some(ext);
+        }
+
+        let filename = self.filename();
+        let stem = &filename[..filename.len() - ext.len() - 1];
+        match stem.rfind('.') {
+            Some(idx) => Some(&stem[idx + 1..]),
+            None => Some(ext),
+        }
+    }
+
     /// Calculate compression ratio
     pub fn compression_ratio(&self) -> f64 {
         if self.uncompressed_size == 0 {
@@ -75,6 +124,82 @@ impl P4kEntry {
 
         (year, month, day, hour, minute, second)
     }
+
+    /// Guess the kind of contents from the file extension
+    ///
+    /// Split parts (`.dds.1`) are classified by their base extension.
+    /// Unknown extensions count as binary.
+    pub fn guessed_content_type(&self) -> ContentType {
+        let ext = self.base_extension().map(str::to_ascii_lowercase);
+        match ext.as_deref() {
+            Some(ext) if TEXT_EXTENSIONS.contains(&ext) => ContentType::Text,
+            Some(ext) if MODEL_EXTENSIONS.contains(&ext) => ContentType::Model,
+            _ => ContentType::Binary,
+        }
+    }
+
+    /// Refine the extension guess with the first bytes of the contents
+    ///
+    /// A recognized magic wins over the extension, which matters for the
+    /// many `.xml` entries stored as binary CryXml. Otherwise, an unknown
+    /// extension whose `head` is valid UTF-8 without control characters is
+    /// taken as text.
+    pub fn guessed_content_type_from(&self, head: &[u8]) -> ContentType {
+        match detect_format(std::io::Cursor::new(head)) {
+            Some(FormatKind::Cgf) => return ContentType::Model,
+            Some(_) => return ContentType::Binary,
+            None => {}
+        }
+
+        let guess = self.guessed_content_type();
+        if guess == ContentType::Binary && self.base_extension().is_none() && looks_like_text(head) {
+            ContentType::Text
+        } else {
+            guess
+        }
+    }
+
+    /// Check if the entry is probably text, judging by its extension
+    pub fn is_likely_text(&self) -> bool {
+        self.guessed_content_type() == ContentType::Text
+    }
+}
+
+/// Whether `head` is UTF-8 without control characters other than whitespace
+///
+/// A multi-byte character cut off at the end of `head` is tolerated.
+fn looks_like_text(head: &[u8]) -> bool {
+    let valid = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => {
+            // Truncated mid-character; the valid prefix is safe to unwrap
+            std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    !head.is_empty() && valid.chars().all(|c| !c.is_control() || c.is_ascii_whitespace())
+}
+
+/// Stored bytes of an entry along with the metadata needed to repack it
+#[derive(Debug, Clone)]
+pub struct P4kRawEntry {
+    /// Stored (possibly compressed) bytes following the local header
+    pub data: Vec<u8>,
+    /// Compression method of `data`
+    pub compression: CompressionMethod,
+    /// CRC32 checksum of the uncompressed data
+    pub crc32: u32,
+    /// Size of compressed data
+    pub compressed_size: u64,
+    /// Size of uncompressed data
+    pub uncompressed_size: u64,
+}
+
+impl P4kRawEntry {
+    /// Decompress the stored bytes
+    pub fn decompress(&self) -> crate::traits::ParseResult<Vec<u8>> {
+        super::P4kCompression::decompress(&self.data, self.compression, self.uncompressed_size as usize)
+    }
 }
 
 /// Lightweight entry info for listing/searching
@@ -122,6 +247,32 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Convert an archive entry name to `/` separators
+///
+/// Some P4K entries are stored with Windows `\\` separators, which would
+/// otherwise break `parent`/`filename` and directory placement.
+pub fn normalize_entry_path(name: &str) -> String {
+    name.replace('\\', "/")
+}
+
+/// Encode a timestamp as DOS `(date, time)`, the inverse of [`P4kEntry::modification_datetime`]
+///
+/// DOS time has 2-second resolution, so odd seconds round down. Fields
+/// outside the representable range (years 1980-2107, month 1-12, day 1-31,
+/// hour 0-23, minute and second 0-59) are clamped.
+pub fn encode_dos_datetime(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> (u16, u16) {
+    let year = year.clamp(1980, 1980 + 0x7F) - 1980;
+    let month = month.clamp(1, 12) as u16;
+    let day = day.clamp(1, 31) as u16;
+    let hour = hour.min(23) as u16;
+    let minute = minute.min(59) as u16;
+    let second = second.min(59) as u16;
+
+    let date = (year << 9) | (month << 5) | day;
+    let time = (hour << 11) | (minute << 5) | (second / 2);
+    (date, time)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,6 +280,7 @@ mod tests {
     fn make_test_entry(path: &str) -> P4kEntry {
         P4kEntry {
             path: path.to_string(),
+            original_path: None,
             compression: CompressionMethod::Store,
             crc32: 0,
             compressed_size: 100,
@@ -158,6 +310,10 @@ mod tests {
 
         let entry = make_test_entry("Data");
         assert_eq!(entry.parent(), None);
+
+        let entry = make_test_entry(&normalize_entry_path("Data\\Libs\\Config\\game.xml"));
+        assert_eq!(entry.parent(), Some("Data/Libs/Config"));
+        assert_eq!(entry.filename(), "game.xml");
     }
 
     #[test]
@@ -175,6 +331,59 @@ mod tests {
         assert_eq!(entry.extension(), None);
     }
 
+    #[test]
+    fn test_base_extension() {
+        assert_eq!(make_test_entry("texture.dds.1").base_extension(), Some("dds"));
+        assert_eq!(make_test_entry("texture.dds").base_extension(), Some("dds"));
+        assert_eq!(make_test_entry("archive.001").base_extension(), Some("001"));
+        assert_eq!(make_test_entry("Data/").base_extension(), None);
+    }
+
+    #[test]
+    fn test_guessed_content_type() {
+        assert_eq!(make_test_entry("Data/Libs/Config/game.xml").guessed_content_type(), ContentType::Text);
+        assert_eq!(make_test_entry("Data/Scripts/main.CFG").guessed_content_type(), ContentType::Text);
+        assert_eq!(make_test_entry("Data/Textures/hull.dds").guessed_content_type(), ContentType::Binary);
+        assert_eq!(make_test_entry("Data/Textures/hull.dds.3").guessed_content_type(), ContentType::Binary);
+        assert_eq!(make_test_entry("Data/Objects/ship.cgf").guessed_content_type(), ContentType::Model);
+        assert_eq!(make_test_entry("Data/Objects/ship.cgfm").guessed_content_type(), ContentType::Model);
+        assert_eq!(make_test_entry("Data/").guessed_content_type(), ContentType::Binary);
+
+        assert!(make_test_entry("game.json").is_likely_text());
+        assert!(!make_test_entry("sounds.bnk").is_likely_text());
+    }
+
+    #[test]
+    fn test_guessed_content_type_from_head() {
+        // Binary CryXml behind a text extension
+        let entry = make_test_entry("Data/Libs/Config/game.xml");
+        assert_eq!(entry.guessed_content_type_from(b"CryXmlB\0"), ContentType::Binary);
+        assert_eq!(entry.guessed_content_type_from(b"<Root/>"), ContentType::Text);
+
+        let entry = make_test_entry("Data/README");
+        assert_eq!(entry.guessed_content_type_from(b"plain notes\r\n"), ContentType::Text);
+        assert_eq!(entry.guessed_content_type_from(b"\x00\x01\x02"), ContentType::Binary);
+        assert_eq!(entry.guessed_content_type_from(b"#ivo\x01\x00"), ContentType::Model);
+        // "é" cut in half by the sniff window
+        assert_eq!(entry.guessed_content_type_from(b"caf\xc3"), ContentType::Text);
+    }
+
+    #[test]
+    fn test_dos_datetime_round_trip() {
+        let mut entry = make_test_entry("game.xml");
+        assert_eq!(encode_dos_datetime(2019, 1, 1, 0, 0, 0), (0x4E21, 0));
+
+        // Odd seconds round down to the 2-second resolution
+        (entry.mod_date, entry.mod_time) = encode_dos_datetime(2023, 11, 28, 17, 42, 31);
+        assert_eq!(entry.modification_datetime(), (2023, 11, 28, 17, 42, 30));
+
+        // Out-of-range fields clamp instead of bleeding into neighbours
+        (entry.mod_date, entry.mod_time) = encode_dos_datetime(1970, 0, 40, 25, 60, 61);
+        assert_eq!(entry.modification_datetime(), (1980, 1, 31, 23, 59, 58));
+        (entry.mod_date, entry.mod_time) = encode_dos_datetime(2200, 13, 0, 0, 0, 0);
+        assert_eq!(entry.modification_datetime(), (2107, 12, 1, 0, 0, 0));
+    }
+
     #[test]
     fn test_format_bytes() {
         assert_eq!(format_bytes(500), "500 B");
@@ -34,13 +34,21 @@
 mod archive;
 mod entry;
 mod compression;
+mod parallel;
+mod resume;
+mod validate;
+mod volume;
 
 pub use archive::P4kArchive;
-pub use archive::DirectoryNode;
-pub use entry::{P4kEntry, P4kEntryInfo};
-pub use compression::P4kCompression;
-
-use std::io::{Read, Seek, SeekFrom};
+pub use archive::{ArchiveDiff, DirectoryNode, DiffMarker, ModifiedEntry, TreeDiff};
+pub use entry::{encode_dos_datetime, normalize_entry_path, ContentType, P4kEntry, P4kEntryInfo, P4kRawEntry};
+pub use compression::{CrcWriter, P4kCompression};
+pub use parallel::{ExtractLayout, ParallelExtractOptions};
+pub use resume::{ExtractionManifest, ResumeProgress};
+pub use validate::{CrcCheck, ValidationProblem, ValidationReport};
+pub use volume::MultiVolumeReader;
+
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -50,22 +58,34 @@ use crate::traits::{
 };
 
 /// Magic bytes for ZIP-based P4K format
-const P4K_MAGIC: &[u8] = &[0x50, 0x4B, 0x03, 0x04]; // "PK\x03\x04"
+pub(crate) const P4K_MAGIC: &[u8] = &[0x50, 0x4B, 0x03, 0x04]; // "PK\x03\x04"
 
 /// End of central directory signature
-const EOCD_SIGNATURE: u32 = 0x06054B50;
+pub(crate) const EOCD_SIGNATURE: u32 = 0x06054B50;
+
+/// Size of the end of central directory record without its comment
+pub(crate) const EOCD_SIZE: usize = 22;
 
 /// Central directory file header signature
-const CD_SIGNATURE: u32 = 0x02014B50;
+pub(crate) const CD_SIGNATURE: u32 = 0x02014B50;
 
 /// Local file header signature
-const LOCAL_HEADER_SIGNATURE: u32 = 0x04034B50;
+pub(crate) const LOCAL_HEADER_SIGNATURE: u32 = 0x04034B50;
+
+/// Size of the fixed part of a local file header, before the file name
+pub(crate) const LOCAL_HEADER_SIZE: usize = 30;
+
+/// Data descriptor signature (trails streamed entries)
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x08074B50;
+
+/// General purpose flag: crc/sizes are stored in a trailing data descriptor
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
 
 /// ZIP64 end of central directory locator signature
 const ZIP64_EOCD_SIGNATURE: u32 = 0x06064B50;
 
 /// ZIP64 end of central directory locator signature
-const ZIP64_EOCD_LOCATOR_SIGNATURE: u32 = 0x07064B50;
+pub(crate) const ZIP64_EOCD_LOCATOR_SIGNATURE: u32 = 0x07064B50;
 
 /// Compression methods
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -114,32 +134,54 @@ impl P4kParser {
     }
 
     /// Parse the end of central directory recrod
+    ///
+    /// The scan is bounded to the last `EOCD_SIZE + u16::MAX` bytes. A
+    /// signature only counts if a full record follows it and its comment ends
+    /// exactly at EOF, which skips signature bytes that happen to appear in
+    /// the comment or in compressed data.
     fn parse_eocd<R: Read + Seek>(&self, reader: &mut R) -> ParseResult<EndOfCentralDirectory> {
         // Seek to end and search backwards for EOCD signature
         let file_size = reader.seek(SeekFrom::End(0))?;
 
+        if file_size < EOCD_SIZE as u64 {
+            return Err(ParseError::CorruptedData {
+                offset: 0,
+                message: format!(
+                    "File is {} bytes, too small to hold an end of central directory record ({} bytes)",
+                    file_size, EOCD_SIZE
+                ),
+            });
+        }
+
         // EOCD is at least 22 bytes, search within last 65KB for comment
-        let search_start = file_size.saturating_sub(65536 + 22);
+        let search_start = file_size.saturating_sub(u16::MAX as u64 + EOCD_SIZE as u64);
+        phase_span!("p4k.eocd_scan", file_size = file_size, search_start = search_start);
         reader.seek(SeekFrom::Start(search_start))?;
 
         let mut buffer = vec![0u8; (file_size - search_start) as usize];
         reader.read_exact(&mut buffer)?;
 
-        // Search for EOCD signature from end
+        // Search for EOCD signature from end, only where a whole record fits
         let sig_bytes = EOCD_SIGNATURE.to_le_bytes();
-        let eocd_offset = buffer.windows(4)
-            .rposition(|w| w == sig_bytes)
+        let eocd_offset = (0..=buffer.len() - EOCD_SIZE)
+            .rev()
+            .find(|&pos| {
+                if buffer[pos..pos + 4] != sig_bytes {
+                    return false;
+                }
+                let comment_length = u16::from_le_bytes([buffer[pos + 20], buffer[pos + 21]]) as usize;
+                pos + EOCD_SIZE + comment_length == buffer.len()
+            })
             .ok_or_else(|| ParseError::InvalidMagic {
                 expected: sig_bytes.to_vec(),
                 found: vec![],
             })?;
 
         let eocd_abs_offset = search_start + eocd_offset as u64;
-        reader.seek(SeekFrom::Start(eocd_abs_offset))?;
+        phase_event!("found end of central directory", offset = eocd_abs_offset);
 
         // Parse EOCD
-        let mut eocd_data = [0u8; 22];
-        reader.read_exact(&mut eocd_data)?;
+        let eocd_data = &buffer[eocd_offset..eocd_offset + EOCD_SIZE];
 
         let disk_number     = u16::from_le_bytes([eocd_data[4], eocd_data[5]]);
         let cd_disk         = u16::from_le_bytes([eocd_data[6], eocd_data[7]]);
@@ -150,22 +192,32 @@ impl P4kParser {
         let comment_length  = u16::from_le_bytes([eocd_data[20], eocd_data[21]]);
 
         // Check for ZIP64
-        let (cd_offset, total_entries) = if cd_offset == 0xFFFFFFFF || total_entries == 0xFFFF {
+        let (cd_offset, total_entries, cd_size) = if cd_offset == 0xFFFFFFFF || total_entries == 0xFFFF {
             self.parse_zip64_eocd(reader, eocd_abs_offset)?
         } else {
-            (cd_offset as u64, total_entries as u64)
+            (cd_offset as u64, total_entries as u64, cd_size as u64)
         };
 
+        // The central directory has to sit before the EOCD
+        if cd_offset.checked_add(cd_size).is_none_or(|end| end > eocd_abs_offset) {
+            return Err(ParseError::CorruptedData {
+                offset: eocd_abs_offset,
+                message: format!(
+                    "Central directory ({} bytes at offset {}) extends past the end of central directory record",
+                    cd_size, cd_offset
+                ),
+            });
+        }
+
         Ok(EndOfCentralDirectory {
             disk_number,
             cd_disk,
             disk_entries: disk_entries as u64,
             total_entries,
-            cd_size: cd_size as u64,
+            cd_size,
             cd_offset,
             comment_length,
         })
-
     }
 
     /// Parse ZIP64 end of central directory
@@ -173,7 +225,7 @@ impl P4kParser {
         &self,
         reader: &mut R,
         eocd_offset: u64
-    ) -> ParseResult<(u64, u64)> {
+    ) -> ParseResult<(u64, u64, u64)> {
         // Look for ZIP64 EOCD locator before EOCD
         let locator_offset = eocd_offset.saturating_sub(20);
         reader.seek(SeekFrom::Start(locator_offset))?;
@@ -186,7 +238,7 @@ impl P4kParser {
             return Err(ParseError::InvalidMagic {
                 expected: ZIP64_EOCD_LOCATOR_SIGNATURE.to_le_bytes().to_vec(),
                 found: sig.to_le_bytes().to_vec(),
-            });
+            }.at_offset(locator_offset));
         }
 
         let zip64_eocd_offset = u64::from_le_bytes([
@@ -205,7 +257,7 @@ impl P4kParser {
             return Err(ParseError::InvalidMagic {
                 expected: ZIP64_EOCD_SIGNATURE.to_le_bytes().to_vec(),
                 found: sig.to_le_bytes().to_vec(),
-            });
+            }.at_offset(zip64_eocd_offset));
         }
 
         let total_entries = u64::from_le_bytes([
@@ -213,12 +265,17 @@ impl P4kParser {
             zip64_eocd[36], zip64_eocd[37], zip64_eocd[38], zip64_eocd[39],
         ]);
 
+        let cd_size = u64::from_le_bytes([
+            zip64_eocd[40], zip64_eocd[41], zip64_eocd[42], zip64_eocd[43],
+            zip64_eocd[44], zip64_eocd[45], zip64_eocd[46], zip64_eocd[47],
+        ]);
+
         let cd_offset = u64::from_le_bytes([
             zip64_eocd[48], zip64_eocd[49], zip64_eocd[50], zip64_eocd[51],
             zip64_eocd[52], zip64_eocd[53], zip64_eocd[54], zip64_eocd[55],
         ]);
 
-        Ok((cd_offset, total_entries))
+        Ok((cd_offset, total_entries, cd_size))
     }
 
     /// Parse central directory entries
@@ -226,16 +283,28 @@ impl P4kParser {
         &self,
         reader: &mut R,
         eocd: &EndOfCentralDirectory,
+        options: &ParseOptions,
         progress: Option<&ProgressCallback>,
     ) -> ParseResult<Vec<P4kEntry>> {
+        phase_span!(
+            "p4k.central_directory",
+            entries = eocd.total_entries,
+            offset = eocd.cd_offset,
+            size = eocd.cd_size,
+        );
         reader.seek(SeekFrom::Start(eocd.cd_offset))?;
 
         let mut entries = Vec::with_capacity(eocd.total_entries as usize);
 
         for i in 0..eocd.total_entries {
+            options.check_cancelled()?;
             let entry = self.parse_cd_entry(reader)?;
             entries.push(entry);
 
+            if i % 1000 == 0 {
+                phase_event!("central directory progress", entries = i, offset = reader.stream_position()?);
+            }
+
             if let Some(ref cb) = progress {
                 if i % 1000 == 0 {
                     cb(ParseProgress {
@@ -255,6 +324,7 @@ impl P4kParser {
 
     /// Parse a single central directory entry
     fn parse_cd_entry<R: Read + Seek>(&self, reader: &mut R) -> ParseResult<P4kEntry> {
+        let entry_offset = reader.stream_position()?;
         let mut header = [0u8; 46];
         reader.read_exact(&mut header)?;
 
@@ -263,7 +333,7 @@ impl P4kParser {
             return Err(ParseError::InvalidMagic {
                 expected: CD_SIGNATURE.to_le_bytes().to_vec(),
                 found: sig.to_le_bytes().to_vec(),
-            });
+            }.at_offset(entry_offset));
         }
 
         let _version_made        = u16::from_le_bytes([header[4], header[5]]);
@@ -286,7 +356,9 @@ impl P4kParser {
         // Read filename
         let mut name_bytes = vec![0u8; name_length];
         reader.read_exact(&mut name_bytes)?;
-        let path = String::from_utf8_lossy(&name_bytes).to_string();
+        let raw_path = String::from_utf8_lossy(&name_bytes).to_string();
+        let path = normalize_entry_path(&raw_path);
+        let original_path = (path != raw_path).then_some(raw_path);
 
         // Read extra field
         let mut extra = vec![0u8; extra_length];
@@ -302,6 +374,7 @@ impl P4kParser {
         let is_directory = path.ends_with('/');
         Ok(P4kEntry {
             path,
+            original_path,
             compression,
             crc32,
             compressed_size,
@@ -375,16 +448,107 @@ impl P4kParser {
         Ok((compressed, uncompressed, offset))
     }
 
-    /// Extract file data from local header
-    fn extract_data<R: Read + Seek>(
+    /// Fingerprint an archive file from its central directory alone
+    ///
+    /// No entry data is read, making this a cheap "did anything change" check
+    /// compared to diffing two fully parsed archives.
+    pub fn fingerprint_file(&self, path: impl AsRef<std::path::Path>) -> ParseResult<u64> {
+        Ok(self.parse_file(path.as_ref())?.fingerprint())
+    }
+
+    /// Read an entry's stored bytes without decompressing them
+    ///
+    /// Repacking tools can copy unchanged entries byte-for-byte using the
+    /// returned stream and metadata instead of decompressing and
+    /// recompressing them.
+    pub fn read_raw_entry<R: Read + Seek>(
         &self,
         reader: &mut R,
         entry: &P4kEntry,
-    ) -> ParseResult<Vec<u8>> {
+    ) -> ParseResult<P4kRawEntry> {
+        self.read_raw_entry_with_options(reader, entry, &ParseOptions::default())
+    }
+
+    /// Read an entry's stored bytes under `options`
+    ///
+    /// See [`read_raw_entry`](Self::read_raw_entry).
+    pub fn read_raw_entry_with_options<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        entry: &P4kEntry,
+        options: &ParseOptions,
+    ) -> ParseResult<P4kRawEntry> {
+        let (crc32, compressed_size, uncompressed_size) = self.seek_entry_data(reader, entry, options)?;
+
+        // Read compressed data
+        let mut data = vec![0u8; compressed_size as usize];
+        reader.read_exact(&mut data)?;
+
+        Ok(P4kRawEntry {
+            data,
+            compression: entry.compression,
+            crc32,
+            compressed_size,
+            uncompressed_size,
+        })
+    }
+
+    /// Decompress an entry straight into `writer`, returning the CRC32 of the output
+    ///
+    /// The CRC is computed by a [`CrcWriter`] as the data streams through, so
+    /// comparing it to `entry.crc32` costs no second pass.
+    pub fn extract_entry_to<R: Read + Seek, W: Write>(
+        &self,
+        reader: &mut R,
+        entry: &P4kEntry,
+        writer: W,
+    ) -> ParseResult<u32> {
+        self.extract_entry_to_with_options(reader, entry, writer, &ParseOptions::default())
+    }
+
+    /// Decompress an entry straight into `writer` under `options`
+    ///
+    /// See [`extract_entry_to`](Self::extract_entry_to).
+    pub fn extract_entry_to_with_options<R: Read + Seek, W: Write>(
+        &self,
+        reader: &mut R,
+        entry: &P4kEntry,
+        writer: W,
+        options: &ParseOptions,
+    ) -> ParseResult<u32> {
+        let (_, compressed_size, uncompressed_size) = self.seek_entry_data(reader, entry, options)?;
+
+        let mut crc_writer = CrcWriter::new(writer);
+        if compressed_size == 0 {
+            // Zero-length entry: nothing to decode
+            crc_writer.flush()?;
+            return Ok(crc_writer.crc32());
+        }
+        P4kCompression::decompress_to(
+            reader.take(compressed_size),
+            entry.compression,
+            uncompressed_size as usize,
+            &mut crc_writer,
+        ).map_err(|e| e.at_offset(entry.local_header_offset))?;
+        crc_writer.flush()?;
+
+        Ok(crc_writer.crc32())
+    }
+
+    /// Position `reader` at an entry's data, returning `(crc32, compressed_size, uncompressed_size)`
+    ///
+    /// The data descriptor scan of streamed entries is bounded by
+    /// `options.decompression_memory_limit`.
+    fn seek_entry_data<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        entry: &P4kEntry,
+        options: &ParseOptions,
+    ) -> ParseResult<(u32, u64, u64)> {
         reader.seek(SeekFrom::Start(entry.local_header_offset))?;
 
         // Read local header
-        let mut local_header = [0u8; 30];
+        let mut local_header = [0u8; LOCAL_HEADER_SIZE];
         reader.read_exact(&mut local_header)?;
 
         let sig = u32::from_le_bytes([local_header[0], local_header[1], local_header[2], local_header[3]]);
@@ -392,27 +556,123 @@ impl P4kParser {
             return Err(ParseError::InvalidMagic {
                 expected: LOCAL_HEADER_SIGNATURE.to_le_bytes().to_vec(),
                 found: sig.to_le_bytes().to_vec(),
-            });
+            }.at_offset(entry.local_header_offset));
         }
 
         let name_len = u16::from_le_bytes([local_header[26], local_header[27]]) as u64;
         let extra_len = u16::from_le_bytes([local_header[28], local_header[29]]) as u64;
 
         // Skip to data
-        reader.seek(SeekFrom::Current((name_len + extra_len) as i64))?;
+        let data_start = reader.seek(SeekFrom::Current((name_len + extra_len) as i64))?;
+
+        // Streamed entries may only record their crc/sizes in the trailing
+        // data descriptor, leaving zeros in the central directory
+        if entry.flags & FLAG_DATA_DESCRIPTOR != 0 && entry.compressed_size == 0 {
+            let limit = options.decompression_memory_limit;
+            let descriptor = self.locate_data_descriptor(reader, data_start, entry.compression, limit)?;
+            reader.seek(SeekFrom::Start(data_start))?;
+            Ok(descriptor)
+        } else {
+            Ok((entry.crc32, entry.compressed_size, entry.uncompressed_size))
+        }
+    }
 
-        // Read compressed data
-        let mut compressed = vec![0u8; entry.compressed_size as usize];
-        reader.read_exact(&mut compressed)?;
+    /// Extract file data from local header
+    fn extract_data<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        entry: &P4kEntry,
+    ) -> ParseResult<Vec<u8>> {
+        self.read_raw_entry(reader, entry)?
+            .decompress()
+            .map_err(|e| e.at_offset(entry.local_header_offset))
+    }
 
-        // Decompress
-        let decompressed = P4kCompression::decompress(
-            &compressed,
-            entry.compression,
-            entry.uncompressed_size as usize,
-        )?;
+    /// Scan forward from `data_start` for the data descriptor of a streamed entry
+    ///
+    /// A signed candidate is accepted when its compressed size equals the
+    /// distance from the start of the data, which rules out signature bytes
+    /// that happen to occur inside the compressed stream. The signature is
+    /// optional, so a bare crc/size triple is accepted too, but only once the
+    /// data before it decodes to that size and CRC. The scan gives up after
+    /// `limit` bytes rather than buffering the rest of the archive. Returns
+    /// `(crc32, compressed_size, uncompressed_size)`.
+    fn locate_data_descriptor<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        data_start: u64,
+        compression: CompressionMethod,
+        limit: usize,
+    ) -> ParseResult<(u32, u64, u64)> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        reader.seek(SeekFrom::Start(data_start))?;
+
+        let mut buf = Vec::new();
+        let mut pos = 0usize;
+        let mut eof = false;
+
+        loop {
+            // Keep enough bytes buffered for a full ZIP64 descriptor
+            while !eof && buf.len() < pos + 24 {
+                let start = buf.len();
+                buf.resize(start + CHUNK_SIZE, 0);
+                let read = reader.read(&mut buf[start..])?;
+                buf.truncate(start + read);
+                eof = read == 0;
+            }
+
+            if pos > limit {
+                return Err(ParseError::InvalidStructure(format!(
+                    "No data descriptor within {} bytes of streamed entry at offset {}",
+                    limit, data_start
+                )));
+            }
+            if pos + 12 > buf.len() {
+                return Err(ParseError::InvalidStructure(format!(
+                    "No data descriptor found for streamed entry at offset {}",
+                    data_start
+                )));
+            }
+
+            let sig = u32::from_le_bytes([buf[pos], buf[pos + 1], buf[pos + 2], buf[pos + 3]]);
+            if sig == DATA_DESCRIPTOR_SIGNATURE {
+                if let Some(descriptor) = Self::descriptor_at(&buf, pos, pos + 4) {
+                    return Ok(descriptor);
+                }
+            }
 
-        Ok(decompressed)
+            if let Some((crc32, compressed, uncompressed)) = Self::descriptor_at(&buf, pos, pos) {
+                let decodes = uncompressed as usize <= limit
+                    && P4kCompression::decompress(&buf[..pos], compression, uncompressed as usize)
+                        .is_ok_and(|data| data.len() as u64 == uncompressed && P4kCompression::crc32(&data) == crc32);
+                if decodes {
+                    return Ok((crc32, compressed, uncompressed));
+                }
+            }
+
+            pos += 1;
+        }
+    }
+
+    /// Read a descriptor body (crc, then 32- or 64-bit sizes) at `body`
+    /// whose compressed size equals `data_len`
+    fn descriptor_at(buf: &[u8], data_len: usize, body: usize) -> Option<(u32, u64, u64)> {
+        let crc32 = u32::from_le_bytes(buf.get(body..body + 4)?.try_into().unwrap());
+
+        let compressed = u32::from_le_bytes(buf.get(body + 4..body + 8)?.try_into().unwrap());
+        if compressed as usize == data_len {
+            let uncompressed = u32::from_le_bytes(buf.get(body + 8..body + 12)?.try_into().unwrap());
+            return Some((crc32, compressed as u64, uncompressed as u64));
+        }
+
+        let compressed = u64::from_le_bytes(buf.get(body + 4..body + 12)?.try_into().unwrap());
+        if compressed == data_len as u64 {
+            let uncompressed = u64::from_le_bytes(buf.get(body + 12..body + 20)?.try_into().unwrap());
+            return Some((crc32, compressed, uncompressed));
+        }
+
+        None
     }
 }
 
@@ -440,9 +700,11 @@ impl Parser for P4kParser {
     fn parse_with_options<R: Read + Seek>(
         &self,
         mut reader: R,
-        _options: &ParseOptions,
+        options: &ParseOptions,
         progress: Option<ProgressCallback>,
     ) -> ParseResult<Self::Output> {
+        phase_span!("p4k.parse");
+
         // Verify magic bytes
         let mut magic = [0u8; 4];
         reader.read_exact(&mut magic)?;
@@ -451,7 +713,7 @@ impl Parser for P4kParser {
             return Err(ParseError::InvalidMagic {
                 expected: P4K_MAGIC.to_vec(),
                 found: magic.to_vec(),
-            });
+            }.at_offset(0));
         }
 
         // Report header reading progress
@@ -470,7 +732,7 @@ impl Parser for P4kParser {
         let eocd = self.parse_eocd(&mut reader)?;
 
         // Parse central directory
-        let entries = self.parse_central_directory(&mut reader, &eocd, progress.as_ref())?;
+        let entries = self.parse_central_directory(&mut reader, &eocd, options, progress.as_ref())?;
 
         // Build path index
         let mut path_index = HashMap::with_capacity(entries.len());
@@ -493,6 +755,7 @@ impl Parser for P4kParser {
         Ok(P4kArchive {
             entries,
             path_index,
+            dir_index: None,
         })
     }
 }
@@ -504,7 +767,7 @@ impl RandomAccessParser for P4kParser {
     fn list_entries<R: Read + Seek>(&self, reader: R) -> ParseResult<Vec<(Self::EntryId, Self::EntryMeta)>> {
         let archive = self.parse(reader)?;
 
-        Ok(archive.entries.iter().map(|e| {
+        Ok(archive.entries_iter().map(|e| {
             (e.path.clone(), P4kEntryInfo {
                 path: e.path.clone(),
                 compressed_size: e.compressed_size,
@@ -545,6 +808,255 @@ struct EndOfCentralDirectory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::{cd_entry, eocd, local_entry, p4k_file};
+
+    #[test]
+    fn test_read_raw_entry_deflate_round_trip() {
+        let original = b"Data/Libs/Config/defaultprofile.xml ".repeat(32);
+        let compressed = P4kCompression::compress(&original, CompressionMethod::Deflate).unwrap();
+        let (buf, entry) = local_entry("Data/test.xml", &compressed, CompressionMethod::Deflate, &original);
+
+        let parser = P4kParser::new();
+        let raw = parser.read_raw_entry(&mut std::io::Cursor::new(&buf), &entry).unwrap();
+
+        // Raw bytes are the stored stream, untouched
+        assert_eq!(raw.data, compressed);
+        assert_eq!(raw.compression, CompressionMethod::Deflate);
+        assert_eq!(raw.compressed_size, compressed.len() as u64);
+        assert_eq!(raw.uncompressed_size, original.len() as u64);
+
+        // Copy the raw stream into a fresh entry and check it still decodes
+        let (repacked, repacked_entry) = local_entry("Data/test.xml", &raw.data, raw.compression, &original);
+        let extracted = parser.extract_data(&mut std::io::Cursor::new(&repacked), &repacked_entry).unwrap();
+
+        assert_eq!(extracted, original);
+        assert!(P4kCompression::verify_crc32(&extracted, raw.crc32));
+        assert_eq!(raw.decompress().unwrap(), original);
+    }
+
+    /// Central directory record for an empty stored entry
+    fn make_cd_entry(name: &str) -> Vec<u8> {
+        cd_entry(&local_entry(name, b"", CompressionMethod::Store, b"").1)
+    }
+
+    #[test]
+    fn test_bad_cd_signature_reports_offset() {
+        let mut buf = vec![0u8; 0x20];
+        let mut record = make_cd_entry("Data/readme.txt");
+        record[0] = b'X';
+        buf.extend_from_slice(&record);
+
+        let mut reader = std::io::Cursor::new(buf);
+        reader.set_position(0x20);
+        let err = P4kParser::new().parse_cd_entry(&mut reader).unwrap_err();
+
+        assert!(matches!(&err, ParseError::AtOffset { source, .. } if matches!(**source, ParseError::InvalidMagic { .. })));
+        assert_eq!(err.offset(), Some(0x20));
+        assert!(err.to_string().ends_with("(at offset 0x20)"));
+    }
+
+    #[test]
+    fn test_cd_entry_backslash_path_normalized() {
+        let parser = P4kParser::new();
+        let entry = parser
+            .parse_cd_entry(&mut std::io::Cursor::new(make_cd_entry("Data\\Objects\\ship.cgf")))
+            .unwrap();
+
+        assert_eq!(entry.path, "Data/Objects/ship.cgf");
+        assert_eq!(entry.raw_path(), "Data\\Objects\\ship.cgf");
+        assert_eq!(entry.parent(), Some("Data/Objects"));
+        assert_eq!(entry.filename(), "ship.cgf");
+
+        let dir = parser
+            .parse_cd_entry(&mut std::io::Cursor::new(make_cd_entry("Data\\Objects\\")))
+            .unwrap();
+        assert!(dir.is_directory);
+        assert!(dir.original_path.is_some());
+
+        let plain = parser
+            .parse_cd_entry(&mut std::io::Cursor::new(make_cd_entry("Data/readme.txt")))
+            .unwrap();
+        assert!(plain.original_path.is_none());
+
+        let mut archive = P4kArchive::new();
+        archive.entries = vec![dir, entry];
+        archive.rebuild_index();
+
+        let tree = archive.build_tree();
+        assert!(tree.children["Data"].children["Objects"].children["ship.cgf"].is_file);
+        assert_eq!(archive.list_directory("Data/Objects").len(), 1);
+        assert!(archive.contains("Data\\Objects\\ship.cgf"));
+    }
+
+    #[test]
+    fn test_extract_entry_with_zero_declared_size() {
+        let original = b"entry written before its size fixup ".repeat(16);
+        let compressed = P4kCompression::compress(&original, CompressionMethod::Deflate).unwrap();
+        let (buf, mut entry) = local_entry("Data/fixup.xml", &compressed, CompressionMethod::Deflate, &original);
+        entry.uncompressed_size = 0;
+
+        let extracted = P4kParser::new().extract_data(&mut std::io::Cursor::new(&buf), &entry).unwrap();
+        assert_eq!(extracted, original);
+    }
+
+    #[test]
+    fn test_eocd_rejects_tiny_file() {
+        let parser = P4kParser::new();
+        let mut data = P4K_MAGIC.to_vec();
+        data.extend_from_slice(&[0u8; 6]);
+
+        let result = parser.parse_eocd(&mut std::io::Cursor::new(&data));
+        assert!(matches!(result, Err(ParseError::CorruptedData { .. })));
+    }
+
+    #[test]
+    fn test_eocd_rejects_impossible_cd_offset() {
+        let parser = P4kParser::new();
+        let mut data = P4K_MAGIC.to_vec();
+        data.extend_from_slice(&eocd(1, 46, 0x7FFF_0000));
+
+        let result = parser.parse_eocd(&mut std::io::Cursor::new(&data));
+        assert!(matches!(result, Err(ParseError::CorruptedData { .. })));
+    }
+
+    #[test]
+    fn test_eocd_skips_signature_in_comment() {
+        let parser = P4kParser::new();
+        let mut data = P4K_MAGIC.to_vec();
+        let mut eocd = eocd(0, 0, 4);
+        // Comment that itself contains an EOCD signature
+        let comment = EOCD_SIGNATURE.to_le_bytes();
+        eocd[20..22].copy_from_slice(&(comment.len() as u16).to_le_bytes());
+        eocd.extend_from_slice(&comment);
+        data.extend_from_slice(&eocd);
+
+        let eocd = parser.parse_eocd(&mut std::io::Cursor::new(&data)).unwrap();
+        assert_eq!(eocd.cd_offset, 4);
+        assert_eq!(eocd.comment_length, 4);
+    }
+
+    #[test]
+    fn test_extract_entry_to_computes_crc() {
+        let original = b"Data/Objects/Spaceships/Ships/AEGS/Gladius/gladius.cga ".repeat(64);
+        let compressed = P4kCompression::compress(&original, CompressionMethod::Deflate).unwrap();
+        let (buf, entry) = local_entry("Data/gladius.cga", &compressed, CompressionMethod::Deflate, &original);
+
+        let parser = P4kParser::new();
+        let mut output = Vec::new();
+        let crc = parser.extract_entry_to(&mut std::io::Cursor::new(&buf), &entry, &mut output).unwrap();
+
+        assert_eq!(crc, entry.crc32);
+        assert_eq!(output, original);
+    }
+
+    #[test]
+    fn test_extract_streamed_entry() {
+        let original = b"streamed entry with a data descriptor ".repeat(16);
+        let compressed = P4kCompression::compress(&original, CompressionMethod::Deflate).unwrap();
+        let (mut buf, mut entry) = local_entry("Data/streamed.xml", &compressed, CompressionMethod::Deflate, &original);
+        let crc32 = entry.crc32;
+
+        // Flag bit 3 set, crc/sizes zeroed in the header and moved to the descriptor
+        buf[6..8].copy_from_slice(&FLAG_DATA_DESCRIPTOR.to_le_bytes());
+        buf[14..26].fill(0);
+        buf.extend_from_slice(&DATA_DESCRIPTOR_SIGNATURE.to_le_bytes());
+        buf.extend_from_slice(&crc32.to_le_bytes());
+        buf.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&(original.len() as u32).to_le_bytes());
+
+        entry.flags = FLAG_DATA_DESCRIPTOR;
+        entry.crc32 = 0;
+        entry.compressed_size = 0;
+        entry.uncompressed_size = 0;
+
+        let parser = P4kParser::new();
+        let raw = parser.read_raw_entry(&mut std::io::Cursor::new(&buf), &entry).unwrap();
+        assert_eq!(raw.crc32, crc32);
+        assert_eq!(raw.compressed_size, compressed.len() as u64);
+        assert_eq!(raw.uncompressed_size, original.len() as u64);
+
+        let extracted = parser.extract_data(&mut std::io::Cursor::new(&buf), &entry).unwrap();
+        assert_eq!(extracted, original);
+    }
+
+    #[test]
+    fn test_extract_streamed_entry_unsigned_descriptor() {
+        let original = b"descriptor without its signature ".repeat(16);
+        let compressed = P4kCompression::compress(&original, CompressionMethod::Deflate).unwrap();
+        let (mut buf, mut entry) = local_entry("Data/unsigned.xml", &compressed, CompressionMethod::Deflate, &original);
+        let crc32 = entry.crc32;
+
+        buf[6..8].copy_from_slice(&FLAG_DATA_DESCRIPTOR.to_le_bytes());
+        buf[14..26].fill(0);
+        buf.extend_from_slice(&crc32.to_le_bytes());
+        buf.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&(original.len() as u32).to_le_bytes());
+
+        entry.flags = FLAG_DATA_DESCRIPTOR;
+        entry.crc32 = 0;
+        entry.compressed_size = 0;
+        entry.uncompressed_size = 0;
+
+        let parser = P4kParser::new();
+        let raw = parser.read_raw_entry(&mut std::io::Cursor::new(&buf), &entry).unwrap();
+        assert_eq!(raw.crc32, crc32);
+        assert_eq!(raw.compressed_size, compressed.len() as u64);
+
+        let extracted = parser.extract_data(&mut std::io::Cursor::new(&buf), &entry).unwrap();
+        assert_eq!(extracted, original);
+
+        // The descriptor scan is bounded by the caller's options
+        let options = ParseOptions { decompression_memory_limit: 16, ..Default::default() };
+        let err = parser
+            .read_raw_entry_with_options(&mut std::io::Cursor::new(&buf), &entry, &options)
+            .unwrap_err();
+        assert!(err.to_string().contains("within 16 bytes"), "{}", err);
+        let err = parser
+            .extract_entry_to_with_options(&mut std::io::Cursor::new(&buf), &entry, std::io::sink(), &options)
+            .unwrap_err();
+        assert!(err.to_string().contains("within 16 bytes"), "{}", err);
+    }
+
+    #[test]
+    fn test_data_descriptor_scan_is_bounded() {
+        let data = vec![0xAAu8; 4096];
+        let parser = P4kParser::new();
+
+        let err = parser
+            .locate_data_descriptor(&mut std::io::Cursor::new(&data), 0, CompressionMethod::Store, 1024)
+            .unwrap_err();
+        assert!(err.to_string().contains("within 1024 bytes"), "{}", err);
+    }
+
+    #[test]
+    fn test_cancel_stops_central_directory_loop() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let bytes = p4k_file(&[("Data/a.xml", b"<A/>"), ("Data/b.xml", b"<B/>")]);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let options = ParseOptions { cancel: Some(cancel.clone()), ..Default::default() };
+
+        // Cancel as soon as the first entry has been read
+        let indexed = Arc::new(AtomicBool::new(false));
+        let progress: ProgressCallback = {
+            let indexed = indexed.clone();
+            Box::new(move |p: ParseProgress| {
+                if p.phase == ParsePhase::Indexing {
+                    indexed.store(true, Ordering::Relaxed);
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            })
+        };
+
+        let result = P4kParser::new().parse_with_options(std::io::Cursor::new(&bytes), &options, Some(progress));
+        assert!(matches!(result, Err(ParseError::Cancelled)));
+        assert!(indexed.load(Ordering::Relaxed));
+
+        // Unset, the same options parse everything
+        options.cancel.as_ref().unwrap().store(false, Ordering::Relaxed);
+        let archive = P4kParser::new().parse_with_options(std::io::Cursor::new(&bytes), &options, None).unwrap();
+        assert_eq!(archive.entry_count(), 2);
+    }
 
     #[test]
     fn test_compression_method_conversion() {
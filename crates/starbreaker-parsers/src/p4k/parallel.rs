@@ -0,0 +1,392 @@
+// starbreaker-parsers/src/p4k/parallel.rs
+//! Parallel bulk extraction
+//!
+//! Entries are decompressed by a pool of worker threads, each with its own
+//! archive handle, and handed to the calling thread for writing through a
+//! bounded queue. When disk writes fall behind, workers block on the queue
+//! instead of piling decompressed data up in memory.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use super::archive::{fnv1a, FNV_OFFSET_BASIS};
+use super::resume::partial_path;
+use super::{P4kEntry, P4kParser, ResumeProgress};
+use crate::traits::{ParsePhase, ParseProgress, ParseResult, ProgressCallback};
+
+/// Where extracted files land under the output directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExtractLayout {
+    /// Keep the archive path, e.g. `Data/Objects/ship.cgf`
+    #[default]
+    Mirror,
+    /// Write every file straight into the output directory by file name;
+    /// later files whose name is taken get `_1`, `_2`, ... appended
+    Flatten,
+    /// Shard by a hash of the archive path, e.g. `3f/3fa4c2..._ship.cgf`
+    HashPrefix,
+}
+
+impl std::str::FromStr for ExtractLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mirror" => Ok(ExtractLayout::Mirror),
+            "flatten" => Ok(ExtractLayout::Flatten),
+            "hash" | "hash-prefix" => Ok(ExtractLayout::HashPrefix),
+            _ => Err(format!("Unknown extract layout: {}", s)),
+        }
+    }
+}
+
+impl ExtractLayout {
+    /// Output paths relative to the output directory, one per entry in order
+    ///
+    /// Flatten collisions are resolved case-insensitively, so the result is
+    /// also safe on case-insensitive filesystems.
+    pub fn output_paths(&self, entries: &[&P4kEntry]) -> Vec<PathBuf> {
+        match self {
+            ExtractLayout::Mirror => entries.iter().map(|e| PathBuf::from(&e.path)).collect(),
+            ExtractLayout::Flatten => {
+                let mut taken = HashSet::new();
+                entries.iter().map(|e| flatten_name(file_name(&e.path), &mut taken).into()).collect()
+            }
+            ExtractLayout::HashPrefix => entries
+                .iter()
+                .map(|e| {
+                    let hash = fnv1a(FNV_OFFSET_BASIS, e.path.as_bytes());
+                    Path::new(&format!("{:02x}", hash >> 56)).join(format!("{:016x}_{}", hash, file_name(&e.path)))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Last component of an archive path
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// First free variant of `name`: `name`, then `stem_1.ext`, `stem_2.ext`, ...
+fn flatten_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let (stem, ext) = match name.rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(dot),
+        _ => (name, ""),
+    };
+
+    let mut candidate = name.to_string();
+    let mut counter = 0;
+    while !taken.insert(candidate.to_lowercase()) {
+        counter += 1;
+        candidate = format!("{}_{}{}", stem, counter, ext);
+    }
+    candidate
+}
+
+/// Settings for [`P4kParser::extract_parallel`]
+#[derive(Debug, Clone)]
+pub struct ParallelExtractOptions {
+    /// Number of worker threads (0 uses the available parallelism)
+    pub threads: usize,
+    /// Decompressed entries allowed to wait for writing, per worker
+    pub queue_depth: usize,
+    /// Overwrite files that already exist in the output directory
+    pub overwrite: bool,
+    /// Create directories for directory entries, even when they hold no files
+    pub create_empty_dirs: bool,
+    /// Output directory layout
+    pub layout: ExtractLayout,
+}
+
+impl Default for ParallelExtractOptions {
+    fn default() -> Self {
+        Self {
+            threads: 0,
+            queue_depth: 2,
+            overwrite: false,
+            create_empty_dirs: false,
+            layout: ExtractLayout::Mirror,
+        }
+    }
+}
+
+impl ParallelExtractOptions {
+    /// Workers to start for `pending` entries
+    fn worker_count(&self, pending: usize) -> usize {
+        let threads = match self.threads {
+            0 => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            n => n,
+        };
+        threads.min(pending).max(1)
+    }
+}
+
+impl P4kParser {
+    /// Extract `entries` into `out_dir` using a pool of worker threads
+    ///
+    /// Each worker opens its own handle on `archive_path`. Files are placed
+    /// according to `options.layout`. Directory entries are only created with
+    /// `options.create_empty_dirs` under the mirror layout, and existing files
+    /// are skipped unless `options.overwrite` is set. Zero-length entries
+    /// produce empty files. Files are written to a `.part` file and renamed into place.
+    /// `progress` is called from the calling thread after every file written.
+    pub fn extract_parallel(
+        &self,
+        archive_path: impl AsRef<Path>,
+        entries: &[&P4kEntry],
+        out_dir: impl AsRef<Path>,
+        options: &ParallelExtractOptions,
+        progress: Option<ProgressCallback>,
+    ) -> ParseResult<ResumeProgress> {
+        let archive_path = archive_path.as_ref();
+        self.extract_parallel_with(
+            || File::open(archive_path).map(BufReader::new),
+            entries,
+            out_dir.as_ref(),
+            options,
+            progress.as_ref(),
+        )
+    }
+
+    /// Parallel extraction with workers reading from handles made by `open`
+    pub(crate) fn extract_parallel_with<R, F>(
+        &self,
+        open: F,
+        entries: &[&P4kEntry],
+        out_dir: &Path,
+        options: &ParallelExtractOptions,
+        progress: Option<&ProgressCallback>,
+    ) -> ParseResult<ResumeProgress>
+    where
+        R: Read + Seek + Send,
+        F: Fn() -> io::Result<R>,
+    {
+        let mut summary = ResumeProgress::default();
+        fs::create_dir_all(out_dir)?;
+
+        if options.create_empty_dirs && options.layout == ExtractLayout::Mirror {
+            for entry in entries.iter().filter(|e| e.is_directory) {
+                fs::create_dir_all(out_dir.join(&entry.path))?;
+            }
+        }
+
+        let files: Vec<&P4kEntry> = entries.iter().copied().filter(|e| !e.is_directory).collect();
+        let mut pending = Vec::with_capacity(files.len());
+        for (entry, relative) in files.iter().copied().zip(options.layout.output_paths(&files)) {
+            let output_path = out_dir.join(relative);
+            if !options.overwrite && output_path.exists() {
+                summary.skipped += 1;
+            } else {
+                pending.push((entry, output_path));
+            }
+        }
+        if pending.is_empty() {
+            return Ok(summary);
+        }
+
+        let total_bytes: u64 = pending.iter().map(|(e, _)| e.uncompressed_size).sum();
+        let workers = options.worker_count(pending.len());
+        let readers = (0..workers).map(|_| open()).collect::<io::Result<Vec<_>>>()?;
+
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::sync_channel(workers * options.queue_depth.max(1));
+
+        thread::scope(|scope| {
+            for mut reader in readers {
+                let tx = tx.clone();
+                let (next, pending) = (&next, &pending);
+                scope.spawn(move || {
+                    while let Some((entry, output_path)) = pending.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        let result = self.extract_data(&mut reader, entry);
+                        if tx.send((*entry, output_path, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+
+            let mut bytes_written = 0u64;
+            for (done, (entry, output_path, result)) in rx.iter().enumerate() {
+                let result = result.and_then(|data| {
+                    if let Some(parent) = output_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    let tmp = partial_path(output_path);
+                    fs::write(&tmp, &data)?;
+                    fs::rename(&tmp, output_path)?;
+                    Ok(data.len() as u64)
+                });
+
+                match result {
+                    Ok(len) => {
+                        summary.extracted += 1;
+                        bytes_written += len;
+                    }
+                    Err(e) => summary.failed.push((entry.path.clone(), e.to_string())),
+                }
+
+                if let Some(cb) = progress {
+                    cb(ParseProgress {
+                        phase: ParsePhase::Decompressing,
+                        bytes_processed: bytes_written,
+                        total_bytes: Some(total_bytes),
+                        current_item: Some(entry.path.clone()),
+                        items_processed: done as u64 + 1,
+                        total_items: Some(pending.len() as u64),
+                    });
+                }
+            }
+        });
+
+        // Completion order depends on scheduling
+        summary.failed.sort();
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p4k::CompressionMethod;
+    use crate::testutil::stored_archive;
+    use std::io::Cursor;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_extract_parallel_many_entries() {
+        let files: Vec<(String, Vec<u8>)> = (0..200)
+            .map(|i| (format!("Data/Dir{}/file{}.bin", i % 7, i), vec![i as u8; 100 + i]))
+            .collect();
+        let file_refs: Vec<(&str, &[u8])> = files.iter().map(|(p, d)| (p.as_str(), d.as_slice())).collect();
+        let (buf, archive) = stored_archive(&file_refs);
+        let mut entries = archive.entries;
+
+        // One entry points at garbage and must be reported, not abort the run
+        let mut broken = entries[0].clone();
+        broken.path = "Data/broken.bin".to_string();
+        broken.local_header_offset = 1;
+        entries.push(broken);
+
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("Data/Dir3")).unwrap();
+        fs::write(dir.join("Data/Dir3/file3.bin"), b"keep").unwrap();
+
+        let reported = Arc::new(AtomicUsize::new(0));
+        let counter = reported.clone();
+        let callback: ProgressCallback = Box::new(move |p| {
+            counter.fetch_max(p.items_processed as usize, Ordering::Relaxed);
+        });
+
+        let options = ParallelExtractOptions { threads: 4, queue_depth: 1, ..Default::default() };
+        let refs: Vec<&P4kEntry> = entries.iter().collect();
+        let summary = P4kParser::new()
+            .extract_parallel_with(|| Ok(Cursor::new(&buf)), &refs, dir, &options, Some(&callback))
+            .unwrap();
+
+        assert_eq!(summary.extracted, 199);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].0, "Data/broken.bin");
+        assert_eq!(summary.total(), 201);
+        assert_eq!(reported.load(Ordering::Relaxed), 200);
+
+        for (path, data) in &files {
+            let expected: &[u8] = if path == "Data/Dir3/file3.bin" { b"keep" } else { data };
+            assert_eq!(fs::read(dir.join(path)).unwrap(), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_extract_empty_dirs_and_files() {
+        let files: [(&str, &[u8]); 3] = [
+            ("Data/zero.bin", b""),
+            ("Data/zero_zstd.bin", b""),
+            ("Data/full.bin", b"content"),
+        ];
+        let (buf, archive) = stored_archive(&files);
+        let mut entries = archive.entries;
+        // Compressed zero-length entries have no stream to decode either
+        entries[1].compression = CompressionMethod::Zstd;
+
+        let mut empty_dir = entries[0].clone();
+        empty_dir.path = "Data/Empty/".to_string();
+        empty_dir.is_directory = true;
+        entries.push(empty_dir);
+
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("out");
+        let refs: Vec<&P4kEntry> = entries.iter().collect();
+        let extract = |create_empty_dirs| {
+            let _ = fs::remove_dir_all(&dir);
+            let options = ParallelExtractOptions { threads: 2, create_empty_dirs, ..Default::default() };
+            P4kParser::new()
+                .extract_parallel_with(|| Ok(Cursor::new(&buf)), &refs, &dir, &options, None)
+                .unwrap()
+        };
+
+        let summary = extract(false);
+        assert_eq!(summary.extracted, 3);
+        assert!(summary.failed.is_empty());
+        assert!(!dir.join("Data/Empty").exists());
+
+        let summary = extract(true);
+        assert_eq!(summary.extracted, 3);
+        assert!(dir.join("Data/Empty").is_dir());
+        assert_eq!(fs::read(dir.join("Data/zero.bin")).unwrap(), b"");
+        assert_eq!(fs::read(dir.join("Data/zero_zstd.bin")).unwrap(), b"");
+        assert_eq!(fs::read(dir.join("Data/full.bin")).unwrap(), b"content");
+    }
+
+    #[test]
+    fn test_extract_flatten_layout() {
+        let files: [(&str, &[u8]); 3] = [
+            ("Data/Ships/Aurora/hull.mtl", b"aurora"),
+            ("Data/Ships/Gladius/hull.mtl", b"gladius"),
+            ("Data/Ships/Gladius/HULL_1.mtl", b"upper"),
+        ];
+        let (buf, archive) = stored_archive(&files);
+        let entries = archive.entries;
+
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+
+        let options = ParallelExtractOptions { threads: 2, layout: ExtractLayout::Flatten, ..Default::default() };
+        let refs: Vec<&P4kEntry> = entries.iter().collect();
+        let summary = P4kParser::new()
+            .extract_parallel_with(|| Ok(Cursor::new(&buf)), &refs, dir, &options, None)
+            .unwrap();
+
+        assert_eq!(summary.extracted, 3);
+        assert_eq!(fs::read(dir.join("hull.mtl")).unwrap(), b"aurora");
+        assert_eq!(fs::read(dir.join("hull_1.mtl")).unwrap(), b"gladius");
+        // The literal `HULL_1.mtl` collides with the generated name and moves on
+        assert_eq!(fs::read(dir.join("HULL_1_1.mtl")).unwrap(), b"upper");
+        assert!(!dir.join("Data").exists());
+    }
+
+    #[test]
+    fn test_hash_prefix_layout() {
+        let (_, archive) = stored_archive(&[("Data/a/ship.cgf", b""), ("Data/b/ship.cgf", b"")]);
+        let entries = archive.entries;
+        let refs: Vec<&P4kEntry> = entries.iter().collect();
+        let paths = ExtractLayout::HashPrefix.output_paths(&refs);
+
+        assert_ne!(paths[0], paths[1]);
+        for path in &paths {
+            let shard = path.parent().unwrap().to_str().unwrap();
+            let name = path.file_name().unwrap().to_str().unwrap();
+            assert_eq!(shard.len(), 2);
+            assert!(name.starts_with(shard) && name.ends_with("_ship.cgf"));
+        }
+        assert_eq!(paths, ExtractLayout::HashPrefix.output_paths(&refs));
+    }
+}
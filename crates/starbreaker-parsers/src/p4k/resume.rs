@@ -0,0 +1,217 @@
+// starbreaker-parsers/src/p4k/resume.rs
+//! Resumable bulk extraction
+//!
+//! Extracting a full `Data.p4k` can take hours. The functions here record
+//! every completed entry in a JSON manifest so an interrupted run can pick up
+//! where it stopped instead of starting over.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{P4kArchive, P4kParser};
+use crate::traits::{ParseError, ParseResult, Parser};
+
+/// Number of newly extracted entries between manifest flushes
+const MANIFEST_FLUSH_INTERVAL: usize = 256;
+
+/// Record of the entries a previous extraction run completed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractionManifest {
+    /// Archive path to the CRC32 of the extracted data
+    pub entries: BTreeMap<String, u32>,
+}
+
+impl ExtractionManifest {
+    /// Load a manifest, returning an empty one if the file does not exist
+    pub fn load(path: impl AsRef<Path>) -> ParseResult<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read(path)?;
+        serde_json::from_slice(&data)
+            .map_err(|e| ParseError::InvalidStructure(format!("Invalid extraction manifest: {}", e)))
+    }
+
+    /// Write the manifest atomically via a temporary file and rename
+    pub fn save(&self, path: impl AsRef<Path>) -> ParseResult<()> {
+        let path = path.as_ref();
+        let tmp = partial_path(path);
+        let data = serde_json::to_vec_pretty(self)
+            .map_err(|e| ParseError::InvalidStructure(format!("Failed to serialize manifest: {}", e)))?;
+        fs::write(&tmp, data)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Outcome of a bulk extraction run (resumable or parallel)
+#[derive(Debug, Clone, Default)]
+pub struct ResumeProgress {
+    /// Entries extracted during this run
+    pub extracted: usize,
+    /// Entries skipped because the manifest and output file already matched
+    pub skipped: usize,
+    /// Entries that failed to extract, with the error message
+    pub failed: Vec<(String, String)>,
+}
+
+impl ResumeProgress {
+    /// Total number of file entries considered
+    pub fn total(&self) -> usize {
+        self.extracted + self.skipped + self.failed.len()
+    }
+}
+
+impl P4kParser {
+    /// Extract every file in an archive, resuming from `manifest_path` if present
+    ///
+    /// An entry is skipped only when the manifest lists it with the same CRC32
+    /// and the output file exists with the expected size. Anything else,
+    /// including files left half-written by an interrupted run, is extracted
+    /// again. Output is written to a `.part` file and renamed into place, so a
+    /// file at its final path is always complete.
+    pub fn extract_all_resumable(
+        &self,
+        archive_path: impl AsRef<Path>,
+        out_dir: impl AsRef<Path>,
+        manifest_path: impl AsRef<Path>,
+    ) -> ParseResult<ResumeProgress> {
+        let archive_path = archive_path.as_ref();
+        let archive = self.parse_file(archive_path)?;
+        let mut reader = BufReader::new(fs::File::open(archive_path)?);
+
+        self.extract_resumable(&mut reader, &archive, out_dir.as_ref(), manifest_path.as_ref())
+    }
+
+    /// Resumable extraction over an already parsed archive
+    pub(crate) fn extract_resumable<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        archive: &P4kArchive,
+        out_dir: &Path,
+        manifest_path: &Path,
+    ) -> ParseResult<ResumeProgress> {
+        let mut manifest = ExtractionManifest::load(manifest_path)?;
+        let mut progress = ResumeProgress::default();
+        let mut unsaved = 0;
+
+        fs::create_dir_all(out_dir)?;
+
+        for entry in archive.entries_iter().filter(|e| !e.is_directory) {
+            let output_path = out_dir.join(&entry.path);
+
+            let verified = manifest.entries.get(&entry.path) == Some(&entry.crc32)
+                && fs::metadata(&output_path)
+                    .map(|m| m.is_file() && m.len() == entry.uncompressed_size)
+                    .unwrap_or(false);
+            if verified {
+                progress.skipped += 1;
+                continue;
+            }
+
+            // Drop any stale record before rewriting the file
+            manifest.entries.remove(&entry.path);
+
+            let result = self.extract_data(reader, entry).and_then(|data| {
+                if let Some(parent) = output_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let tmp = partial_path(&output_path);
+                fs::write(&tmp, &data)?;
+                fs::rename(&tmp, &output_path)?;
+                Ok(())
+            });
+
+            match result {
+                Ok(()) => {
+                    manifest.entries.insert(entry.path.clone(), entry.crc32);
+                    progress.extracted += 1;
+                    unsaved += 1;
+                    if unsaved >= MANIFEST_FLUSH_INTERVAL {
+                        manifest.save(manifest_path)?;
+                        unsaved = 0;
+                    }
+                }
+                Err(e) => progress.failed.push((entry.path.clone(), e.to_string())),
+            }
+        }
+
+        manifest.save(manifest_path)?;
+        Ok(progress)
+    }
+}
+
+/// Sibling path used while a file is being written
+pub(super) fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p4k::P4kCompression;
+    use crate::testutil::stored_archive;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resume_extracts_only_remaining_entries() {
+        let files: [(&str, &[u8]); 4] = [
+            ("Data/a.xml", b"aaaa"),
+            ("Data/b.xml", b"bbbb"),
+            ("Data/Sub/c.xml", b"cccc"),
+            ("Data/Sub/d.xml", b"dddd"),
+        ];
+        let (buf, archive) = stored_archive(&files);
+
+        let dir = TempDir::new().unwrap();
+        let out_dir = dir.path().join("out");
+        let manifest_path = dir.path().join("manifest.json");
+        fs::create_dir_all(out_dir.join("Data/Sub")).unwrap();
+
+        // Simulate an interrupted run: the first half is recorded as done
+        // (with sentinel contents so a re-extraction would be visible), and
+        // c.xml was left half-written without a manifest record
+        let mut manifest = ExtractionManifest::default();
+        for (path, data) in &files[..2] {
+            fs::write(out_dir.join(path), b"zzzz").unwrap();
+            manifest.entries.insert(path.to_string(), P4kCompression::crc32(data));
+        }
+        fs::write(out_dir.join("Data/Sub/c.xml"), b"cc").unwrap();
+        manifest.save(&manifest_path).unwrap();
+
+        let parser = P4kParser::new();
+        let progress = parser
+            .extract_resumable(&mut Cursor::new(&buf), &archive, &out_dir, &manifest_path)
+            .unwrap();
+
+        assert_eq!(progress.skipped, 2);
+        assert_eq!(progress.extracted, 2);
+        assert!(progress.failed.is_empty());
+
+        // Completed entries were left alone, the rest were written
+        assert_eq!(fs::read(out_dir.join("Data/a.xml")).unwrap(), b"zzzz");
+        assert_eq!(fs::read(out_dir.join("Data/b.xml")).unwrap(), b"zzzz");
+        assert_eq!(fs::read(out_dir.join("Data/Sub/c.xml")).unwrap(), b"cccc");
+        assert_eq!(fs::read(out_dir.join("Data/Sub/d.xml")).unwrap(), b"dddd");
+
+        // The manifest now covers the whole archive
+        let manifest = ExtractionManifest::load(&manifest_path).unwrap();
+        assert_eq!(manifest.entries.len(), 4);
+
+        // A second run has nothing left to do
+        let progress = parser
+            .extract_resumable(&mut Cursor::new(&buf), &archive, &out_dir, &manifest_path)
+            .unwrap();
+        assert_eq!(progress.skipped, 4);
+        assert_eq!(progress.extracted, 0);
+    }
+}
@@ -0,0 +1,218 @@
+// starbreaker-parsers/src/p4k/validate.rs
+//! Archive integrity checks
+//!
+//! Walks every entry of a parsed archive and checks that its local header is
+//! where the central directory says, carries the right signature, and that
+//! its data fits inside the file. CRCs can additionally be recomputed for a
+//! sample of entries or for all of them, which means decompressing them.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use thiserror::Error;
+
+use super::{P4kArchive, P4kEntry, P4kParser, LOCAL_HEADER_SIGNATURE, LOCAL_HEADER_SIZE};
+use crate::traits::{ParseOptions, ParseResult, Parser};
+
+/// Which entries [`P4kParser::validate`] decompresses to check CRCs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CrcCheck {
+    /// Only check headers and offsets
+    #[default]
+    None,
+    /// Up to this many files, spread evenly over the archive
+    Sample(usize),
+    /// Every file
+    Full,
+}
+
+/// A problem found with one entry
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationProblem {
+    #[error("Local header offset {offset} is past the end of the {file_len}-byte file")]
+    OffsetOutOfRange { offset: u64, file_len: u64 },
+
+    #[error("Bad local header signature 0x{found:08X}")]
+    BadSignature { found: u32 },
+
+    #[error("Entry data ends at {end}, past the end of the {file_len}-byte file")]
+    DataOutOfRange { end: u64, file_len: u64 },
+
+    #[error("CRC mismatch: expected {expected:08X}, got {actual:08X}")]
+    CrcMismatch { expected: u32, actual: u32 },
+
+    #[error("Read failed: {0}")]
+    ReadFailed(String),
+}
+
+/// Outcome of [`P4kParser::validate`]
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// Entries whose headers were checked
+    pub checked: usize,
+    /// Entries whose CRC was recomputed
+    pub crc_checked: usize,
+    /// Problems by entry path, in archive order
+    pub problems: Vec<(String, ValidationProblem)>,
+}
+
+impl ValidationReport {
+    /// Whether no problems were found
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl P4kParser {
+    /// Parse the archive at `path` and check the integrity of every entry
+    ///
+    /// See [`validate`](Self::validate).
+    pub fn validate_file(&self, path: impl AsRef<Path>, crc: CrcCheck) -> ParseResult<ValidationReport> {
+        let path = path.as_ref();
+        let archive = self.parse_file(path)?;
+        let mut reader = BufReader::new(File::open(path)?);
+        self.validate(&mut reader, &archive, crc)
+    }
+
+    /// Check the integrity of every entry of `archive`, read from `reader`
+    ///
+    /// Header problems are reported per entry and never stop the pass;
+    /// only failing to measure the file is an error. Encrypted entries and
+    /// directories are never CRC-checked.
+    pub fn validate<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        archive: &P4kArchive,
+        crc: CrcCheck,
+    ) -> ParseResult<ValidationReport> {
+        self.validate_with_options(reader, archive, crc, &ParseOptions::default())
+    }
+
+    /// Check the integrity of every entry of `archive` under `options`
+    ///
+    /// See [`validate`](Self::validate).
+    pub fn validate_with_options<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        archive: &P4kArchive,
+        crc: CrcCheck,
+        options: &ParseOptions,
+    ) -> ParseResult<ValidationReport> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+
+        let files: Vec<usize> = archive.entries_iter()
+            .enumerate()
+            .filter(|(_, e)| !e.is_directory && !e.is_encrypted)
+            .map(|(idx, _)| idx)
+            .collect();
+        let mut verify_crc = vec![false; archive.entry_count()];
+        match crc {
+            CrcCheck::None => {}
+            CrcCheck::Sample(count) => {
+                let count = count.min(files.len());
+                for i in 0..count {
+                    verify_crc[files[i * files.len() / count]] = true;
+                }
+            }
+            CrcCheck::Full => files.iter().for_each(|&idx| verify_crc[idx] = true),
+        }
+
+        let mut report = ValidationReport::default();
+        for (entry, verify_crc) in archive.entries_iter().zip(verify_crc) {
+            report.checked += 1;
+            let result = self.check_entry(reader, entry, file_len, verify_crc, options);
+            // Entries failing before their data is read never had a CRC computed
+            if verify_crc && matches!(result, Ok(()) | Err(ValidationProblem::CrcMismatch { .. })) {
+                report.crc_checked += 1;
+            }
+            if let Err(problem) = result {
+                report.problems.push((entry.path.clone(), problem));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Check one entry's header, data range and optionally CRC
+    fn check_entry<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        entry: &P4kEntry,
+        file_len: u64,
+        verify_crc: bool,
+        options: &ParseOptions,
+    ) -> Result<(), ValidationProblem> {
+        let offset = entry.local_header_offset;
+        if offset.saturating_add(LOCAL_HEADER_SIZE as u64) > file_len {
+            return Err(ValidationProblem::OffsetOutOfRange { offset, file_len });
+        }
+
+        let read_failed = |e: io::Error| ValidationProblem::ReadFailed(e.to_string());
+        let mut signature = [0u8; 4];
+        reader.seek(SeekFrom::Start(offset)).map_err(read_failed)?;
+        reader.read_exact(&mut signature).map_err(read_failed)?;
+        let found = u32::from_le_bytes(signature);
+        if found != LOCAL_HEADER_SIGNATURE {
+            return Err(ValidationProblem::BadSignature { found });
+        }
+
+        let (expected, compressed_size, _) = self.seek_entry_data(reader, entry, options)
+            .map_err(|e| ValidationProblem::ReadFailed(e.to_string()))?;
+        let data_start = reader.stream_position().map_err(read_failed)?;
+        let end = data_start + compressed_size;
+        if end > file_len {
+            return Err(ValidationProblem::DataOutOfRange { end, file_len });
+        }
+
+        if verify_crc {
+            let actual = self.extract_entry_to_with_options(reader, entry, io::sink(), options)
+                .map_err(|e| ValidationProblem::ReadFailed(e.to_string()))?;
+            if actual != expected {
+                return Err(ValidationProblem::CrcMismatch { expected, actual });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::stored_archive;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_validate_flags_broken_entries() {
+        let (mut buf, mut archive) = stored_archive(&[
+            ("Data/good.xml", b"<ok/>"),
+            ("Data/moved.bin", b"payload"),
+            ("Data/corrupt.bin", b"checksum me"),
+            ("Data/missing.bin", b"gone"),
+        ]);
+        archive.entries[1].local_header_offset += 1;
+        let corrupt_data = archive.entries[2].local_header_offset as usize + 30 + "Data/corrupt.bin".len();
+        buf[corrupt_data] ^= 0xFF;
+        archive.entries[3].local_header_offset = buf.len() as u64 + 100;
+
+        let parser = P4kParser::new();
+        let report = parser.validate(&mut Cursor::new(&buf), &archive, CrcCheck::None).unwrap();
+        assert_eq!(report.checked, 4);
+        assert_eq!(report.crc_checked, 0);
+        let paths: Vec<&str> = report.problems.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(paths, ["Data/moved.bin", "Data/missing.bin"]);
+        assert!(matches!(report.problems[0].1, ValidationProblem::BadSignature { .. }));
+        assert!(matches!(report.problems[1].1, ValidationProblem::OffsetOutOfRange { .. }));
+
+        // Only the two entries whose data could be read count as CRC-checked
+        let report = parser.validate(&mut Cursor::new(&buf), &archive, CrcCheck::Full).unwrap();
+        assert_eq!(report.crc_checked, 2);
+        assert_eq!(report.problems.len(), 3);
+        assert_eq!(report.problems[1].0, "Data/corrupt.bin");
+        assert!(matches!(report.problems[1].1, ValidationProblem::CrcMismatch { .. }));
+
+        let report = parser.validate(&mut Cursor::new(&buf), &archive, CrcCheck::Sample(2)).unwrap();
+        assert_eq!(report.crc_checked, 2);
+    }
+}
@@ -0,0 +1,195 @@
+// starbreaker-parsers/src/p4k/volume.rs
+//! Multi-volume archive support
+//!
+//! Some distributions ship a P4K split into numbered volumes
+//! (`Data.p4k.001`, `Data.p4k.002`, ...) that are byte-for-byte pieces of
+//! one archive. [`MultiVolumeReader`] stitches them back into a single
+//! seekable stream so every offset in the central directory stays valid,
+//! including entries whose data crosses a volume boundary.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use super::{P4kArchive, P4kParser};
+use crate::traits::{ParseError, ParseResult, Parser};
+
+/// Presents several volume files as one contiguous `Read + Seek` stream
+pub struct MultiVolumeReader<R> {
+    volumes: Vec<R>,
+    /// Absolute offset at which each volume starts
+    starts: Vec<u64>,
+    /// Combined length of all volumes
+    len: u64,
+    pos: u64,
+}
+
+impl<R: Read + Seek> MultiVolumeReader<R> {
+    /// Join `volumes` in order, measuring each one's length
+    pub fn new(mut volumes: Vec<R>) -> io::Result<Self> {
+        let mut starts = Vec::with_capacity(volumes.len());
+        let mut len = 0u64;
+
+        for volume in &mut volumes {
+            starts.push(len);
+            len += volume.seek(SeekFrom::End(0))?;
+        }
+
+        Ok(Self {
+            volumes,
+            starts,
+            len,
+            pos: 0,
+        })
+    }
+
+    /// Number of volumes
+    pub fn volume_count(&self) -> usize {
+        self.volumes.len()
+    }
+
+    /// Combined length of all volumes
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Check if there is no data in any volume
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Index of the volume holding absolute offset `pos` (which must be below `len`)
+    fn volume_at(&self, pos: u64) -> usize {
+        // Empty volumes share a start with their successor; take the last one
+        self.starts.partition_point(|&start| start <= pos) - 1
+    }
+}
+
+impl MultiVolumeReader<BufReader<File>> {
+    /// Open volume files in the given order
+    pub fn open(paths: &[PathBuf]) -> io::Result<Self> {
+        let volumes = paths.iter()
+            .map(|path| File::open(path).map(BufReader::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        Self::new(volumes)
+    }
+}
+
+impl<R: Read + Seek> Read for MultiVolumeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.pos >= self.len {
+            return Ok(0);
+        }
+
+        // Reads stop at the end of the current volume; `read_exact` and
+        // friends simply call again to continue into the next one
+        let idx = self.volume_at(self.pos);
+        let volume_end = self.starts.get(idx + 1).copied().unwrap_or(self.len);
+        let available = (volume_end - self.pos).min(buf.len() as u64) as usize;
+
+        let volume = &mut self.volumes[idx];
+        volume.seek(SeekFrom::Start(self.pos - self.starts[idx]))?;
+        let read = volume.read(&mut buf[..available])?;
+
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Read + Seek> Seek for MultiVolumeReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        };
+
+        match target {
+            Some(target) => {
+                self.pos = target;
+                Ok(target)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl P4kParser {
+    /// Parse an archive split into several volume files
+    ///
+    /// `paths` must be in volume order. Entry offsets refer to the joined
+    /// stream, so extract with a [`MultiVolumeReader`] opened on the same
+    /// paths.
+    pub fn parse_volumes(&self, paths: &[PathBuf]) -> ParseResult<P4kArchive> {
+        if paths.is_empty() {
+            return Err(ParseError::InvalidStructure(
+                "No volumes provided".to_string()
+            ));
+        }
+
+        self.parse(MultiVolumeReader::open(paths)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p4k::LOCAL_HEADER_SIZE;
+    use crate::testutil::p4k_file;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    fn make_reader(parts: &[&[u8]]) -> MultiVolumeReader<Cursor<Vec<u8>>> {
+        MultiVolumeReader::new(parts.iter().map(|p| Cursor::new(p.to_vec())).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_reads_across_volumes() {
+        let mut reader = make_reader(&[b"abc", b"", b"defg", b"h"]);
+        assert_eq!(reader.len(), 8);
+        assert_eq!(reader.volume_count(), 4);
+
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(all, b"abcdefgh");
+
+        reader.seek(SeekFrom::Start(2)).unwrap();
+        let mut buf = [0u8; 5];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"cdefg");
+
+        assert_eq!(reader.seek(SeekFrom::End(-1)).unwrap(), 7);
+        assert_eq!(reader.seek(SeekFrom::Current(-4)).unwrap(), 3);
+        assert!(reader.seek(SeekFrom::Current(-4)).is_err());
+    }
+
+    #[test]
+    fn test_entry_spanning_volumes() {
+        let original = b"volume boundary payload ".repeat(8);
+        let name = "Data/split.bin";
+        let buf = p4k_file(&[(name, original.as_slice())]);
+        let data_start = LOCAL_HEADER_SIZE + name.len();
+
+        // Split in the middle of the entry's data
+        let dir = TempDir::new().unwrap();
+        let split = data_start + original.len() / 2;
+        let paths = vec![dir.path().join("Data.p4k.001"), dir.path().join("Data.p4k.002")];
+        std::fs::write(&paths[0], &buf[..split]).unwrap();
+        std::fs::write(&paths[1], &buf[split..]).unwrap();
+
+        let parser = P4kParser::new();
+        let archive = parser.parse_volumes(&paths).unwrap();
+        let entry = archive.get(name).unwrap();
+
+        let mut reader = MultiVolumeReader::open(&paths).unwrap();
+        let mut extracted = Vec::new();
+        let crc = parser.extract_entry_to(&mut reader, entry, &mut extracted).unwrap();
+
+        assert_eq!(extracted, original);
+        assert_eq!(crc, entry.crc32);
+        assert!(parser.parse_volumes(&[]).is_err());
+    }
+}
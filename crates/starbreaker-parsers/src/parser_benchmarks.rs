@@ -79,6 +79,7 @@ fn bench_pattern_matching(c: &mut Criterion) {
         
         entries.push(P4kEntry {
             path,
+            original_path: None,
             compression: CompressionMethod::Store,
             crc32: 0,
             compressed_size: 1000,
@@ -97,7 +98,7 @@ fn bench_pattern_matching(c: &mut Criterion) {
         path_index.insert(entry.path.clone(), idx);
     }
     
-    let archive = P4kArchive { entries, path_index };
+    let archive = P4kArchive { entries, path_index, dir_index: None };
 
     let mut group = c.benchmark_group("pattern_matching");
 
@@ -142,6 +143,7 @@ fn bench_tree_building(c: &mut Criterion) {
     for dir in &dirs {
         entries.push(P4kEntry {
             path: dir.to_string(),
+            original_path: None,
             compression: CompressionMethod::Store,
             crc32: 0,
             compressed_size: 0,
@@ -162,6 +164,7 @@ fn bench_tree_building(c: &mut Criterion) {
         
         entries.push(P4kEntry {
             path: format!("{}file_{}.cgf", base, i),
+            original_path: None,
             compression: CompressionMethod::Store,
             crc32: 0,
             compressed_size: 1000,
@@ -180,7 +183,7 @@ fn bench_tree_building(c: &mut Criterion) {
         path_index.insert(entry.path.clone(), idx);
     }
     
-    let archive = P4kArchive { entries, path_index };
+    let archive = P4kArchive { entries, path_index, dir_index: None };
 
     c.bench_function("build_tree", |b| {
         b.iter(|| archive.build_tree())
@@ -6,6 +6,7 @@
 //! modifying existing code.
 
 use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 use std::sync::{Arc, RwLock};
 
@@ -229,6 +230,61 @@ impl ParserRegistry {
         }).collect())
     }
 
+    /// List every parser whose magic bytes match the start of `prefix`
+    ///
+    /// Unlike [`get_for_path`](Self::get_for_path), which stops at the first
+    /// match, this returns all candidates (highest priority first, ties by
+    /// ID) so callers can offer an "open as..." choice for ambiguous data.
+    /// Parsers without magic bytes never match.
+    pub fn parsers_for_magic(&self, prefix: &[u8]) -> Result<Vec<ParserInfo>, RegistryError> {
+        let mut candidates = self.list()?;
+        candidates.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.id.cmp(&b.id)));
+
+        let mut matches = Vec::new();
+        for info in candidates {
+            let parser = self.get(&info.id)?;
+            if parser.magic_bytes().is_some_and(|magic| !magic.is_empty() && prefix.starts_with(magic)) {
+                matches.push(info);
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Identify the parser for a stream by magic bytes, without consuming it
+    ///
+    /// Reads just enough bytes from the current position to compare against
+    /// every registered magic, then seeks back to that position so the reader
+    /// can be handed straight to the parser. Returns the ID of the highest
+    /// priority match, or `None` if nothing matches or the reader fails.
+    pub fn detect_from_reader<R: Read + Seek>(&self, reader: &mut R) -> Option<String> {
+        let magic_len = self.list().ok()?.iter()
+            .filter_map(|info| self.get(&info.id).ok())
+            .filter_map(|parser| parser.magic_bytes().map(<[u8]>::len))
+            .max()?;
+
+        let start = reader.stream_position().ok()?;
+        let mut prefix = Vec::with_capacity(magic_len);
+        let read = reader.by_ref().take(magic_len as u64).read_to_end(&mut prefix);
+        reader.seek(SeekFrom::Start(start)).ok()?;
+        read.ok()?;
+
+        self.parsers_for_magic(&prefix).ok()?
+            .into_iter()
+            .next()
+            .map(|info| info.id)
+    }
+
+    /// Get a parser for a stream, the reader counterpart of [`get_for_path`](Self::get_for_path)
+    ///
+    /// Detection leaves the reader where it was, see
+    /// [`detect_from_reader`](Self::detect_from_reader).
+    pub fn get_for_reader<R: Read + Seek>(&self, reader: &mut R) -> Result<Arc<dyn AnyParser>, RegistryError> {
+        let id = self.detect_from_reader(reader)
+            .ok_or(RegistryError::NoParserForReader)?;
+        self.get(&id)
+    }
+
     /// Get typed parser instance
     pub fn get_typed<T: Parser + 'static>(&self, id: &str) -> Result<Arc<T>, RegistryError> {
         let parser = self.get(id)?;
@@ -282,6 +338,9 @@ pub enum RegistryError {
     #[error("No parser available for path: {0}")]
     NoParserForPath(std::path::PathBuf),
 
+    #[error("No parser recognizes the stream's magic bytes")]
+    NoParserForReader,
+
     #[error("Type mismatch: expected {expected}, found {found}")]
     TypeMismatch { expected: String, found: String },
 
@@ -395,7 +454,7 @@ impl Default for ParserRegistrationBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::{Read, Seek};
+    use std::io::Cursor;
     use crate::traits::{ParseOptions, ProgressCallback, ParseResult};
 
     // Mock parser for testing
@@ -422,6 +481,92 @@ mod tests {
         }
     }
 
+    // Mock parser identified by magic bytes
+    struct MagicParser;
+
+    impl Parser for MagicParser {
+        type Output = Vec<u8>;
+
+        fn extensions(&self) -> &[&str] {
+            &["magic"]
+        }
+
+        fn magic_bytes(&self) -> Option<&[u8]> {
+            Some(b"MOCK")
+        }
+
+        fn name(&self) -> &str {
+            "Magic Parser"
+        }
+
+        fn parse_with_options<R: Read + Seek>(
+            &self,
+            _reader: R,
+            _options: &ParseOptions,
+            _progress: Option<ProgressCallback>,
+        ) -> ParseResult<Self::Output> {
+            Ok(vec![4, 5, 6])
+        }
+    }
+
+    #[test]
+    fn test_parsers_for_magic_returns_all_in_priority_order() {
+        let registry = ParserRegistry::new();
+
+        for (id, priority) in [("magic_low", 1), ("magic_high", 50)] {
+            registry.register(ParserRegistrationBuilder::new()
+                .id(id)
+                .extensions(&["magic"])
+                .priority(priority)
+                .factory(|| MagicParser)
+                .build()
+                .unwrap()).unwrap();
+        }
+        registry.register(ParserRegistrationBuilder::new()
+            .id("mock")
+            .extensions(&["mock"])
+            .priority(100)
+            .factory(|| MockParser)
+            .build()
+            .unwrap()).unwrap();
+
+        let ids: Vec<_> = registry.parsers_for_magic(b"MOCK\x01\x02")
+            .unwrap()
+            .into_iter()
+            .map(|info| info.id)
+            .collect();
+        assert_eq!(ids, vec!["magic_high", "magic_low"]);
+
+        assert!(registry.parsers_for_magic(b"MOC").unwrap().is_empty());
+        assert!(registry.parsers_for_magic(b"NOPE").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detect_from_reader_restores_position() {
+        let registry = ParserRegistry::new();
+        for (id, priority, factory) in [
+            ("magic", 10, ParserRegistrationBuilder::new().factory(|| MagicParser)),
+            ("mock", 100, ParserRegistrationBuilder::new().factory(|| MockParser)),
+        ] {
+            registry.register(factory.id(id).extensions(&[id]).priority(priority).build().unwrap()).unwrap();
+        }
+
+        let mut reader = Cursor::new(b"..MOCK payload".to_vec());
+        reader.set_position(2);
+        assert_eq!(registry.detect_from_reader(&mut reader).as_deref(), Some("magic"));
+        assert_eq!(reader.position(), 2);
+        assert_eq!(registry.get_for_reader(&mut reader).unwrap().name(), "Magic Parser");
+        assert_eq!(reader.position(), 2);
+
+        // No match, and a stream shorter than the magic
+        reader.set_position(0);
+        assert_eq!(registry.detect_from_reader(&mut reader), None);
+        assert_eq!(reader.position(), 0);
+        let mut short = Cursor::new(b"MO".to_vec());
+        assert!(matches!(registry.get_for_reader(&mut short), Err(RegistryError::NoParserForReader)));
+        assert_eq!(short.position(), 0);
+    }
+
     #[test]
     fn test_registry_registration() {
         let registry = ParserRegistry::new();
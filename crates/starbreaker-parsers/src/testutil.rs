@@ -0,0 +1,287 @@
+//! Synthetic fixtures for tests
+//!
+//! Builds small P4K archives, DDS textures, CGF models and DataCores in memory so tests don't need
+//! game files. Compiled for this crate's own tests and, with the `test-util`
+//! feature, for the tests of crates depending on it.
+
+use std::collections::HashMap;
+
+use crate::cgf::{ChunkType, IVO_MAGIC};
+use crate::dds::{pf_flags, DdsHeader, DdsTexture, PixelFormat, TextureFormat, DDS_MAGIC};
+use crate::dcb::{DataCore, DataCoreHeader, DataType, PropertyDef, Record, RecordValue, StringEncoding, StringTable, StructDef};
+use crate::p4k::{
+    CompressionMethod, P4kArchive, P4kCompression, P4kEntry, CD_SIGNATURE, EOCD_SIGNATURE,
+    LOCAL_HEADER_SIGNATURE,
+};
+
+fn method_id(method: CompressionMethod) -> u16 {
+    match method {
+        CompressionMethod::Store => 0,
+        CompressionMethod::Deflate => 8,
+        CompressionMethod::Zstd => 93,
+        CompressionMethod::Lz4 => 99,
+        CompressionMethod::Unknown(id) => id,
+    }
+}
+
+/// Build a single local file header followed by `data`
+///
+/// `data` is written as-is and `uncompressed` only supplies the CRC and
+/// size, so callers compress it themselves for other methods. The entry's
+/// `local_header_offset` is 0.
+pub fn local_entry(path: &str, data: &[u8], method: CompressionMethod, uncompressed: &[u8]) -> (Vec<u8>, P4kEntry) {
+    let crc32 = P4kCompression::crc32(uncompressed);
+
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&LOCAL_HEADER_SIGNATURE.to_le_bytes());
+    buf.extend_from_slice(&20u16.to_le_bytes()); // version
+    buf.extend_from_slice(&0u16.to_le_bytes()); // flags
+    buf.extend_from_slice(&method_id(method).to_le_bytes());
+    buf.extend_from_slice(&[0u8; 4]); // mod time/date
+    buf.extend_from_slice(&crc32.to_le_bytes());
+    buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&(uncompressed.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&(path.len() as u16).to_le_bytes());
+    buf.extend_from_slice(&0u16.to_le_bytes()); // extra length
+    buf.extend_from_slice(path.as_bytes());
+    buf.extend_from_slice(data);
+
+    let entry = P4kEntry {
+        path: path.to_string(),
+        original_path: None,
+        compression: method,
+        crc32,
+        compressed_size: data.len() as u64,
+        uncompressed_size: uncompressed.len() as u64,
+        local_header_offset: 0,
+        flags: 0,
+        mod_time: 0,
+        mod_date: 0,
+        is_encrypted: false,
+        is_directory: false,
+    };
+
+    (buf, entry)
+}
+
+/// Central directory record for `entry`, with no extra field or comment
+///
+/// The name is written from `raw_path`, so entries parsed from backslash
+/// paths round-trip.
+pub fn cd_entry(entry: &P4kEntry) -> Vec<u8> {
+    let name = entry.raw_path();
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&CD_SIGNATURE.to_le_bytes());
+    buf.extend_from_slice(&[20, 0, 20, 0]); // versions
+    buf.extend_from_slice(&entry.flags.to_le_bytes());
+    buf.extend_from_slice(&method_id(entry.compression).to_le_bytes());
+    buf.extend_from_slice(&[0u8; 4]); // mod time/date
+    buf.extend_from_slice(&entry.crc32.to_le_bytes());
+    buf.extend_from_slice(&(entry.compressed_size as u32).to_le_bytes());
+    buf.extend_from_slice(&(entry.uncompressed_size as u32).to_le_bytes());
+    buf.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    buf.extend_from_slice(&[0u8; 12]); // extra/comment lengths, disk, attributes
+    buf.extend_from_slice(&(entry.local_header_offset as u32).to_le_bytes());
+    buf.extend_from_slice(name.as_bytes());
+    buf
+}
+
+/// EOCD record with no comment
+pub fn eocd(total_entries: u16, cd_size: u32, cd_offset: u32) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&EOCD_SIGNATURE.to_le_bytes());
+    buf.extend_from_slice(&[0u8; 4]); // disk numbers
+    buf.extend_from_slice(&total_entries.to_le_bytes());
+    buf.extend_from_slice(&total_entries.to_le_bytes());
+    buf.extend_from_slice(&cd_size.to_le_bytes());
+    buf.extend_from_slice(&cd_offset.to_le_bytes());
+    buf.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    buf
+}
+
+/// Stored entries laid out back to back, with the archive indexing them
+///
+/// There is no central directory; use [`p4k_file`] for bytes the parser
+/// can open.
+pub fn stored_archive(files: &[(&str, &[u8])]) -> (Vec<u8>, P4kArchive) {
+    let mut buf = Vec::new();
+    let mut archive = P4kArchive::new();
+
+    for (path, data) in files {
+        let (bytes, mut entry) = local_entry(path, data, CompressionMethod::Store, data);
+        entry.local_header_offset = buf.len() as u64;
+        buf.extend_from_slice(&bytes);
+        archive.entries.push(entry);
+    }
+    archive.rebuild_index();
+
+    (buf, archive)
+}
+
+/// Minimal P4K with stored entries: local headers, central directory, EOCD
+pub fn p4k_file(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let (mut buf, archive) = stored_archive(files);
+
+    let cd_offset = buf.len() as u32;
+    for entry in &archive.entries {
+        buf.extend_from_slice(&cd_entry(entry));
+    }
+    let cd_size = buf.len() as u32 - cd_offset;
+    buf.extend_from_slice(&eocd(files.len() as u16, cd_size, cd_offset));
+    buf
+}
+
+/// Header of a square `size`x`size` texture with `mips` levels in a FourCC format
+pub fn dds_header(fourcc: [u8; 4], size: u32, mips: u32) -> DdsHeader {
+    DdsHeader {
+        size: 124,
+        flags: 0,
+        height: size,
+        width: size,
+        pitch_or_linear_size: 0,
+        depth: 0,
+        mipmap_count: mips,
+        reserved1: [0; 11],
+        pixel_format: PixelFormat {
+            size: 32,
+            flags: pf_flags::FOURCC,
+            fourcc,
+            rgb_bit_count: 0,
+            r_bit_mask: 0,
+            g_bit_mask: 0,
+            b_bit_mask: 0,
+            a_bit_mask: 0,
+        },
+        caps: 0,
+        caps2: 0,
+        caps3: 0,
+        caps4: 0,
+        reserved2: 0,
+    }
+}
+
+/// DDS file bytes: the "DDS " magic, `header` and then `data` as-is
+pub fn dds_file(header: &DdsHeader, data: &[u8]) -> Vec<u8> {
+    let mut buf = DDS_MAGIC.to_le_bytes().to_vec();
+    header.write(&mut buf).unwrap();
+    buf.extend_from_slice(data);
+    buf
+}
+
+/// Square BC3 texture with a full chain of `mips` levels
+///
+/// The data counts up byte by byte so each level's contents differ.
+pub fn bc3_texture(size: u32, mips: u32) -> DdsTexture {
+    // 16 bytes per 4x4 block, e.g. 256 + 64 + 16 for a 16x16 3-mip chain
+    let data_size: u32 = (0..mips)
+        .map(|level| {
+            let blocks = (size >> level).max(1).div_ceil(4);
+            blocks * blocks * 16
+        })
+        .sum();
+
+    DdsTexture {
+        header: dds_header(*b"DXT5", size, mips),
+        dx10_header: None,
+        data: (0..data_size).map(|i| i as u8).collect(),
+        format: TextureFormat::BC3,
+        was_split: false,
+    }
+}
+
+/// Ivo header and chunk table for `(type, id, offset, size)` entries;
+/// callers append the chunk data, which starts at `16 + 20 * chunks.len()`
+pub fn ivo_file(chunks: &[(ChunkType, u32, u32, u32)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(IVO_MAGIC);
+    data.extend_from_slice(&0x900u32.to_le_bytes()); // version
+    data.extend_from_slice(&(chunks.len() as u32).to_le_bytes()); // chunk count
+    data.extend_from_slice(&16u32.to_le_bytes()); // chunk table offset
+    for &(chunk_type, id, offset, size) in chunks {
+        data.extend_from_slice(&chunk_type.to_u32().to_le_bytes());
+        data.extend_from_slice(&0x800u32.to_le_bytes()); // chunk version
+        data.extend_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(&id.to_le_bytes());
+        data.extend_from_slice(&size.to_le_bytes());
+    }
+    data
+}
+
+/// Builds a small in-memory DataCore
+///
+/// Struct and record ids follow insertion order, and each struct's
+/// properties are appended to one shared property table as in real files.
+#[derive(Debug, Default)]
+pub struct DataCoreBuilder {
+    structs: Vec<StructDef>,
+    properties: Vec<PropertyDef>,
+    records: Vec<Record>,
+}
+
+impl DataCoreBuilder {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a struct with its own properties, returning its id
+    pub fn add_struct(&mut self, name: &str, parent_id: Option<u32>, properties: &[(&str, DataType)]) -> u32 {
+        let id = self.structs.len() as u32;
+        self.structs.push(StructDef {
+            id,
+            name: name.to_string(),
+            parent_id,
+            property_start: self.properties.len() as u32,
+            property_count: properties.len() as u32,
+            size: 0,
+            flags: 0,
+        });
+        for (name, data_type) in properties {
+            self.properties.push(PropertyDef {
+                id: self.properties.len() as u32,
+                name: name.to_string(),
+                data_type: data_type.clone(),
+                struct_id: None,
+                conversion: 0,
+            });
+        }
+        id
+    }
+
+    /// Add a record of `struct_id` with the given values, returning its id
+    pub fn add_record<'a>(
+        &mut self,
+        struct_id: u32,
+        name: &str,
+        guid: u64,
+        values: impl IntoIterator<Item = (&'a str, RecordValue)>,
+    ) -> u32 {
+        let id = self.records.len() as u32;
+        self.records.push(Record {
+            id,
+            struct_id,
+            name: name.to_string(),
+            guid,
+            values: values.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<HashMap<_, _>>(),
+        });
+        id
+    }
+
+    /// Assemble the DataCore with a version 5, UTF-8 header
+    pub fn build(self) -> DataCore {
+        let header = DataCoreHeader {
+            version: 5,
+            struct_count: self.structs.len() as u32,
+            property_count: self.properties.len() as u32,
+            record_count: self.records.len() as u32,
+            string_offset: 0,
+            struct_offset: 0,
+            property_offset: 0,
+            record_offset: 0,
+            value_offset: None,
+            enum_offset: None,
+            string_encoding: StringEncoding::Utf8,
+        };
+        DataCore::from_parts(header, StringTable::default(), self.structs, self.properties, self.records)
+    }
+}
@@ -9,7 +9,10 @@
 
 use std::io::{Read, Seek};
 use std::path::Path;
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use thiserror::Error;
 
@@ -52,6 +55,16 @@ pub enum ParseError {
         #[source]
         source: Box<ParseError>,
     },
+
+    #[error("{source} (at offset 0x{offset:X})")]
+    AtOffset {
+        offset: u64,
+        #[source]
+        source: Box<ParseError>,
+    },
+
+    #[error("Parsing was cancelled")]
+    Cancelled,
 }
 
 impl ParseError {
@@ -62,6 +75,29 @@ impl ParseError {
             source: Box::new(self),
         }
     }
+
+    /// Record the file offset where this error occurred
+    ///
+    /// Errors that already carry an offset keep it, so the innermost (most
+    /// precise) position wins when read sites are nested.
+    pub fn at_offset(self, offset: u64) -> Self {
+        if self.offset().is_some() {
+            return self;
+        }
+        ParseError::AtOffset {
+            offset,
+            source: Box::new(self),
+        }
+    }
+
+    /// File offset where the error occurred, if known
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            ParseError::AtOffset { offset, .. } | ParseError::CorruptedData { offset, .. } => Some(*offset),
+            ParseError::Nested { source, .. } => source.offset(),
+            _ => None,
+        }
+    }
 }
 
 /// Result type alias for parsing operations
@@ -100,6 +136,192 @@ impl ParseProgress {
     }
 }
 
+/// Time-based throttle around a [`ProgressCallback`]
+///
+/// Forwards an event only when at least `interval` has elapsed since the last
+/// forwarded one, when it is the final item (`items_processed == total_items`),
+/// or when the phase is `Complete`. Convert it with [`ProgressThrottle::into_callback`]
+/// to pass it to any parser that accepts a `ProgressCallback`.
+pub struct ProgressThrottle {
+    inner: ProgressCallback,
+    interval: Duration,
+    last_forwarded: Mutex<Option<Instant>>,
+}
+
+impl ProgressThrottle {
+    /// Wrap `inner`, forwarding at most once per `interval`
+    pub fn new(inner: ProgressCallback, interval: Duration) -> Self {
+        Self {
+            inner,
+            interval,
+            last_forwarded: Mutex::new(None),
+        }
+    }
+
+    /// Shorthand for `ProgressThrottle::new(inner, interval).into_callback()`
+    pub fn wrap(inner: ProgressCallback, interval: Duration) -> ProgressCallback {
+        Self::new(inner, interval).into_callback()
+    }
+
+    /// Feed one progress event through the throttle
+    pub fn report(&self, progress: ParseProgress) {
+        let is_final = progress.phase == ParsePhase::Complete
+            || progress.total_items.is_some_and(|total| progress.items_processed >= total);
+
+        let now = Instant::now();
+        {
+            let mut last = self.last_forwarded.lock().unwrap_or_else(|e| e.into_inner());
+            let due = last.is_none_or(|at| now.duration_since(at) >= self.interval);
+            if !is_final && !due {
+                return;
+            }
+            *last = Some(now);
+        }
+
+        (self.inner)(progress);
+    }
+
+    /// Turn the throttle into a plain callback
+    pub fn into_callback(self) -> ProgressCallback {
+        Box::new(move |progress| self.report(progress))
+    }
+}
+
+impl From<ProgressThrottle> for ProgressCallback {
+    fn from(throttle: ProgressThrottle) -> Self {
+        throttle.into_callback()
+    }
+}
+
+/// Rate and remaining-time estimate attached to a progress event
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProgressEstimate {
+    /// Units (bytes, or items when only an item total is known) per second
+    pub throughput: Option<f64>,
+    /// Estimated time until the current phase finishes
+    pub eta: Option<Duration>,
+}
+
+/// Callback receiving progress events together with an estimate
+pub type EstimatedProgressCallback = Box<dyn Fn(ParseProgress, ProgressEstimate) + Send + Sync>;
+
+/// ETA and throughput estimator for [`ParseProgress`] events
+///
+/// Rates are measured over the last few events of the current phase, so a
+/// slow header read doesn't skew the estimate for record parsing. Progress
+/// is tracked in bytes when `total_bytes` is known and in items otherwise;
+/// without either total there is a throughput but no ETA.
+pub struct ProgressEstimator {
+    window: usize,
+    state: Mutex<EstimatorState>,
+}
+
+#[derive(Default)]
+struct EstimatorState {
+    phase: Option<ParsePhase>,
+    samples: VecDeque<(Instant, u64)>,
+    total: Option<u64>,
+}
+
+impl ProgressEstimator {
+    /// Number of recent events used for smoothing by default
+    pub const DEFAULT_WINDOW: usize = 8;
+
+    /// Create an estimator smoothing over [`DEFAULT_WINDOW`](Self::DEFAULT_WINDOW) events
+    pub fn new() -> Self {
+        Self::with_window(Self::DEFAULT_WINDOW)
+    }
+
+    /// Create an estimator smoothing over the last `window` events (at least 2)
+    pub fn with_window(window: usize) -> Self {
+        Self {
+            window: window.max(2),
+            state: Mutex::new(EstimatorState::default()),
+        }
+    }
+
+    /// Record an event received now
+    pub fn record(&self, progress: &ParseProgress) {
+        self.record_at(progress, Instant::now());
+    }
+
+    /// Record an event received at `at`
+    pub fn record_at(&self, progress: &ParseProgress, at: Instant) {
+        let (done, total) = match (progress.total_bytes, progress.total_items) {
+            (Some(total), _) => (progress.bytes_processed, Some(total)),
+            (None, Some(total)) => (progress.items_processed, Some(total)),
+            (None, None) => (progress.bytes_processed, None),
+        };
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.phase != Some(progress.phase) {
+            state.phase = Some(progress.phase);
+            state.samples.clear();
+        }
+        state.total = total;
+        state.samples.push_back((at, done));
+        while state.samples.len() > self.window {
+            state.samples.pop_front();
+        }
+    }
+
+    /// Units per second over the smoothing window
+    pub fn throughput(&self) -> Option<f64> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        Self::rate(&state)
+    }
+
+    /// Estimated time remaining in the current phase
+    ///
+    /// `None` when the total is unknown or no progress has been measured yet.
+    pub fn eta(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let total = state.total?;
+        let &(_, done) = state.samples.back()?;
+        let remaining = total.saturating_sub(done);
+        if remaining == 0 {
+            return Some(Duration::ZERO);
+        }
+
+        let rate = Self::rate(&state).filter(|&r| r > 0.0)?;
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+
+    /// Current throughput and ETA
+    pub fn estimate(&self) -> ProgressEstimate {
+        ProgressEstimate {
+            throughput: self.throughput(),
+            eta: self.eta(),
+        }
+    }
+
+    /// Wrap `inner` so every event is recorded and forwarded with an estimate
+    pub fn wrap(self, inner: EstimatedProgressCallback) -> ProgressCallback {
+        Box::new(move |progress| {
+            self.record(&progress);
+            let estimate = self.estimate();
+            inner(progress, estimate);
+        })
+    }
+
+    fn rate(state: &EstimatorState) -> Option<f64> {
+        let &(first_at, first_done) = state.samples.front()?;
+        let &(last_at, last_done) = state.samples.back()?;
+
+        let elapsed = last_at.checked_duration_since(first_at)?.as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        Some(last_done.saturating_sub(first_done) as f64 / elapsed)
+    }
+}
+
+impl Default for ProgressEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Phases of the parsing process
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParsePhase {
@@ -119,6 +341,67 @@ pub enum ParsePhase {
     Complete,
 }
 
+/// A recoverable problem encountered while parsing
+///
+/// Produced by the `parse_with_report` methods for chunks or records that
+/// could not be read but did not stop the rest of the file from parsing.
+#[derive(Debug, Clone)]
+pub struct ParseWarning {
+    /// File offset of the chunk or record that failed
+    pub offset: u64,
+    /// What was being parsed (e.g. "Mesh chunk 3")
+    pub context: String,
+    /// Why it failed
+    pub reason: String,
+}
+
+impl ParseWarning {
+    /// Create a warning from the error that caused it
+    pub fn new(offset: u64, context: impl Into<String>, error: &ParseError) -> Self {
+        Self {
+            offset,
+            context: context.into(),
+            reason: error.to_string(),
+        }
+    }
+
+    /// Create a warning for non-finite floats replaced by `sanitize_floats`
+    pub fn sanitized(offset: u64, context: impl Into<String>) -> Self {
+        Self {
+            offset,
+            context: context.into(),
+            reason: "non-finite float replaced with 0.0".to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at offset {}: {}", self.context, self.offset, self.reason)
+    }
+}
+
+/// Parsed output together with any recoverable warnings
+#[derive(Debug)]
+pub struct ParseReport<T> {
+    /// The (possibly partial) parse result
+    pub output: T,
+    /// Chunks or records that were skipped
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl<T> ParseReport<T> {
+    /// Check if the parse completed without any warnings
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Discard the warnings and return the output
+    pub fn into_output(self) -> T {
+        self.output
+    }
+}
+
 /// Configuration options for parsing
 #[derive(Debug, Clone)]
 pub struct ParseOptions {
@@ -136,6 +419,28 @@ pub struct ParseOptions {
     pub use_memory_mapping: bool,
     /// Minimum file size to enable memory mapping
     pub memory_mapping_threshold: u64,
+    /// Whether to replace NaN/Inf float values with 0.0
+    ///
+    /// Replacements are reported as warnings by the `parse_with_report` methods.
+    pub sanitize_floats: bool,
+    /// Whether mesh color streams are stored as BGRA and should be swizzled to RGBA
+    ///
+    /// CryEngine writes vertex colors in BGRA order; leave this off for assets
+    /// that were already converted.
+    pub bgra_vertex_colors: bool,
+    /// Whether skin weights from a separate CompiledIntSkinVertices chunk are
+    /// merged onto compiled meshes that carry none of their own
+    pub merge_int_skin_vertices: bool,
+    /// Threads used to decode independent chunks of one file
+    ///
+    /// `1` parses serially from the reader; anything else reads the whole
+    /// file into memory first, with `0` sharing rayon's global pool.
+    pub thread_count: usize,
+    /// Flag another thread can set to stop a long parse early
+    ///
+    /// Checked between entries by parsers that loop over many of them; once
+    /// set, parsing stops with [`ParseError::Cancelled`].
+    pub cancel: Option<Arc<AtomicBool>>,
 }
 
 impl Default for ParseOptions {
@@ -148,10 +453,82 @@ impl Default for ParseOptions {
             decompression_memory_limit: 512 * 1024 * 1024, // 512 MB
             use_memory_mapping: true,
             memory_mapping_threshold: 10 * 1024 * 1024, // 10 MB
+            sanitize_floats: false,
+            bgra_vertex_colors: false,
+            merge_int_skin_vertices: true,
+            thread_count: 1,
+            cancel: None,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Preset favouring throughput over checking
+    ///
+    /// Disables strict validation, skips unknown chunks, memory maps
+    /// anything above 1 MB and decodes chunks on every core.
+    pub fn fast() -> Self {
+        Self {
+            strict_validation: false,
+            skip_unknown_chunks: true,
+            use_memory_mapping: true,
+            memory_mapping_threshold: 1024 * 1024, // 1 MB
+            thread_count: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Preset favouring completeness over speed
+    ///
+    /// Enables strict validation, follows nested files to the full default
+    /// depth and sanitizes non-finite floats. Unknown chunks are still skipped,
+    /// since real game files carry chunk types no parser handles yet; use
+    /// `CgfModel::chunk_coverage` to see what was left out. Tangent generation
+    /// is not a parse-time step; request it through the exporter options instead.
+    pub fn thorough() -> Self {
+        Self {
+            strict_validation: true,
+            parse_nested: true,
+            skip_unknown_chunks: true,
+            sanitize_floats: true,
+            ..Default::default()
+        }
+    }
+
+    /// Preset for untrusted or possibly corrupt input
+    ///
+    /// Enables strict validation, disables memory mapping, caps decompression
+    /// buffers at 64 MB and limits nesting to 8 levels.
+    pub fn safe() -> Self {
+        Self {
+            strict_validation: true,
+            max_nesting_depth: 8,
+            decompression_memory_limit: 64 * 1024 * 1024, // 64 MB
+            use_memory_mapping: false,
+            sanitize_floats: true,
+            ..Default::default()
+        }
+    }
+
+    /// Fail with [`ParseError::Cancelled`] if the `cancel` flag has been set
+    pub fn check_cancelled(&self) -> ParseResult<()> {
+        match &self.cancel {
+            Some(flag) if flag.load(Ordering::Relaxed) => Err(ParseError::Cancelled),
+            _ => Ok(()),
         }
     }
 }
 
+/// Replace non-finite values with 0.0, returning whether any were replaced
+pub(crate) fn sanitize_f32s(values: &mut [f32]) -> bool {
+    let mut replaced = false;
+    for v in values.iter_mut().filter(|v| !v.is_finite()) {
+        *v = 0.0;
+        replaced = true;
+    }
+    replaced
+}
+
 /// Core trait for all file format parsers
 /// 
 /// Implementors of this trait provide the ability to parse a specific
@@ -395,4 +772,127 @@ mod tests {
             _ => panic!("Expected Nested error"),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_error_offset() {
+        let error = ParseError::DecompressionFailed("bad block".to_string()).at_offset(0x40);
+        assert_eq!(error.offset(), Some(0x40));
+        assert_eq!(error.to_string(), "Decompression failed: bad block (at offset 0x40)");
+
+        // The inner offset is kept when wrapped again
+        let error = error.at_offset(0x10).with_context("extracting entry");
+        assert_eq!(error.offset(), Some(0x40));
+
+        assert_eq!(ParseError::MissingField("name".to_string()).offset(), None);
+    }
+
+    #[test]
+    fn test_parse_options_fast_preset() {
+        let options = ParseOptions::fast();
+        assert!(!options.strict_validation);
+        assert!(options.skip_unknown_chunks);
+        assert!(options.use_memory_mapping);
+        assert_eq!(options.memory_mapping_threshold, 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_options_thorough_preset() {
+        let options = ParseOptions::thorough();
+        assert!(options.strict_validation);
+        assert!(options.parse_nested);
+        assert!(options.skip_unknown_chunks);
+        assert!(options.sanitize_floats);
+    }
+
+    #[test]
+    fn test_parse_options_safe_preset() {
+        let options = ParseOptions::safe();
+        assert!(options.strict_validation);
+        assert!(!options.use_memory_mapping);
+        assert_eq!(options.max_nesting_depth, 8);
+        assert_eq!(options.decompression_memory_limit, 64 * 1024 * 1024);
+        assert!(options.decompression_memory_limit < ParseOptions::default().decompression_memory_limit);
+    }
+
+    #[test]
+    fn test_progress_throttle_drops_intermediate_events() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let callback = ProgressThrottle::wrap(
+            Box::new(move |p: ParseProgress| sink.lock().unwrap().push((p.phase, p.items_processed))),
+            Duration::from_secs(60),
+        );
+
+        let event = |phase, items_processed| ParseProgress {
+            phase,
+            bytes_processed: 0,
+            total_bytes: None,
+            current_item: None,
+            items_processed,
+            total_items: Some(1000),
+        };
+
+        for i in 0..1000 {
+            callback(event(ParsePhase::ParsingRecords, i));
+        }
+        callback(event(ParsePhase::ParsingRecords, 1000));
+        callback(event(ParsePhase::Complete, 1000));
+
+        let seen = seen.lock().unwrap();
+        // First event, the final item, and Complete; everything in between dropped
+        assert_eq!(
+            *seen,
+            vec![
+                (ParsePhase::ParsingRecords, 0),
+                (ParsePhase::ParsingRecords, 1000),
+                (ParsePhase::Complete, 1000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_progress_estimator_eta_and_throughput() {
+        let estimator = ProgressEstimator::with_window(4);
+        let start = Instant::now();
+        let event = |phase, items_processed, total_items| ParseProgress {
+            phase,
+            bytes_processed: 0,
+            total_bytes: None,
+            current_item: None,
+            items_processed,
+            total_items,
+        };
+
+        assert_eq!(estimator.throughput(), None);
+        assert_eq!(estimator.eta(), None);
+
+        // Speeding up: 100, 200, 300, 400 items per 100 ms step
+        let mut items = 0;
+        let mut last_throughput = 0.0;
+        for (step, batch) in [0u64, 100, 200, 300, 400].into_iter().enumerate() {
+            items += batch;
+            let at = start + Duration::from_millis(100 * step as u64);
+            estimator.record_at(&event(ParsePhase::ParsingRecords, items, Some(10_000)), at);
+
+            if let Some(throughput) = estimator.throughput() {
+                assert!(throughput >= last_throughput, "{} < {}", throughput, last_throughput);
+                last_throughput = throughput;
+            }
+        }
+
+        // Window holds the last four samples: 100..=1000 items over 300 ms
+        let throughput = estimator.throughput().unwrap();
+        assert!((throughput - 3000.0).abs() < 1.0, "{}", throughput);
+        let eta = estimator.eta().unwrap();
+        assert!((eta.as_secs_f64() - 3.0).abs() < 0.01, "{:?}", eta);
+
+        // A new phase starts measuring from scratch
+        estimator.record_at(&event(ParsePhase::LinkingReferences, 0, Some(50)), start + Duration::from_secs(1));
+        assert_eq!(estimator.throughput(), None);
+
+        // Unknown totals give a rate but no ETA
+        estimator.record_at(&event(ParsePhase::Validating, 0, None), start);
+        estimator.record_at(&event(ParsePhase::Validating, 0, None), start + Duration::from_secs(1));
+        assert_eq!(estimator.eta(), None);
+    }
+}
@@ -20,6 +20,7 @@ use crate::traits::{Parser, RandomAccessParser, ParseOptions};
 fn make_entry(path: &str, compressed: u64, uncompressed: u64, is_dir: bool) -> P4kEntry {
     P4kEntry {
         path: path.to_string(),
+        original_path: None,
         compression: CompressionMethod::Store,
         crc32: 0xDEADBEEF,
         compressed_size: compressed,
@@ -57,7 +58,7 @@ fn make_test_archive() -> P4kArchive {
         path_index.insert(entry.path.clone(), idx);
     }
 
-    P4kArchive { entries, path_index }
+    P4kArchive { entries, path_index, dir_index: None }
 }
 
 mod entry_tests {
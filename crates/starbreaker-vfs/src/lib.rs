@@ -4,15 +4,19 @@
 //! including local filesystem, P4K archives, and DCB virtual folders.
 
 use std::collections::HashMap;
+use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use parking_lot::RwLock;
+use starbreaker_parsers::ParseOptions;
 use thiserror::Error;
 
 pub mod mounts;
+pub mod texture;
 
 pub use mounts::p4k::P4kMountPoint;
+pub use texture::LazyDdsTexture;
 
 /// VFS errors
 #[derive(Error, Debug)]
@@ -41,6 +45,9 @@ pub enum VfsError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    #[error("Parse error: {0}")]
+    Parse(#[from] starbreaker_parsers::ParseError),
+
     #[error("Invalid path: {0}")]
     InvalidPath(String),
 }
@@ -80,6 +87,23 @@ pub struct VfsEntry {
     pub compressed_size: Option<u64>,
 }
 
+impl From<VfsNode> for VfsEntry {
+    fn from(node: VfsNode) -> Self {
+        Self {
+            name: node.name,
+            path: node.path,
+            is_directory: node.is_directory,
+            size: if node.is_directory { None } else { Some(node.size) },
+            compressed_size: node.compressed_size,
+        }
+    }
+}
+
+/// A seekable byte stream returned by [`MountPoint::open_reader`]
+pub trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
 /// Trait for mount point implementations
 pub trait MountPoint: Send + Sync {
     /// Get the mount path
@@ -103,15 +127,42 @@ pub trait MountPoint: Send + Sync {
     /// Read file as string
     fn read_to_string(&self, path: &Path) -> VfsResult<String>;
 
+    /// Open a file for streaming reads
+    ///
+    /// Lets parsers consume a file without the mount handing over a full
+    /// copy first. The default buffers via [`read`](Self::read); mounts
+    /// backed by real files should override it.
+    fn open_reader(&self, path: &Path) -> VfsResult<Box<dyn ReadSeek>> {
+        Ok(Box::new(std::io::Cursor::new(self.read(path)?)))
+    }
+
     /// List directory contents
     fn list(&self, path: &Path) -> VfsResult<Vec<VfsEntry>>;
 
     /// Get file/directory metadata
     fn metadata(&self, path: &Path) -> VfsResult<VfsNode>;
 
+    /// Get metadata for several paths at once, in the same order
+    ///
+    /// The default calls [`metadata`](Self::metadata) per path; mounts with
+    /// an in-memory index can override it to resolve everything in one pass.
+    fn metadata_batch(&self, paths: &[&Path]) -> Vec<VfsResult<VfsNode>> {
+        paths.iter().map(|path| self.metadata(path)).collect()
+    }
+
     /// Find files matching a pattern
     fn find(&self, pattern: &str) -> VfsResult<Vec<PathBuf>>;
 
+    /// Find up to `limit` matches starting at match `offset`, plus the total
+    ///
+    /// The default pages over [`find`](Self::find); mounts with an in-memory
+    /// index can override it to count matches without collecting them.
+    fn find_paged(&self, pattern: &str, offset: usize, limit: usize) -> VfsResult<(Vec<PathBuf>, usize)> {
+        let found = self.find(pattern)?;
+        let total = found.len();
+        Ok((found.into_iter().skip(offset).take(limit).collect(), total))
+    }
+
     /// Write file contents (optional, returns error for read-only mounts)
     fn write(&self, _path: &Path, _data: &[u8]) -> VfsResult<()> {
         Err(VfsError::ReadOnly)
@@ -170,6 +221,20 @@ impl Vfs {
         Ok(())
     }
 
+    /// Parse the P4K archive at `archive_path` and mount it at `mount_point`
+    ///
+    /// Archive mounts are read-only. Parse failures come back as
+    /// [`VfsError::Parse`] naming the archive.
+    pub fn mount_archive(&self, archive_path: &Path, mount_point: &Path, options: &ParseOptions) -> VfsResult<()> {
+        let mount = P4kMountPoint::with_options(archive_path, mount_point, options).map_err(|e| match e {
+            mounts::p4k::P4kMountError::ParseFailed(e) => {
+                VfsError::Parse(e.with_context(format!("Mounting {}", archive_path.display())))
+            }
+            other => other.into(),
+        })?;
+        self.mount(mount)
+    }
+
     /// Unmount a mount point by path
     pub fn unmount(&self, path: &Path) -> VfsResult<()> {
         let mut mounts = self.mounts.write();
@@ -202,6 +267,21 @@ impl Vfs {
             .unwrap_or(false)
     }
 
+    /// Return the first of `candidates` that exists, trying them in order
+    ///
+    /// Useful for fallback lookups such as a texture stored either whole or
+    /// as split `.dds.1` parts.
+    pub fn first_existing(&self, candidates: &[&Path]) -> Option<PathBuf> {
+        candidates.iter()
+            .find(|path| self.exists(path))
+            .map(|path| path.to_path_buf())
+    }
+
+    /// Check if any of `candidates` exists
+    pub fn exists_any(&self, candidates: &[&Path]) -> bool {
+        candidates.iter().any(|path| self.exists(path))
+    }
+
     /// Check if a path is a file
     pub fn is_file(&self, path: &Path) -> bool {
         self.get_mount(path)
@@ -230,6 +310,13 @@ impl Vfs {
             .read_to_string(path)
     }
 
+    /// Open a file for streaming reads
+    pub fn open_reader(&self, path: &Path) -> VfsResult<Box<dyn ReadSeek>> {
+        self.get_mount(path)
+            .ok_or_else(|| VfsError::NoMountPoint(path.to_path_buf()))?
+            .open_reader(path)
+    }
+
     /// List directory contents
     pub fn list(&self, path: &Path) -> VfsResult<Vec<VfsEntry>> {
         self.get_mount(path)
@@ -244,6 +331,34 @@ impl Vfs {
             .metadata(path)
     }
 
+    /// Get metadata for several paths, taking the mount table lock once
+    ///
+    /// Consecutive paths on the same mount are handed to that mount's
+    /// [`MountPoint::metadata_batch`] together.
+    pub fn metadata_batch(&self, paths: &[&Path]) -> Vec<VfsResult<VfsNode>> {
+        let mounts = self.mounts.read();
+        let mount_for = |path: &Path| mounts.iter().position(|m| path.starts_with(m.mount_path()));
+
+        let mut results = Vec::with_capacity(paths.len());
+        let mut start = 0;
+        while start < paths.len() {
+            let mount = mount_for(paths[start]);
+            let end = paths[start..].iter()
+                .position(|p| mount_for(p) != mount)
+                .map_or(paths.len(), |offset| start + offset);
+
+            match mount {
+                Some(idx) => results.extend(mounts[idx].metadata_batch(&paths[start..end])),
+                None => results.extend(
+                    paths[start..end].iter().map(|p| Err(VfsError::NoMountPoint(p.to_path_buf())))
+                ),
+            }
+            start = end;
+        }
+
+        results
+    }
+
     /// Find files matching a pattern across all mounts
     pub fn find(&self, pattern: &str) -> VfsResult<Vec<PathBuf>> {
         let mounts = self.mounts.read();
@@ -258,6 +373,28 @@ impl Vfs {
         Ok(results)
     }
 
+    /// Find one page of files matching a pattern, plus the total match count
+    ///
+    /// Matches are numbered in [`find`](Self::find) order across mounts;
+    /// up to `limit` of them are returned starting at match `offset`.
+    /// Mounts that fail to search are skipped, as in `find`.
+    pub fn find_paged(&self, pattern: &str, offset: usize, limit: usize) -> VfsResult<(Vec<PathBuf>, usize)> {
+        let mounts = self.mounts.read();
+        let mut page = Vec::new();
+        let mut total = 0;
+
+        for mount in mounts.iter() {
+            let local_offset = offset.saturating_sub(total);
+            let local_limit = limit - page.len();
+            if let Ok((found, count)) = mount.find_paged(pattern, local_offset, local_limit) {
+                page.extend(found);
+                total += count;
+            }
+        }
+
+        Ok((page, total))
+    }
+
     /// Write file contents
     pub fn write(&self, path: &Path, data: &[u8]) -> VfsResult<()> {
         self.get_mount(path)
@@ -377,6 +514,13 @@ impl MountPoint for LocalMount {
         std::fs::read_to_string(real_path).map_err(VfsError::from)
     }
 
+    fn open_reader(&self, path: &Path) -> VfsResult<Box<dyn ReadSeek>> {
+        let real_path = self.resolve_path(path)
+            .ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?;
+        let file = std::fs::File::open(real_path)?;
+        Ok(Box::new(std::io::BufReader::new(file)))
+    }
+
     fn list(&self, path: &Path) -> VfsResult<Vec<VfsEntry>> {
         let real_path = self.resolve_path(path)
             .ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?;
@@ -516,6 +660,7 @@ impl LocalMount {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use starbreaker_parsers::testutil::p4k_file;
     use std::fs;
     use tempfile::TempDir;
 
@@ -572,6 +717,30 @@ mod tests {
         assert_eq!(content, "hello");
     }
 
+    #[test]
+    fn test_vfs_mount_archive() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = dir.path().join("Data.p4k");
+        fs::write(&archive_path, p4k_file(&[
+            ("Data/game.xml", b"<Game/>"),
+            ("Data/Scripts/ship.xml", b"<Ship/>"),
+        ])).unwrap();
+
+        let vfs = Vfs::new();
+        vfs.mount_archive(&archive_path, Path::new("/game"), &ParseOptions::default()).unwrap();
+
+        assert_eq!(vfs.read_to_string(Path::new("/game/Data/Scripts/ship.xml")).unwrap(), "<Ship/>");
+        assert!(vfs.is_directory(Path::new("/game/Data/Scripts")));
+        assert!(vfs.write(Path::new("/game/Data/new.xml"), b"<New/>").is_err());
+
+        fs::write(dir.path().join("Broken.p4k"), b"not an archive").unwrap();
+        let err = vfs
+            .mount_archive(&dir.path().join("Broken.p4k"), Path::new("/broken"), &ParseOptions::default())
+            .unwrap_err();
+        assert!(matches!(err, VfsError::Parse(_)));
+        assert!(err.to_string().contains("Broken.p4k"));
+    }
+
     #[test]
     fn test_vfs_multiple_mounts() {
         let dir1 = setup_test_dir();
@@ -585,6 +754,73 @@ mod tests {
         assert!(vfs.exists(Path::new("/mount2/file1.txt")));
     }
 
+    #[test]
+    fn test_vfs_first_existing() {
+        let dir = setup_test_dir();
+        let vfs = Vfs::new();
+        vfs.mount(LocalMount::new(dir.path(), "/data")).unwrap();
+
+        let missing = Path::new("/data/file1.dds.1");
+        let present = Path::new("/data/file2.txt");
+        assert_eq!(vfs.first_existing(&[missing, present]), Some(present.to_path_buf()));
+        assert_eq!(
+            vfs.first_existing(&[Path::new("/data/file1.txt"), present]),
+            Some(PathBuf::from("/data/file1.txt"))
+        );
+        assert!(vfs.exists_any(&[missing, present]));
+
+        let unmounted = Path::new("/elsewhere/file2.txt");
+        assert_eq!(vfs.first_existing(&[missing, unmounted]), None);
+        assert!(!vfs.exists_any(&[missing, unmounted]));
+        assert!(!vfs.exists_any(&[]));
+    }
+
+    #[test]
+    fn test_vfs_find_paged() {
+        let dir = setup_test_dir();
+        let vfs = Vfs::new();
+        vfs.mount(LocalMount::new(dir.path(), "/data")).unwrap();
+        vfs.mount(LocalMount::new(dir.path().join("subdir"), "/more")).unwrap();
+
+        let all = vfs.find("*.txt").unwrap();
+        assert_eq!(all.len(), 4);
+
+        let (page, total) = vfs.find_paged("*.txt", 0, 2).unwrap();
+        assert_eq!(page, all[..2]);
+        assert_eq!(total, 4);
+
+        // A window spanning both mounts
+        let (page, total) = vfs.find_paged("*.txt", 2, 5).unwrap();
+        assert_eq!(page, all[2..]);
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn test_local_mount_open_reader() {
+        let dir = setup_test_dir();
+        let vfs = Vfs::new();
+        vfs.mount(LocalMount::new(dir.path(), "/data")).unwrap();
+
+        let mut reader = vfs.open_reader(Path::new("/data/subdir/nested.txt")).unwrap();
+        let mut chunks = Vec::new();
+        let mut buf = [0u8; 4];
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            chunks.push(buf[..n].to_vec());
+        }
+        assert_eq!(chunks, vec![b"nest".to_vec(), b"ed".to_vec()]);
+
+        reader.seek(std::io::SeekFrom::Start(2)).unwrap();
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "sted");
+
+        assert!(vfs.open_reader(Path::new("/data/missing.txt")).is_err());
+    }
+
     #[test]
     fn test_read_only_mount() {
         let dir = setup_test_dir();
@@ -5,7 +5,7 @@
 
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek};
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -13,9 +13,13 @@ use parking_lot::RwLock;
 use thiserror::Error;
 
 use starbreaker_parsers::p4k::{P4kArchive, P4kEntry, P4kParser, DirectoryNode};
-use starbreaker_parsers::traits::{Parser, RandomAccessParser};
+use starbreaker_parsers::traits::{ParseOptions, Parser, RandomAccessParser};
 
-use crate::{VfsNode, VfsEntry, VfsError, VfsResult, MountPoint};
+use crate::{VfsNode, VfsEntry, VfsError, VfsResult, MountPoint, ReadSeek};
+
+/// Decompressed entries larger than this are spilled to a temp file by
+/// `open_reader` instead of being held in memory (64 MB)
+const SPILL_THRESHOLD: u64 = 64 * 1024 * 1024;
 
 /// Errors specific to P4K mounting
 #[derive(Error, Debug)]
@@ -24,7 +28,7 @@ pub enum P4kMountError {
     OpenFailed(#[from] std::io::Error),
 
     #[error("Failed to parse archive: {0}")]
-    ParseFailed(String),
+    ParseFailed(#[from] starbreaker_parsers::ParseError),
 
     #[error("Entry not found: {0}")]
     EntryNotFound(String),
@@ -35,7 +39,11 @@ pub enum P4kMountError {
 
 impl From<P4kMountError> for VfsError {
     fn from(err: P4kMountError) -> Self {
-        VfsError::MountError(err.to_string())
+        match err {
+            P4kMountError::OpenFailed(e) => VfsError::IoError(e),
+            P4kMountError::ParseFailed(e) => VfsError::Parse(e),
+            other => VfsError::MountError(other.to_string()),
+        }
     }
 }
 
@@ -56,6 +64,18 @@ pub struct P4kMountPoint {
     cache: RwLock<LruCache>,
     /// Pre-built directory tree for fast navigation
     tree: DirectoryNode,
+    /// Reject undecodable or binary data in `read_to_string` instead of
+    /// replacing bad sequences
+    strict_text: bool,
+}
+
+/// Cached entry data viewed as a byte slice, so readers can share the cache's buffer
+struct SharedBytes(Arc<Vec<u8>>);
+
+impl AsRef<[u8]> for SharedBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
 }
 
 /// Simple LRU cache for extracted file data
@@ -144,23 +164,102 @@ impl P4kMountPoint {
         cache_size_mb: Option<usize>,
     ) -> Result<Self, P4kMountError> {
         let archive_path = archive_path.as_ref().to_path_buf();
-        let mount_path = mount_path.as_ref().to_path_buf();
+        let archive = Self::parse_archive(&archive_path)?;
+
+        Ok(Self::from_archive(archive_path, mount_path.as_ref(), archive, cache_size_mb))
+    }
+
+    /// Create a P4K mount point, parsing the archive with `options`
+    pub fn with_options(
+        archive_path: impl AsRef<Path>,
+        mount_path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<Self, P4kMountError> {
+        let archive_path = archive_path.as_ref().to_path_buf();
+        let archive = P4kParser::new().parse_file_with_options(&archive_path, options, None)?;
+
+        Ok(Self::from_archive(archive_path, mount_path.as_ref(), archive, None))
+    }
+
+    /// Create a P4K mount point that only exposes entries matching a predicate
+    ///
+    /// Non-matching files are dropped before the directory tree is built, so
+    /// they are invisible to `list`/`exists`/`read` and cost no memory.
+    /// Directories are kept only if they still contain a matching file.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mount = P4kMountPoint::with_filter("Data.p4k", "/game", |e| {
+    ///     e.path.starts_with("Data/Scripts/")
+    /// })?;
+    /// ```
+    pub fn with_filter<F>(
+        archive_path: impl AsRef<Path>,
+        mount_path: impl AsRef<Path>,
+        predicate: F,
+    ) -> Result<Self, P4kMountError>
+    where
+        F: Fn(&P4kEntry) -> bool,
+    {
+        let archive_path = archive_path.as_ref().to_path_buf();
+        let mut archive = Self::parse_archive(&archive_path)?;
+        Self::filter_archive(&mut archive, predicate);
 
-        let parser = P4kParser::new();
-        let archive = parser.parse_file(&archive_path)
-            .map_err(|e| P4kMountError::ParseFailed(e.to_string()))?;
+        Ok(Self::from_archive(archive_path, mount_path.as_ref(), archive, None))
+    }
 
+    /// Parse the archive's central directory
+    fn parse_archive(archive_path: &Path) -> Result<P4kArchive, P4kMountError> {
+        Ok(P4kParser::new().parse_file(archive_path)?)
+    }
+
+    /// Drop non-matching files and any directories left empty
+    fn filter_archive<F>(archive: &mut P4kArchive, predicate: F)
+    where
+        F: Fn(&P4kEntry) -> bool,
+    {
+        archive.retain(|e| e.is_directory || predicate(e));
+
+        // Every ancestor directory of a surviving file, with trailing slash
+        let mut live_dirs = std::collections::HashSet::new();
+        for entry in archive.entries_iter().filter(|e| !e.is_directory) {
+            let mut end = 0;
+            while let Some(idx) = entry.path[end..].find('/') {
+                end += idx + 1;
+                live_dirs.insert(entry.path[..end].to_string());
+            }
+        }
+
+        archive.retain(|e| !e.is_directory || live_dirs.contains(&e.path));
+    }
+
+    /// Assemble a mount point around an already-parsed archive
+    fn from_archive(
+        archive_path: PathBuf,
+        mount_path: &Path,
+        archive: P4kArchive,
+        cache_size_mb: Option<usize>,
+    ) -> Self {
         let tree = archive.build_tree();
         let cache_size = cache_size_mb.unwrap_or(256) * 1024 * 1024;
 
-        Ok(Self {
+        Self {
             archive_path,
-            mount_path,
+            mount_path: mount_path.to_path_buf(),
             archive: Arc::new(archive),
-            parser,
+            parser: P4kParser::new(),
             cache: RwLock::new(LruCache::new(cache_size)),
             tree,
-        })
+            strict_text: false,
+        }
+    }
+
+    /// Make `read_to_string` fail on invalid or binary-looking data
+    ///
+    /// By default bad sequences are replaced with U+FFFD instead.
+    pub fn with_strict_text(mut self, strict: bool) -> Self {
+        self.strict_text = strict;
+        self
     }
 
     /// Get the archive metadata
@@ -208,11 +307,7 @@ impl P4kMountPoint {
         let file = File::open(&self.archive_path)?;
         let mut reader = BufReader::new(file);
 
-        let data = self.parser.extract_entry(&mut reader, &path.to_string())
-            .map_err(|e| VfsError::IoError(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                e.to_string()
-            )))?;
+        let data = self.parser.extract_entry(&mut reader, &path.to_string())?;
 
         // Cache the result
         let data_arc = {
@@ -224,6 +319,38 @@ impl P4kMountPoint {
         Ok(data_arc)
     }
 
+    /// Decompress a large entry into an anonymous temp file, rewound to the start
+    ///
+    /// The file is deleted by the OS once the returned handle is dropped.
+    fn spill_entry(&self, entry: &P4kEntry) -> VfsResult<File> {
+        let mut reader = BufReader::new(File::open(&self.archive_path)?);
+        let mut spill = tempfile::tempfile()?;
+
+        let crc = self.parser.extract_entry_to(&mut reader, entry, BufWriter::new(&mut spill))?;
+        if crc != entry.crc32 {
+            return Err(VfsError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("CRC mismatch for {}: expected {:08X}, got {:08X}", entry.path, entry.crc32, crc)
+            )));
+        }
+
+        spill.flush()?;
+        spill.seek(SeekFrom::Start(0))?;
+        Ok(spill)
+    }
+
+    /// Build the VFS node for an archive entry
+    fn entry_node(path: &Path, entry: &P4kEntry) -> VfsNode {
+        VfsNode {
+            path: path.to_path_buf(),
+            name: entry.filename().to_string(),
+            is_directory: entry.is_directory,
+            size: entry.uncompressed_size,
+            compressed_size: Some(entry.compressed_size),
+            modified: None, // Could parse DOS datetime if needed
+        }
+    }
+
     /// Find directory node for a path
     fn find_node(&self, path: &str) -> Option<&DirectoryNode> {
         if path.is_empty() || path == "/" {
@@ -283,19 +410,36 @@ impl MountPoint for P4kMountPoint {
 
     fn read(&self, path: &Path) -> VfsResult<Vec<u8>> {
         let archive_path = self.resolve_path(path)
+            .filter(|p| self.archive.contains(p))
             .ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?;
 
         let data = self.extract_cached(&archive_path)?;
         Ok((*data).clone())
     }
 
+    fn open_reader(&self, path: &Path) -> VfsResult<Box<dyn ReadSeek>> {
+        let archive_path = self.resolve_path(path)
+            .ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?;
+        let entry = self.archive.get(&archive_path)
+            .filter(|e| !e.is_directory)
+            .ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?;
+
+        // Huge entries would blow the cache; stream them through a temp file
+        if entry.uncompressed_size > SPILL_THRESHOLD {
+            return Ok(Box::new(BufReader::new(self.spill_entry(entry)?)));
+        }
+
+        let data = self.extract_cached(&archive_path)?;
+        Ok(Box::new(Cursor::new(SharedBytes(data))))
+    }
+
     fn read_to_string(&self, path: &Path) -> VfsResult<String> {
-        let data = self.read(path)?;
-        String::from_utf8(data)
-            .map_err(|e| VfsError::IoError(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                e.to_string()
-            )))
+        let archive_path = self.resolve_path(path)
+            .filter(|p| self.archive.contains(p))
+            .ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?;
+
+        let data = self.extract_cached(&archive_path)?;
+        decode_text(&data, self.strict_text)
     }
 
     fn list(&self, path: &Path) -> VfsResult<Vec<VfsEntry>> {
@@ -323,14 +467,30 @@ impl MountPoint for P4kMountPoint {
             .or_else(|| self.archive.get(&format!("{}/", archive_path)))
             .ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?;
 
-        Ok(VfsNode {
-            path: path.to_path_buf(),
-            name: entry.filename().to_string(),
-            is_directory: entry.is_directory,
-            size: entry.uncompressed_size,
-            compressed_size: Some(entry.compressed_size),
-            modified: None, // Could parse DOS datetime if needed
-        })
+        Ok(Self::entry_node(path, entry))
+    }
+
+    fn metadata_batch(&self, paths: &[&Path]) -> Vec<VfsResult<VfsNode>> {
+        // One pass over the index; directories fall back to their "dir/" entry
+        let archive = &*self.archive;
+        let mut dir_key = String::new();
+
+        paths.iter().map(|path| {
+            let archive_path = self.resolve_path(path)
+                .ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?;
+
+            let entry = match archive.get(&archive_path) {
+                Some(entry) => entry,
+                None => {
+                    dir_key.clear();
+                    dir_key.push_str(&archive_path);
+                    dir_key.push('/');
+                    archive.get(&dir_key).ok_or_else(|| VfsError::NotFound(path.to_path_buf()))?
+                }
+            };
+
+            Ok(Self::entry_node(path, entry))
+        }).collect()
     }
 
     fn find(&self, pattern: &str) -> VfsResult<Vec<PathBuf>> {
@@ -339,6 +499,11 @@ impl MountPoint for P4kMountPoint {
             .map(|e| self.mount_path.join(&e.path))
             .collect())
     }
+
+    fn find_paged(&self, pattern: &str, offset: usize, limit: usize) -> VfsResult<(Vec<PathBuf>, usize)> {
+        let (page, total) = self.archive.find_paged(pattern, offset, limit);
+        Ok((page.into_iter().map(|e| self.mount_path.join(&e.path)).collect(), total))
+    }
 }
 
 /// Archive statistics including cache info
@@ -354,10 +519,208 @@ pub struct ArchiveStatistics {
     pub cache_entries: usize,
 }
 
+/// Decode text, honouring a UTF-8 or UTF-16 (LE/BE) byte order mark
+///
+/// The BOM is stripped and data without one is read as UTF-8. Leniently,
+/// bad sequences become U+FFFD; strictly, they are an error, and so is
+/// BOM-less data containing NUL bytes since that is almost surely binary.
+fn decode_text(data: &[u8], strict: bool) -> VfsResult<String> {
+    match data {
+        [0xEF, 0xBB, 0xBF, body @ ..] => decode_utf8(body, strict),
+        [0xFF, 0xFE, body @ ..] => decode_utf16(body, strict, u16::from_le_bytes),
+        [0xFE, 0xFF, body @ ..] => decode_utf16(body, strict, u16::from_be_bytes),
+        _ if strict && data.contains(&0) => Err(invalid_text("data looks binary (contains NUL bytes)")),
+        _ => decode_utf8(data, strict),
+    }
+}
+
+fn decode_utf8(body: &[u8], strict: bool) -> VfsResult<String> {
+    if strict {
+        std::str::from_utf8(body)
+            .map(str::to_owned)
+            .map_err(|e| invalid_text(&e.to_string()))
+    } else {
+        Ok(String::from_utf8_lossy(body).into_owned())
+    }
+}
+
+fn decode_utf16(body: &[u8], strict: bool, unit: fn([u8; 2]) -> u16) -> VfsResult<String> {
+    if strict && !body.len().is_multiple_of(2) {
+        return Err(invalid_text("UTF-16 text has an odd number of bytes"));
+    }
+
+    // A dangling last byte becomes a replacement character
+    let units = body.chunks(2).map(|pair| match *pair {
+        [a, b] => unit([a, b]),
+        _ => char::REPLACEMENT_CHARACTER as u16,
+    });
+    let mut text = String::with_capacity(body.len() / 2);
+    for c in char::decode_utf16(units) {
+        match c {
+            Ok(c) => text.push(c),
+            Err(_) if strict => return Err(invalid_text("unpaired surrogate in UTF-16 text")),
+            Err(_) => text.push(char::REPLACEMENT_CHARACTER),
+        }
+    }
+    Ok(text)
+}
+
+fn invalid_text(message: &str) -> VfsError {
+    VfsError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use starbreaker_parsers::p4k::CompressionMethod;
+    use tempfile::TempDir;
+
+    fn make_test_archive() -> P4kArchive {
+        let paths = [
+            "Data/",
+            "Data/Scripts/",
+            "Data/Scripts/Entities/",
+            "Data/Scripts/Entities/ship.xml",
+            "Data/Scripts/readme.txt",
+            "Data/Textures/",
+            "Data/Textures/ship.dds",
+            "Data/game.xml",
+        ];
+
+        let mut archive = P4kArchive::new();
+        archive.entries = paths.iter().map(|path| P4kEntry {
+            path: path.to_string(),
+            original_path: None,
+            compression: CompressionMethod::Store,
+            crc32: 0,
+            compressed_size: 10,
+            uncompressed_size: 10,
+            local_header_offset: 0,
+            flags: 0,
+            mod_time: 0,
+            mod_date: 0,
+            is_encrypted: false,
+            is_directory: path.ends_with('/'),
+        }).collect();
+        archive.rebuild_index();
+        archive
+    }
+
+    fn make_xml_mount() -> P4kMountPoint {
+        let mut archive = make_test_archive();
+        P4kMountPoint::filter_archive(&mut archive, |e| e.path.ends_with(".xml"));
+        P4kMountPoint::from_archive(PathBuf::from("Data.p4k"), Path::new("/game"), archive, Some(1))
+    }
+
+    #[test]
+    fn test_filtered_mount_listing() {
+        let mount = make_xml_mount();
+
+        let mut names: Vec<_> = mount.list(Path::new("/game/Data")).unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["Scripts", "game.xml"]);
+
+        let scripts: Vec<_> = mount.list(Path::new("/game/Data/Scripts")).unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(scripts, vec!["Entities"]);
+    }
+
+    #[test]
+    fn test_filtered_mount_hides_entries() {
+        let mount = make_xml_mount();
+
+        assert!(mount.exists(Path::new("/game/Data/Scripts/Entities/ship.xml")));
+        assert!(!mount.exists(Path::new("/game/Data/Scripts/readme.txt")));
+        assert!(!mount.exists(Path::new("/game/Data/Textures")));
+        assert!(!mount.is_file(Path::new("/game/Data/Textures/ship.dds")));
+        assert!(matches!(
+            mount.read(Path::new("/game/Data/Textures/ship.dds")),
+            Err(VfsError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_metadata_batch_matches_single_lookups() {
+        let archive = make_test_archive();
+        let mount = P4kMountPoint::from_archive(PathBuf::from("Data.p4k"), Path::new("/game"), archive, Some(1));
+
+        let paths = [
+            Path::new("/game/Data/game.xml"),
+            Path::new("/game/Data/Scripts"),
+            Path::new("/game/Data/missing.xml"),
+            Path::new("/game/Data/Textures/ship.dds"),
+        ];
+        let batch = mount.metadata_batch(&paths);
+        assert_eq!(batch.len(), paths.len());
+
+        for (path, batched) in paths.iter().zip(&batch) {
+            match (mount.metadata(path), batched) {
+                (Ok(single), Ok(batched)) => {
+                    assert_eq!(single.path, batched.path);
+                    assert_eq!(single.name, batched.name);
+                    assert_eq!(single.is_directory, batched.is_directory);
+                    assert_eq!(single.size, batched.size);
+                    assert_eq!(single.compressed_size, batched.compressed_size);
+                }
+                (Err(VfsError::NotFound(a)), Err(VfsError::NotFound(b))) => assert_eq!(&a, b),
+                (single, batched) => panic!("{:?} != {:?}", single, batched),
+            }
+        }
+        assert!(batch[1].as_ref().unwrap().is_directory);
+        assert!(batch[2].is_err());
+    }
+
+    #[test]
+    fn test_vfs_entry_from_node() {
+        let archive = make_test_archive();
+        let mount = P4kMountPoint::from_archive(PathBuf::from("Data.p4k"), Path::new("/game"), archive, Some(1));
+
+        let node = mount.metadata(Path::new("/game/Data/game.xml")).unwrap();
+        let entry = VfsEntry::from(node.clone());
+        assert_eq!(entry.name, node.name);
+        assert_eq!(entry.path, node.path);
+        assert!(!entry.is_directory);
+        assert_eq!(entry.size, Some(node.size));
+        assert_eq!(entry.compressed_size, node.compressed_size);
+
+        let dir = VfsEntry::from(mount.metadata(Path::new("/game/Data/Scripts")).unwrap());
+        assert!(dir.is_directory);
+        assert_eq!(dir.size, None);
+    }
+
+    #[test]
+    fn test_read_to_string_decodes_bom() {
+        let text = "<Ship name=\"Ärgo\"/>";
+        let utf16_le: Vec<u8> = [0xFF, 0xFE].into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let utf16_be: Vec<u8> = [0xFE, 0xFF].into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect();
+        let utf8_bom = [&[0xEF, 0xBB, 0xBF][..], text.as_bytes()].concat();
+
+        // Seed the cache so reads don't touch the (nonexistent) archive file
+        let mount = make_xml_mount();
+        let path = Path::new("/game/Data/game.xml");
+        for data in [utf16_le, utf16_be, utf8_bom] {
+            mount.cache.write().insert("Data/game.xml".to_string(), data);
+            assert_eq!(mount.read_to_string(path).unwrap(), text);
+        }
+
+        mount.cache.write().insert("Data/game.xml".to_string(), vec![b'o', b'k', 0xFF, 0x00]);
+        assert_eq!(mount.read_to_string(path).unwrap(), "ok\u{FFFD}\0");
+
+        let strict = make_xml_mount().with_strict_text(true);
+        strict.cache.write().insert("Data/game.xml".to_string(), vec![b'o', b'k', 0x00]);
+        assert!(matches!(strict.read_to_string(path), Err(VfsError::IoError(_))));
+    }
+
     #[test]
     fn test_lru_cache_basic() {
         let mut cache = LruCache::new(1000);
@@ -367,6 +730,25 @@ mod tests {
         assert!(cache.get("key2").is_none());
     }
 
+    #[test]
+    fn test_parse_error_keeps_source() {
+        use starbreaker_parsers::ParseError;
+        use std::error::Error;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bad.p4k");
+        std::fs::write(&path, b"not an archive").unwrap();
+        let err = P4kMountPoint::parse_archive(&path).unwrap_err();
+
+        assert!(matches!(err, P4kMountError::ParseFailed(_)));
+        let source = err.source().and_then(|s| s.downcast_ref::<ParseError>()).unwrap();
+        assert_eq!(source.offset(), Some(0));
+
+        let err = VfsError::from(err);
+        assert!(matches!(err, VfsError::Parse(_)));
+        assert!(err.source().is_some_and(|s| s.is::<ParseError>()));
+    }
+
     #[test]
     fn test_lru_cache_eviction() {
         let mut cache = LruCache::new(10);
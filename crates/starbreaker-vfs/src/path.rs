@@ -83,57 +83,7 @@ pub fn join_paths(base: &str, relative: &str) -> String {
 /// Check if path matches a glob pattern
 /// Supports * (any chars) and ? (single char)
 pub fn glob_match(pattern: &str, path: &str) -> bool {
-    glob_match_impl(pattern.as_bytes(), path.as_bytes())
-}
-
-fn glob_match_impl(pattern: &[u8], text: &[u8]) -> bool {
-    let mut p = 0;
-    let mut t = 0;
-    let mut star_p = None;
-    let mut star_t = None;
-    
-    while t < text.len() {
-        if p < pattern.len() {
-            match pattern[p] {
-                b'*' => {
-                    star_p = Some(p);
-                    star_t = Some(t);
-                    p += 1;
-                    continue;
-                }
-                b'?' => {
-                    p += 1;
-                    t += 1;
-                    continue;
-                }
-                c if c == text[t] => {
-                    p += 1;
-                    t += 1;
-                    continue;
-                }
-                _ => {}
-            }
-        }
-        
-        // Mismatch - backtrack to last star if any
-        if let Some(sp) = star_p {
-            p = sp + 1;
-            if let Some(st) = star_t {
-                star_t = Some(st + 1);
-                t = st + 1;
-                continue;
-            }
-        }
-        
-        return false;
-    }
-    
-    // Match remaining stars
-    while p < pattern.len() && pattern[p] == b'*' {
-        p += 1;
-    }
-    
-    p == pattern.len()
+    starbreaker_parsers::glob::glob_match(pattern, path)
 }
 
 /// Get file extension from path
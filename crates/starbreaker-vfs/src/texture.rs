@@ -0,0 +1,383 @@
+//! Progressive DDS loading through the VFS
+//!
+//! [`LazyDdsTexture`] reads only the DDS headers when opened and fetches
+//! individual mip levels on demand, using range reads against whichever split
+//! part (`.dds.1`, `.dds.2`, `.dds.3a`, ...) holds them.
+//! [`Vfs::read_texture`] reads and combines every part up front.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use starbreaker_parsers::dds::{DX10Header, DdsHeader, TextureFormat};
+use starbreaker_parsers::{DdsCombiner, DdsTexture, ParseError};
+
+use crate::{Vfs, VfsError, VfsResult};
+
+/// DDS file magic number "DDS "
+const DDS_MAGIC: u32 = 0x20534444;
+
+/// A byte range of one file that forms part of the texture's mip chain
+#[derive(Debug, Clone)]
+struct Segment {
+    path: PathBuf,
+    start: u64,
+    len: u64,
+}
+
+/// A DDS texture whose mip levels are loaded from the VFS on first access
+///
+/// The mip chain is laid out exactly as [`starbreaker_parsers::DdsCombiner`]
+/// would combine it: the data following the base file's headers, then each
+/// split part in order. Loaded levels are cached, so repeated calls to
+/// [`get_mipmap`](Self::get_mipmap) don't touch the VFS again.
+pub struct LazyDdsTexture {
+    vfs: Arc<Vfs>,
+    /// DDS header
+    pub header: DdsHeader,
+    /// DX10 extended header (if present)
+    pub dx10_header: Option<DX10Header>,
+    /// Detected texture format
+    pub format: TextureFormat,
+    segments: Vec<Segment>,
+    mips: RwLock<HashMap<u32, Arc<[u8]>>>,
+}
+
+impl LazyDdsTexture {
+    /// Open a texture by its base path (`texture.dds`), reading only the headers
+    ///
+    /// Split parts are found the same way as [`Vfs::read_texture`] finds
+    /// them. Only their sizes are looked up here; no pixel data is read.
+    pub fn open(vfs: Arc<Vfs>, base_path: &Path) -> VfsResult<Self> {
+        let mut reader = vfs.open_reader(base_path)?;
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if u32::from_le_bytes(magic) != DDS_MAGIC {
+            return Err(ParseError::InvalidMagic {
+                expected: DDS_MAGIC.to_le_bytes().to_vec(),
+                found: magic.to_vec(),
+            }
+            .into());
+        }
+
+        let header = DdsHeader::parse(&mut reader)?;
+        let dx10_header = if header.has_dx10_header() {
+            Some(DX10Header::parse(&mut reader)?)
+        } else {
+            None
+        };
+        let format = TextureFormat::from_header(&header, dx10_header.as_ref());
+
+        let data_start = reader.stream_position()?;
+        let base_size = vfs.metadata(base_path)?.size;
+        let mut segments = vec![Segment {
+            path: base_path.to_path_buf(),
+            start: data_start,
+            len: base_size.saturating_sub(data_start),
+        }];
+        for path in split_parts(&vfs, base_path)? {
+            let len = vfs.metadata(&path)?.size;
+            segments.push(Segment { path, start: 0, len });
+        }
+
+        Ok(Self {
+            vfs,
+            header,
+            dx10_header,
+            format,
+            segments,
+            mips: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Get width in pixels
+    pub fn width(&self) -> u32 {
+        self.header.width
+    }
+
+    /// Get height in pixels
+    pub fn height(&self) -> u32 {
+        self.header.height
+    }
+
+    /// Get mipmap count
+    pub fn mipmap_count(&self) -> u32 {
+        self.header.mipmap_count.max(1)
+    }
+
+    /// Number of split parts found after the base file
+    pub fn split_part_count(&self) -> usize {
+        self.segments.len() - 1
+    }
+
+    /// Check if a mip level has already been loaded
+    pub fn is_loaded(&self, level: u32) -> bool {
+        self.mips.read().contains_key(&level)
+    }
+
+    /// Get the dimensions of a specific mipmap level
+    pub fn get_mipmap_dimensions(&self, level: u32) -> Option<(u32, u32)> {
+        if level >= self.mipmap_count() {
+            return None;
+        }
+        Some(((self.width() >> level).max(1), (self.height() >> level).max(1)))
+    }
+
+    /// Get data for a mip level, loading it from the VFS on first access
+    ///
+    /// Returns `Ok(None)` if the level doesn't exist, and an error if the
+    /// base file and split parts don't hold enough data to reach it.
+    pub fn get_mipmap(&self, level: u32) -> VfsResult<Option<Arc<[u8]>>> {
+        if level >= self.mipmap_count() {
+            return Ok(None);
+        }
+
+        if let Some(data) = self.mips.read().get(&level) {
+            return Ok(Some(data.clone()));
+        }
+
+        let offset: u64 = (0..level).map(|l| self.mip_size(l)).sum();
+        let data: Arc<[u8]> = self.read_range(offset, self.mip_size(level))?.into();
+        self.mips.write().insert(level, data.clone());
+
+        Ok(Some(data))
+    }
+
+    /// Size in bytes of a mip level
+    fn mip_size(&self, level: u32) -> u64 {
+        let (block_width, block_height, bytes_per_block) = self.format.block_dim();
+        let width = (self.width() >> level).max(1);
+        let height = (self.height() >> level).max(1);
+        width.div_ceil(block_width) as u64 * height.div_ceil(block_height) as u64 * bytes_per_block as u64
+    }
+
+    /// Read `len` bytes starting at `offset` in the combined mip chain
+    fn read_range(&self, offset: u64, len: u64) -> VfsResult<Vec<u8>> {
+        let mut data = Vec::with_capacity(len as usize);
+        let end = offset + len;
+        let mut segment_start = 0u64;
+
+        for segment in &self.segments {
+            let segment_end = segment_start + segment.len;
+            if segment_end > offset && segment_start < end {
+                let from = offset.max(segment_start) - segment_start;
+                let to = end.min(segment_end) - segment_start;
+
+                let mut reader = self.vfs.open_reader(&segment.path)?;
+                reader.seek(SeekFrom::Start(segment.start + from))?;
+                let mut chunk = vec![0u8; (to - from) as usize];
+                reader.read_exact(&mut chunk)?;
+                data.extend_from_slice(&chunk);
+            }
+            if segment_end >= end {
+                break;
+            }
+            segment_start = segment_end;
+        }
+
+        if (data.len() as u64) < len {
+            return Err(VfsError::Parse(ParseError::InvalidStructure(format!(
+                "Split parts hold {} bytes, mip data ends at byte {}",
+                self.segments.iter().map(|s| s.len).sum::<u64>(),
+                end
+            ))));
+        }
+
+        Ok(data)
+    }
+}
+
+/// Find the split parts of `base_path` in combine order
+///
+/// Parts are found by listing the parent directory for `base.N`, `base.Na`,
+/// `base.Nb`, ... (case-insensitively) and sorted by index, then letter.
+fn split_parts(vfs: &Vfs, base_path: &Path) -> VfsResult<Vec<PathBuf>> {
+    let base_name = base_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .ok_or_else(|| VfsError::InvalidPath(base_path.display().to_string()))?;
+
+    let mut parts: Vec<((u32, String), PathBuf)> = match base_path.parent() {
+        Some(parent) if vfs.is_directory(parent) => vfs
+            .list(parent)?
+            .into_iter()
+            .filter(|entry| !entry.is_directory)
+            .filter_map(|entry| {
+                let suffix = entry.name.to_lowercase().strip_prefix(&base_name)?.strip_prefix('.')?.to_string();
+                let digits = suffix.find(|c: char| !c.is_ascii_digit()).unwrap_or(suffix.len());
+                let index = suffix[..digits].parse().ok()?;
+                let letter = &suffix[digits..];
+                (letter.len() <= 1 && letter.chars().all(|c| c.is_ascii_lowercase()))
+                    .then(|| ((index, letter.to_string()), entry.path))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    parts.sort();
+
+    Ok(parts.into_iter().map(|(_, path)| path).collect())
+}
+
+impl Vfs {
+    /// Read a DDS texture, combining it with any split parts next to it
+    ///
+    /// `path` may name the base file (`texture.dds`) or any of its parts
+    /// (`texture.dds.1`). Parts are combined in index order (`.1`, `.2`,
+    /// `.3a`, `.3b`, ...); a texture without parts is read as-is.
+    pub fn read_texture(&self, path: &Path) -> VfsResult<DdsTexture> {
+        let base_path = DdsCombiner::get_base_path(path);
+        let mut data = vec![self.read(&base_path)?];
+        for part in split_parts(self, &base_path)? {
+            data.push(self.read(&part)?);
+        }
+
+        Ok(DdsCombiner::new().combine_from_bytes(&data)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LocalMount, MountPoint, ReadSeek, VfsEntry, VfsNode};
+    use parking_lot::Mutex;
+    use starbreaker_parsers::dds::PixelFormat;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Local mount that records every path opened for reading
+    struct RecordingMount {
+        inner: LocalMount,
+        opened: Arc<Mutex<Vec<PathBuf>>>,
+    }
+
+    impl MountPoint for RecordingMount {
+        fn mount_path(&self) -> &Path { self.inner.mount_path() }
+        fn is_read_only(&self) -> bool { true }
+        fn exists(&self, path: &Path) -> bool { self.inner.exists(path) }
+        fn is_file(&self, path: &Path) -> bool { self.inner.is_file(path) }
+        fn is_directory(&self, path: &Path) -> bool { self.inner.is_directory(path) }
+        fn read(&self, path: &Path) -> VfsResult<Vec<u8>> {
+            self.opened.lock().push(path.to_path_buf());
+            self.inner.read(path)
+        }
+        fn read_to_string(&self, path: &Path) -> VfsResult<String> { self.inner.read_to_string(path) }
+        fn open_reader(&self, path: &Path) -> VfsResult<Box<dyn ReadSeek>> {
+            self.opened.lock().push(path.to_path_buf());
+            self.inner.open_reader(path)
+        }
+        fn list(&self, path: &Path) -> VfsResult<Vec<VfsEntry>> { self.inner.list(path) }
+        fn metadata(&self, path: &Path) -> VfsResult<VfsNode> { self.inner.metadata(path) }
+        fn find(&self, pattern: &str) -> VfsResult<Vec<PathBuf>> { self.inner.find(pattern) }
+    }
+
+    /// 8x8 RGBA8 texture with 4 mips spread over `tex.dds`, `.1` and `.2a`
+    ///
+    /// Each level's bytes are filled with its level number.
+    fn write_split_texture(dir: &Path) {
+        let header = DdsHeader {
+            size: 124,
+            flags: 0x2100F,
+            height: 8,
+            width: 8,
+            pitch_or_linear_size: 32,
+            depth: 0,
+            mipmap_count: 4,
+            reserved1: [0; 11],
+            pixel_format: PixelFormat {
+                size: 32,
+                flags: 0x41,
+                fourcc: [0; 4],
+                rgb_bit_count: 32,
+                r_bit_mask: 0x000000FF,
+                g_bit_mask: 0x0000FF00,
+                b_bit_mask: 0x00FF0000,
+                a_bit_mask: 0xFF000000,
+            },
+            caps: 0x401008,
+            caps2: 0,
+            caps3: 0,
+            caps4: 0,
+            reserved2: 0,
+        };
+
+        let mut base = DDS_MAGIC.to_le_bytes().to_vec();
+        header.write(&mut base).unwrap();
+        // Mip 0 straddles the base and .1, mip 1 straddles .1 and .2a
+        base.extend(vec![0u8; 200]);
+        fs::write(dir.join("tex.dds"), base).unwrap();
+
+        let mut rest = vec![0u8; 56];
+        rest.extend(vec![1u8; 64]);
+        rest.extend(vec![2u8; 16]);
+        fs::write(dir.join("tex.dds.1"), &rest[..100]).unwrap();
+        let mut tail = rest[100..].to_vec();
+        tail.extend(vec![3u8; 4]);
+        fs::write(dir.join("tex.dds.2a"), tail).unwrap();
+    }
+
+    #[test]
+    fn test_lazy_dds_loads_mips_on_demand() {
+        let dir = TempDir::new().unwrap();
+        write_split_texture(dir.path());
+
+        let opened = Arc::new(Mutex::new(Vec::new()));
+        let vfs = Arc::new(Vfs::new());
+        vfs.mount(RecordingMount {
+            inner: LocalMount::read_only(dir.path(), "/textures"),
+            opened: opened.clone(),
+        })
+        .unwrap();
+
+        let texture = LazyDdsTexture::open(vfs, Path::new("/textures/tex.dds")).unwrap();
+        assert_eq!((texture.width(), texture.height(), texture.mipmap_count()), (8, 8, 4));
+        assert_eq!(texture.split_part_count(), 2);
+        // Only the base file's headers were read
+        assert_eq!(*opened.lock(), vec![PathBuf::from("/textures/tex.dds")]);
+        assert!(!texture.is_loaded(0));
+
+        let mip = texture.get_mipmap(1).unwrap().unwrap();
+        assert_eq!(mip.len(), 64);
+        assert!(mip.iter().all(|&b| b == 1));
+        assert!(texture.is_loaded(1));
+        assert!(!texture.is_loaded(0));
+        assert_eq!(opened.lock().len(), 3);
+
+        // Cached levels don't hit the VFS again
+        texture.get_mipmap(1).unwrap();
+        assert_eq!(opened.lock().len(), 3);
+
+        let smallest = texture.get_mipmap(3).unwrap().unwrap();
+        assert_eq!(&*smallest, &[3, 3, 3, 3]);
+        assert_eq!(texture.get_mipmap_dimensions(3), Some((1, 1)));
+        assert!(texture.get_mipmap(4).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_read_texture_combines_split_parts() {
+        let dir = TempDir::new().unwrap();
+        write_split_texture(dir.path());
+        fs::write(dir.path().join("tex.dds.mtl"), b"not a part").unwrap();
+        fs::write(dir.path().join("other.dds"), b"").unwrap();
+
+        let vfs = Vfs::new();
+        vfs.mount(LocalMount::read_only(dir.path(), "/textures")).unwrap();
+
+        // 200 bytes after the base headers, then 100 and 40 in the parts
+        let texture = vfs.read_texture(Path::new("/textures/tex.dds")).unwrap();
+        assert!(texture.was_split);
+        assert_eq!(texture.data_size(), 340);
+        assert_eq!(texture.get_mipmap(3).unwrap(), &[3, 3, 3, 3]);
+
+        let from_part = vfs.read_texture(Path::new("/textures/tex.dds.1")).unwrap();
+        assert_eq!(from_part.data_size(), 340);
+
+        fs::remove_file(dir.path().join("tex.dds.1")).unwrap();
+        fs::remove_file(dir.path().join("tex.dds.2a")).unwrap();
+        let plain = vfs.read_texture(Path::new("/textures/tex.dds")).unwrap();
+        assert!(!plain.was_split);
+        assert_eq!(plain.data_size(), 200);
+    }
+}
@@ -5,6 +5,15 @@ use std::sync::{Arc, RwLock};
 use crate::mount::{MountPoint, MountResult, MountError};
 use crate::node::VfsNode;
 use crate::path;
+use starbreaker_parsers::DdsCombiner;
+
+/// Options for batch extraction
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// Write split DDS textures (`.dds` + `.dds.N`) as a single combined `.dds`
+    /// when the base file and all of its fragments are selected
+    pub combine_split_dds: bool,
+}
 
 /// Virtual File System tree
 /// Manages multiple mount points and provides unified file access
@@ -194,9 +203,21 @@ impl VfsTree {
     /// Extract multiple files to a directory
     /// Returns (success_count, total_bytes_written)
     pub fn extract_batch(&self, file_list: &[String], output_dir: impl AsRef<std::path::Path>) -> (usize, u64) {
+        self.extract_batch_with_options(file_list, output_dir, &ExtractOptions::default())
+    }
+
+    /// Extract multiple files to a directory with extraction options
+    /// Returns (success_count, total_bytes_written)
+    pub fn extract_batch_with_options(
+        &self,
+        file_list: &[String],
+        output_dir: impl AsRef<std::path::Path>,
+        options: &ExtractOptions,
+    ) -> (usize, u64) {
         let output_dir = output_dir.as_ref();
         let mut success_count = 0;
         let mut total_bytes = 0u64;
+        let mut written = HashMap::new();
 
         for vfs_path in file_list {
             // Create output path preserving directory structure
@@ -214,12 +235,82 @@ impl VfsTree {
             if let Ok(bytes) = self.extract_file(vfs_path, &output_path) {
                 success_count += 1;
                 total_bytes += bytes;
+                written.insert(path::normalize_path(vfs_path), (output_path, bytes));
             }
         }
 
+        if options.combine_split_dds {
+            let (removed, bytes_delta) = self.combine_split_dds(&written);
+            success_count -= removed;
+            total_bytes = total_bytes.saturating_add_signed(bytes_delta);
+        }
+
         (success_count, total_bytes)
     }
 
+    /// Replace extracted split DDS sets with a single combined file
+    ///
+    /// Only sets whose base file and every fragment present in the VFS were
+    /// extracted are combined; anything else is left as-is. Returns the number
+    /// of fragment files removed and the change in bytes on disk.
+    fn combine_split_dds(&self, written: &HashMap<String, (std::path::PathBuf, u64)>) -> (usize, i64) {
+        // Group extracted fragments by their base texture
+        let mut sets: HashMap<&str, Vec<&str>> = HashMap::new();
+        for vfs_path in written.keys() {
+            if DdsCombiner::is_split_file(vfs_path) {
+                if let Some(base) = vfs_path.rfind(".dds.").map(|pos| &vfs_path[..pos + 4]) {
+                    sets.entry(base).or_default().push(vfs_path);
+                }
+            }
+        }
+
+        let mut removed = 0;
+        let mut bytes_delta = 0i64;
+
+        for (base, fragments) in sets {
+            let Some((base_output, base_bytes)) = written.get(base) else {
+                continue;
+            };
+
+            // Every fragment the VFS knows about must have been extracted
+            let parent = path::parent_path(base).unwrap_or_else(|| "/".to_string());
+            let base_name = path::filename(base);
+            let complete = self.list_directory(&parent)
+                .map(|nodes| {
+                    nodes.iter()
+                        .filter(|n| n.is_file() && DdsCombiner::is_split_file(&n.name))
+                        .filter(|n| DdsCombiner::get_base_path(&n.name).as_os_str() == base_name)
+                        .all(|n| written.contains_key(&path::join_paths(&parent, &n.name)))
+                })
+                .unwrap_or(false);
+            if !complete {
+                continue;
+            }
+
+            let mut paths = vec![base_output.clone()];
+            paths.extend(fragments.iter().map(|f| written[*f].0.clone()));
+
+            let mut combined = Vec::new();
+            let result = DdsCombiner::new()
+                .combine_from_paths(&paths)
+                .and_then(|texture| texture.write(&mut combined));
+            if result.is_err() || std::fs::write(base_output, &combined).is_err() {
+                continue;
+            }
+
+            bytes_delta += combined.len() as i64 - *base_bytes as i64;
+            for fragment in &fragments {
+                let (fragment_output, fragment_bytes) = &written[*fragment];
+                if std::fs::remove_file(fragment_output).is_ok() {
+                    removed += 1;
+                    bytes_delta -= *fragment_bytes as i64;
+                }
+            }
+        }
+
+        (removed, bytes_delta)
+    }
+
     /// Extract all files from a directory recursively
     pub fn extract_directory(&self, vfs_dir: &str, output_dir: impl AsRef<std::path::Path>) -> MountResult<(usize, u64)> {
         let output_dir = output_dir.as_ref();
@@ -249,8 +340,61 @@ impl Default for VfsTree {
 mod tests {
     use super::*;
     use crate::mount::FilesystemMount;
+    use starbreaker_parsers::testutil::{dds_file, dds_header};
     use std::sync::Arc;
 
+    /// Minimal DXT1 header (magic + 124-byte header) followed by `data`
+    fn make_dds(data: &[u8]) -> Vec<u8> {
+        dds_file(&dds_header(*b"DXT1", 4, 0), data)
+    }
+
+    #[test]
+    fn test_extract_combines_split_dds() {
+        let source = tempfile::TempDir::new().unwrap();
+        let output = tempfile::TempDir::new().unwrap();
+
+        std::fs::write(source.path().join("ship.dds"), make_dds(&[1u8; 8])).unwrap();
+        std::fs::write(source.path().join("ship.dds.1"), [2u8; 32]).unwrap();
+        std::fs::write(source.path().join("notes.txt"), b"hello").unwrap();
+
+        let vfs = VfsTree::new();
+        vfs.add_mount(Arc::new(FilesystemMount::new(1, "src", source.path()).unwrap()));
+
+        let files = vec!["/ship.dds".to_string(), "/ship.dds.1".to_string(), "/notes.txt".to_string()];
+        let options = ExtractOptions { combine_split_dds: true };
+        let (count, bytes) = vfs.extract_batch_with_options(&files, output.path(), &options);
+
+        assert_eq!(count, 2);
+        assert!(!output.path().join("ship.dds.1").exists());
+
+        let combined = std::fs::read(output.path().join("ship.dds")).unwrap();
+        assert_eq!(combined.len(), 128 + 8 + 32);
+        assert_eq!(&combined[128..136], &[1u8; 8]);
+        assert_eq!(&combined[136..], &[2u8; 32]);
+        assert_eq!(bytes, combined.len() as u64 + 5);
+    }
+
+    #[test]
+    fn test_extract_leaves_incomplete_split_dds() {
+        let source = tempfile::TempDir::new().unwrap();
+        let output = tempfile::TempDir::new().unwrap();
+
+        std::fs::write(source.path().join("ship.dds"), make_dds(&[1u8; 8])).unwrap();
+        std::fs::write(source.path().join("ship.dds.1"), [2u8; 32]).unwrap();
+        std::fs::write(source.path().join("ship.dds.2"), [3u8; 128]).unwrap();
+
+        let vfs = VfsTree::new();
+        vfs.add_mount(Arc::new(FilesystemMount::new(1, "src", source.path()).unwrap()));
+
+        // ship.dds.2 isn't selected, so the set is left as separate files
+        let files = vec!["/ship.dds".to_string(), "/ship.dds.1".to_string()];
+        let options = ExtractOptions { combine_split_dds: true };
+        let (count, _) = vfs.extract_batch_with_options(&files, output.path(), &options);
+
+        assert_eq!(count, 2);
+        assert!(output.path().join("ship.dds.1").exists());
+    }
+
     #[test]
     fn test_vfs_new() {
         let vfs = VfsTree::new();
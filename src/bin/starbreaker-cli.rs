@@ -2,7 +2,7 @@
 //! 
 //! Command-line interface for P4K archive extraction, DCB querying, and asset conversion.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::io::{self, Write};
 use std::fs;
 
@@ -12,8 +12,10 @@ use tracing::{info, wanr, error, debu, Level};
 use tracing_subscriber::{fmt, EnvFilter};
 
 use starbreaker_parsers::{
-    P4kParser, DcdParser, Parser as ParserTrait,
-    traits::{ParseOptions, RandomAccessParser},
+    P4kParser, DcdParser, CgfParser, DdsParser, ExtractLayout, ParallelExtractOptions, Parser as ParserTrait,
+    detect_format, FormatKind,
+    p4k::CrcCheck,
+    traits::{ParseOptions, ProgressCallback},
 };
 
 /// StarBreaker - Star Citizen data mining and asset extraction tool
@@ -81,6 +83,12 @@ enum Commands {
     /// Show archive statistics
     Stats(StatsArg),
 
+    /// Show the archive directory tree
+    Tree(TreeArgs),
+
+    /// Check archive integrity
+    Verify(VerifyArgs),
+
     /// Launch the GUI application
     Gui,
 }
@@ -141,6 +149,21 @@ struct ExtractArgs {
     /// Dry run - show what would be extracted
     #[arg(long)]
     dry_run: bool,
+
+    /// Resume an interrupted full extraction using a manifest in the output directory
+    ///
+    /// Resuming always extracts the whole archive sequentially in the mirror
+    /// layout, so it can't be combined with options that change that.
+    #[arg(long, conflicts_with_all = ["pattern", "file", "threads", "dry_run", "empty_dirs", "layout"])]
+    resume: bool,
+
+    /// Create directories stored in the archive even when they are empty
+    #[arg(long)]
+    empty_dirs: bool,
+
+    /// Output layout: mirror (archive paths), flatten (file names only) or hash
+    #[arg(long, default_value = "mirror")]
+    layout: ExtractLayout,
 }
 
 #[derive(Args)]
@@ -166,6 +189,10 @@ struct SearchArgs {
     #[arg(long)]
     contents: bool,
 
+    /// With --contents, also search entries that don't look like text
+    #[arg(long)]
+    include_binary: bool,
+
     /// Case-insensitive search
     #[arg(short, long)]
     ignore_case: bool,
@@ -197,6 +224,22 @@ struct DcbArgs {
     #[arg(long)]
     list_types: bool,
 
+    /// Show the most referenced records and struct types
+    #[arg(long)]
+    top_referenced: bool,
+
+    /// Show property data type statistics (for format research)
+    #[arg(long)]
+    type_stats: bool,
+
+    /// Report references to records that don't exist
+    #[arg(long)]
+    check_refs: bool,
+
+    /// List property changes since an older DCB, grouped by struct
+    #[arg(long)]
+    changelog_against: Option<PathBuf>,
+
     /// Maximum results
     #[arg(long, default_value = "100")]
     limit: usize,
@@ -267,6 +310,39 @@ struct StatsArg {
     top: usize,
 }
 
+#[derive(Args)]
+struct TreeArgs {
+    /// Path to the P4K archive
+    #[arg(short, long)]
+    archive: PathBuf,
+
+    /// Directory to start from (defaults to the archive root)
+    path: Option<String>,
+
+    /// Maximum depth to descend
+    #[arg(short, long)]
+    depth: Option<usize>,
+
+    /// Annotate files with their uncompressed size
+    #[arg(short, long)]
+    sizes: bool,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Path to the P4K archive
+    #[arg(short, long)]
+    archive: PathBuf,
+
+    /// Recompute the CRC of every file (decompresses the whole archive)
+    #[arg(long)]
+    full: bool,
+
+    /// Number of files to CRC-check without --full
+    #[arg(long, default_value = "100")]
+    sample: usize,
+}
+
 fn setup_logging(verbosity: u8) {
     let level = match verbosity {
         0 => Level::WARN,
@@ -300,6 +376,8 @@ fn main() -> Result<()> {
         Commands::Diff(args) => cmd_diff(args, cli.format),
         Commands::Export(args) => cmd_export(args),
         Commands::Stats(args) => cmd_stats(args, cli.format),
+        Commands::Tree(args) => cmd_tree(args, cli.format),
+        Commands::Verify(args) => cmd_verify(args, cli.format),
         Commands::Gui => cmd_gui(),
     }
 }
@@ -311,7 +389,7 @@ fn cmd_list(args: ListArgs, format: OutputFormat) -> Result<()> {
     let archive = parser.parse_file(&args.archive)
         .context("Failed to parse P4K archive")?;
 
-    let mut entries: Vec<_> = archive.entries.iter().collect();
+    let mut entries: Vec<_> = archive.entries_iter().collect();
 
     // Apply filters
     if let Some(ref pattern) = args.pattern {
@@ -378,6 +456,23 @@ fn cmd_extract(args: ExtractArgs) -> Result<()> {
     info!("Opening archive: {:?}", args.archive);
 
     let parser = P4kParser::new();
+
+    if args.resume {
+        let manifest_path = args.output.join(".starbreaker-manifest.json");
+        let progress = parser.extract_all_resumable(&args.archive, &args.output, &manifest_path)
+            .context("Resumable extraction failed")?;
+
+        for (path, err) in &progress.failed {
+            error!("Failed to extract {}: {}", path, err);
+        }
+
+        println!("Extraction complete:");
+        println!("  Extracted: {}", progress.extracted);
+        println!("  Skipped:   {}", progress.skipped);
+        println!("  Errors:    {}", progress.failed.len());
+        return Ok(());
+    }
+
     let file = fs::File::open(&args.archive)
         .context("Failed to open archive")?;
     let mut reader = io::BufReader::new(file);
@@ -393,18 +488,15 @@ fn cmd_extract(args: ExtractArgs) -> Result<()> {
     } else if let Some(ref pattern) = args.pattern {
         archive.find(pattern)
     } else {
-        archive.entries.iter().collect()
+        archive.entries_iter().collect()
     };
 
-    let file_entries: Vec<_> = entries_to_extract.iter()
-        .filter(|e| !e.is_directory)
-        .collect();
-
-    info!("Found {} files to extract", file_entries.len());
+    let file_count = entries_to_extract.iter().filter(|e| !e.is_directory).count();
+    info!("Found {} files to extract", file_count);
 
     if args.dry_run {
-        println!("Dry run - would extract {} files:", file_entries.len());
-        for entry in &file_entries {
+        println!("Dry run - would extract {} files:", file_count);
+        for entry in entries_to_extract.iter().filter(|e| !e.is_directory) {
             println!("  {}", entry.path);
         }
         return Ok(());
@@ -414,49 +506,34 @@ fn cmd_extract(args: ExtractArgs) -> Result<()> {
     fs::create_dir_all(&args.output)
         .context("Failed to create output directory")?;
 
-    let mut extracted = 0;
-    let mut skipped = 0;
-    let mut errors = 0;
-
-    // Re-open file for extraction
-    let file = fs::File::open(&args.archive)?;
-    let mut reader = io::BufReader::new(file);
-
-    for entry in file_entries {
-        let output_path = args.output.joing(&entry.path);
-
-        if output_path.exists() && !args.overwrite {
-            debug!("Skipping existing file: {:?}", output_path);
-            skipped += 1;
-            continue;
-        }
-
-        // Create parent directories
-        if let Some(parent) = output_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        match parser.extract_entry(&mut reader, &entry.path) {
-            Ok(data) => {
-                fs::write(&output_path, &data)?;
-                extracted += 1;
-                debug!("Extracted: {}", entry.path);
-            }
-            Err(e) => {
-                error!("Failed to extract {}: {}", entry.path, e);
-                errors += 1;
+    let options = ParallelExtractOptions {
+        threads: args.threads,
+        overwrite: args.overwrite,
+        create_empty_dirs: args.empty_dirs,
+        layout: args.layout,
+        ..Default::default()
+    };
+    let report_progress: ProgressCallback = Box::new(|p| {
+        if let (Some(item), Some(total)) = (p.current_item, p.total_items) {
+            debug!("Extracted {}/{}: {}", p.items_processed, total, item);
+            if p.items_processed % 1000 == 0 || p.items_processed == total {
+                info!("Progress: {}/{} files, {} bytes", p.items_processed, total, p.bytes_processed);
             }
-
         }
+    });
 
-        // Re-seek to beginning for next extraction
-        reader = io::BufReader::new(fs::File::open(&args.archive)?);
+    let progress = parser
+        .extract_parallel(&args.archive, &entries_to_extract, &args.output, &options, Some(report_progress))
+        .context("Extraction failed")?;
+
+    for (path, err) in &progress.failed {
+        error!("Failed to extract {}: {}", path, err);
     }
 
     println!("Extraction complete:");
-    println!("  Extracted: {}", extracted);
-    println!("  Skipped:   {}", skipped);
-    println!("  Errors:    {}", errors);
+    println!("  Extracted: {}", progress.extracted);
+    println!("  Skipped:   {}", progress.skipped);
+    println!("  Errors:    {}", progress.failed.len());
 
     Ok(())
 }
@@ -468,16 +545,41 @@ fn cmd_info(args: InfoArgs, format: OutputFormat) -> Result<()> {
         bail!("File not found: {:?}", path);
     }
 
+    match detect_info_kind(path)? {
+        Some(FormatKind::P4k | FormatKind::Socpak) => show_p4k_info(path, args.detailed, format),
+        Some(FormatKind::Dcb) => show_dcb_info(path, args.detailed, format),
+        Some(FormatKind::Cgf) => show_cgf_info(path, format),
+        Some(FormatKind::Dds) => show_dds_info(path, format),
+        _ => show_generic_info(path, format),
+    }
+}
+
+/// Identify a file for `info` from its magic, falling back to the extension
+///
+/// Extracted assets often lose their suffix or end up as `.dat`, so the
+/// extension is only a hint for files whose magic isn't recognized.
+fn detect_info_kind(path: &Path) -> Result<Option<FormatKind>> {
+    if path.is_dir() {
+        return Ok(None);
+    }
+
+    let file = fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    if let Some(kind) = detect_format(io::BufReader::new(file)) {
+        return Ok(Some(kind));
+    }
+
     let ext = path.extension()
         .and_then(|e| e.to_str())
         .unwrap_or("")
         .to_lowercase();
 
-    match ext.as_str() {
-        "p4k" => show_p4k_info(path, args.detailed, format),
-        "dcb" => show_dcd_info(path, args.detailed, format),
-        _ => show_generic_info(path, format),
-    }
+    Ok(match ext.as_str() {
+        "p4k" => Some(FormatKind::P4k),
+        "dcb" => Some(FormatKind::Dcb),
+        "cgf" | "cga" | "chr" | "skin" => Some(FormatKind::Cgf),
+        "dds" => Some(FormatKind::Dds),
+        _ => None,
+    })
 }
 
 fn show_p4k_info(path: &Pathbuf, detailed: bool, format: OutputFormat) -> Result<()> {
@@ -556,6 +658,72 @@ fn show_dcb_info(path: &PathBuf, detailed: bool, format: OutputFormat) -> Result
     Ok(())
 }
 
+fn show_cgf_info(path: &PathBuf, format: OutputFormat) -> Result<()> {
+    let parser = CgfParser::new();
+    let model = parser.parse_file(path)?;
+    let coverage = model.chunk_coverage();
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&cgf_info_json(path, &model))?);
+        }
+        _ => {
+            println!("CryEngine Geometry: {:?}", path);
+            println!("  Meshes:   {}", model.meshes.len());
+            println!("  Vertices: {}", model.vertex_count());
+            println!("  Faces:    {}", model.face_count());
+            println!("  Chunks:   {} parsed, {} skipped", coverage.parsed(), coverage.skipped());
+
+            println!("\nChunk coverage:");
+            for chunk in &coverage.chunk_types {
+                println!(
+                    "  {:<28} {:>4} present {:>4} parsed {:>4} skipped",
+                    format!("{:?}", chunk.chunk_type), chunk.present, chunk.parsed, chunk.skipped
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn cgf_info_json(path: &Path, model: &starbreaker_parsers::CgfModel) -> serde_json::Value {
+    serde_json::json!({
+        "type": "CryEngine Geometry",
+        "path": path,
+        "mesh_count": model.meshes.len(),
+        "vertex_count": model.vertex_count(),
+        "face_count": model.face_count(),
+        "chunk_coverage": model.chunk_coverage(),
+    })
+}
+
+fn show_dds_info(path: &PathBuf, format: OutputFormat) -> Result<()> {
+    let texture = DdsParser::new().parse_file(path)?;
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "type": "DDS Texture",
+                "path": path,
+                "width": texture.width(),
+                "height": texture.height(),
+                "mipmap_count": texture.mipmap_count(),
+                "format": format!("{:?}", texture.format),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        _ => {
+            println!("DDS Texture: {:?}", path);
+            println!("  Size:    {}x{}", texture.width(), texture.height());
+            println!("  Mipmaps: {}", texture.mipmap_count());
+            println!("  Format:  {:?}", texture.format);
+        }
+    }
+
+    Ok(())
+}
+
 fn show_generic_info(path: &PathBuf, _format: OutputFormat) -> Result<()> {
     let metadata = fs::metadata(path)?;
     println!("File: {:?}", path);
@@ -574,17 +742,44 @@ fn cmd_search(args: SearchArgs, format: OutputFormat) -> Result<()> {
         args.query.clone()
     };
 
-    let results: Vec<_> = archive.entries.iter()
-        .filter(|e| {
-            let path = if args.ignore_case {
-                e.path.to_lowercase()
-            } else {
-                e.path.clone()
-            };
-            path.contains(&query)
-        })
-        .take(args.max_results)
-        .collect();
+    let matches = |text: &str| {
+        if args.ignore_case {
+            text.to_lowercase().contains(&query)
+        } else {
+            text.contains(&query)
+        }
+    };
+
+    let mut reader = if args.contents {
+        Some(io::BufReader::new(fs::File::open(&args.path)?))
+    } else {
+        None
+    };
+
+    let mut results = Vec::new();
+    for entry in archive.entries_iter() {
+        if results.len() >= args.max_results {
+            break;
+        }
+        if matches(&entry.path) {
+            results.push(entry);
+            continue;
+        }
+
+        // Binary entries rarely hold searchable text, so skip them unless asked
+        let Some(reader) = reader.as_mut() else { continue };
+        if entry.is_directory || !args.include_binary && !entry.is_likely_text() {
+            continue;
+        }
+        let mut data = Vec::new();
+        if let Err(e) = parser.extract_entry_to(reader, entry, &mut data) {
+            debug!("Skipping {}: {}", entry.path, e);
+            continue;
+        }
+        if matches(&String::from_utf8_lossy(&data)) {
+            results.push(entry);
+        }
+    }
 
     match format {
         OutputFormat::Json => {
@@ -630,6 +825,23 @@ fn cmd_dcb(args: DcbArgs, format: OutputFormat) -> Result<()> {
         return Ok(());
     }
 
+    if args.top_referenced {
+        return show_top_referenced(&datacore, args.limit, format);
+    }
+
+    if args.type_stats {
+        return show_type_stats(&datacore, format);
+    }
+
+    if args.check_refs {
+        return show_dangling_refs(&datacore, args.limit, format);
+    }
+
+    if let Some(ref baseline_path) = args.changelog_against {
+        let baseline = parser.parse_file(baseline_path)?;
+        return show_changelog(&datacore, &baseline, args.limit, format);
+    }
+
     let records: Vec<_> = if let Some(ref struct_name) = args.r#struct {
         datacore.find_by_struct(struct_name)
     } else {
@@ -669,6 +881,211 @@ fn cmd_dcb(args: DcbArgs, format: OutputFormat) -> Result<()> {
     Ok(())
 }
 
+fn show_top_referenced(
+    datacore: &starbreaker_parsers::DataCore,
+    limit: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let histogram = datacore.reference_histogram();
+    let by_struct = datacore.struct_reference_counts();
+
+    let record_name = |guid: u64| {
+        datacore.get_record(guid).map(|r| r.name.as_str()).unwrap_or("")
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "records": histogram.iter().take(limit).map(|(guid, count)| {
+                    serde_json::json!({
+                        "guid": format!("{:016X}", guid),
+                        "name": record_name(*guid),
+                        "references": count,
+                    })
+                }).collect::<Vec<_>>(),
+                "structs": by_struct.iter().take(limit).map(|(name, count)| {
+                    serde_json::json!({
+                        "struct": name,
+                        "references": count,
+                    })
+                }).collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Csv => {
+            println!("guid,name,references");
+            for (guid, count) in histogram.iter().take(limit) {
+                println!("{:016X},{},{}", guid, record_name(*guid), count);
+            }
+        }
+        OutputFormat::Text => {
+            println!("Most referenced records:");
+            for (i, (guid, count)) in histogram.iter().take(limit).enumerate() {
+                println!("  {}. {} (GUID: {:016X}) - {} references", i + 1, record_name(*guid), guid, count);
+            }
+
+            println!("\nMost referenced struct types:");
+            for (i, (name, count)) in by_struct.iter().take(limit).enumerate() {
+                println!("  {}. {} - {} references", i + 1, name, count);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn show_type_stats(datacore: &starbreaker_parsers::DataCore, format: OutputFormat) -> Result<()> {
+    let stats = datacore.type_statistics();
+    let property_types = stats.sorted_property_types();
+
+    let mut unknown_values: Vec<_> = stats.unknown_values.iter().collect();
+    unknown_values.sort_by_key(|(id, _)| **id);
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "property_types": property_types.iter().map(|(t, count)| {
+                    serde_json::json!({ "type": format!("{:?}", t), "count": count })
+                }).collect::<Vec<_>>(),
+                "unknown_values": unknown_values.iter().map(|(id, count)| {
+                    serde_json::json!({ "type_id": format!("0x{:08X}", id), "count": count })
+                }).collect::<Vec<_>>(),
+                "value_count": stats.value_count,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Csv => {
+            println!("kind,type,count");
+            for (t, count) in &property_types {
+                println!("property,{:?},{}", t, count);
+            }
+            for (id, count) in &unknown_values {
+                println!("unknown_value,0x{:08X},{}", id, count);
+            }
+        }
+        OutputFormat::Text => {
+            println!("Property types ({} properties):", datacore.properties.len());
+            for (t, count) in &property_types {
+                println!("  {:<30} {}", format!("{:?}", t), count);
+            }
+
+            println!("\nUnknown value type ids ({} values scanned):", stats.value_count);
+            if unknown_values.is_empty() {
+                println!("  none");
+            }
+            for (id, count) in &unknown_values {
+                println!("  0x{:08X}  {}", id, count);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn show_dangling_refs(
+    datacore: &starbreaker_parsers::DataCore,
+    limit: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let dangling = datacore.validate_references();
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "count": dangling.len(),
+                "dangling": dangling.iter().take(limit).map(|d| {
+                    serde_json::json!({
+                        "guid": format!("{:016X}", d.source_guid),
+                        "name": d.source_name,
+                        "property": d.property,
+                        "record_id": d.reference.record_id,
+                        "struct_id": d.reference.struct_id,
+                    })
+                }).collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Csv => {
+            println!("guid,name,property,record_id,struct_id");
+            for d in dangling.iter().take(limit) {
+                println!(
+                    "{:016X},{},{},{},{}",
+                    d.source_guid, d.source_name, d.property, d.reference.record_id, d.reference.struct_id
+                );
+            }
+        }
+        OutputFormat::Text => {
+            if dangling.is_empty() {
+                println!("All references resolve");
+                return Ok(());
+            }
+
+            println!("Dangling references ({}):", dangling.len());
+            for d in dangling.iter().take(limit) {
+                println!(
+                    "  {}.{} -> record {} (struct {})",
+                    d.source_name, d.property, d.reference.record_id, d.reference.struct_id
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn show_changelog(
+    datacore: &starbreaker_parsers::DataCore,
+    baseline: &starbreaker_parsers::DataCore,
+    limit: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let changes = datacore.changelog_against(baseline);
+    let value_json = |value: &Option<starbreaker_parsers::RecordValue>| {
+        value.as_ref().map_or(serde_json::Value::Null, |v| v.to_json())
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let groups = starbreaker_parsers::dcb::group_changes_by_struct(&changes);
+            let json = serde_json::json!({
+                "count": changes.len(),
+                "structs": groups.iter().map(|(name, changes)| {
+                    let changes: Vec<_> = changes.iter().take(limit).map(|c| {
+                        serde_json::json!({
+                            "guid": format!("{:016X}", c.record_guid),
+                            "record": c.record_name,
+                            "property": c.property,
+                            "old": value_json(&c.old_value),
+                            "new": value_json(&c.new_value),
+                        })
+                    }).collect();
+                    (name.to_string(), serde_json::Value::Array(changes))
+                }).collect::<serde_json::Map<_, _>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        _ => {
+            if changes.is_empty() {
+                println!("No property changes");
+                return Ok(());
+            }
+
+            println!("Property changes ({}):", changes.len());
+            for (name, changes) in starbreaker_parsers::dcb::group_changes_by_struct(&changes) {
+                println!("\n{} ({}):", name, changes.len());
+                for c in changes.iter().take(limit) {
+                    println!(
+                        "  {}.{}: {} -> {}",
+                        c.record_name, c.property, value_json(&c.old_value), value_json(&c.new_value)
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn cmd_diff(args: DiffArgs, format: OutputFormat) -> Result<()> {
     info!("Comparing archives...");
     info!(" Old: {:?}", args.old);
@@ -676,82 +1093,60 @@ fn cmd_diff(args: DiffArgs, format: OutputFormat) -> Result<()> {
 
     let parser = P4kParser::new();
 
-    let old_archive = parser.parse_file(args.old)
+    let old_archive = parser.parse_file(&args.old)
         .context("Failed to parse old archive")?;
     let new_archive = parser.parse_file(&args.new)
         .context("Failed to parse new archive")?;
 
-    let old_paths: std::collections::HashSet<_> = old_archive.entries.iter()
-        .map(|e| &e.path)
-        .collect();
-    let new_paths: std::collections::HashSet<_> = new_archive.entries.iter()
-        .map(|e| &e.path)
-        .collect();
-
-    let added: Vec<_> = new_paths.difference(&old_paths).collect();
-    let removed: Vec<_> = old_paths.difference(&new_paths).collect();
-
-    // Find modified files (same path, different size or CRC)
-    let modified: Vec<_> = old_archive.entries.iter()
-        .filter_map(|old_entry| {
-            new_archive.get(&old_entry.path).and_then(|new_entry| {
-                if old_entry.crc32 != new_entry.crc32 ||
-                   old_entry.uncompressed_size != new_entry.uncompressed_size {
-                    Some((&old_entry.path, old_entry, new_entry))
-                   } else {
-                    None
-                   }
-            })
-        })
-        .collect();
+    let diff = old_archive.diff(&new_archive);
 
     match format {
-        OutputFormat::Jso => {
+        OutputFormat::Json => {
             let json = serde_json::json!({
-                "added": added.len(),
-                "removed": removed.len(),
-                "modified": modified.len(),
-                "added_files": added,
-                "removed_files": removed,
-                "modified_files": modified.iter().map(|(p, _, _)| p).collect::<Vec<_>>(),
+                "added": diff.added.len(),
+                "removed": diff.removed.len(),
+                "modified": diff.modified.len(),
+                "added_files": diff.added,
+                "removed_files": diff.removed,
+                "modified_files": diff.modified,
             });
             println!("{}", serde_json::to_string_pretty(&json)?);
         }
         _ => {
             println!("Diff Summary:");
-            println!("  Added:      {} files", added.len());
-            println!("  Removed:    {} files", removed.len());
-            println!("  Modified:   {} files", modified.len());
+            println!("  Added:      {} files", diff.added.len());
+            println!("  Removed:    {} files", diff.removed.len());
+            println!("  Modified:   {} files", diff.modified.len());
 
-            if !args.removed_only && !args.modified_only && !added.is_empty() {
+            if !args.removed_only && !args.modified_only && !diff.added.is_empty() {
                 println!("\nAdded files:");
-                for path in added.iter().take(20) {
+                for path in diff.added.iter().take(20) {
                     println!("  + {}", path);
                 }
-                if added.len() > 20 {
-                    println!("  ... and {} more", added.len() - 20);
+                if diff.added.len() > 20 {
+                    println!("  ... and {} more", diff.added.len() - 20);
                 }
             }
 
-            if !args.added_only && !args.modified_only && !removed.is_empty() {
+            if !args.added_only && !args.modified_only && !diff.removed.is_empty() {
                 println!("\nRemoved files:");
-                for path in removed.iter().take(20) {
+                for path in diff.removed.iter().take(20) {
                     println!("  - {}", path);
                 }
-                if removed.len() > 20 {
-                    println!("  ... and {} more", removed.len() - 20);
+                if diff.removed.len() > 20 {
+                    println!("  ... and {} more", diff.removed.len() - 20);
                 }
             }
 
-            if !args.added_only && !args.removed_only && !modified.is_empty() {
+            if !args.added_only && !args.removed_only && !diff.modified.is_empty() {
                 println!("\nModified files:");
-                for (path, old, new) in modfiied.iter().take(20) {
-                    let size_diff = new.uncompressed_size as i64 - old.uncompressed_size as i64;
+                for entry in diff.modified.iter().take(20) {
+                    let size_diff = entry.size_delta();
                     let sign = if size_diff >= 0 { "+" } else { "" };
-                    println!("  ~ {} ({}{} bytes)", path, sign, size_diff);
+                    println!("  ~ {} ({}{} bytes)", entry.path, sign, size_diff);
                 }
-                if modified.len() > 20 {
-                    println!("  ... and {} more", modified.len() - 20);
+                if diff.modified.len() > 20 {
+                    println!("  ... and {} more", diff.modified.len() - 20);
                 }
             }
         }
@@ -763,16 +1158,16 @@ fn cmd_diff(args: DiffArgs, format: OutputFormat) -> Result<()> {
             "old_archive": args.old,
             "new_archive": args.new,
             "summary": {
-                "added": added.len(),
-                "removed": removed.len(),
-                "modified": modified.len(),
+                "added": diff.added.len(),
+                "removed": diff.removed.len(),
+                "modified": diff.modified.len(),
             },
-            "added_files": added,
-            "removed_files": removed,
-            "modified_files": modified.iter().map(|(p, _, _)| p).collect::<Vec<_>>(),
+            "added_files": diff.added,
+            "removed_files": diff.removed,
+            "modified_files": diff.modified,
         });
-        fs::write(&output_path, serder_json::to_string_pretty(&report)?)?;
-        println!(\nReport written to: {:?}", output_path);")
+        fs::write(&output_path, serde_json::to_string_pretty(&report)?)?;
+        println!("\nReport written to: {:?}", output_path);
     }
 
     Ok(())
@@ -791,7 +1186,7 @@ fn cmd_stats(args: StatsArgs, format: OutputFormat) -> Result<()> {
     let stats = archive.statistics();
 
     // Find largest files
-    let mut entries: Vec<_> = archive.entries.iter()
+    let mut entries: Vec<_> = archive.entries_iter()
         .filter(|e| !e.is_directory)
         .collect();
     entries.sort_by_key(|e| std::cmp::Reverse(e.uncompressed_size));
@@ -849,6 +1244,168 @@ fn cmd_stats(args: StatsArgs, format: OutputFormat) -> Result<()> {
     Ok(())
 }
 
+fn cmd_tree(args: TreeArgs, format: OutputFormat) -> Result<()> {
+    let parser = P4kParser::new();
+    let archive = parser.parse_file(&args.archive)?;
+    let tree = archive.build_tree();
+
+    let start = args.path.as_deref().unwrap_or("").trim_matches('/');
+    let node = match tree.find(start) {
+        Some(node) => node,
+        None => bail!("Path not found in archive: {}", start),
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let json = tree_to_json(node, start, &archive, args.depth, args.sizes);
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        _ => {
+            let mut out = String::new();
+            render_tree(node, start, &archive, args.depth, args.sizes, &mut out);
+            print!("{}", out);
+        }
+    }
+
+    Ok(())
+}
+
+/// Join a tree path and a child name
+fn tree_child_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", path, name)
+    }
+}
+
+/// Render a directory node `tree`-style, one line per child
+fn render_tree(
+    node: &starbreaker_parsers::p4k::DirectoryNode,
+    path: &str,
+    archive: &starbreaker_parsers::P4kArchive,
+    max_depth: Option<usize>,
+    sizes: bool,
+    out: &mut String,
+) {
+    /// Settings shared by every level of the walk
+    struct TreeWalk<'a> {
+        archive: &'a starbreaker_parsers::P4kArchive,
+        max_depth: Option<usize>,
+        sizes: bool,
+    }
+
+    impl TreeWalk<'_> {
+        fn walk(
+            &self,
+            node: &starbreaker_parsers::p4k::DirectoryNode,
+            path: &str,
+            depth: usize,
+            prefix: &str,
+            out: &mut String,
+        ) {
+            if self.max_depth.is_some_and(|max| depth >= max) {
+                return;
+            }
+
+            let children = node.sorted_children();
+            for (i, name) in children.iter().enumerate() {
+                let child = &node.children[*name];
+                let child_path = tree_child_path(path, name);
+                let last = i + 1 == children.len();
+
+                out.push_str(prefix);
+                out.push_str(if last { "└── " } else { "├── " });
+                out.push_str(name);
+
+                if child.is_file {
+                    if let Some(entry) = self.archive.get(&child_path).filter(|_| self.sizes) {
+                        out.push_str(&format!(" ({})", format_size(entry.uncompressed_size)));
+                    }
+                    out.push('\n');
+                } else {
+                    out.push_str("/\n");
+                    let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                    self.walk(child, &child_path, depth + 1, &prefix, out);
+                }
+            }
+        }
+    }
+
+    out.push_str(if path.is_empty() { "." } else { path });
+    out.push('\n');
+    TreeWalk { archive, max_depth, sizes }.walk(node, path, 0, "", out);
+}
+
+/// Nested JSON form of a directory node
+fn tree_to_json(
+    node: &starbreaker_parsers::p4k::DirectoryNode,
+    path: &str,
+    archive: &starbreaker_parsers::P4kArchive,
+    max_depth: Option<usize>,
+    sizes: bool,
+) -> serde_json::Value {
+    let mut json = serde_json::json!({
+        "name": node.name,
+        "path": path,
+        "type": if node.is_file { "file" } else { "directory" },
+    });
+
+    if node.is_file {
+        if let Some(entry) = archive.get(path).filter(|_| sizes) {
+            json["size"] = entry.uncompressed_size.into();
+        }
+    } else if max_depth.is_none_or(|max| max > 0) {
+        json["children"] = node.sorted_children().iter().map(|name| {
+            tree_to_json(
+                &node.children[*name],
+                &tree_child_path(path, name),
+                archive,
+                max_depth.map(|max| max - 1),
+                sizes,
+            )
+        }).collect::<Vec<_>>().into();
+    }
+
+    json
+}
+
+fn cmd_verify(args: VerifyArgs, format: OutputFormat) -> Result<()> {
+    let crc = if args.full { CrcCheck::Full } else { CrcCheck::Sample(args.sample) };
+    let report = P4kParser::new().validate_file(&args.archive, crc)
+        .with_context(|| format!("Failed to verify {:?}", args.archive))?;
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "checked": report.checked,
+                "crc_checked": report.crc_checked,
+                "problems": report.problems.iter().map(|(path, problem)| {
+                    serde_json::json!({
+                        "path": path,
+                        "problem": problem.to_string(),
+                    })
+                }).collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        _ => {
+            for (path, problem) in &report.problems {
+                println!("{}: {}", path, problem);
+            }
+            println!(
+                "Checked {} entries ({} CRCs): {} problem(s)",
+                report.checked, report.crc_checked, report.problems.len()
+            );
+        }
+    }
+
+    if !report.is_ok() {
+        bail!("Archive failed verification");
+    }
+    Ok(())
+}
+
 fn cmd_gui() -> Result<()> {
     println!("Launching GUI...");
     // TODO: Launch the eframe GUI
@@ -870,4 +1427,108 @@ fn format_size(bytes: u64) -> String {
     } else {
         format!("{} B", bytes)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starbreaker_parsers::{P4kArchive, P4kEntry, CompressionMethod};
+    use starbreaker_parsers::cgf::ChunkType;
+    use starbreaker_parsers::testutil::ivo_file;
+    use tempfile::TempDir;
+
+    fn make_archive() -> P4kArchive {
+        let mut archive = P4kArchive::new();
+        for (path, size) in [
+            ("Data/Libs/Config/defaultprofile.xml", 2048),
+            ("Data/Objects/ship.cgf", 100),
+            ("Data/Objects/ship.mtl", 10),
+            ("Data/readme.txt", 5),
+        ] {
+            archive.entries.push(P4kEntry {
+                path: path.to_string(),
+                original_path: None,
+                compression: CompressionMethod::Store,
+                crc32: 0,
+                compressed_size: size,
+                uncompressed_size: size,
+                local_header_offset: 0,
+                flags: 0,
+                mod_time: 0,
+                mod_date: 0,
+                is_encrypted: false,
+                is_directory: false,
+            });
+        }
+        archive.rebuild_index();
+        archive
+    }
+
+    #[test]
+    fn test_info_detects_cgf_by_magic() {
+        // Ivo file with one empty mesh chunk
+        let mut data = ivo_file(&[(ChunkType::Mesh, 1, 36, 48)]);
+        data.extend_from_slice(&[0u8; 48]);
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("model.bin");
+        fs::write(&path, &data).unwrap();
+        let kind = detect_info_kind(&path);
+        let model = CgfParser::new().parse_file(&path);
+
+        assert_eq!(kind.unwrap(), Some(FormatKind::Cgf));
+        let json = cgf_info_json(&path, &model.unwrap());
+        assert_eq!(json["type"], "CryEngine Geometry");
+        assert_eq!(json["mesh_count"], 1);
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let archive = make_archive();
+        let tree = archive.build_tree();
+
+        let mut out = String::new();
+        render_tree(&tree, "", &archive, None, false, &mut out);
+        assert_eq!(out, "\
+.
+└── Data/
+    ├── Libs/
+    │   └── Config/
+    │       └── defaultprofile.xml
+    ├── Objects/
+    │   ├── ship.cgf
+    │   └── ship.mtl
+    └── readme.txt
+");
+    }
+
+    #[test]
+    fn test_render_subtree_with_depth_and_sizes() {
+        let archive = make_archive();
+        let tree = archive.build_tree();
+
+        let mut out = String::new();
+        render_tree(tree.find("Data").unwrap(), "Data", &archive, Some(1), true, &mut out);
+        assert_eq!(out, "\
+Data
+├── Libs/
+├── Objects/
+└── readme.txt (5 B)
+");
+
+        let json = tree_to_json(tree.find("Data/Objects").unwrap(), "Data/Objects", &archive, None, true);
+        assert_eq!(json["children"][0]["path"], "Data/Objects/ship.cgf");
+        assert_eq!(json["children"][0]["size"], 100);
+    }
+
+    #[test]
+    fn test_resume_rejects_unsupported_extract_options() {
+        let base = ["starbreaker-cli", "extract", "-a", "Data.p4k", "-o", "out", "--resume"];
+        assert!(Cli::try_parse_from(base).is_ok());
+
+        for extra in [&["--threads", "8"][..], &["--empty-dirs"], &["--layout", "flatten"], &["--dry-run"], &["-p", "*.xml"]] {
+            let args = base.iter().chain(extra);
+            assert!(Cli::try_parse_from(args).is_err(), "--resume accepted {:?}", extra);
+        }
+    }
+}